@@ -57,6 +57,8 @@ async fn main() {
             let opts = || EvaluationOptions {
                 trace,
                 max_depth: 10,
+                concurrent: false,
+                ..Default::default()
             };
             engine
                 .evaluate_with_opts(e.file.as_str(), input.clone(), opts())
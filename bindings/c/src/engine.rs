@@ -1,49 +1,61 @@
+use arc_swap::ArcSwap;
 use serde_json::Value;
-use std::ffi::{c_char, CStr, CString};
+use std::ffi::{c_char, c_int, CStr, CString};
 use std::marker::{PhantomData, PhantomPinned};
-use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+use zen_engine::nodes::http_handler::{DynamicHttpHandler, HttpHandlerConfig};
 use zen_engine::{DecisionEngine, EvaluationOptions};
 
 use crate::custom_node::DynamicCustomNode;
 use crate::custom_node::ZenCustomNodeResult;
 use crate::decision::{ZenDecision, ZenDecisionStruct};
 use crate::error::ZenError;
-use crate::helper::safe_str_from_ptr;
+use crate::helper::{safe_cstr_from_ptr, safe_str_from_ptr};
 use crate::languages::native::NativeCustomNode;
 use crate::loader::{DynamicDecisionLoader, ZenEngineLoaderConfig};
 use crate::mt::{tokio_runtime, worker_pool};
 use crate::result::ZenResult;
 use serde_json::json;
 
-pub(crate) struct ZenEngine(DecisionEngine);
-
-impl Deref for ZenEngine {
-    type Target = DecisionEngine;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl DerefMut for ZenEngine {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
+/// Engine handed out to C callers. The decision engine sits behind an [`ArcSwap`] so that its
+/// configuration can be replaced while other threads keep evaluating with the previous one.
+pub(crate) struct ZenEngine(ArcSwap<DecisionEngine>);
 
 impl Default for ZenEngine {
     fn default() -> Self {
-        Self(DecisionEngine::new(
+        Self::from(DecisionEngine::new(
             Arc::new(DynamicDecisionLoader::default()),
             Arc::new(DynamicCustomNode::default()),
         ))
     }
 }
 
+impl From<DecisionEngine> for ZenEngine {
+    fn from(engine: DecisionEngine) -> Self {
+        Self(ArcSwap::from_pointee(engine))
+    }
+}
+
 impl ZenEngine {
     pub fn new(loader: DynamicDecisionLoader, custom_node: DynamicCustomNode) -> Self {
-        Self(DecisionEngine::new(Arc::new(loader), Arc::new(custom_node)))
+        Self::from(DecisionEngine::new(Arc::new(loader), Arc::new(custom_node)))
+    }
+
+    /// Current decision engine, unaffected by later configuration changes
+    pub fn load(&self) -> Arc<DecisionEngine> {
+        self.0.load_full()
+    }
+
+    pub fn compile(&self) {
+        self.0.load().compile();
+    }
+
+    /// Compiles a copy of the engine using `http_handler` and swaps it in, evaluations already
+    /// running finish on the engine they started with
+    pub fn set_http_handler(&self, http_handler: DynamicHttpHandler) {
+        let engine = DecisionEngine::clone(&self.0.load()).with_http_handler(http_handler);
+        engine.compile();
+        self.0.store(Arc::new(engine));
     }
 }
 
@@ -64,6 +76,8 @@ impl Into<EvaluationOptions> for ZenEngineEvaluationOptions {
         EvaluationOptions {
             trace: self.trace,
             max_depth: self.max_depth,
+            concurrent: false,
+            ..Default::default()
         }
     }
 }
@@ -98,6 +112,42 @@ pub extern "C" fn zen_engine_new_with_loader_config(
     ZenResult::ok(Box::into_raw(Box::new(engine)) as *mut ZenEngineStruct)
 }
 
+/// Replaces the HTTP handler of function nodes with a built-in one described by a JSON config,
+/// e.g. `{"mode":"replay","path":"fixtures.json"}`. Returns 1 on success.
+/// Caller is responsible for freeing: config and ZenResult.
+#[no_mangle]
+pub extern "C" fn zen_engine_set_http_handler_config(
+    engine: *mut ZenEngineStruct,
+    config: *const c_char,
+) -> ZenResult<c_int> {
+    if engine.is_null() {
+        return ZenResult::error(ZenError::InvalidArgument);
+    }
+
+    let Some(config_cstr) = safe_cstr_from_ptr(config) else {
+        return ZenResult::error(ZenError::InvalidArgument);
+    };
+
+    let Ok(http_config) = serde_json::from_slice::<HttpHandlerConfig>(config_cstr.to_bytes())
+    else {
+        return ZenResult::error(ZenError::JsonDeserializationFailed);
+    };
+
+    let http_handler = match http_config.into_http_handler() {
+        Ok(handler) => handler,
+        Err(error) => {
+            return ZenResult::error(ZenError::HttpHandlerConfigError {
+                message: error.to_string(),
+            })
+        }
+    };
+
+    let zen_engine = unsafe { &*(engine as *const ZenEngine) };
+    zen_engine.set_http_handler(Some(http_handler));
+
+    ZenResult::ok(Box::into_raw(Box::new(1)))
+}
+
 /// Frees the ZenEngine instance reference from the memory
 #[no_mangle]
 pub extern "C" fn zen_engine_free(engine: *mut ZenEngineStruct) {
@@ -123,7 +173,10 @@ pub extern "C" fn zen_engine_create_decision(
     };
 
     let zen_engine = unsafe { &*(engine as *mut ZenEngine) };
-    let decision = match zen_engine.create_decision(Arc::new(decision_content)) {
+    let decision = match zen_engine
+        .load()
+        .create_decision(Arc::new(decision_content))
+    {
         Ok(d) => d,
         Err(_) => return ZenResult::error(ZenError::InvalidArgument),
     };
@@ -160,7 +213,7 @@ pub extern "C" fn zen_engine_evaluate(
         return ZenResult::error(ZenError::JsonDeserializationFailed);
     };
 
-    let maybe_result = tokio_runtime().block_on(zen_engine.evaluate_with_opts(
+    let maybe_result = tokio_runtime().block_on(zen_engine.load().evaluate_with_opts(
         str_key,
         var_context,
         options.into(),
@@ -225,7 +278,7 @@ pub extern "C" fn zen_engine_evaluate_batch(
     }
 
     let zen_engine = unsafe { &*(engine as *const ZenEngine) };
-    let decision_engine: DecisionEngine = DecisionEngine::clone(zen_engine);
+    let decision_engine: DecisionEngine = DecisionEngine::clone(&zen_engine.load());
     let eval_options: EvaluationOptions = options.into();
 
     let pool = worker_pool();
@@ -235,6 +288,7 @@ pub extern "C" fn zen_engine_evaluate_batch(
             Err(message) => BatchTask::Failed(json!(format!("invalid context: {message}"))),
             Ok(value) => {
                 let engine = decision_engine.clone();
+                let eval_options = eval_options.clone();
                 BatchTask::Pending(pool.spawn_pinned(move || async move {
                     let value = zen_engine::Variable::try_from_value(value)
                         .map_err(|e| json!(format!("invalid context: {e}")))?;
@@ -293,7 +347,7 @@ pub extern "C" fn zen_engine_get_decision(
     };
 
     let zen_engine = unsafe { &*(engine as *mut ZenEngine) };
-    let decision = match tokio_runtime().block_on(zen_engine.get_decision(str_key)) {
+    let decision = match tokio_runtime().block_on(zen_engine.load().get_decision(str_key)) {
         Ok(Ok(d)) => d,
         Ok(Err(_)) => return ZenResult::error(ZenError::InvalidArgument),
         Err(e) => return ZenResult::from(&e),
@@ -428,6 +482,51 @@ mod tests {
         zen_engine_free(engine);
     }
 
+    #[test]
+    fn engine_http_handler_config() {
+        let path = CString::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../test-data")).unwrap();
+        let config = ZenEngineLoaderConfig {
+            kind: ZenLoaderConfigKind::Filesystem,
+            content: path.as_ptr(),
+            bytes: null(),
+            bytes_len: 0,
+        };
+        let engine = zen_engine_new_with_loader_config(config, None).result_ptr();
+
+        let invalid = CString::new(r#"{"mode":"replay","path":"missing-recording.json"}"#).unwrap();
+        let result = zen_engine_set_http_handler_config(engine, invalid.as_ptr());
+        assert_eq!(
+            result.error_code(),
+            ZenErrorDiscriminants::HttpHandlerConfigError as u8
+        );
+
+        let http_config = CString::new(
+            r#"{"mode":"mock","mocks":[{"match":{"url":"https://fakestoreapi.com/*"},"response":{"status":200,"data":{"id":1}}}]}"#,
+        )
+        .unwrap();
+        let result = zen_engine_set_http_handler_config(engine, http_config.as_ptr());
+        assert_eq!(result.error_code(), 0);
+
+        let key = CString::new("http-function.json").unwrap();
+        let context = CString::new("{}").unwrap();
+        let result = zen_engine_evaluate(
+            engine,
+            key.as_ptr(),
+            context.as_ptr(),
+            ZenEngineEvaluationOptions {
+                trace: false,
+                max_depth: 5,
+            },
+        );
+
+        assert_eq!(result.error_code(), 0);
+        let response = unsafe { CString::from_raw(result.result_ptr()) };
+        let response: Value = serde_json::from_slice(response.to_bytes()).unwrap();
+        assert_eq!(response["result"]["data"], serde_json::json!({ "id": 1 }));
+
+        zen_engine_free(engine);
+    }
+
     #[test]
     fn engine_from_invalid_zip_loader_config() {
         let bytes = [0u8; 4];
@@ -23,6 +23,8 @@ pub enum ZenError {
     TemplateEngineError { template: String, message: String },
 
     LoaderConfigError { message: String },
+
+    HttpHandlerConfigError { message: String },
 }
 
 impl ZenError {
@@ -40,6 +42,9 @@ impl ZenError {
             ZenError::LoaderConfigError { message } => {
                 Some(json!({ "message": message }).to_string())
             }
+            ZenError::HttpHandlerConfigError { message } => {
+                Some(json!({ "message": message }).to_string())
+            }
             _ => None,
         }
     }
@@ -54,6 +54,16 @@ typedef struct ZenCustomNodeResult {
   char *error;
 } ZenCustomNodeResult;
 
+/**
+ * CResult can be seen as Either<Result, Error>. It cannot, and should not, be initialized
+ * manually. Instead, use error or ok functions for initialisation.
+ */
+typedef struct ZenResult_c_int {
+  int *result;
+  uint8_t error;
+  char *details;
+} ZenResult_c_int;
+
 /**
  * CResult can be seen as Either<Result, Error>. It cannot, and should not, be initialized
  * manually. Instead, use error or ok functions for initialisation.
@@ -69,16 +79,6 @@ typedef struct ZenEngineEvaluateBatchRequest {
   const char *context;
 } ZenEngineEvaluateBatchRequest;
 
-/**
- * CResult can be seen as Either<Result, Error>. It cannot, and should not, be initialized
- * manually. Instead, use error or ok functions for initialisation.
- */
-typedef struct ZenResult_c_int {
-  int *result;
-  uint8_t error;
-  char *details;
-} ZenResult_c_int;
-
 typedef struct ZenDecisionLoaderResult {
   char *content;
   char *error;
@@ -114,6 +114,14 @@ struct ZenEngineStruct *zen_engine_new(void);
 struct ZenResult_ZenEngineStruct zen_engine_new_with_loader_config(struct ZenEngineLoaderConfig config,
                                                                    struct ZenCustomNodeResult (*maybe_custom_node)(const char *request));
 
+/**
+ * Replaces the HTTP handler of function nodes with a built-in one described by a JSON config,
+ * e.g. `{"mode":"replay","path":"fixtures.json"}`. Returns 1 on success.
+ * Caller is responsible for freeing: config and ZenResult.
+ */
+struct ZenResult_c_int zen_engine_set_http_handler_config(struct ZenEngineStruct *engine,
+                                                          const char *config);
+
 /**
  * Frees the ZenEngine instance reference from the memory
  */
@@ -69,6 +69,7 @@ export type PolicyDiagnosticCode =
   | 'UNRESOLVED_FUNCTION_TYPE'
   | 'IMPLICIT_ANY'
   | 'UNCHECKED_NODE'
+  | 'INVALID_NODE_CONFIG'
   | 'NULLABILITY_DIVERGENCE'
   | 'REDUNDANT_NULLISH'
   | 'REPEATED_DERIVATION'
@@ -260,6 +261,9 @@ export interface PolicyWireBlock {
  * - `replaceBlock` — overwrite an existing block's wire content.
  * - `deleteBlock`  — remove a block by id.
  * - `insertBlock`  — append a new block (after `afterBlockId` if given).
+ * - `replaceNode` / `replaceEdge` — overwrite a graph node or edge by id.
+ * - `deleteNode` / `deleteEdge`   — remove a graph node or edge by id.
+ * - `insertNode` / `insertEdge`   — append a graph node or edge.
  */
 export type PolicyEngineEdit =
   | {
@@ -280,7 +284,17 @@ export type PolicyEngineEdit =
       document: string;
       nodeId: string;
       newNode: unknown;
-    };
+    }
+  | { kind: 'deleteNode'; document: string; nodeId: string }
+  | { kind: 'insertNode'; document: string; newNode: unknown }
+  | {
+      kind: 'replaceEdge';
+      document: string;
+      edgeId: string;
+      newEdge: unknown;
+    }
+  | { kind: 'deleteEdge'; document: string; edgeId: string }
+  | { kind: 'insertEdge'; document: string; newEdge: unknown };
 
 /**
  * What kind of usage site a `PolicyReferenceSite` represents.
@@ -69,6 +69,7 @@ export type PolicyDiagnosticCode =
   | 'UNRESOLVED_FUNCTION_TYPE'
   | 'IMPLICIT_ANY'
   | 'UNCHECKED_NODE'
+  | 'INVALID_NODE_CONFIG'
   | 'NULLABILITY_DIVERGENCE'
   | 'REDUNDANT_NULLISH'
   | 'REPEATED_DERIVATION'
@@ -260,6 +261,9 @@ export interface PolicyWireBlock {
  * - `replaceBlock` — overwrite an existing block's wire content.
  * - `deleteBlock`  — remove a block by id.
  * - `insertBlock`  — append a new block (after `afterBlockId` if given).
+ * - `replaceNode` / `replaceEdge` — overwrite a graph node or edge by id.
+ * - `deleteNode` / `deleteEdge`   — remove a graph node or edge by id.
+ * - `insertNode` / `insertEdge`   — append a graph node or edge.
  */
 export type PolicyEngineEdit =
   | {
@@ -280,7 +284,17 @@ export type PolicyEngineEdit =
       document: string;
       nodeId: string;
       newNode: unknown;
-    };
+    }
+  | { kind: 'deleteNode'; document: string; nodeId: string }
+  | { kind: 'insertNode'; document: string; newNode: unknown }
+  | {
+      kind: 'replaceEdge';
+      document: string;
+      edgeId: string;
+      newEdge: unknown;
+    }
+  | { kind: 'deleteEdge'; document: string; edgeId: string }
+  | { kind: 'insertEdge'; document: string; newEdge: unknown };
 
 /**
  * What kind of usage site a `PolicyReferenceSite` represents.
@@ -437,12 +451,14 @@ export type PolicyBlockTrace =
     };
 
 export declare class Workspace {
-  constructor(resolveFunctionType?: (source: string, inputType: PolicyVariableType) => string | null | undefined)
+  constructor(resolveFunctionType?: (source: string, inputType: PolicyVariableType, libraries: Record<string, string>) => string | null | undefined)
   functionResolutionRequests(): Array<PolicyFunctionResolutionRequest>
   setFunctionType(source: string, inputType: PolicyVariableType, tsType?: string | undefined | null): void
   setDocument(path: string, document: any): void
   setPolicy(path: string, document: any): void
   removePath(path: string): boolean
+  setLibrary(key: string, source: string): void
+  removeLibrary(key: string): boolean
   isGraph(path: string): boolean
   uncheckedNodes(path: string): Array<string>
   paths(): Array<string>
@@ -526,6 +542,9 @@ export declare function evaluateUnaryExpression(expression: string, context: any
 
 export declare function evaluateUnaryExpressionSync(expression: string, context: any): boolean
 
+/** TypeScript declarations of the native function modules, to load alongside function sources */
+export declare function functionModuleDeclarations(): string
+
 export declare function nlEncodeString(value: string): string | null
 
 export declare function nlTokenizeBatch(requests: NlTokenizeRequest[], rootType: PolicyVariableType, strict?: boolean): NlResult[]
@@ -621,6 +640,8 @@ export interface PolicyFieldKindInfo {
 export interface PolicyFunctionResolutionRequest {
   source: string
   inputType: PolicyVariableType
+  /** Imported library modules by loader key */
+  libraries: Record<string, string>
 }
 
 export interface PolicyGlobalInfo {
@@ -717,7 +738,7 @@ export interface ZenEngineHandlerResponse {
 export interface ZenEngineOptions {
 loader?: ((key: string) => Promise<Buffer | ZenDecisionContent>) | { type: 'static'; content: Record<string, object> } | { type: 'fs'; path: string } | { type: 'zip'; bytes: Buffer }
 customHandler?: (request: ZenEngineHandlerRequest) => Promise<ZenEngineHandlerResponse>
-httpHandler?: (request: ZenHttpHandlerRequest) => Promise<ZenHttpHandlerResponse>
+httpHandler?: ((request: ZenHttpHandlerRequest) => Promise<ZenHttpHandlerResponse>) | { mode: 'mock'; mocks?: Array<{ match?: { method?: string; url?: string; body?: any }; response: ZenHttpHandlerResponse }>; path?: string } | { mode: 'record'; path: string } | { mode: 'replay'; path: string }
 }
 
 export interface ZenEngineResponse {
@@ -739,6 +760,7 @@ export interface ZenEngineTrace {
 export interface ZenEvaluateOptions {
   maxDepth?: number
   trace?: boolean | 'string' | 'reference' | 'referenceString'
+  concurrent?: boolean
 }
 
 export interface ZenHttpHandlerRequest {
@@ -581,6 +581,7 @@ module.exports.evaluateExpression = nativeBinding.evaluateExpression
 module.exports.evaluateExpressionSync = nativeBinding.evaluateExpressionSync
 module.exports.evaluateUnaryExpression = nativeBinding.evaluateUnaryExpression
 module.exports.evaluateUnaryExpressionSync = nativeBinding.evaluateUnaryExpressionSync
+module.exports.functionModuleDeclarations = nativeBinding.functionModuleDeclarations
 module.exports.nlEncodeString = nativeBinding.nlEncodeString
 module.exports.nlTokenizeBatch = nativeBinding.nlTokenizeBatch
 module.exports.overrideConfig = nativeBinding.overrideConfig
@@ -24,6 +24,7 @@ use crate::safe_result::SafeResult;
 use crate::types::{ZenEngineHandlerRequest, ZenEngineHandlerResponse};
 use zen_engine::loader::{DynamicLoader, LoaderConfig};
 use zen_engine::model::DecisionContent;
+use zen_engine::nodes::http_handler::HttpHandlerConfig;
 use zen_engine::{
     DecisionEngine, EvaluationOptions, EvaluationSerializedOptions, EvaluationTraceKind,
 };
@@ -87,6 +88,7 @@ pub struct ZenEvaluateOptions {
     pub max_depth: Option<u8>,
     #[napi(ts_type = "boolean | 'string' | 'reference' | 'referenceString'")]
     pub trace: Option<JsEvaluationTraceKind>,
+    pub concurrent: Option<bool>,
 }
 
 impl Default for ZenEvaluateOptions {
@@ -94,6 +96,7 @@ impl Default for ZenEvaluateOptions {
         Self {
             max_depth: Some(5),
             trace: Some(JsEvaluationTraceKind::default()),
+            concurrent: Some(false),
         }
     }
 }
@@ -103,6 +106,8 @@ impl From<ZenEvaluateOptions> for EvaluationSerializedOptions {
         Self {
             max_depth: value.max_depth.unwrap_or(5),
             trace: value.trace.unwrap_or_default().0,
+            concurrent: value.concurrent.unwrap_or_default(),
+            ..Default::default()
         }
     }
 }
@@ -146,9 +151,15 @@ pub struct ZenEngineOptions {
     pub custom_handler:
         Option<Function<'static, ZenEngineHandlerRequest, Promise<ZenEngineHandlerResponse>>>,
 
-    #[napi(ts_type = "(request: ZenHttpHandlerRequest) => Promise<ZenHttpHandlerResponse>")]
-    pub http_handler:
-        Option<Function<'static, ZenHttpHandlerRequest, Promise<ZenHttpHandlerResponse>>>,
+    #[napi(
+        ts_type = "((request: ZenHttpHandlerRequest) => Promise<ZenHttpHandlerResponse>) | { mode: 'mock'; mocks?: Array<{ match?: { method?: string; url?: string; body?: any }; response: ZenHttpHandlerResponse }>; path?: string } | { mode: 'record'; path: string } | { mode: 'replay'; path: string }"
+    )]
+    pub http_handler: Option<
+        Either<
+            Function<'static, ZenHttpHandlerRequest, Promise<ZenHttpHandlerResponse>>,
+            Object<'static>,
+        >,
+    >,
 }
 
 #[napi]
@@ -222,18 +233,26 @@ impl ZenEngine {
         };
 
         let mut decision_engine = DecisionEngine::new(loader, Arc::new(custom_node));
-        if let Some(h) = opts.http_handler {
-            let http_tsfn = h
-                .build_threadsafe_function()
-                .max_queue_size::<0>()
-                .callee_handled::<false>()
-                .weak()
-                .build()?;
-
-            let arc_http_handler_tsfn = Arc::new(http_tsfn);
-            http_handler_tsfn_opt = Some(arc_http_handler_tsfn.clone());
-            decision_engine = decision_engine
-                .with_http_handler(Some(Arc::new(NodeHttpHandler::new(arc_http_handler_tsfn))));
+        match opts.http_handler {
+            None => {}
+            Some(Either::A(h)) => {
+                let http_tsfn = h
+                    .build_threadsafe_function()
+                    .max_queue_size::<0>()
+                    .callee_handled::<false>()
+                    .weak()
+                    .build()?;
+
+                let arc_http_handler_tsfn = Arc::new(http_tsfn);
+                http_handler_tsfn_opt = Some(arc_http_handler_tsfn.clone());
+                decision_engine = decision_engine
+                    .with_http_handler(Some(Arc::new(NodeHttpHandler::new(arc_http_handler_tsfn))));
+            }
+            Some(Either::B(config_obj)) => {
+                let config: HttpHandlerConfig = env.from_js_value(config_obj)?;
+                let http_handler = config.into_http_handler().map_err(|e| anyhow!(e))?;
+                decision_engine = decision_engine.with_http_handler(Some(http_handler));
+            }
         }
 
         decision_engine.compile();
@@ -261,6 +280,8 @@ impl ZenEngine {
             let options = EvaluationOptions {
                 trace: mode != EvaluationTraceKind::None,
                 max_depth: serialized.max_depth,
+                concurrent: serialized.concurrent,
+                ..Default::default()
             };
 
             async move {
@@ -349,6 +370,7 @@ impl ZenEngine {
         let options: EvaluationSerializedOptions = opts.unwrap_or_default().into();
         let mode = options.trace;
         let max_depth = options.max_depth;
+        let concurrent = options.concurrent;
 
         let mut handles = Vec::with_capacity(requests.len());
         for req in requests {
@@ -358,6 +380,8 @@ impl ZenEngine {
                 let eval_opts = EvaluationOptions {
                     trace: mode != EvaluationTraceKind::None,
                     max_depth,
+                    concurrent,
+                    ..Default::default()
                 };
 
                 let context = zen_engine::Variable::try_from_value(context).map_err(
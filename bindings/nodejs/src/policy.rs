@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use napi::anyhow::anyhow;
@@ -9,7 +10,7 @@ use serde_json::Value;
 use zen_engine::policy::PolicyDocument;
 use zen_engine::workspace;
 
-type ResolverRef = FunctionRef<FnArgs<(String, Value)>, Option<String>>;
+type ResolverRef = FunctionRef<FnArgs<(String, Value, HashMap<String, String>)>, Option<String>>;
 
 #[napi(object)]
 pub struct PolicyExpressionCursor {
@@ -360,6 +361,7 @@ fn variable_type_from_json(value: &Value) -> zen_expression::variable::VariableT
         "string" => VariableType::String,
         "number" => VariableType::Number,
         "date" => VariableType::Date,
+        "duration" => VariableType::Duration,
         "interval" => VariableType::Interval,
         "const" => value
             .get("value")
@@ -415,6 +417,7 @@ pub(crate) fn variable_type_to_json(vt: &zen_expression::variable::VariableType)
         VariableType::String => serde_json::json!({ "type": "string" }),
         VariableType::Number => serde_json::json!({ "type": "number" }),
         VariableType::Date => serde_json::json!({ "type": "date" }),
+        VariableType::Duration => serde_json::json!({ "type": "duration" }),
         VariableType::Interval => serde_json::json!({ "type": "interval" }),
         VariableType::Const(c) => serde_json::json!({ "type": "const", "value": c.as_ref() }),
         VariableType::Enum(name, values) => {
@@ -447,6 +450,13 @@ pub(crate) fn variable_type_to_json(vt: &zen_expression::variable::VariableType)
     }
 }
 
+fn library_map(libraries: &[workspace::FunctionLibrary]) -> HashMap<String, String> {
+    libraries
+        .iter()
+        .map(|library| (library.key.to_string(), library.source.to_string()))
+        .collect()
+}
+
 fn dependency_node_to_json(node: &workspace::DependencyNode) -> Value {
     let mut obj = serde_json::Map::new();
     obj.insert("property".into(), Value::String(node.property.to_string()));
@@ -482,11 +492,19 @@ impl From<PolicyScopeRequest> for workspace::ScopeRequest {
     }
 }
 
+/// TypeScript declarations of the native function modules, to load alongside function sources
+#[napi]
+pub fn function_module_declarations() -> &'static str {
+    workspace::FUNCTION_MODULE_DECLARATIONS
+}
+
 #[napi(object)]
 pub struct PolicyFunctionResolutionRequest {
     pub source: String,
     #[napi(ts_type = "PolicyVariableType")]
     pub input_type: Value,
+    /// Imported library modules by loader key
+    pub libraries: HashMap<String, String>,
 }
 
 #[napi]
@@ -500,7 +518,7 @@ impl Workspace {
     #[napi(constructor)]
     pub fn new(
         #[napi(
-            ts_arg_type = "(source: string, inputType: PolicyVariableType) => string | null | undefined"
+            ts_arg_type = "(source: string, inputType: PolicyVariableType, libraries: Record<string, string>) => string | null | undefined"
         )]
         resolve_function_type: Option<ResolverRef>,
     ) -> Self {
@@ -522,8 +540,11 @@ impl Workspace {
             let function = resolver.borrow_back(env)?;
             for request in requests {
                 let input_json = variable_type_to_json(&request.input);
-                let resolved: Option<String> =
-                    function.call(FnArgs::from((request.source.to_string(), input_json)))?;
+                let resolved: Option<String> = function.call(FnArgs::from((
+                    request.source.to_string(),
+                    input_json,
+                    library_map(&request.libraries),
+                )))?;
                 self.inner
                     .set_function_type(&request.source, &request.input, resolved.as_deref());
             }
@@ -539,6 +560,7 @@ impl Workspace {
             .map(|request| PolicyFunctionResolutionRequest {
                 source: request.source.to_string(),
                 input_type: variable_type_to_json(&request.input),
+                libraries: library_map(&request.libraries),
             })
             .collect()
     }
@@ -576,6 +598,16 @@ impl Workspace {
         self.inner.remove_path(&path)
     }
 
+    #[napi]
+    pub fn set_library(&mut self, key: String, source: String) {
+        self.inner.set_library(key, source);
+    }
+
+    #[napi]
+    pub fn remove_library(&mut self, key: String) -> bool {
+        self.inner.remove_library(&key)
+    }
+
     #[napi]
     pub fn is_graph(&self, path: String) -> bool {
         self.inner.is_graph(&path)
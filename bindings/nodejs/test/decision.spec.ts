@@ -86,6 +86,25 @@ describe('ZenEngine', () => {
 
   });
 
+  it('Serves HTTP calls from mocks', async () => {
+    const engine = new ZenEngine({
+      loader,
+      httpHandler: {
+        mode: 'mock',
+        mocks: [
+          {
+            match: { method: 'GET', url: 'https://fakestoreapi.com/products/*' },
+            response: { status: 200, headers: {}, data: { id: 1, title: 'Mocked' } },
+          },
+        ],
+      },
+    });
+
+    const r = await engine.evaluate('http-function.json', {});
+    expect(r.result.status).toEqual(200);
+    expect(r.result.data).toEqual({ id: 1, title: 'Mocked' });
+  });
+
   it('Parses ZenDecisionContent', async () => {
     const decisionContent = new ZenDecisionContent(await loader('table.json'));
 
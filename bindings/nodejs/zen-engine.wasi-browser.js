@@ -65,6 +65,7 @@ export const evaluateExpression = __napiModule.exports.evaluateExpression
 export const evaluateExpressionSync = __napiModule.exports.evaluateExpressionSync
 export const evaluateUnaryExpression = __napiModule.exports.evaluateUnaryExpression
 export const evaluateUnaryExpressionSync = __napiModule.exports.evaluateUnaryExpressionSync
+export const functionModuleDeclarations = __napiModule.exports.functionModuleDeclarations
 export const nlEncodeString = __napiModule.exports.nlEncodeString
 export const nlTokenizeBatch = __napiModule.exports.nlTokenizeBatch
 export const overrideConfig = __napiModule.exports.overrideConfig
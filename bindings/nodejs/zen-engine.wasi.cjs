@@ -117,6 +117,7 @@ module.exports.evaluateExpression = __napiModule.exports.evaluateExpression
 module.exports.evaluateExpressionSync = __napiModule.exports.evaluateExpressionSync
 module.exports.evaluateUnaryExpression = __napiModule.exports.evaluateUnaryExpression
 module.exports.evaluateUnaryExpressionSync = __napiModule.exports.evaluateUnaryExpressionSync
+module.exports.functionModuleDeclarations = __napiModule.exports.functionModuleDeclarations
 module.exports.nlEncodeString = __napiModule.exports.nlEncodeString
 module.exports.nlTokenizeBatch = __napiModule.exports.nlTokenizeBatch
 module.exports.overrideConfig = __napiModule.exports.overrideConfig
@@ -17,6 +17,7 @@ use pythonize::{depythonize, pythonize};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use zen_engine::loader::{DynamicLoader, LoaderConfig};
+use zen_engine::nodes::http_handler::HttpHandlerConfig;
 use zen_engine::{DecisionEngine, EvaluationOptions};
 
 #[pyclass]
@@ -29,6 +30,7 @@ pub struct PyZenEngine {
 pub struct PyZenEvaluateOptions {
     pub trace: Option<bool>,
     pub max_depth: Option<u8>,
+    pub concurrent: Option<bool>,
 }
 
 impl From<PyZenEvaluateOptions> for EvaluationOptions {
@@ -36,6 +38,8 @@ impl From<PyZenEvaluateOptions> for EvaluationOptions {
         Self {
             max_depth: value.max_depth.unwrap_or(5),
             trace: value.trace.unwrap_or_default(),
+            concurrent: value.concurrent.unwrap_or_default(),
+            ..Default::default()
         }
     }
 }
@@ -54,7 +58,16 @@ impl<'py> FromPyObject<'py> for PyZenEvaluateOptions {
             .map(|v| v.extract::<u8>())
             .transpose()?;
 
-        Ok(PyZenEvaluateOptions { trace, max_depth })
+        let concurrent = dict
+            .get_item("concurrent")?
+            .map(|v| v.extract::<bool>())
+            .transpose()?;
+
+        Ok(PyZenEvaluateOptions {
+            trace,
+            max_depth,
+            concurrent,
+        })
     }
 }
 
@@ -63,6 +76,7 @@ impl Default for PyZenEvaluateOptions {
         Self {
             trace: None,
             max_depth: None,
+            concurrent: None,
         }
     }
 }
@@ -157,10 +171,19 @@ impl PyZenEngine {
             None => Arc::new(PyDecisionLoader::default()),
         };
 
+        let http_handler = match options.get_item("httpHandler")? {
+            Some(config) => {
+                let config: HttpHandlerConfig = depythonize(&config)?;
+                Some(config.into_http_handler()?)
+            }
+            None => None,
+        };
+
         let engine = DecisionEngine::new(
             loader,
             Arc::new(PyCustomNode::new(custom_node, make_locals())),
-        );
+        )
+        .with_http_handler(http_handler);
         engine.compile();
 
         Ok(Self {
@@ -202,6 +225,7 @@ impl PyZenEngine {
             .into_iter()
             .map(|request| {
                 let engine = self.engine.clone();
+                let options = options.clone();
                 worker_pool().spawn_pinned(move || async move {
                     let context = zen_engine::Variable::try_from_value(request.context)
                         .map_err(|e| Value::String(format!("invalid context: {e}")))?;
@@ -148,6 +148,22 @@ class ZenEngine(unittest.TestCase):
         engine.evaluate("http-function.json", {})
         self.assertTrue(True)
 
+    def test_http_handler_mock(self):
+        engine = zen.ZenEngine({
+            "loader": loader,
+            "httpHandler": {
+                "mode": "mock",
+                "mocks": [{
+                    "match": {"method": "GET", "url": "https://fakestoreapi.com/products/*"},
+                    "response": {"status": 200, "data": {"id": 1, "title": "Mocked"}},
+                }],
+            },
+        })
+
+        r = engine.evaluate("http-function.json", {})
+        self.assertEqual(r["result"]["status"], 200)
+        self.assertEqual(r["result"]["data"], {"id": 1, "title": "Mocked"})
+
     def test_additional_options(self):
         engine = zen.ZenEngine({"loader": loader, "customHandler": custom_handler})
 
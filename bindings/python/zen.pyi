@@ -5,6 +5,7 @@ from typing import Any, Optional, TypedDict, Literal, TypeAlias, Union
 class DecisionEvaluateOptions(TypedDict, total=False):
     max_depth: int
     trace: bool
+    concurrent: bool
 
 
 class EvaluateResponse(TypedDict):
@@ -36,9 +37,46 @@ ZenLoaderConfig: TypeAlias = Union[StaticLoaderConfig, FilesystemLoaderConfig, Z
 ZenLoaderCallback: TypeAlias = Callable[[str], Union[str, dict, ZenDecisionContent, Awaitable[Union[str, dict, ZenDecisionContent]]]]
 
 
+class HttpRequestMatcher(TypedDict, total=False):
+    method: str
+    url: str
+    body: Any
+
+
+class HttpMockResponse(TypedDict, total=False):
+    status: int
+    headers: dict
+    data: Any
+
+
+class HttpMock(TypedDict, total=False):
+    match: HttpRequestMatcher
+    response: HttpMockResponse
+
+
+class MockHttpHandlerConfig(TypedDict, total=False):
+    mode: Literal["mock"]
+    mocks: list[HttpMock]
+    path: str
+
+
+class RecordHttpHandlerConfig(TypedDict):
+    mode: Literal["record"]
+    path: str
+
+
+class ReplayHttpHandlerConfig(TypedDict):
+    mode: Literal["replay"]
+    path: str
+
+
+ZenHttpHandlerConfig: TypeAlias = Union[MockHttpHandlerConfig, RecordHttpHandlerConfig, ReplayHttpHandlerConfig]
+
+
 class ZenEngineOptions(TypedDict, total=False):
     loader: Union[ZenLoaderCallback, ZenLoaderConfig]
     customHandler: Callable
+    httpHandler: ZenHttpHandlerConfig
 
 
 class EvaluateBatchRequest(TypedDict):
@@ -3,6 +3,7 @@ use crate::custom_node::{
 };
 use crate::decision::ZenDecision;
 use crate::error::ZenError;
+use crate::http_handler::ZenHttpHandler;
 use crate::loader::{NoopDecisionLoader, ZenDecisionLoaderCallbackWrapper, ZenLoader};
 use crate::types::{JsonBuffer, ZenBatchRequest, ZenBatchResult, ZenEngineResponse};
 use serde_json::Value;
@@ -37,16 +38,19 @@ impl From<ZenEvaluateOptions> for EvaluationOptions {
         Self {
             max_depth: value.max_depth.unwrap_or(5),
             trace: value.trace.unwrap_or(false),
+            concurrent: false,
+            ..Default::default()
         }
     }
 }
 
 #[uniffi::export(async_runtime = "tokio")]
 impl ZenEngine {
-    #[uniffi::constructor(default(loader = None, custom_node = None))]
+    #[uniffi::constructor(default(loader = None, custom_node = None, http_handler = None))]
     pub fn new(
         loader: Option<ZenLoader>,
         custom_node: Option<Box<dyn ZenCustomNodeCallback>>,
+        http_handler: Option<ZenHttpHandler>,
     ) -> Result<Self, ZenError> {
         let loader: DynamicLoader = match loader {
             Some(loader) => loader.into_dynamic_loader()?,
@@ -60,7 +64,8 @@ impl ZenEngine {
             Arc::new(ZenCustomNodeCallbackWrapper(
                 custom_node.unwrap_or_else(|| Box::new(NoopCustomNodeCallback)),
             )),
-        );
+        )
+        .with_http_handler(http_handler.map(|h| h.into_http_handler()).transpose()?);
         engine.compile();
 
         Ok(Self {
@@ -114,6 +119,7 @@ impl ZenEngine {
             .into_iter()
             .map(|request| {
                 let engine = self.engine.clone();
+                let options = options.clone();
                 task::spawn_blocking(move || {
                     Handle::current().block_on(async move {
                         let context: Value = request.context.try_into()?;
@@ -225,14 +231,14 @@ mod tests {
             JsonBuffer(include_bytes!("../../../test-data/table.json").to_vec()),
         )]);
 
-        let engine = ZenEngine::new(Some(ZenLoader::Static { content }), None).unwrap();
+        let engine = ZenEngine::new(Some(ZenLoader::Static { content }), None, None).unwrap();
         assert_table_output(&engine).await;
     }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn with_filesystem_loader_config() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../test-data").to_string();
-        let engine = ZenEngine::new(Some(ZenLoader::Filesystem { path }), None).unwrap();
+        let engine = ZenEngine::new(Some(ZenLoader::Filesystem { path }), None, None).unwrap();
         assert_table_output(&engine).await;
     }
 
@@ -253,15 +259,47 @@ mod tests {
                 callback: Arc::new(FsCallback),
             }),
             None,
+            None,
         )
         .unwrap();
         assert_table_output(&engine).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn with_mock_http_handler() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../test-data").to_string();
+        let mocks = serde_json::json!([{
+            "match": { "method": "GET", "url": "https://fakestoreapi.com/products/*" },
+            "response": { "status": 200, "data": { "id": 1 } }
+        }]);
+
+        let engine = ZenEngine::new(
+            Some(ZenLoader::Filesystem { path }),
+            None,
+            Some(ZenHttpHandler::Mock {
+                mocks: Some(JsonBuffer(serde_json::to_vec(&mocks).unwrap())),
+                path: None,
+            }),
+        )
+        .unwrap();
+
+        let response = engine
+            .evaluate(
+                "http-function.json".to_string(),
+                JsonBuffer(b"{}".to_vec()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let result: Value = response.result.try_into().unwrap();
+        assert_eq!(result["data"], serde_json::json!({ "id": 1 }));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn evaluate_batch_mixed_results() {
         let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../test-data").to_string();
-        let engine = ZenEngine::new(Some(ZenLoader::Filesystem { path }), None).unwrap();
+        let engine = ZenEngine::new(Some(ZenLoader::Filesystem { path }), None, None).unwrap();
 
         let requests = vec![
             ZenBatchRequest {
@@ -302,6 +340,7 @@ mod tests {
                 content: HashMap::new(),
             }),
             None,
+            None,
         )
         .unwrap();
 
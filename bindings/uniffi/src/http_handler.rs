@@ -0,0 +1,54 @@
+use crate::error::ZenError;
+use crate::types::JsonBuffer;
+use std::sync::Arc;
+use zen_engine::nodes::http_handler::{HttpHandler, HttpHandlerConfig, HttpMock};
+
+/// Built-in HTTP handler used by function nodes instead of the network
+#[derive(uniffi::Enum)]
+pub enum ZenHttpHandler {
+    /// `mocks` is a JSON array of `{ "match": ..., "response": ... }` objects
+    Mock {
+        mocks: Option<JsonBuffer>,
+        path: Option<String>,
+    },
+    /// `redacted_headers` and `redacted_query_params` replace the default lists of headers and
+    /// query parameters whose values are not recorded
+    Record {
+        path: String,
+        redacted_headers: Option<Vec<String>>,
+        redacted_query_params: Option<Vec<String>>,
+    },
+    Replay {
+        path: String,
+    },
+}
+
+impl ZenHttpHandler {
+    pub fn into_http_handler(self) -> Result<Arc<dyn HttpHandler + Send + Sync>, ZenError> {
+        let config = match self {
+            ZenHttpHandler::Mock { mocks, path } => {
+                let mocks: Vec<HttpMock> = match mocks {
+                    Some(buffer) => serde_json::from_slice(buffer.0.as_slice())
+                        .map_err(|_| ZenError::JsonDeserializationFailed)?,
+                    None => Vec::new(),
+                };
+
+                HttpHandlerConfig::Mock { mocks, path }
+            }
+            ZenHttpHandler::Record {
+                path,
+                redacted_headers,
+                redacted_query_params,
+            } => HttpHandlerConfig::Record {
+                path,
+                redacted_headers,
+                redacted_query_params,
+            },
+            ZenHttpHandler::Replay { path } => HttpHandlerConfig::Replay { path },
+        };
+
+        config
+            .into_http_handler()
+            .map_err(|e| ZenError::ValidationError(e.to_string()))
+    }
+}
@@ -5,5 +5,6 @@ mod decision;
 mod engine;
 mod error;
 mod expression;
+mod http_handler;
 mod loader;
 mod types;
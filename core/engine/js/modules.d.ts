@@ -0,0 +1,53 @@
+type BinaryLike = string | Uint8Array | ArrayBuffer;
+type DigestEncoding = 'hex' | 'base64' | 'base64url';
+
+declare module 'crypto' {
+  export function sha1(data: BinaryLike, encoding?: DigestEncoding): string;
+  export function sha256(data: BinaryLike, encoding?: DigestEncoding): string;
+  export function sha512(data: BinaryLike, encoding?: DigestEncoding): string;
+  export function hmac(
+    algorithm: 'sha1' | 'sha256' | 'sha512',
+    key: BinaryLike,
+    data: BinaryLike,
+    encoding?: DigestEncoding,
+  ): string;
+  export function timingSafeEqual(a: BinaryLike, b: BinaryLike): boolean;
+  export function randomBytes(length: number): Uint8Array;
+  export function uuidV4(): string;
+  export function uuidV7(): string;
+
+  const crypto: {
+    sha1: typeof sha1;
+    sha256: typeof sha256;
+    sha512: typeof sha512;
+    hmac: typeof hmac;
+    timingSafeEqual: typeof timingSafeEqual;
+    randomBytes: typeof randomBytes;
+    uuidV4: typeof uuidV4;
+    uuidV7: typeof uuidV7;
+  };
+  export default crypto;
+}
+
+declare module 'encoding' {
+  export function encodeBase64(data: BinaryLike): string;
+  export function decodeBase64(text: string): Uint8Array;
+  export function encodeBase64Url(data: BinaryLike): string;
+  export function decodeBase64Url(text: string): Uint8Array;
+  export function encodeHex(data: BinaryLike): string;
+  export function decodeHex(text: string): Uint8Array;
+  export function encodeUtf8(text: string): Uint8Array;
+  export function decodeUtf8(data: Uint8Array | ArrayBuffer): string;
+
+  const encoding: {
+    encodeBase64: typeof encodeBase64;
+    decodeBase64: typeof decodeBase64;
+    encodeBase64Url: typeof encodeBase64Url;
+    decodeBase64Url: typeof decodeBase64Url;
+    encodeHex: typeof encodeHex;
+    decodeHex: typeof decodeHex;
+    encodeUtf8: typeof encodeUtf8;
+    decodeUtf8: typeof decodeUtf8;
+  };
+  export default encoding;
+}
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use zen_expression::vm::InterruptHandler;
+
+/// Cooperative cancellation flag shared between the caller and running evaluations
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptReason {
+    Cancelled,
+    DeadlineExceeded,
+}
+
+impl Display for InterruptReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterruptReason::Cancelled => write!(f, "Evaluation cancelled"),
+            InterruptReason::DeadlineExceeded => write!(f, "Evaluation deadline exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for InterruptReason {}
+
+/// Deadline and cancellation token of a single evaluation, shared with nested sub-decisions
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EvaluationLimits {
+    deadline: Option<Instant>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl EvaluationLimits {
+    pub fn new(deadline: Option<Instant>, cancellation: Option<CancellationToken>) -> Self {
+        Self {
+            deadline,
+            cancellation,
+        }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.deadline.is_none() && self.cancellation.is_none()
+    }
+
+    pub fn check(&self) -> Result<(), InterruptReason> {
+        if self.cancellation.as_ref().is_some_and(|c| c.is_cancelled()) {
+            return Err(InterruptReason::Cancelled);
+        }
+
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(InterruptReason::DeadlineExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Handler for the expression VM, `None` when there is nothing to check
+    pub fn interrupt_handler(&self) -> Option<InterruptHandler> {
+        if self.is_unbounded() {
+            return None;
+        }
+
+        let limits = self.clone();
+        Some(Rc::new(move || limits.check().is_err()))
+    }
+}
@@ -6,6 +6,8 @@ pub struct ZenConfig {
     pub nodes_in_context: AtomicBool,
     pub function_timeout_millis: AtomicU64,
     pub http_auth: AtomicBool,
+    pub wasm_fuel: AtomicU64,
+    pub wasm_max_memory_bytes: AtomicU64,
 }
 
 impl Default for ZenConfig {
@@ -14,6 +16,8 @@ impl Default for ZenConfig {
             nodes_in_context: AtomicBool::new(true),
             function_timeout_millis: AtomicU64::new(5_000),
             http_auth: AtomicBool::new(true),
+            wasm_fuel: AtomicU64::new(10_000_000),
+            wasm_max_memory_bytes: AtomicU64::new(16 * 1024 * 1024),
         }
     }
 }
@@ -1,15 +1,22 @@
 use crate::decision_graph::graph::{DecisionGraph, DecisionGraphConfig, DecisionGraphResponse};
-use crate::engine::{EvaluationOptions, EvaluationSerializedOptions, EvaluationTraceKind};
+use crate::engine::{EvaluationOptions, EvaluationSerializedOptions};
+use crate::expression_context::ExpressionContext;
 use crate::loader::{DynamicLoader, NoopLoader};
 use crate::model::GraphContent;
 use crate::nodes::custom::{DynamicCustomNode, NoopCustomNode};
+use crate::nodes::decision::memo::Memoization;
 use crate::nodes::function::http_handler::DynamicHttpHandler;
+use crate::nodes::function::pool::{FunctionPool, FunctionRuntimeCell};
+use crate::nodes::wasm::WasmRuntime;
 use crate::nodes::NodeHandlerExtensions;
+use crate::observer::{observe_loader, DynamicEvaluationObserver};
+use crate::policy::resolver::DynamicPropertyResolver;
 use crate::{DecisionGraphValidationError, EvaluationError};
 use serde_json::Value;
 use std::cell::OnceCell;
 use std::sync::Arc;
 use zen_expression::variable::Variable;
+use zen_expression::vm::{CalendarRegistry, NumericContext};
 
 /// Represents a JDM decision which can be evaluated
 #[derive(Debug, Clone)]
@@ -18,6 +25,12 @@ pub struct Decision {
     loader: DynamicLoader,
     adapter: DynamicCustomNode,
     http_handler: DynamicHttpHandler,
+    observer: DynamicEvaluationObserver,
+    property_resolver: DynamicPropertyResolver,
+    expression_context: ExpressionContext,
+    function_pool: Option<FunctionPool>,
+    wasm_runtime: Option<WasmRuntime>,
+    memoization: Memoization,
 }
 
 impl From<GraphContent> for Decision {
@@ -27,6 +40,12 @@ impl From<GraphContent> for Decision {
             loader: Arc::new(NoopLoader::default()),
             adapter: Arc::new(NoopCustomNode::default()),
             http_handler: None,
+            observer: None,
+            property_resolver: None,
+            expression_context: Default::default(),
+            function_pool: None,
+            wasm_runtime: None,
+            memoization: Memoization::Disabled,
         }
     }
 }
@@ -38,6 +57,12 @@ impl From<Arc<GraphContent>> for Decision {
             loader: Arc::new(NoopLoader::default()),
             adapter: Arc::new(NoopCustomNode::default()),
             http_handler: None,
+            observer: None,
+            property_resolver: None,
+            expression_context: Default::default(),
+            function_pool: None,
+            wasm_runtime: None,
+            memoization: Memoization::Disabled,
         }
     }
 }
@@ -58,6 +83,53 @@ impl Decision {
         self
     }
 
+    pub fn with_observer(mut self, observer: DynamicEvaluationObserver) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Resolver used by decision nodes and `zen.evaluate` when they call policy documents
+    pub fn with_property_resolver(mut self, property_resolver: DynamicPropertyResolver) -> Self {
+        self.property_resolver = property_resolver;
+        self
+    }
+
+    /// Calendars available to business-day date methods in expressions
+    pub fn with_calendars(mut self, calendars: Option<Arc<CalendarRegistry>>) -> Self {
+        self.expression_context.calendars = calendars;
+        self
+    }
+
+    /// Scale and rounding applied to divisions in expressions
+    pub fn with_numeric_context(mut self, numeric: NumericContext) -> Self {
+        self.expression_context.numeric = numeric;
+        self
+    }
+
+    /// Coerces policy input against data models before it is validated
+    pub fn with_input_coercion(mut self, coerce_input: bool) -> Self {
+        self.expression_context.coerce_input = coerce_input;
+        self
+    }
+
+    /// Reuses warm function node runtimes across evaluations, see [`FunctionPool`]
+    pub fn with_function_pool(mut self, function_pool: Option<FunctionPool>) -> Self {
+        self.function_pool = function_pool;
+        self
+    }
+
+    /// Shares compiled WebAssembly modules with other decisions of the same engine
+    pub fn with_wasm_runtime(mut self, wasm_runtime: WasmRuntime) -> Self {
+        self.wasm_runtime = Some(wasm_runtime);
+        self
+    }
+
+    /// Reuses results of sub-decisions called again with the same input, see [`Memoization`]
+    pub fn with_memoization(mut self, memoization: Memoization) -> Self {
+        self.memoization = memoization;
+        self
+    }
+
     /// Evaluates a decision using an in-memory reference stored in struct
     pub async fn evaluate(
         &self,
@@ -76,16 +148,23 @@ impl Decision {
             content: self.content.clone(),
             max_depth: options.max_depth,
             trace: options.trace,
+            concurrent: options.concurrent,
             iteration: 0,
             extensions: NodeHandlerExtensions {
-                loader: self.loader.clone(),
+                loader: observe_loader(self.loader.clone(), &self.observer),
                 custom_node: self.adapter.clone(),
                 http_handler: self.http_handler.clone(),
                 compiled_cache: self.content.compiled_cache.clone(),
                 dt_indexes: self.content.dt_indexes.clone(),
                 stripped_functions: self.content.stripped_functions.clone(),
                 validator_cache: Arc::new(OnceCell::from(self.content.validator_cache.clone())),
-                ..Default::default()
+                limits: options.limits(),
+                observer: self.observer.clone(),
+                property_resolver: self.property_resolver.clone(),
+                expression_context: self.expression_context.clone(),
+                function_runtime: FunctionRuntimeCell::shared(self.function_pool.clone()),
+                wasm_runtime: self.shared_wasm_runtime(),
+                memo: self.memoization.evaluation_memo(),
             },
         })?;
 
@@ -94,19 +173,21 @@ impl Decision {
         Ok(response)
     }
 
+    fn shared_wasm_runtime(&self) -> Arc<OnceCell<WasmRuntime>> {
+        let cell = Arc::<OnceCell<WasmRuntime>>::default();
+        if let Some(wasm_runtime) = &self.wasm_runtime {
+            let _ = cell.set(wasm_runtime.clone());
+        }
+        cell
+    }
+
     pub async fn evaluate_serialized(
         &self,
         context: Variable,
         options: EvaluationSerializedOptions,
     ) -> Result<Value, Value> {
         let response = self
-            .evaluate_with_opts(
-                context,
-                EvaluationOptions {
-                    trace: options.trace != EvaluationTraceKind::None,
-                    max_depth: options.max_depth,
-                },
-            )
+            .evaluate_with_opts(context, options.evaluation_options())
             .await;
 
         match response {
@@ -124,6 +205,7 @@ impl Decision {
             content: self.content.clone(),
             max_depth: 1,
             trace: false,
+            concurrent: false,
             iteration: 0,
             extensions: Default::default(),
         })?;
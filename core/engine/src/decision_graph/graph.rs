@@ -1,9 +1,10 @@
+use crate::cancellation::{EvaluationLimits, InterruptReason};
 use crate::decision_graph::cleaner::VariableCleaner;
 use crate::decision_graph::schema_dict;
 use crate::decision_graph::tracer::NodeTracer;
 use crate::decision_graph::walker::{GraphWalker, NodeData, StableDiDecisionGraph};
 use crate::engine::EvaluationTraceKind;
-use crate::model::{DecisionNodeKind, GraphContent};
+use crate::model::{DecisionNodeKind, GraphContent, WasmNodeContent};
 use crate::nodes::custom::CustomNodeHandler;
 use crate::nodes::decision::DecisionNodeHandler;
 use crate::nodes::decision_table::DecisionTableNodeHandler;
@@ -12,20 +13,26 @@ use crate::nodes::function::FunctionNodeHandler;
 use crate::nodes::input::InputNodeHandler;
 use crate::nodes::output::OutputNodeHandler;
 use crate::nodes::transform_attributes::TransformAttributesExecution;
+use crate::nodes::wasm::WasmNodeHandler;
 use crate::nodes::{
-    NodeContext, NodeContextBase, NodeContextConfig, NodeDataType, NodeHandler,
+    NodeContext, NodeContextBase, NodeContextConfig, NodeDataType, NodeError, NodeHandler,
     NodeHandlerExtensions, NodeResponse, NodeResult, TraceDataType,
 };
+use crate::observer::{EvaluationEnd, EvaluationStart, NodeEnd, NodeStart};
 use crate::{DecisionGraphTrace, DecisionGraphValidationError, EvaluationError};
 use ahash::{HashMap, HashMapExt};
-use petgraph::algo::is_cyclic_directed;
+use futures::stream::{FuturesUnordered, StreamExt};
+use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::matrix_graph::Zero;
+use petgraph::prelude::NodeIndex;
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
 use std::cell::RefCell;
+use std::future::Future;
 use std::ops::Deref;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use zen_expression::variable::{ToVariable, Variable};
 use zen_types::decision::{DecisionNode, InputNodeContent, OutputNodeContent};
 
@@ -41,6 +48,7 @@ pub struct DecisionGraph {
 pub struct DecisionGraphConfig {
     pub content: Arc<GraphContent>,
     pub trace: bool,
+    pub concurrent: bool,
     pub iteration: u8,
     pub max_depth: u8,
     pub extensions: NodeHandlerExtensions,
@@ -112,6 +120,206 @@ impl DecisionGraph {
         Ok(())
     }
 
+    pub async fn evaluate(
+        &mut self,
+        context: Variable,
+    ) -> Result<DecisionGraphResponse, Box<EvaluationError>> {
+        let depth = self.config.iteration;
+        let observer = self.config.extensions.observer.clone();
+        if let Some(observer) = &observer {
+            observer.on_evaluation_start(&EvaluationStart { depth });
+        }
+
+        let start = Instant::now();
+        // Boxed to keep the stack of nested sub-decisions shallow
+        let evaluation = Box::pin(self.evaluate_graph(context));
+        #[cfg(feature = "tracing")]
+        let evaluation = tracing::Instrument::instrument(
+            evaluation,
+            tracing::debug_span!("zen.evaluate", depth),
+        );
+
+        let response = evaluation.await;
+        if let Some(observer) = &observer {
+            observer.on_evaluation_end(&EvaluationEnd {
+                depth,
+                duration: start.elapsed(),
+                error: response.as_ref().err().map(|err| err.as_ref()),
+            });
+        }
+
+        response
+    }
+
+    async fn evaluate_graph(
+        &mut self,
+        context: Variable,
+    ) -> Result<DecisionGraphResponse, Box<EvaluationError>> {
+        let root_start = Instant::now();
+
+        self.validate()?;
+        check_depth(self.config.iteration, self.config.max_depth)?;
+
+        let mut walker = GraphWalker::new(&self.graph)
+            .with_expression_context(self.config.extensions.expression_context.clone());
+        let mut tracer = NodeTracer::new(self.config.trace);
+        let limits = self.config.extensions.limits.clone();
+
+        if self.config.concurrent {
+            let config = &self.config;
+            let graph = &mut self.graph;
+            let parent_nodes = self.parent_nodes.as_ref();
+            let context = &context;
+            if config.trace {
+                if let Ok(order) = toposort(&*graph, None) {
+                    tracer.rank_by(order.into_iter().map(|nid| graph[nid].id.clone()));
+                }
+            }
+
+            // Each node starts as soon as its own dependencies complete
+            let mut running = FuturesUnordered::new();
+            let mut in_flight: Vec<NodeIndex> = Vec::new();
+            loop {
+                let ready = walker.next_batch(graph, tracer.trace_callback(), &in_flight);
+                if !ready.is_empty() {
+                    if let Err(reason) = limits.check() {
+                        return Err(interrupted(tracer, reason));
+                    }
+                }
+
+                for nid in ready {
+                    let node = graph[nid].clone();
+                    let (input, input_trace) = walker.incoming_node_data(graph, nid);
+                    let nodes = walker.nodes_context();
+                    in_flight.push(nid);
+                    running.push(async move {
+                        let execution = config
+                            .execute_node(node, input, input_trace, nodes, context)
+                            .await;
+                        (nid, execution)
+                    });
+                }
+
+                let Some((nid, execution)) = running.next().await else {
+                    break;
+                };
+                in_flight.retain(|&running| running != nid);
+                match Self::complete_node(
+                    &graph[nid],
+                    parent_nodes,
+                    &mut walker,
+                    &mut tracer,
+                    nid,
+                    execution,
+                ) {
+                    Ok(false) => {}
+                    // Nodes still running are dropped, nothing they have already sent is undone
+                    Ok(true) => break,
+                    Err(err) => return Err(node_error(tracer, err, &limits)),
+                }
+            }
+        } else {
+            while let Some(nid) = walker.next(&mut self.graph, tracer.trace_callback()) {
+                if let Some(_) = walker.get_node_data(nid) {
+                    continue;
+                }
+
+                if let Err(reason) = limits.check() {
+                    return Err(interrupted(tracer, reason));
+                }
+
+                let node = self.graph[nid].clone();
+                let (input, input_trace) = walker.incoming_node_data(&self.graph, nid);
+                let execution = self
+                    .config
+                    .execute_node(
+                        node.clone(),
+                        input,
+                        input_trace,
+                        walker.nodes_context(),
+                        &context,
+                    )
+                    .await;
+                match Self::complete_node(
+                    &node,
+                    self.parent_nodes.as_ref(),
+                    &mut walker,
+                    &mut tracer,
+                    nid,
+                    execution,
+                ) {
+                    Ok(false) => {}
+                    Ok(true) => break,
+                    Err(err) => return Err(node_error(tracer, err, &limits)),
+                }
+            }
+        }
+
+        let result = walker.ending_variables(&self.graph);
+        let trace = tracer.into_traces();
+
+        if self.config.iteration.is_zero() {
+            let mut cleaner = VariableCleaner::new();
+            cleaner.clean(&result);
+            if let Some(t) = &trace {
+                t.values().for_each(|v| {
+                    cleaner.clean(&v.input);
+                    cleaner.clean(&v.output);
+                    if let Some(td) = &v.trace_data {
+                        cleaner.clean(td);
+                    }
+                })
+            }
+        }
+
+        Ok(DecisionGraphResponse {
+            performance: format!("{:.1?}", root_start.elapsed()),
+            result,
+            trace: trace.map(EvaluationTrace::Graph),
+        })
+    }
+
+    /// Records the node result, returns `true` once the walk should terminate
+    fn complete_node(
+        node: &DecisionNode,
+        parent_nodes: Option<&Variable>,
+        walker: &mut GraphWalker,
+        tracer: &mut NodeTracer,
+        nid: NodeIndex,
+        execution: NodeExecution,
+    ) -> Result<bool, NodeError> {
+        tracer.record_execution(
+            node,
+            execution.input_trace,
+            &execution.result,
+            execution.duration,
+        );
+
+        let output = execution.result?.output;
+        let nodes_view = match (&node.kind, parent_nodes) {
+            (DecisionNodeKind::InputNode { .. }, Some(parent_nodes)) => {
+                let view = output.depth_clone(1);
+                view.dot_insert(Variable::nodes_key().as_ref(), parent_nodes.clone());
+                Some(view)
+            }
+            _ => None,
+        };
+
+        walker.set_node_data(
+            nid,
+            NodeData {
+                name: zen_types::symbol::Symbol::from(node.name.deref()),
+                data: output,
+                nodes_view,
+            },
+        );
+
+        // Terminate once Output node is reached
+        Ok(matches!(node.kind, DecisionNodeKind::OutputNode { .. }))
+    }
+}
+
+impl DecisionGraphConfig {
     async fn validation_schema(
         &self,
         node_id: &str,
@@ -120,18 +328,16 @@ impl DecisionGraph {
         let Some(schema) = schema else {
             return Ok(None);
         };
-        if let Some(resolved) = &self.config.content.resolved_schemas {
+        if let Some(resolved) = &self.content.resolved_schemas {
             return Ok(resolved.get(node_id).cloned());
         }
         if !schema_dict::schema_references_dictionary(schema) {
             return Ok(None);
         }
 
-        let dictionaries = schema_dict::load_import_dictionaries(
-            self.config.extensions.loader(),
-            &self.config.content.imports,
-        )
-        .await?;
+        let dictionaries =
+            schema_dict::load_import_dictionaries(self.extensions.loader(), &self.content.imports)
+                .await?;
         schema_dict::resolve_schema(schema, &dictionaries)
             .map(|resolved| Some((Arc::new(resolved.0), resolved.1)))
     }
@@ -147,176 +353,203 @@ impl DecisionGraph {
             name: node.name.clone(),
             input,
             nodes,
-            extensions: self.config.extensions.clone(),
-            iteration: self.config.iteration,
-            trace: match self.config.trace {
+            extensions: self.extensions.clone(),
+            iteration: self.iteration,
+            trace: match self.trace {
                 true => Some(RefCell::new(Variable::Null)),
                 false => None,
             },
             config: NodeContextConfig {
-                max_depth: self.config.max_depth,
-                trace: self.config.trace,
+                max_depth: self.max_depth,
+                trace: self.trace,
+                concurrent: self.concurrent,
                 ..Default::default()
             },
         }
     }
 
-    pub async fn evaluate(
-        &mut self,
-        context: Variable,
-    ) -> Result<DecisionGraphResponse, Box<EvaluationError>> {
-        let root_start = Instant::now();
-
-        self.validate()?;
-        if self.config.iteration >= self.config.max_depth {
-            return Err(Box::new(EvaluationError::DepthLimitExceeded));
+    async fn execute_node(
+        &self,
+        node: Arc<DecisionNode>,
+        input: Variable,
+        input_trace: Variable,
+        nodes: Option<Variable>,
+        context: &Variable,
+    ) -> NodeExecution {
+        let depth = self.iteration;
+        let observer = &self.extensions.observer;
+        if let Some(observer) = observer {
+            observer.on_node_start(&NodeStart {
+                id: &node.id,
+                name: &node.name,
+                kind: node.kind.type_name(),
+                depth,
+            });
         }
 
-        let mut walker = GraphWalker::new(&self.graph);
-        let mut tracer = NodeTracer::new(self.config.trace);
+        let start = (self.trace || observer.is_some()).then(Instant::now);
+        let base_ctx = self.build_node_context(node.deref(), input, nodes);
+
+        let run = self.run_node(&node, base_ctx, &input_trace, context);
+        #[cfg(feature = "tracing")]
+        let run = tracing::Instrument::instrument(
+            run,
+            tracing::debug_span!(
+                "zen.node",
+                id = node.id.deref(),
+                kind = node.kind.type_name(),
+                depth
+            ),
+        );
+
+        let result = run.await;
+        let duration = start.map(|s| s.elapsed()).unwrap_or_default();
+        if let Some(observer) = observer {
+            observer.on_node_end(&NodeEnd {
+                id: &node.id,
+                name: &node.name,
+                kind: node.kind.type_name(),
+                depth,
+                duration,
+                error: result.as_ref().err().map(|err| err.source.as_ref()),
+            });
+        }
 
-        while let Some(nid) = walker.next(&mut self.graph, tracer.trace_callback()) {
-            if let Some(_) = walker.get_node_data(nid) {
-                continue;
-            }
+        NodeExecution {
+            input_trace,
+            result,
+            duration,
+        }
+    }
 
-            let node = &self.graph[nid];
-            let start = self.config.trace.then(Instant::now);
-            let (input, input_trace) = walker.incoming_node_data(&self.graph, nid);
-            let mut base_ctx = self.build_node_context(node.deref(), input, walker.nodes_context());
-
-            let node_execution = match &node.kind {
-                DecisionNodeKind::InputNode { content } => {
-                    base_ctx.input = context.clone();
-                    match self
-                        .validation_schema(&node.id, content.schema.as_deref())
-                        .await
-                    {
-                        Err(message) => base_ctx.error(message),
-                        Ok(None) => handle_node(base_ctx, content.clone(), InputNodeHandler).await,
-                        Ok(Some((schema, salt))) => {
-                            base_ctx.config.validation_salt = salt;
-                            let resolved = InputNodeContent {
-                                schema: Some(schema),
-                            };
-                            handle_node(base_ctx, resolved, InputNodeHandler).await
-                        }
-                    }
-                }
-                DecisionNodeKind::OutputNode { content } => {
-                    match self
-                        .validation_schema(&node.id, content.schema.as_deref())
-                        .await
-                    {
-                        Err(message) => base_ctx.error(message),
-                        Ok(None) => handle_node(base_ctx, content.clone(), OutputNodeHandler).await,
-                        Ok(Some((schema, salt))) => {
-                            base_ctx.config.validation_salt = salt;
-                            let resolved = OutputNodeContent {
-                                schema: Some(schema),
-                            };
-                            handle_node(base_ctx, resolved, OutputNodeHandler).await
-                        }
+    async fn run_node(
+        &self,
+        node: &DecisionNode,
+        mut base_ctx: NodeContextBase,
+        input_trace: &Variable,
+        context: &Variable,
+    ) -> NodeResult {
+        match &node.kind {
+            DecisionNodeKind::InputNode { content } => {
+                base_ctx.input = context.clone();
+                match self
+                    .validation_schema(&node.id, content.schema.as_deref())
+                    .await
+                {
+                    Err(message) => base_ctx.error(message),
+                    Ok(None) => handle_node(base_ctx, content.clone(), InputNodeHandler).await,
+                    Ok(Some((schema, salt))) => {
+                        base_ctx.config.validation_salt = salt;
+                        let resolved = InputNodeContent {
+                            schema: Some(schema),
+                            ..content.clone()
+                        };
+                        handle_node(base_ctx, resolved, InputNodeHandler).await
                     }
                 }
-                DecisionNodeKind::SwitchNode { .. } => Ok(NodeResponse {
-                    output: input_trace.clone(),
-                    trace_data: None,
-                }),
-                DecisionNodeKind::FunctionNode { content } => {
-                    handle_node(base_ctx, content.clone(), FunctionNodeHandler).await
-                }
-                DecisionNodeKind::DecisionNode { content } => {
-                    handle_node(base_ctx, content.clone(), DecisionNodeHandler::default()).await
-                }
-                DecisionNodeKind::DecisionTableNode { content } => {
-                    handle_node(base_ctx, content.clone(), DecisionTableNodeHandler).await
-                }
-                DecisionNodeKind::ExpressionNode { content } => {
-                    handle_node(base_ctx, content.clone(), ExpressionNodeHandler).await
-                }
-                DecisionNodeKind::CustomNode { content } => {
-                    handle_node(base_ctx, content.clone(), CustomNodeHandler).await
-                }
-            };
-
-            tracer.record_execution(
-                node.deref(),
-                input_trace,
-                &node_execution,
-                start.map(|s| s.elapsed()).unwrap_or_default(),
-            );
-
-            let output = match node_execution {
-                Ok(ok) => ok.output,
-                Err(err) => {
-                    let trace = tracer.into_traces();
-                    if let Some(t) = &trace {
-                        let mut cleaner = VariableCleaner::new();
-                        t.values().for_each(|v| {
-                            cleaner.clean(&v.input);
-                            cleaner.clean(&v.output);
-                            if let Some(td) = &v.trace_data {
-                                cleaner.clean(td);
-                            }
-                        })
+            }
+            DecisionNodeKind::OutputNode { content } => {
+                match self
+                    .validation_schema(&node.id, content.schema.as_deref())
+                    .await
+                {
+                    Err(message) => base_ctx.error(message),
+                    Ok(None) => handle_node(base_ctx, content.clone(), OutputNodeHandler).await,
+                    Ok(Some((schema, salt))) => {
+                        base_ctx.config.validation_salt = salt;
+                        let resolved = OutputNodeContent {
+                            schema: Some(schema),
+                        };
+                        handle_node(base_ctx, resolved, OutputNodeHandler).await
                     }
-
-                    return Err(Box::new(EvaluationError::NodeError {
-                        node_id: err.node_id,
-                        source: err.source,
-                        trace: trace.map(|t| t.to_variable()),
-                    }));
-                }
-            };
-
-            let nodes_view = match (&node.kind, &self.parent_nodes) {
-                (DecisionNodeKind::InputNode { .. }, Some(parent_nodes)) => {
-                    let view = output.depth_clone(1);
-                    view.dot_insert(Variable::nodes_key().as_ref(), parent_nodes.clone());
-                    Some(view)
                 }
-                _ => None,
-            };
-
-            walker.set_node_data(
-                nid,
-                NodeData {
-                    name: zen_types::symbol::Symbol::from(node.name.deref()),
-                    data: output,
-                    nodes_view,
-                },
-            );
-
-            // Terminate once Output node is reached
-            if matches!(node.kind, DecisionNodeKind::OutputNode { .. }) {
-                break;
+            }
+            DecisionNodeKind::SwitchNode { .. } => Ok(NodeResponse {
+                output: input_trace.clone(),
+                trace_data: None,
+            }),
+            DecisionNodeKind::FunctionNode { content } => {
+                handle_node(base_ctx, content.clone(), FunctionNodeHandler).await
+            }
+            DecisionNodeKind::DecisionNode { content } => {
+                handle_node(base_ctx, content.clone(), DecisionNodeHandler::default()).await
+            }
+            DecisionNodeKind::DecisionTableNode { content } => {
+                handle_node(base_ctx, content.clone(), DecisionTableNodeHandler).await
+            }
+            DecisionNodeKind::ExpressionNode { content } => {
+                handle_node(base_ctx, content.clone(), ExpressionNodeHandler).await
+            }
+            DecisionNodeKind::CustomNode { content } => {
+                handle_node(base_ctx, content.clone(), CustomNodeHandler).await
+            }
+            DecisionNodeKind::WasmNode { content } => {
+                handle_wasm_node(base_ctx, content.clone()).await
             }
         }
+    }
+}
 
-        let result = walker.ending_variables(&self.graph);
-        let trace = tracer.into_traces();
+struct NodeExecution {
+    input_trace: Variable,
+    result: NodeResult,
+    duration: Duration,
+}
 
-        if self.config.iteration.is_zero() {
-            let mut cleaner = VariableCleaner::new();
-            cleaner.clean(&result);
-            if let Some(t) = &trace {
-                t.values().for_each(|v| {
-                    cleaner.clean(&v.input);
-                    cleaner.clean(&v.output);
-                    if let Some(td) = &v.trace_data {
-                        cleaner.clean(td);
-                    }
-                })
+fn collect_trace(tracer: NodeTracer) -> Option<Variable> {
+    let trace = tracer.into_traces();
+    if let Some(t) = &trace {
+        let mut cleaner = VariableCleaner::new();
+        t.values().for_each(|v| {
+            cleaner.clean(&v.input);
+            cleaner.clean(&v.output);
+            if let Some(td) = &v.trace_data {
+                cleaner.clean(td);
             }
-        }
-
-        Ok(DecisionGraphResponse {
-            performance: format!("{:.1?}", root_start.elapsed()),
-            result,
-            trace: trace.map(EvaluationTrace::Graph),
         })
     }
+
+    trace.map(|t| t.to_variable())
+}
+
+fn node_error(
+    tracer: NodeTracer,
+    err: NodeError,
+    limits: &EvaluationLimits,
+) -> Box<EvaluationError> {
+    let trace = collect_trace(tracer);
+    // Nodes aborted by an interrupt fail with whichever error surfaced first
+    if let Err(reason) = limits.check() {
+        return Box::new(EvaluationError::interrupted(
+            reason,
+            Some(err.node_id),
+            trace,
+        ));
+    }
+
+    Box::new(EvaluationError::NodeError {
+        node_id: err.node_id,
+        source: err.source,
+        trace,
+    })
+}
+
+fn interrupted(tracer: NodeTracer, reason: InterruptReason) -> Box<EvaluationError> {
+    Box::new(EvaluationError::interrupted(
+        reason,
+        None,
+        collect_trace(tracer),
+    ))
+}
+
+/// Depth accounting shared by nested graphs and policies, `iteration` is the depth of the
+/// evaluation about to start
+pub(crate) fn check_depth(iteration: u8, max_depth: u8) -> Result<(), Box<EvaluationError>> {
+    if iteration >= max_depth {
+        return Err(Box::new(EvaluationError::DepthLimitExceeded));
+    }
+
+    Ok(())
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -389,6 +622,14 @@ impl DecisionGraphResponse {
     }
 }
 
+/// Created outside of `run_node` so its large state does not grow the stack of nested decisions
+fn handle_wasm_node(
+    base_ctx: NodeContextBase,
+    content: WasmNodeContent,
+) -> Pin<Box<dyn Future<Output = NodeResult>>> {
+    Box::pin(handle_node(base_ctx, content, WasmNodeHandler))
+}
+
 async fn handle_node<NodeData, TraceData, NodeHandlerType>(
     base_ctx: NodeContextBase,
     content: NodeData,
@@ -402,18 +643,23 @@ where
     let ctx = NodeContext::<NodeData, TraceData>::from_base(base_ctx.clone(), content);
     if let Some(transform_attributes) = handler.transform_attributes(&ctx) {
         return transform_attributes
-            .run_with(base_ctx, move |input, has_more| {
-                let handler = handler.clone();
-                let mut new_ctx = ctx.clone();
-                new_ctx.input = input;
-
-                async move {
-                    match has_more {
-                        false => handler.handle(new_ctx).await,
-                        true => {
-                            let result = handler.handle(new_ctx.clone()).await;
-                            handler.after_transform_attributes(&new_ctx).await?;
-                            result
+            .run_with(base_ctx, || {
+                let handler = handler.fork();
+                let ctx = ctx.clone();
+
+                move |input, has_more| {
+                    let handler = handler.clone();
+                    let mut new_ctx = ctx.clone();
+                    new_ctx.input = input;
+
+                    async move {
+                        match has_more {
+                            false => handler.handle(new_ctx).await,
+                            true => {
+                                let result = handler.handle(new_ctx.clone()).await;
+                                handler.after_transform_attributes(&new_ctx).await?;
+                                result
+                            }
                         }
                     }
                 }
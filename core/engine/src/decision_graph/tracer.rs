@@ -6,11 +6,20 @@ use zen_expression::variable::ToVariable;
 use zen_types::decision::{DecisionNode, DecisionNodeKind};
 use zen_types::variable::Variable;
 
-pub(crate) struct NodeTracer(Option<HashMap<Arc<str>, DecisionGraphTrace>>);
+pub(crate) struct NodeTracer(
+    Option<HashMap<Arc<str>, DecisionGraphTrace>>,
+    Option<HashMap<Arc<str>, usize>>,
+);
 
 impl NodeTracer {
     pub fn new(enabled: bool) -> Self {
-        Self(enabled.then(|| HashMap::default()))
+        Self(enabled.then(|| HashMap::default()), None)
+    }
+
+    /// Orders traces by the position of their node in `ids` instead of by completion, which
+    /// varies between runs once nodes execute concurrently
+    pub fn rank_by(&mut self, ids: impl Iterator<Item = Arc<str>>) {
+        self.1 = Some(ids.enumerate().map(|(rank, id)| (id, rank)).collect());
     }
 
     pub fn record_execution(
@@ -70,7 +79,17 @@ impl NodeTracer {
     }
 
     pub fn into_traces(self) -> Option<HashMap<Arc<str>, DecisionGraphTrace>> {
-        self.0
+        let mut traces = self.0?;
+        let Some(ranks) = self.1 else {
+            return Some(traces);
+        };
+
+        let mut ordered: Vec<&mut DecisionGraphTrace> = traces.values_mut().collect();
+        ordered.sort_by_key(|trace| (ranks.get(&trace.id).copied(), trace.order));
+        for (order, trace) in ordered.into_iter().enumerate() {
+            trace.order = order as u32;
+        }
+        Some(traces)
     }
 }
 
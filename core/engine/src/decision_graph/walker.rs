@@ -11,6 +11,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use crate::config::ZEN_CONFIG;
+use crate::expression_context::ExpressionContext;
 use crate::model::{
     DecisionEdge, DecisionNode, DecisionNodeKind, SwitchStatement, SwitchStatementHitPolicy,
 };
@@ -31,7 +32,9 @@ pub(crate) struct GraphWalker {
     node_data: HashMap<NodeIndex, NodeData>,
     ordered: FixedBitSet,
     to_visit: Vec<NodeIndex>,
+    deferred: Vec<NodeIndex>,
     visited_switch_nodes: Vec<NodeIndex>,
+    expression_context: ExpressionContext,
 
     nodes_in_context: bool,
 }
@@ -45,6 +48,12 @@ impl GraphWalker {
         walker
     }
 
+    /// Calendars and numeric settings applied to switch node conditions
+    pub(crate) fn with_expression_context(mut self, expression_context: ExpressionContext) -> Self {
+        self.expression_context = expression_context;
+        self
+    }
+
     fn initialize_input_nodes(&mut self, g: &StableDiDecisionGraph) {
         // find all initial nodes (nodes without incoming edges)
         self.to_visit
@@ -58,8 +67,10 @@ impl GraphWalker {
         Self {
             ordered: graph.visit_map(),
             to_visit: Vec::new(),
+            deferred: Vec::new(),
             node_data: Default::default(),
             visited_switch_nodes: Default::default(),
+            expression_context: Default::default(),
             iter: 0,
 
             nodes_in_context: ZEN_CONFIG.nodes_in_context.load(Ordering::Relaxed),
@@ -69,6 +80,7 @@ impl GraphWalker {
     pub fn reset(&mut self, g: &StableDiDecisionGraph) {
         self.ordered.clear();
         self.to_visit.clear();
+        self.deferred.clear();
         self.initialize_input_nodes(g);
 
         self.iter += 1;
@@ -141,9 +153,42 @@ impl GraphWalker {
     }
 
     pub fn next<F: FnMut(DecisionGraphTrace)>(
+        &mut self,
+        g: &mut StableDiDecisionGraph,
+        on_trace: Option<F>,
+    ) -> Option<NodeIndex> {
+        self.advance(g, on_trace, &[])
+    }
+
+    /// Collects every node that can run without waiting on a node that is still running.
+    ///
+    /// `in_flight` are nodes returned earlier that have no data yet, they are never returned
+    /// again, even when a switch node restarts the walk.
+    pub fn next_batch<F: FnMut(DecisionGraphTrace)>(
         &mut self,
         g: &mut StableDiDecisionGraph,
         mut on_trace: Option<F>,
+        in_flight: &[NodeIndex],
+    ) -> Vec<NodeIndex> {
+        self.to_visit.append(&mut self.deferred);
+
+        let mut pending = in_flight.to_vec();
+        let mut batch = Vec::new();
+        while let Some(nid) = self.advance(g, on_trace.as_mut(), &pending) {
+            if !self.node_data.contains_key(&nid) && !pending.contains(&nid) {
+                pending.push(nid);
+                batch.push(nid);
+            }
+        }
+
+        batch
+    }
+
+    fn advance<F: FnMut(DecisionGraphTrace)>(
+        &mut self,
+        g: &mut StableDiDecisionGraph,
+        mut on_trace: Option<F>,
+        in_flight: &[NodeIndex],
     ) -> Option<NodeIndex> {
         let start = Instant::now();
         if self.iter >= ITER_MAX {
@@ -156,19 +201,36 @@ impl GraphWalker {
             }
 
             if !self.all_dependencies_resolved(g, nid) {
-                self.to_visit.push(nid);
-                self.to_visit
-                    .extend(self.get_unresolved_dependencies(g, nid));
+                let unresolved = self.get_unresolved_dependencies(g, nid);
+                // Waiting on a node postponed to the next batch
+                match unresolved.iter().any(|dep| self.deferred.contains(dep)) {
+                    true => self.deferred.push(nid),
+                    false => self.to_visit.push(nid),
+                }
+
+                self.to_visit.extend(
+                    unresolved
+                        .into_iter()
+                        .filter(|dep| !self.deferred.contains(dep)),
+                );
+                continue;
+            }
+
+            let decision_node = g.node_weight(nid)?.clone();
+            if g.neighbors_directed(nid, Incoming)
+                .any(|dep| in_flight.contains(&dep))
+            {
+                self.deferred.push(nid);
                 continue;
             }
 
             self.ordered.visit(nid);
 
-            let decision_node = g.node_weight(nid)?.clone();
             if let DecisionNodeKind::SwitchNode { content } = &decision_node.kind {
                 if !self.visited_switch_nodes.contains(&nid) {
                     let (input, input_trace) = self.incoming_node_data(g, nid);
                     let mut isolate = Isolate::with_environment(input);
+                    self.expression_context.apply(&mut isolate);
                     if let Some(nodes) = self.nodes_context() {
                         isolate.set_local(Variable::nodes_key(), nodes);
                     }
@@ -0,0 +1,324 @@
+use ahash::{HashMap, HashMapExt};
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::dmn::feel::Feel;
+use crate::dmn::{DmnDiagnostic, DmnExport};
+use crate::model::{
+    DecisionNode, DecisionNodeKind, DecisionTableContent, DecisionTableHitPolicy,
+    ExpressionNodeContent, GraphContent, TransformAttributes,
+};
+
+const DMN_NAMESPACE: &str = "https://www.omg.org/spec/DMN/20191111/MODEL/";
+const MODEL_NAMESPACE: &str = "https://gorules.io/dmn";
+
+/// Writes a JDM graph as DMN 1.3 definitions named `name`.
+///
+/// Input nodes become input data, decision table and expression nodes become decisions, and
+/// edges between them become information requirements. Other node kinds are reported and left
+/// out together with their edges.
+pub fn export(graph: &GraphContent, name: &str) -> DmnExport {
+    let mut exporter = Exporter {
+        xml: String::new(),
+        diagnostics: Vec::new(),
+    };
+    exporter.definitions(graph, name);
+    DmnExport {
+        xml: exporter.xml,
+        diagnostics: exporter.diagnostics,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    Input,
+    Decision,
+    Skipped,
+}
+
+struct Exporter {
+    xml: String,
+    diagnostics: Vec<DmnDiagnostic>,
+}
+
+impl Exporter {
+    fn definitions(&mut self, graph: &GraphContent, name: &str) {
+        let roles: HashMap<&str, Role> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_ref(), self.role(node)))
+            .collect();
+        let mut requirements: HashMap<&str, Vec<(&str, Role)>> = HashMap::new();
+        for edge in &graph.edges {
+            let source = roles.get(edge.source_id.as_ref()).copied();
+            if let Some(role @ (Role::Input | Role::Decision)) = source {
+                requirements
+                    .entry(edge.target_id.as_ref())
+                    .or_default()
+                    .push((edge.source_id.as_ref(), role));
+            }
+        }
+
+        self.xml
+            .push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            self.xml,
+            "<definitions xmlns=\"{DMN_NAMESPACE}\" id=\"{}\" name=\"{}\" namespace=\"{MODEL_NAMESPACE}\">",
+            Self::escape(&Self::identifier(name)),
+            Self::escape(name),
+        );
+
+        for node in &graph.nodes {
+            if roles.get(node.id.as_ref()) == Some(&Role::Input) {
+                let _ = writeln!(
+                    self.xml,
+                    "  <inputData id=\"{}\" name=\"{}\"/>",
+                    Self::escape(&node.id),
+                    Self::escape(&node.name),
+                );
+            }
+        }
+        for node in &graph.nodes {
+            if roles.get(node.id.as_ref()) != Some(&Role::Decision) {
+                continue;
+            }
+            let required = requirements
+                .get(node.id.as_ref())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            self.decision(node, required);
+        }
+
+        self.xml.push_str("</definitions>\n");
+    }
+
+    fn role(&mut self, node: &DecisionNode) -> Role {
+        match &node.kind {
+            DecisionNodeKind::InputNode { .. } => Role::Input,
+            DecisionNodeKind::OutputNode { .. } => Role::Skipped,
+            DecisionNodeKind::DecisionTableNode { content } => {
+                self.check_transform(node, &content.transform_attributes);
+                Role::Decision
+            }
+            DecisionNodeKind::ExpressionNode { content } => {
+                self.check_transform(node, &content.transform_attributes);
+                Role::Decision
+            }
+            kind => {
+                self.report(
+                    node,
+                    format!("{} has no DMN equivalent and was skipped", kind.type_name()),
+                );
+                Role::Skipped
+            }
+        }
+    }
+
+    fn check_transform(&mut self, node: &DecisionNode, attributes: &TransformAttributes) {
+        if *attributes != TransformAttributes::default() {
+            self.report(
+                node,
+                "input field, output path, loop and pass-through settings are not exported",
+            );
+        }
+    }
+
+    fn decision(&mut self, node: &DecisionNode, required: &[(&str, Role)]) {
+        let variable = match &node.kind {
+            DecisionNodeKind::DecisionTableNode { content } if content.outputs.len() == 1 => {
+                content.outputs[0].field.clone()
+            }
+            DecisionNodeKind::ExpressionNode { content } if content.expressions.len() == 1 => {
+                content.expressions[0].key.clone()
+            }
+            _ => node.name.clone(),
+        };
+
+        let _ = writeln!(
+            self.xml,
+            "  <decision id=\"{}\" name=\"{}\">",
+            Self::escape(&node.id),
+            Self::escape(&node.name),
+        );
+        let _ = writeln!(
+            self.xml,
+            "    <variable name=\"{}\"/>",
+            Self::escape(&variable)
+        );
+        for (source, role) in required {
+            let element = match role {
+                Role::Input => "requiredInput",
+                _ => "requiredDecision",
+            };
+            let _ = writeln!(
+                self.xml,
+                "    <informationRequirement><{element} href=\"#{}\"/></informationRequirement>",
+                Self::escape(source),
+            );
+        }
+
+        match &node.kind {
+            DecisionNodeKind::DecisionTableNode { content } => self.decision_table(node, content),
+            DecisionNodeKind::ExpressionNode { content } => self.expressions(node, content),
+            _ => {}
+        }
+        self.xml.push_str("  </decision>\n");
+    }
+
+    fn decision_table(&mut self, node: &DecisionNode, table: &DecisionTableContent) {
+        let hit_policy = match table.hit_policy {
+            DecisionTableHitPolicy::First => "FIRST",
+            DecisionTableHitPolicy::Collect => "RULE ORDER",
+        };
+        let _ = writeln!(
+            self.xml,
+            "    <decisionTable id=\"{}-table\" hitPolicy=\"{hit_policy}\">",
+            Self::escape(&node.id),
+        );
+
+        for input in table.inputs.iter() {
+            let field = input.field.as_deref().unwrap_or_default();
+            if field.is_empty() {
+                self.report(
+                    node,
+                    format!(
+                        "input column '{}' has no field, exported as empty",
+                        input.id
+                    ),
+                );
+            }
+            let field = self.feel(node, field, Feel::expression_to_feel);
+            let _ = writeln!(
+                self.xml,
+                "      <input id=\"{}\" label=\"{}\"><inputExpression id=\"{}-expression\"><text>{}</text></inputExpression></input>",
+                Self::escape(&input.id),
+                Self::escape(&input.name),
+                Self::escape(&input.id),
+                Self::escape(&field),
+            );
+        }
+        for output in table.outputs.iter() {
+            let type_ref = output
+                .column_type
+                .as_ref()
+                .map(|t| format!(" typeRef=\"{}\"", Self::escape(t)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                self.xml,
+                "      <output id=\"{}\" name=\"{}\" label=\"{}\"{type_ref}/>",
+                Self::escape(&output.id),
+                Self::escape(&output.field),
+                Self::escape(&output.name),
+            );
+        }
+
+        for (index, rule) in table.rules.iter().enumerate() {
+            let id: Arc<str> = rule
+                .get("_id")
+                .cloned()
+                .unwrap_or_else(|| Arc::from(format!("{}-rule-{index}", node.id)));
+            let _ = writeln!(self.xml, "      <rule id=\"{}\">", Self::escape(&id));
+            if let Some(description) = rule.get("_description").filter(|d| !d.is_empty()) {
+                let _ = writeln!(
+                    self.xml,
+                    "        <description>{}</description>",
+                    Self::escape(description)
+                );
+            }
+            for input in table.inputs.iter() {
+                let text = rule.get(&input.id).map(|t| t.as_ref()).unwrap_or_default();
+                let text = self.feel(node, text, Feel::unary_to_feel);
+                let _ = writeln!(
+                    self.xml,
+                    "        <inputEntry><text>{}</text></inputEntry>",
+                    Self::escape(&text),
+                );
+            }
+            for output in table.outputs.iter() {
+                let text = rule.get(&output.id).map(|t| t.as_ref()).unwrap_or_default();
+                let text = self.feel(node, text, Feel::expression_to_feel);
+                let _ = writeln!(
+                    self.xml,
+                    "        <outputEntry><text>{}</text></outputEntry>",
+                    Self::escape(&text),
+                );
+            }
+            self.xml.push_str("      </rule>\n");
+        }
+        self.xml.push_str("    </decisionTable>\n");
+    }
+
+    fn expressions(&mut self, node: &DecisionNode, content: &ExpressionNodeContent) {
+        if let [expression] = content.expressions.as_slice() {
+            let text = self.feel(node, &expression.value, Feel::expression_to_feel);
+            let _ = writeln!(
+                self.xml,
+                "    <literalExpression id=\"{}\"><text>{}</text></literalExpression>",
+                Self::escape(&expression.id),
+                Self::escape(&text),
+            );
+            return;
+        }
+
+        let _ = writeln!(
+            self.xml,
+            "    <context id=\"{}-context\">",
+            Self::escape(&node.id)
+        );
+        for expression in content.expressions.iter() {
+            let text = self.feel(node, &expression.value, Feel::expression_to_feel);
+            let _ = writeln!(
+                self.xml,
+                "      <contextEntry id=\"{}\"><variable name=\"{}\"/><literalExpression><text>{}</text></literalExpression></contextEntry>",
+                Self::escape(&expression.id),
+                Self::escape(&expression.key),
+                Self::escape(&text),
+            );
+        }
+        self.xml.push_str("    </context>\n");
+    }
+
+    /// Zen `text` translated to FEEL, reported and written unchanged when it has no FEEL form
+    fn feel(
+        &mut self,
+        node: &DecisionNode,
+        text: &str,
+        translate: fn(&str) -> Result<String, String>,
+    ) -> String {
+        translate(text).unwrap_or_else(|reason| {
+            self.report(node, format!("{reason}, exported unchanged: {text}"));
+            text.to_string()
+        })
+    }
+
+    fn report(&mut self, node: &DecisionNode, message: impl Into<String>) {
+        self.diagnostics
+            .push(DmnDiagnostic::new(Some(&node.id), message));
+    }
+
+    fn identifier(name: &str) -> String {
+        let id: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        match id.chars().next() {
+            Some(c) if c.is_ascii_alphabetic() => id,
+            _ => format!("definitions_{id}"),
+        }
+    }
+
+    fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&apos;"),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,331 @@
+use zen_expression::validate::{validate_expression, validate_unary_expression};
+
+/// FEEL keywords with no zen counterpart. `for`, `some`, `every` and `if` are only rejected in
+/// their keyword form, since zen has functions of the same name.
+const UNSUPPORTED_KEYWORDS: &[&str] = &["between", "instance", "satisfies", "then", "function"];
+const UNSUPPORTED_PREFIX_KEYWORDS: &[&str] = &["for", "some", "every", "if"];
+
+/// Zen functions and the FEEL built-ins they translate to
+const FUNCTIONS: &[(&str, &str)] = &[
+    ("startsWith", "starts with"),
+    ("endsWith", "ends with"),
+    ("contains", "contains"),
+    ("matches", "matches"),
+    ("upper", "upper case"),
+    ("lower", "lower case"),
+    ("abs", "abs"),
+    ("floor", "floor"),
+    ("ceil", "ceiling"),
+    ("sum", "sum"),
+    ("min", "min"),
+    ("max", "max"),
+    ("avg", "mean"),
+    ("median", "median"),
+    ("mode", "mode"),
+    ("flatten", "flatten"),
+];
+
+/// Operator keywords that may be followed by a parenthesis without being a call
+const OPERATOR_KEYWORDS: &[&str] = &["not", "in", "and", "or"];
+
+pub(crate) struct Feel;
+
+impl Feel {
+    /// Translates FEEL unary tests (a decision table input entry) into a zen unary expression
+    pub(crate) fn unary_to_zen(text: &str) -> Result<String, String> {
+        let text = text.trim();
+        if text.is_empty() || text == "-" {
+            return Ok(String::new());
+        }
+
+        let translated = match Self::negated(text) {
+            Some(inner) => {
+                let items = Self::split_top_level(inner);
+                if items.iter().any(|item| Self::is_test(item)) {
+                    return Err(format!(
+                        "negated ranges and comparisons are not supported: {text}"
+                    ));
+                }
+                let items: Vec<String> =
+                    items.iter().map(|item| Self::replace_input(item)).collect();
+                format!("not ($ in [{}])", items.join(", "))
+            }
+            None => Self::split_top_level(text)
+                .iter()
+                .map(|item| Self::translate_test(item))
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        let translated = Self::feel_calls_to_zen(&Self::equality_to_zen(&translated));
+
+        Self::check_keywords(&translated)?;
+        validate_unary_expression(&translated).map_err(|e| e.to_string())?;
+        Ok(translated)
+    }
+
+    /// Translates a FEEL expression (input expression, output entry or literal expression)
+    pub(crate) fn expression_to_zen(text: &str) -> Result<String, String> {
+        let text = text.trim();
+        Self::check_keywords(text)?;
+        let translated = Self::feel_calls_to_zen(&Self::equality_to_zen(text));
+        validate_expression(&translated).map_err(|e| e.to_string())?;
+        Ok(translated)
+    }
+
+    /// Zen unary expression as FEEL unary tests, `-` standing for "any"
+    pub(crate) fn unary_to_feel(text: &str) -> Result<String, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok("-".to_string());
+        }
+        if let Some(items) = text
+            .strip_prefix("not ($ in [")
+            .and_then(|rest| rest.strip_suffix("])"))
+        {
+            return Ok(format!("not({})", Self::expression_to_feel(items)?));
+        }
+        let translated = Self::expression_to_feel(text)?;
+        Ok(Self::map_outside_strings(&translated, |c| {
+            if c == '$' {
+                '?'
+            } else {
+                c
+            }
+        }))
+    }
+
+    /// Zen expression as a FEEL expression, failing on calls with no FEEL built-in
+    pub(crate) fn expression_to_feel(text: &str) -> Result<String, String> {
+        let text = Self::equality_to_feel(text.trim());
+        let blanked = Self::blank_strings(&text);
+        let mut translated = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (start, end) in Self::words(&blanked) {
+            let name = &text[start..end];
+            if !blanked[end..].trim_start().starts_with('(') || OPERATOR_KEYWORDS.contains(&name) {
+                continue;
+            }
+            if blanked[..start].ends_with('.') {
+                return Err(format!("zen method '{name}' has no FEEL equivalent"));
+            }
+            let Some((_, feel)) = FUNCTIONS.iter().find(|(zen, _)| *zen == name) else {
+                return Err(format!("zen function '{name}' has no FEEL equivalent"));
+            };
+            translated.push_str(&text[copied..start]);
+            translated.push_str(feel);
+            copied = end;
+        }
+        translated.push_str(&text[copied..]);
+        Ok(translated)
+    }
+
+    /// FEEL `=` compares, rewritten to zen `==` so it does not parse as an assignment
+    fn equality_to_zen(text: &str) -> String {
+        let blanked = Self::blank_strings(text);
+        let bytes = blanked.as_bytes();
+        let mut translated = String::with_capacity(text.len());
+        for (i, c) in text.char_indices() {
+            translated.push(c);
+            let previous = i.checked_sub(1).map(|p| bytes[p]);
+            let equality = bytes[i] == b'='
+                && !matches!(previous, Some(b'!' | b'<' | b'>' | b'='))
+                && bytes.get(i + 1) != Some(&b'=');
+            if equality {
+                translated.push('=');
+            }
+        }
+        translated
+    }
+
+    fn equality_to_feel(text: &str) -> String {
+        let blanked = Self::blank_strings(text);
+        let bytes = blanked.as_bytes();
+        text.char_indices()
+            .filter(|(i, _)| {
+                !(bytes[*i] == b'=' && i.checked_sub(1).map(|p| bytes[p]) == Some(b'='))
+            })
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// FEEL built-in calls renamed to the zen functions of [`FUNCTIONS`]
+    fn feel_calls_to_zen(text: &str) -> String {
+        let blanked = Self::blank_strings(text);
+        let mut translated = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut index = 0;
+        while index < blanked.len() {
+            let at_word = blanked[..index]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_' && c != '.');
+            let call = FUNCTIONS.iter().find(|(_, feel)| {
+                at_word
+                    && blanked[index..].starts_with(feel)
+                    && blanked[index + feel.len()..].trim_start().starts_with('(')
+            });
+            match call {
+                Some((zen, feel)) => {
+                    translated.push_str(&text[copied..index]);
+                    translated.push_str(zen);
+                    index += feel.len();
+                    copied = index;
+                }
+                None => index += blanked[index..].chars().next().map_or(1, char::len_utf8),
+            }
+        }
+        translated.push_str(&text[copied..]);
+        translated
+    }
+
+    /// Byte ranges of identifiers in `blanked`
+    fn words(blanked: &str) -> Vec<(usize, usize)> {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (i, c) in blanked.char_indices() {
+            let word = c.is_alphanumeric() || c == '_';
+            match (start, word) {
+                (None, true) if !c.is_numeric() => start = Some(i),
+                (Some(s), false) => {
+                    words.push((s, i));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            words.push((s, blanked.len()));
+        }
+        words
+    }
+
+    /// Contents of `not(...)` when the negation spans the whole entry
+    fn negated(text: &str) -> Option<&str> {
+        let inner = text.strip_prefix("not")?.trim_start().strip_prefix('(')?;
+        let mut depth = 1;
+        let mut quoted = false;
+        for (i, c) in inner.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return (i + 1 == inner.len()).then_some(&inner[..i]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn translate_test(item: &str) -> String {
+        let item = item.trim();
+        let item = if item.contains("..") {
+            let opened = match item.strip_prefix(']') {
+                Some(rest) => format!("({rest}"),
+                None => item.to_string(),
+            };
+            match opened.strip_suffix('[') {
+                Some(rest) => format!("{rest})"),
+                None => opened,
+            }
+        } else {
+            item.to_string()
+        };
+        Self::replace_input(&item)
+    }
+
+    fn replace_input(item: &str) -> String {
+        Self::map_outside_strings(item.trim(), |c| if c == '?' { '$' } else { c })
+    }
+
+    fn is_test(item: &str) -> bool {
+        let item = item.trim();
+        item.contains("..") || item.starts_with(['<', '>', '='])
+    }
+
+    fn check_keywords(text: &str) -> Result<(), String> {
+        let blanked = Self::blank_strings(text);
+        let mut rest = blanked.as_str();
+        while !rest.is_empty() {
+            let start = rest.find(|c: char| c.is_alphabetic()).unwrap_or(rest.len());
+            rest = &rest[start..];
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            let after = rest[end..].trim_start();
+            rest = &rest[end..];
+
+            if UNSUPPORTED_KEYWORDS.contains(&word)
+                || (UNSUPPORTED_PREFIX_KEYWORDS.contains(&word) && !after.starts_with('('))
+            {
+                return Err(format!("FEEL '{word}' has no zen equivalent"));
+            }
+        }
+        Ok(())
+    }
+
+    fn split_top_level(text: &str) -> Vec<&str> {
+        let mut items = Vec::new();
+        let mut depth = 0i32;
+        let mut quoted = false;
+        let mut start = 0;
+        for (i, c) in text.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                // `]1..10[` opens with a closing bracket and closes with an opening one
+                '[' if !quoted && Self::ends_item(&text[i + 1..]) => depth = (depth - 1).max(0),
+                '(' | '[' | '{' if !quoted => depth += 1,
+                ')' | ']' | '}' if !quoted && depth > 0 => depth -= 1,
+                ',' if !quoted && depth == 0 => {
+                    items.push(text[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        items.push(text[start..].trim());
+        items
+    }
+
+    fn ends_item(rest: &str) -> bool {
+        let rest = rest.trim_start();
+        rest.is_empty() || rest.starts_with(',')
+    }
+
+    fn map_outside_strings(text: &str, f: impl Fn(char) -> char) -> String {
+        let mut quoted = false;
+        text.chars()
+            .map(|c| {
+                if c == '"' {
+                    quoted = !quoted;
+                    c
+                } else if quoted {
+                    c
+                } else {
+                    f(c)
+                }
+            })
+            .collect()
+    }
+
+    /// `text` with string literals replaced by spaces, keeping byte offsets
+    fn blank_strings(text: &str) -> String {
+        let mut quoted = false;
+        let mut blanked = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '"' {
+                quoted = !quoted;
+                blanked.push(' ');
+            } else if quoted {
+                blanked.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            } else {
+                blanked.push(c);
+            }
+        }
+        blanked
+    }
+}
@@ -0,0 +1,428 @@
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use roxmltree::{Document, Node};
+use std::sync::Arc;
+
+use crate::dmn::feel::Feel;
+use crate::dmn::{DmnDiagnostic, DmnError, DmnImport};
+use crate::model::{
+    DecisionEdge, DecisionNode, DecisionNodeKind, DecisionTableContent, DecisionTableHitPolicy,
+    DecisionTableInputField, DecisionTableOutputField, Expression, ExpressionNodeContent,
+    GraphContent, InputNodeContent, OutputNodeContent, TransformAttributes,
+};
+
+const INPUT_NODE_ID: &str = "input";
+const OUTPUT_NODE_ID: &str = "output";
+
+/// Element names that carry documentation or layout only
+const IGNORED_ELEMENTS: &[&str] = &[
+    "description",
+    "extensionElements",
+    "textAnnotation",
+    "association",
+    "DMNDI",
+];
+
+/// Converts DMN definitions into a JDM graph.
+///
+/// A decision table with one output writes it under the output name, falling back to the
+/// decision variable name; tables with several outputs write each under its own name.
+pub fn import(xml: &str) -> Result<DmnImport, DmnError> {
+    let document = Document::parse(xml).map_err(|e| DmnError::Xml(e.to_string()))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "definitions" {
+        return Err(DmnError::NotDefinitions(root.tag_name().name().to_string()));
+    }
+
+    let mut importer = Importer::default();
+    importer.definitions(root);
+    Ok(importer.finish())
+}
+
+#[derive(Default)]
+struct Importer {
+    input_data: Vec<(Arc<str>, Arc<str>)>,
+    nodes: Vec<Arc<DecisionNode>>,
+    requirements: Vec<(Arc<str>, Requirement)>,
+    diagnostics: Vec<DmnDiagnostic>,
+}
+
+enum Requirement {
+    Decision(Arc<str>),
+    Input,
+}
+
+impl Importer {
+    fn definitions(&mut self, root: Node) {
+        for element in root.children().filter(Node::is_element) {
+            match element.tag_name().name() {
+                "inputData" => self.input_data.push((
+                    Arc::from(Xml::attr(element, "id")),
+                    Arc::from(Xml::attr(element, "name")),
+                )),
+                "decision" => self.decision(element),
+                name if IGNORED_ELEMENTS.contains(&name) => {}
+                name => self.report(
+                    element,
+                    format!("'{name}' elements are not supported and were skipped"),
+                ),
+            }
+        }
+    }
+
+    fn decision(&mut self, element: Node) {
+        let id = Xml::attr(element, "id");
+        let name = Xml::attr(element, "name");
+        let variable = Xml::child(element, "variable")
+            .map(|v| Xml::attr(v, "name"))
+            .filter(|v| !v.is_empty())
+            .unwrap_or(name);
+
+        let kind = if let Some(table) = Xml::child(element, "decisionTable") {
+            self.decision_table(table, id, variable)
+        } else if let Some(literal) = Xml::child(element, "literalExpression") {
+            let value = self.expression(literal, &Xml::text(literal));
+            Some(Self::expression_node(vec![Expression {
+                id: Arc::from(format!("{id}-expression")),
+                key: Arc::from(variable),
+                value: Arc::from(value),
+            }]))
+        } else if let Some(context) = Xml::child(element, "context") {
+            self.context(context, id)
+        } else {
+            let kind = element
+                .children()
+                .filter(Node::is_element)
+                .map(|c| c.tag_name().name())
+                .find(|n| !matches!(*n, "variable" | "informationRequirement" | "description"))
+                .unwrap_or("empty");
+            self.report(
+                element,
+                format!("decision logic '{kind}' is not supported, decision was skipped"),
+            );
+            None
+        };
+        let Some(kind) = kind else {
+            return;
+        };
+
+        for requirement in Xml::children(element, "informationRequirement") {
+            if let Some(required) = Xml::child(requirement, "requiredDecision") {
+                match Xml::href(required) {
+                    Some(target) => self
+                        .requirements
+                        .push((Arc::from(id), Requirement::Decision(Arc::from(target)))),
+                    None => self.report(required, "external decision references are not supported"),
+                }
+            } else if Xml::child(requirement, "requiredInput").is_some() {
+                self.requirements.push((Arc::from(id), Requirement::Input));
+            }
+        }
+        for requirement in Xml::children(element, "knowledgeRequirement") {
+            self.report(
+                requirement,
+                "business knowledge model invocations are not supported",
+            );
+        }
+
+        self.nodes.push(Arc::new(DecisionNode {
+            id: Arc::from(id),
+            name: Arc::from(name),
+            kind,
+        }));
+    }
+
+    fn decision_table(
+        &mut self,
+        table: Node,
+        decision_id: &str,
+        variable: &str,
+    ) -> Option<DecisionNodeKind> {
+        let hit_policy = self.hit_policy(table);
+
+        let inputs: Vec<DecisionTableInputField> = Xml::children(table, "input")
+            .enumerate()
+            .map(|(i, input)| {
+                let expression = Xml::child(input, "inputExpression")
+                    .map(|e| self.expression(e, &Xml::text(e)))
+                    .unwrap_or_default();
+                DecisionTableInputField {
+                    id: Xml::id_or(input, || format!("{decision_id}-input-{i}")),
+                    name: Arc::from(Xml::attr(input, "label")),
+                    field: (!expression.is_empty()).then(|| Arc::from(expression)),
+                    column_type: None,
+                }
+            })
+            .collect();
+
+        let output_elements: Vec<Node> = Xml::children(table, "output").collect();
+        let single = output_elements.len() == 1;
+        let outputs: Vec<DecisionTableOutputField> = output_elements
+            .iter()
+            .enumerate()
+            .map(|(i, output)| {
+                let name = Xml::attr(*output, "name");
+                let field = match (name.is_empty(), single) {
+                    (false, _) => name,
+                    (true, true) => variable,
+                    (true, false) => {
+                        self.report(*output, "unnamed output in a multi-output table");
+                        ""
+                    }
+                };
+                let label = Xml::attr(*output, "label");
+                DecisionTableOutputField {
+                    id: Xml::id_or(*output, || format!("{decision_id}-output-{i}")),
+                    name: Arc::from(if label.is_empty() { field } else { label }),
+                    field: Arc::from(field),
+                    column_type: Some(Xml::attr(*output, "typeRef"))
+                        .filter(|t| !t.is_empty())
+                        .map(Arc::from),
+                }
+            })
+            .collect();
+
+        let mut rules = Vec::new();
+        for (index, rule) in Xml::children(table, "rule").enumerate() {
+            let input_entries: Vec<Node> = Xml::children(rule, "inputEntry").collect();
+            let output_entries: Vec<Node> = Xml::children(rule, "outputEntry").collect();
+            if input_entries.len() != inputs.len() || output_entries.len() != outputs.len() {
+                self.report(
+                    rule,
+                    "rule entry count does not match the table columns, rule was skipped",
+                );
+                continue;
+            }
+
+            let mut row: HashMap<Arc<str>, Arc<str>> = HashMap::new();
+            row.insert(
+                Arc::from("_id"),
+                Xml::id_or(rule, || format!("{decision_id}-rule-{index}")),
+            );
+            if let Some(description) = Xml::child(rule, "description") {
+                row.insert(
+                    Arc::from("_description"),
+                    Arc::from(description.text().unwrap_or_default().trim()),
+                );
+            }
+            for (input, entry) in inputs.iter().zip(&input_entries) {
+                let text = Xml::text(*entry);
+                let value = Feel::unary_to_zen(&text).unwrap_or_else(|reason| {
+                    self.report(*entry, reason);
+                    text.clone()
+                });
+                row.insert(input.id.clone(), Arc::from(value));
+            }
+            for (output, entry) in outputs.iter().zip(&output_entries) {
+                let value = self.expression(*entry, &Xml::text(*entry));
+                row.insert(output.id.clone(), Arc::from(value));
+            }
+            rules.push(row);
+        }
+
+        Some(DecisionNodeKind::DecisionTableNode {
+            content: DecisionTableContent {
+                rules: Arc::new(rules),
+                inputs: Arc::new(inputs),
+                outputs: Arc::new(outputs),
+                hit_policy,
+                transform_attributes: TransformAttributes::default(),
+            },
+        })
+    }
+
+    fn hit_policy(&mut self, table: Node) -> DecisionTableHitPolicy {
+        let policy = table.attribute("hitPolicy").unwrap_or("UNIQUE");
+        match policy {
+            "UNIQUE" | "ANY" | "FIRST" => DecisionTableHitPolicy::First,
+            "RULE ORDER" => DecisionTableHitPolicy::Collect,
+            "COLLECT" => {
+                if let Some(aggregation) = table.attribute("aggregation") {
+                    self.report(
+                        table,
+                        format!("{aggregation} aggregation is not supported, matches are collected as a list"),
+                    );
+                }
+                DecisionTableHitPolicy::Collect
+            }
+            "OUTPUT ORDER" => {
+                self.report(
+                    table,
+                    "OUTPUT ORDER is not supported, matches are collected in rule order",
+                );
+                DecisionTableHitPolicy::Collect
+            }
+            other => {
+                self.report(
+                    table,
+                    format!("{other} hit policy is not supported, the first matching rule is used"),
+                );
+                DecisionTableHitPolicy::First
+            }
+        }
+    }
+
+    fn context(&mut self, context: Node, decision_id: &str) -> Option<DecisionNodeKind> {
+        let mut expressions = Vec::new();
+        for (i, entry) in Xml::children(context, "contextEntry").enumerate() {
+            let Some(key) = Xml::child(entry, "variable").map(|v| Xml::attr(v, "name")) else {
+                self.report(entry, "context result entries are not supported");
+                continue;
+            };
+            let Some(literal) = Xml::child(entry, "literalExpression") else {
+                self.report(entry, "only literal expressions are supported in contexts");
+                continue;
+            };
+            let value = self.expression(literal, &Xml::text(literal));
+            expressions.push(Expression {
+                id: Xml::id_or(entry, || format!("{decision_id}-expression-{i}")),
+                key: Arc::from(key),
+                value: Arc::from(value),
+            });
+        }
+        Some(Self::expression_node(expressions))
+    }
+
+    fn expression_node(expressions: Vec<Expression>) -> DecisionNodeKind {
+        DecisionNodeKind::ExpressionNode {
+            content: ExpressionNodeContent {
+                expressions: Arc::new(expressions),
+                transform_attributes: TransformAttributes::default(),
+            },
+        }
+    }
+
+    fn expression(&mut self, element: Node, text: &str) -> String {
+        Feel::expression_to_zen(text).unwrap_or_else(|reason| {
+            self.report(element, reason);
+            text.trim().to_string()
+        })
+    }
+
+    fn report(&mut self, element: Node, message: impl Into<String>) {
+        let id = element
+            .attribute("id")
+            .or_else(|| element.ancestors().find_map(|a| a.attribute("id")));
+        self.diagnostics.push(DmnDiagnostic::new(id, message));
+    }
+
+    fn finish(self) -> DmnImport {
+        let (input_id, input_name) = match self.input_data.as_slice() {
+            [(id, name)] => (id.clone(), name.clone()),
+            _ => (Arc::from(INPUT_NODE_ID), Arc::from("Input")),
+        };
+        let known: HashSet<&Arc<str>> = self.nodes.iter().map(|n| &n.id).collect();
+        let mut diagnostics = self.diagnostics;
+
+        let mut edges: Vec<Arc<DecisionEdge>> = Vec::new();
+        let mut seen: HashSet<(Arc<str>, Arc<str>)> = HashSet::new();
+        let mut required: HashSet<Arc<str>> = HashSet::new();
+        let mut has_requirement: HashSet<Arc<str>> = HashSet::new();
+        let mut add_edge = |source: &Arc<str>, target: &Arc<str>| {
+            if seen.insert((source.clone(), target.clone())) {
+                edges.push(Arc::new(DecisionEdge {
+                    id: Arc::from(format!("{source}-{target}")),
+                    source_id: source.clone(),
+                    target_id: target.clone(),
+                    source_handle: None,
+                }));
+            }
+        };
+
+        for (decision, requirement) in &self.requirements {
+            if !known.contains(decision) {
+                continue;
+            }
+            match requirement {
+                Requirement::Input => add_edge(&input_id, decision),
+                Requirement::Decision(source) if known.contains(source) => {
+                    add_edge(source, decision);
+                    required.insert(source.clone());
+                }
+                Requirement::Decision(source) => {
+                    diagnostics.push(DmnDiagnostic::new(
+                        Some(decision),
+                        format!("required decision '{source}' was not imported"),
+                    ));
+                    continue;
+                }
+            }
+            has_requirement.insert(decision.clone());
+        }
+
+        let output_id: Arc<str> = Arc::from(OUTPUT_NODE_ID);
+        for node in &self.nodes {
+            if !has_requirement.contains(&node.id) {
+                add_edge(&input_id, &node.id);
+            }
+            if !required.contains(&node.id) {
+                add_edge(&node.id, &output_id);
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(self.nodes.len() + 2);
+        nodes.push(Arc::new(DecisionNode {
+            id: input_id,
+            name: input_name,
+            kind: DecisionNodeKind::InputNode {
+                content: InputNodeContent::default(),
+            },
+        }));
+        nodes.extend(self.nodes);
+        nodes.push(Arc::new(DecisionNode {
+            id: output_id,
+            name: Arc::from("Output"),
+            kind: DecisionNodeKind::OutputNode {
+                content: OutputNodeContent::default(),
+            },
+        }));
+
+        DmnImport {
+            graph: GraphContent {
+                nodes,
+                edges,
+                ..Default::default()
+            },
+            diagnostics,
+        }
+    }
+}
+
+struct Xml;
+
+impl Xml {
+    fn attr<'a>(node: Node<'a, '_>, name: &str) -> &'a str {
+        node.attribute(name).unwrap_or_default()
+    }
+
+    fn id_or(node: Node, fallback: impl FnOnce() -> String) -> Arc<str> {
+        match node.attribute("id") {
+            Some(id) if !id.is_empty() => Arc::from(id),
+            _ => Arc::from(fallback()),
+        }
+    }
+
+    fn child<'a, 'input>(node: Node<'a, 'input>, name: &'static str) -> Option<Node<'a, 'input>> {
+        Self::children(node, name).next()
+    }
+
+    fn children<'a, 'input>(
+        node: Node<'a, 'input>,
+        name: &'static str,
+    ) -> impl Iterator<Item = Node<'a, 'input>> {
+        node.children()
+            .filter(move |c| c.is_element() && c.tag_name().name() == name)
+    }
+
+    /// Contents of the `text` child, as used by unary tests and literal expressions
+    fn text(node: Node) -> String {
+        Self::child(node, "text")
+            .and_then(|t| t.text())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    fn href<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+        node.attribute("href")?.strip_prefix('#')
+    }
+}
@@ -0,0 +1,74 @@
+//! Conversion between DMN 1.x XML and JDM graphs.
+//!
+//! Decisions backed by a `decisionTable` become decision table nodes, `literalExpression` and
+//! `context` decisions become expression nodes, and information requirements become edges. All
+//! input data elements share one input node and decisions nothing else requires feed the output
+//! node. Anything without a JDM counterpart is reported as a [`DmnDiagnostic`].
+//!
+//! FEEL equality `=` becomes zen `==` and FEEL built-ins such as `starts with` map to their zen
+//! functions, in both directions; zen functions with no FEEL built-in are reported on export.
+
+mod export;
+mod feel;
+mod import;
+
+use serde::Serialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::model::{DecisionNodeKind, DecisionTableContent, GraphContent};
+
+pub use export::export;
+pub use import::import;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmnDiagnostic {
+    /// DMN element id on import, JDM node id on export
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Arc<str>>,
+    pub message: String,
+}
+
+impl DmnDiagnostic {
+    fn new(id: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            id: id.map(Arc::from),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DmnImport {
+    pub graph: GraphContent,
+    pub diagnostics: Vec<DmnDiagnostic>,
+}
+
+impl DmnImport {
+    /// Decision table imported from the DMN decision with the given id
+    pub fn decision_table(&self, decision_id: &str) -> Option<&DecisionTableContent> {
+        self.graph
+            .nodes
+            .iter()
+            .find(|node| node.id.as_ref() == decision_id)
+            .and_then(|node| match &node.kind {
+                DecisionNodeKind::DecisionTableNode { content } => Some(content),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DmnExport {
+    pub xml: String,
+    pub diagnostics: Vec<DmnDiagnostic>,
+}
+
+#[derive(Debug, Error)]
+pub enum DmnError {
+    #[error("Invalid DMN XML: {0}")]
+    Xml(String),
+    #[error("Expected a DMN definitions root element, found '{0}'")]
+    NotDefinitions(String),
+}
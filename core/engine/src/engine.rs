@@ -1,26 +1,43 @@
+use crate::cancellation::{CancellationToken, EvaluationLimits};
 use crate::decision::Decision;
 use crate::decision_graph::graph::{DecisionGraphResponse, EvaluationTrace};
 use crate::error::ContentKindError;
+use crate::expression_context::ExpressionContext;
 use crate::loader::{ClosureLoader, DynamicLoader, LoaderResponse, LoaderResult, NoopLoader};
 use crate::model::{DecisionContent, GraphContent};
-use crate::nodes::custom::{DynamicCustomNode, NoopCustomNode};
+use crate::nodes::custom::{CustomNodeRegistry, DynamicCustomNode, NoopCustomNode};
+use crate::nodes::decision::memo::Memoization;
 use crate::nodes::function::http_handler::DynamicHttpHandler;
+use crate::nodes::function::pool::{FunctionPool, FunctionPoolConfig};
+use crate::nodes::wasm::WasmRuntime;
+use crate::observer::{observe_loader, DynamicEvaluationObserver, LoadEvent, LoadOutcome};
+use crate::policy::resolver::DynamicPropertyResolver;
 use crate::policy::runtime::{CompiledEntry, CompiledSet};
+use crate::policy::{OpenApiInfo, Workspace};
 use crate::{CompileFailure, EvaluationError};
 use arc_swap::ArcSwapOption;
 use serde_json::Value;
 use std::fmt::Debug;
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 use strum::{EnumString, IntoStaticStr};
 use zen_expression::variable::Variable;
+use zen_expression::vm::{CalendarRegistry, NumericContext};
 
 /// Structure used for generating and evaluating JDM decisions
 #[derive(Clone)]
 pub struct DecisionEngine {
     loader: DynamicLoader,
     adapter: DynamicCustomNode,
+    custom_nodes: Option<Arc<CustomNodeRegistry>>,
     http_handler: DynamicHttpHandler,
+    observer: DynamicEvaluationObserver,
+    property_resolver: DynamicPropertyResolver,
+    expression_context: ExpressionContext,
+    function_pool: Option<FunctionPool>,
+    wasm_runtime: WasmRuntime,
+    memoization: Memoization,
     compiled: Arc<ArcSwapOption<CompiledSet>>,
 }
 
@@ -30,14 +47,40 @@ impl Debug for DecisionEngine {
             .field("loader", &self.loader)
             .field("adapter", &self.adapter)
             .field("http_handler", &self.http_handler)
+            .field("observer", &self.observer)
+            .field("property_resolver", &self.property_resolver)
+            .field("expression_context", &self.expression_context)
+            .field("function_pool", &self.function_pool)
+            .field("memoization", &self.memoization)
             .finish()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct EvaluationOptions {
     pub trace: bool,
     pub max_depth: u8,
+    /// Runs graph nodes that do not depend on each other concurrently, each node starting as
+    /// soon as its own dependencies complete.
+    ///
+    /// The evaluation ends once the Output node completes: no further nodes start and nodes still
+    /// running are dropped, although effects they already caused, such as sent HTTP requests,
+    /// are not undone. `$nodes` only reliably holds the outputs of a node's ancestors.
+    pub concurrent: bool,
+    /// Aborts the evaluation with [`EvaluationError::DeadlineExceeded`] once reached.
+    ///
+    /// Checked between nodes, in loop-mode iterations, in expression closures, while function
+    /// nodes execute JavaScript and whenever a WebAssembly guest calls into or returns to the host.
+    pub deadline: Option<Instant>,
+    /// Aborts the evaluation with [`EvaluationError::Cancelled`] once cancelled, checked at the
+    /// same points as `deadline`
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl EvaluationOptions {
+    pub(crate) fn limits(&self) -> EvaluationLimits {
+        EvaluationLimits::new(self.deadline, self.cancellation.clone())
+    }
 }
 
 impl Default for EvaluationOptions {
@@ -45,14 +88,34 @@ impl Default for EvaluationOptions {
         Self {
             trace: false,
             max_depth: 10,
+            concurrent: false,
+            deadline: None,
+            cancellation: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct EvaluationSerializedOptions {
     pub trace: EvaluationTraceKind,
     pub max_depth: u8,
+    pub concurrent: bool,
+    /// Same as [`EvaluationOptions::deadline`]
+    pub deadline: Option<Instant>,
+    /// Same as [`EvaluationOptions::cancellation`]
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl EvaluationSerializedOptions {
+    pub(crate) fn evaluation_options(&self) -> EvaluationOptions {
+        EvaluationOptions {
+            trace: self.trace != EvaluationTraceKind::None,
+            max_depth: self.max_depth,
+            concurrent: self.concurrent,
+            deadline: self.deadline,
+            cancellation: self.cancellation.clone(),
+        }
+    }
 }
 
 impl Default for EvaluationSerializedOptions {
@@ -60,6 +123,9 @@ impl Default for EvaluationSerializedOptions {
         Self {
             trace: EvaluationTraceKind::None,
             max_depth: 10,
+            concurrent: false,
+            deadline: None,
+            cancellation: None,
         }
     }
 }
@@ -98,7 +164,14 @@ impl Default for DecisionEngine {
         Self {
             loader: Arc::new(NoopLoader::default()),
             adapter: Arc::new(NoopCustomNode::default()),
+            custom_nodes: None,
             http_handler: None,
+            observer: None,
+            property_resolver: None,
+            expression_context: Default::default(),
+            function_pool: None,
+            wasm_runtime: WasmRuntime::default(),
+            memoization: Memoization::Disabled,
             compiled: Arc::new(ArcSwapOption::empty()),
         }
     }
@@ -109,17 +182,39 @@ impl DecisionEngine {
         Self {
             loader,
             adapter,
+            custom_nodes: None,
             http_handler: None,
+            observer: None,
+            property_resolver: None,
+            expression_context: Default::default(),
+            function_pool: None,
+            wasm_runtime: WasmRuntime::default(),
+            memoization: Memoization::Disabled,
             compiled: Arc::new(ArcSwapOption::empty()),
         }
     }
 
     pub fn with_adapter(mut self, adapter: DynamicCustomNode) -> Self {
         self.adapter = adapter;
+        self.custom_nodes = None;
+        self.compiled = Arc::new(ArcSwapOption::empty());
+        self
+    }
+
+    /// Handles custom nodes by their `kind`, see [`CustomNodeRegistry`]. Node configs are
+    /// checked against the declared schemas by [`DecisionEngine::compile`].
+    pub fn with_custom_nodes(mut self, registry: CustomNodeRegistry) -> Self {
+        let registry = Arc::new(registry);
+        self.adapter = registry.clone();
+        self.custom_nodes = Some(registry);
         self.compiled = Arc::new(ArcSwapOption::empty());
         self
     }
 
+    pub fn custom_nodes(&self) -> Option<&Arc<CustomNodeRegistry>> {
+        self.custom_nodes.as_ref()
+    }
+
     pub fn with_loader(mut self, loader: DynamicLoader) -> Self {
         self.loader = loader;
         self.compiled = Arc::new(ArcSwapOption::empty());
@@ -132,6 +227,51 @@ impl DecisionEngine {
         self
     }
 
+    pub fn with_observer(mut self, observer: DynamicEvaluationObserver) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Fetches policy inputs missing from the evaluation context on demand, see
+    /// [`PropertyResolver`](crate::policy::PropertyResolver)
+    pub fn with_property_resolver(mut self, property_resolver: DynamicPropertyResolver) -> Self {
+        self.property_resolver = property_resolver;
+        self
+    }
+
+    /// Registers the calendars that business-day date methods such as
+    /// `addBusinessDays(5, 'DE')` look up by name, see [`CalendarRegistry`]
+    pub fn with_calendars(mut self, calendars: CalendarRegistry) -> Self {
+        self.expression_context.calendars = Some(Arc::new(calendars));
+        self
+    }
+
+    /// Default scale and rounding of divisions in every expression, see [`NumericContext`]
+    pub fn with_numeric_context(mut self, numeric: NumericContext) -> Self {
+        self.expression_context.numeric = numeric;
+        self
+    }
+
+    /// Coerces scalars of policy input to the types their data models declare before it is
+    /// validated, reporting each change in the trace
+    pub fn with_input_coercion(mut self, coerce_input: bool) -> Self {
+        self.expression_context.coerce_input = coerce_input;
+        self
+    }
+
+    /// Reuses results of sub-decisions called again with the same input, see [`Memoization`]
+    pub fn with_memoization(mut self, memoization: Memoization) -> Self {
+        self.memoization = memoization;
+        self
+    }
+
+    /// Keeps function node runtimes warm between evaluations instead of starting one per
+    /// evaluation, see [`FunctionPool`]
+    pub fn with_function_pool(mut self, config: FunctionPoolConfig) -> Self {
+        self.function_pool = Some(FunctionPool::new(config));
+        self
+    }
+
     pub fn with_closure_loader<F, O>(mut self, loader: F) -> Self
     where
         F: Fn(String) -> O + Sync + Send + 'static,
@@ -147,13 +287,37 @@ impl DecisionEngine {
             return Vec::new();
         };
 
-        let set = CompiledSet::build_sync(&self.loader, &keys);
+        let set = CompiledSet::build_sync(&self.loader, &keys, self.custom_nodes.as_deref());
 
         let failures = set.failures().to_vec();
         self.compiled.store(Some(Arc::new(set)));
         failures
     }
 
+    /// OpenAPI document covering every decision key in the loader, with the keys left out of it
+    /// and why. `None` when the loader cannot list or synchronously load its keys.
+    pub fn openapi(&self, info: &OpenApiInfo) -> Option<(Value, Vec<CompileFailure>)> {
+        let keys = self.loader.keys()?;
+        let mut workspace = Workspace::new();
+        workspace.set_custom_nodes(self.custom_nodes.clone());
+        let mut skipped = Vec::new();
+        for key in keys {
+            match self.loader.load_sync(key.as_ref())? {
+                Ok(content) => workspace.set_document_arc(key, content),
+                Err(error) => skipped.push(CompileFailure {
+                    key,
+                    kind: "load",
+                    diagnostics: Vec::new(),
+                    error: Some(error.to_string()),
+                }),
+            }
+        }
+
+        let (document, failures) = workspace.openapi(info);
+        skipped.extend(failures);
+        Some((document, skipped))
+    }
+
     pub fn compile_failures(&self) -> Vec<CompileFailure> {
         self.compiled
             .load_full()
@@ -184,18 +348,31 @@ impl DecisionEngine {
     where
         K: AsRef<str>,
     {
+        if let Err(reason) = options.limits().check() {
+            return Err(Box::new(EvaluationError::interrupted(reason, None, None)));
+        }
+
         let key_str = key.as_ref();
         if let Some(set) = self.compiled.load_full() {
             if let Some(entry) = set.get(key_str) {
+                self.report_precompiled(key_str);
                 return match entry {
                     CompiledEntry::Policy(artifact) => artifact
-                        .evaluate_entry(key_str, context, options.trace)
+                        .evaluate_entry(
+                            key_str,
+                            context,
+                            options.trace,
+                            &self.property_resolver,
+                            &self.expression_context,
+                            &options.limits(),
+                        )
+                        .await
                         .map(|r| DecisionGraphResponse {
                             performance: format!("{:.1?}", r.duration),
                             result: r.output,
                             trace: r.trace.map(EvaluationTrace::Policy),
                         })
-                        .map_err(|e| Box::new(EvaluationError::Policy(e))),
+                        .map_err(EvaluationError::from_policy),
                     CompiledEntry::Graph(graph) => {
                         self.decision_from_graph(graph)
                             .evaluate_with_opts(context, options)
@@ -204,7 +381,8 @@ impl DecisionEngine {
                 };
             }
         }
-        let content = self.loader.load(key_str).await?;
+        let loader = observe_loader(self.loader.clone(), &self.observer);
+        let content = loader.load(key_str).await?;
         match content.as_ref() {
             DecisionContent::Graph(_) => {
                 let decision = self.decision_from_graph_arc(content);
@@ -212,11 +390,13 @@ impl DecisionEngine {
             }
             DecisionContent::Policy(_) => {
                 crate::policy::runtime::evaluate_policy(
-                    &self.loader,
+                    &loader,
                     key_str,
                     content,
                     context,
                     options,
+                    &self.property_resolver,
+                    &self.expression_context,
                 )
                 .await
             }
@@ -235,11 +415,22 @@ impl DecisionEngine {
         let key_str = key.as_ref();
         if let Some(set) = self.compiled.load_full() {
             if let Some(entry) = set.get(key_str) {
+                self.report_precompiled(key_str);
                 match entry {
                     CompiledEntry::Policy(artifact) => {
                         let trace_mode = options.trace;
                         let trace = options.trace != EvaluationTraceKind::None;
-                        return match artifact.evaluate_entry(key_str, context, trace) {
+                        let result = artifact
+                            .evaluate_entry(
+                                key_str,
+                                context,
+                                trace,
+                                &self.property_resolver,
+                                &self.expression_context,
+                                &options.evaluation_options().limits(),
+                            )
+                            .await;
+                        return match result {
                             Ok(r) => {
                                 let response = DecisionGraphResponse {
                                     performance: format!("{:.1?}", r.duration),
@@ -251,7 +442,7 @@ impl DecisionEngine {
                                     .unwrap_or_default())
                             }
                             Err(e) => {
-                                let err = EvaluationError::Policy(e);
+                                let err = EvaluationError::from_policy(e);
                                 Err(err
                                     .serialize_with_mode(serde_json::value::Serializer, trace_mode)
                                     .unwrap_or_default())
@@ -267,8 +458,8 @@ impl DecisionEngine {
                 }
             }
         }
-        let content = self
-            .loader
+        let loader = observe_loader(self.loader.clone(), &self.observer);
+        let content = loader
             .load(key_str)
             .await
             .map_err(|err| Value::String(err.to_string()))?;
@@ -279,17 +470,16 @@ impl DecisionEngine {
                 decision.evaluate_serialized(context, options).await
             }
             DecisionContent::Policy(_) => {
-                let inner_opts = EvaluationOptions {
-                    trace: options.trace != EvaluationTraceKind::None,
-                    max_depth: options.max_depth,
-                };
+                let inner_opts = options.evaluation_options();
                 let trace_mode = options.trace;
                 let response = crate::policy::runtime::evaluate_policy(
-                    &self.loader,
+                    &loader,
                     key_str,
                     content,
                     context,
                     inner_opts,
+                    &self.property_resolver,
+                    &self.expression_context,
                 )
                 .await;
                 match response {
@@ -325,6 +515,24 @@ impl DecisionEngine {
             .with_loader(self.loader.clone())
             .with_adapter(self.adapter.clone())
             .with_http_handler(self.http_handler.clone())
+            .with_observer(self.observer.clone())
+            .with_property_resolver(self.property_resolver.clone())
+            .with_calendars(self.expression_context.calendars.clone())
+            .with_numeric_context(self.expression_context.numeric)
+            .with_input_coercion(self.expression_context.coerce_input)
+            .with_function_pool(self.function_pool.clone())
+            .with_wasm_runtime(self.wasm_runtime.clone())
+            .with_memoization(self.memoization.clone())
+    }
+
+    fn report_precompiled(&self, key: &str) {
+        if let Some(observer) = &self.observer {
+            observer.on_load(&LoadEvent {
+                key,
+                outcome: LoadOutcome::Precompiled,
+                duration: Default::default(),
+            });
+        }
     }
 
     /// Creates a decision from DecisionContent, exists for easier binding creation
@@ -1,3 +1,4 @@
+use crate::cancellation::InterruptReason;
 use crate::engine::EvaluationTraceKind;
 use crate::loader::LoaderError;
 use crate::DecisionGraphValidationError;
@@ -60,6 +61,18 @@ pub enum EvaluationError {
     #[error("Depth limit exceeded")]
     DepthLimitExceeded,
 
+    #[error("Evaluation cancelled")]
+    Cancelled {
+        node_id: Option<Arc<str>>,
+        trace: Option<Variable>,
+    },
+
+    #[error("Evaluation deadline exceeded")]
+    DeadlineExceeded {
+        node_id: Option<Arc<str>>,
+        trace: Option<Variable>,
+    },
+
     #[error("Invalid graph")]
     InvalidGraph(DecisionGraphValidationError),
 
@@ -78,6 +91,39 @@ pub enum EvaluationError {
 }
 
 impl EvaluationError {
+    pub(crate) fn interrupted(
+        reason: InterruptReason,
+        node_id: Option<Arc<str>>,
+        trace: Option<Variable>,
+    ) -> Self {
+        match reason {
+            InterruptReason::Cancelled => EvaluationError::Cancelled { node_id, trace },
+            InterruptReason::DeadlineExceeded => {
+                EvaluationError::DeadlineExceeded { node_id, trace }
+            }
+        }
+    }
+
+    /// Policy errors, with interrupted policies reported like interrupted graphs
+    pub(crate) fn from_policy(error: crate::policy::EvaluationError) -> Box<Self> {
+        Box::new(match error {
+            crate::policy::EvaluationError::Interrupted { reason, .. } => {
+                EvaluationError::interrupted(reason, None, None)
+            }
+            error => EvaluationError::Policy(error),
+        })
+    }
+
+    /// Partial trace of evaluations aborted by a failing node, cancellation or deadline
+    pub fn trace(&self) -> Option<&Variable> {
+        match self {
+            EvaluationError::NodeError { trace, .. }
+            | EvaluationError::Cancelled { trace, .. }
+            | EvaluationError::DeadlineExceeded { trace, .. } => trace.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn serialize_with_mode<S>(
         &self,
         serializer: S,
@@ -92,6 +138,22 @@ impl EvaluationError {
             EvaluationError::DepthLimitExceeded => {
                 map.serialize_entry("type", "DepthLimitExceeded")?;
             }
+            EvaluationError::Cancelled { node_id, trace }
+            | EvaluationError::DeadlineExceeded { node_id, trace } => {
+                let kind = match self {
+                    EvaluationError::Cancelled { .. } => "Cancelled",
+                    _ => "DeadlineExceeded",
+                };
+
+                map.serialize_entry("type", kind)?;
+                if let Some(node_id) = node_id {
+                    map.serialize_entry("nodeId", node_id)?;
+                }
+
+                if let Some(trace) = &trace {
+                    map.serialize_entry("trace", &mode.serialize_trace(trace))?;
+                }
+            }
             EvaluationError::NodeError {
                 node_id,
                 trace,
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+use zen_expression::vm::{CalendarRegistry, NumericContext};
+use zen_expression::Isolate;
+
+/// Engine-level expression settings applied to every isolate of an evaluation
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExpressionContext {
+    pub calendars: Option<Arc<CalendarRegistry>>,
+    pub numeric: NumericContext,
+    /// Coerces policy input against data models before it is validated
+    pub coerce_input: bool,
+}
+
+impl ExpressionContext {
+    pub fn apply(&self, isolate: &mut Isolate) {
+        isolate.set_calendars(self.calendars.clone());
+        isolate.set_numeric_context(self.numeric);
+    }
+}
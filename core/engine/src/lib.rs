@@ -122,19 +122,25 @@
 #![deny(clippy::unwrap_used)]
 #![allow(clippy::module_inception)]
 
+mod cancellation;
 mod config;
 mod decision;
 mod decision_graph;
+pub mod dmn;
 mod engine;
 pub mod error;
+mod expression_context;
 pub mod loader;
 pub mod model;
 pub mod nodes;
+pub mod observer;
 pub mod policy;
+pub mod spreadsheet;
 pub mod workspace;
 
 pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub use cancellation::{CancellationToken, InterruptReason};
 pub use config::ZEN_CONFIG;
 pub use decision::Decision;
 pub use decision_graph::{
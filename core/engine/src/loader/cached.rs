@@ -3,7 +3,9 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
 
-use crate::loader::{DecisionLoader, DynamicLoader, LoaderResponse};
+use crate::loader::{
+    BinaryResponse, DecisionLoader, DynamicLoader, LoaderResponse, ModuleResponse,
+};
 use crate::model::DecisionContent;
 
 #[derive(Debug)]
@@ -97,6 +99,20 @@ impl DecisionLoader for CachedLoader {
         }
         Some(response)
     }
+
+    fn load_module<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ModuleResponse> + 'a + Send>> {
+        self.loader.load_module(key)
+    }
+
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = BinaryResponse> + 'a + Send>> {
+        self.loader.load_binary(key)
+    }
 }
 
 #[cfg(test)]
@@ -51,11 +51,27 @@ impl LoaderConfig {
         let loader = MemoryLoader::default();
         for index in 0..archive.len() {
             let mut entry = archive.by_index(index)?;
-            if !entry.is_file() || !entry.name().ends_with(".json") {
+            if !entry.is_file() {
                 continue;
             }
 
             let key = entry.name().to_string();
+            if [".js", ".mjs", ".ts"].iter().any(|ext| key.ends_with(ext)) {
+                let mut source = String::with_capacity(entry.size() as usize);
+                entry.read_to_string(&mut source)?;
+                loader.add_module(key, source);
+                continue;
+            }
+            if key.ends_with(".wasm") {
+                let mut bytes = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut bytes)?;
+                loader.add_binary(key, bytes);
+                continue;
+            }
+            if !key.ends_with(".json") {
+                continue;
+            }
+
             let mut buffer = Vec::with_capacity(entry.size() as usize);
             entry.read_to_end(&mut buffer)?;
             let content: DecisionContent = serde_json::from_slice(&buffer)?;
@@ -107,6 +123,12 @@ mod tests {
                 .compression_method(zip::CompressionMethod::Deflated);
             writer.start_file("graph.json", options).unwrap();
             writer.write_all(GRAPH_JSON.as_bytes()).unwrap();
+            writer.start_file("lib/scoring.js", options).unwrap();
+            writer
+                .write_all(b"export const score = (a) => a * 2;")
+                .unwrap();
+            writer.start_file("wasm/score.wasm", options).unwrap();
+            writer.write_all(b"\0asm\x01\0\0\0").unwrap();
             writer.finish().unwrap();
         }
 
@@ -118,5 +140,15 @@ mod tests {
 
         assert!(loader.load("graph.json").await.is_ok());
         assert!(loader.load("missing.json").await.is_err());
+        assert!(loader.load_module("lib/scoring.js").await.is_ok());
+        assert!(loader.load("lib/scoring.js").await.is_err());
+        assert_eq!(
+            loader
+                .load_binary("wasm/score.wasm")
+                .await
+                .unwrap()
+                .as_ref(),
+            b"\0asm\x01\0\0\0"
+        );
     }
 }
@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::loader::{DecisionLoader, LoaderError, LoaderResponse};
+use crate::loader::{BinaryResponse, DecisionLoader, LoaderError, LoaderResponse, ModuleResponse};
 use crate::model::DecisionContent;
 
 /// Loads decisions based on filesystem root
@@ -55,6 +55,34 @@ impl FilesystemLoader {
 
         Ok(Arc::new(result))
     }
+
+    fn read_module<K: AsRef<str>>(&self, key: K) -> ModuleResponse {
+        let path = self.key_to_path(key.as_ref());
+        if !Path::exists(&path) {
+            return Err(LoaderError::NotFound(String::from(key.as_ref())));
+        }
+
+        let source = std::fs::read_to_string(path).map_err(|e| LoaderError::Internal {
+            key: String::from(key.as_ref()),
+            source: e.into(),
+        })?;
+
+        Ok(Arc::from(source))
+    }
+
+    fn read_binary<K: AsRef<str>>(&self, key: K) -> BinaryResponse {
+        let path = self.key_to_path(key.as_ref());
+        if !Path::exists(&path) {
+            return Err(LoaderError::NotFound(String::from(key.as_ref())));
+        }
+
+        let bytes = std::fs::read(path).map_err(|e| LoaderError::Internal {
+            key: String::from(key.as_ref()),
+            source: e.into(),
+        })?;
+
+        Ok(Arc::from(bytes))
+    }
 }
 
 impl DecisionLoader for FilesystemLoader {
@@ -69,6 +97,20 @@ impl DecisionLoader for FilesystemLoader {
         Some(self.read_content(key))
     }
 
+    fn load_module<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ModuleResponse> + 'a + Send>> {
+        Box::pin(async move { self.read_module(key) })
+    }
+
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = BinaryResponse> + 'a + Send>> {
+        Box::pin(async move { self.read_binary(key) })
+    }
+
     fn keys(&self) -> Option<Vec<Arc<str>>> {
         let root = Path::new(&self.root);
         let mut keys = Vec::new();
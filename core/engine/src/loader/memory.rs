@@ -1,4 +1,4 @@
-use crate::loader::{DecisionLoader, LoaderError, LoaderResponse};
+use crate::loader::{BinaryResponse, DecisionLoader, LoaderError, LoaderResponse, ModuleResponse};
 use crate::model::DecisionContent;
 use ahash::HashMap;
 use std::future::Future;
@@ -9,6 +9,8 @@ use std::sync::{Arc, RwLock};
 #[derive(Debug, Default)]
 pub struct MemoryLoader {
     memory_refs: RwLock<HashMap<String, Arc<DecisionContent>>>,
+    module_refs: RwLock<HashMap<String, Arc<str>>>,
+    binary_refs: RwLock<HashMap<String, Arc<[u8]>>>,
 }
 
 impl MemoryLoader {
@@ -36,6 +38,44 @@ impl MemoryLoader {
         let mut mref = self.memory_refs.write().unwrap();
         mref.remove(key.as_ref()).is_some()
     }
+
+    /// Adds a library module importable from function nodes, e.g. `lib/scoring.js`
+    pub fn add_module<K, S>(&self, key: K, source: S)
+    where
+        K: Into<String>,
+        S: Into<Arc<str>>,
+    {
+        if let Ok(mut mref) = self.module_refs.write() {
+            mref.insert(key.into(), source.into());
+        }
+    }
+
+    pub fn get_module<K>(&self, key: K) -> Option<Arc<str>>
+    where
+        K: AsRef<str>,
+    {
+        let mref = self.module_refs.read().ok()?;
+        mref.get(key.as_ref()).cloned()
+    }
+
+    /// Adds a binary asset referenced by nodes, e.g. a WebAssembly module `wasm/score.wasm`
+    pub fn add_binary<K, B>(&self, key: K, bytes: B)
+    where
+        K: Into<String>,
+        B: Into<Arc<[u8]>>,
+    {
+        if let Ok(mut bref) = self.binary_refs.write() {
+            bref.insert(key.into(), bytes.into());
+        }
+    }
+
+    pub fn get_binary<K>(&self, key: K) -> Option<Arc<[u8]>>
+    where
+        K: AsRef<str>,
+    {
+        let bref = self.binary_refs.read().ok()?;
+        bref.get(key.as_ref()).cloned()
+    }
 }
 
 impl DecisionLoader for MemoryLoader {
@@ -60,4 +100,24 @@ impl DecisionLoader for MemoryLoader {
                 .ok_or_else(|| LoaderError::NotFound(key.to_string())),
         )
     }
+
+    fn load_module<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ModuleResponse> + 'a + Send>> {
+        Box::pin(async move {
+            self.get_module(key)
+                .ok_or_else(|| LoaderError::NotFound(key.to_string()))
+        })
+    }
+
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = BinaryResponse> + 'a + Send>> {
+        Box::pin(async move {
+            self.get_binary(key)
+                .ok_or_else(|| LoaderError::NotFound(key.to_string()))
+        })
+    }
 }
@@ -25,6 +25,8 @@ pub type DynamicLoader = Arc<dyn DecisionLoader>;
 
 pub type LoaderResult<T> = Result<T, LoaderError>;
 pub type LoaderResponse = LoaderResult<Arc<DecisionContent>>;
+pub type ModuleResponse = LoaderResult<Arc<str>>;
+pub type BinaryResponse = LoaderResult<Arc<[u8]>>;
 
 /// Trait used for implementing a loader for decisions
 pub trait DecisionLoader: Debug + Send + Sync + DowncastSync {
@@ -40,6 +42,23 @@ pub trait DecisionLoader: Debug + Send + Sync + DowncastSync {
     fn load_sync(&self, _key: &str) -> Option<LoaderResponse> {
         None
     }
+
+    /// Source of a JavaScript or TypeScript library module imported by function nodes,
+    /// e.g. `lib/scoring.js`
+    fn load_module<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ModuleResponse> + 'a + Send>> {
+        Box::pin(async move { Err(LoaderError::NotFound(key.to_string())) })
+    }
+
+    /// Raw bytes of a binary asset referenced by nodes, e.g. a WebAssembly module `wasm/score.wasm`
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = BinaryResponse> + 'a + Send>> {
+        Box::pin(async move { Err(LoaderError::NotFound(key.to_string())) })
+    }
 }
 
 impl_downcast!(sync DecisionLoader);
@@ -1,6 +1,6 @@
 use crate::nodes::definition::{NodeDataType, TraceDataType};
 use crate::nodes::extensions::NodeHandlerExtensions;
-use crate::nodes::function::v2::function::Function;
+use crate::nodes::function::pool::FunctionLease;
 use crate::nodes::result::{NodeResponse, NodeResult};
 use crate::nodes::variable_json::{Guards, VariableNode};
 use crate::nodes::NodeError;
@@ -105,7 +105,7 @@ where
         }
     }
 
-    pub(crate) async fn function_runtime(&self) -> Result<&Function, NodeError> {
+    pub(crate) async fn function_runtime(&self) -> Result<FunctionLease<'_>, NodeError> {
         self.extensions.function_runtime().await.node_context(self)
     }
 
@@ -228,6 +228,8 @@ pub(crate) fn make_isolate(
         isolate.set_local(Variable::nodes_key(), nodes.clone());
     }
 
+    isolate.set_interrupt_handler(extensions.limits.interrupt_handler());
+    extensions.expression_context.apply(&mut isolate);
     isolate
 }
 
@@ -359,6 +361,7 @@ impl<'a> From<ValidationError<'a>> for ValidationErrorJson {
 #[derive(Clone)]
 pub struct NodeContextConfig {
     pub trace: bool,
+    pub concurrent: bool,
     pub nodes_in_context: bool,
     pub max_depth: u8,
     pub function_timeout_millis: u64,
@@ -370,6 +373,7 @@ impl Default for NodeContextConfig {
     fn default() -> Self {
         Self {
             trace: false,
+            concurrent: false,
             nodes_in_context: ZEN_CONFIG.nodes_in_context.load(Ordering::Relaxed),
             function_timeout_millis: ZEN_CONFIG.function_timeout_millis.load(Ordering::Relaxed),
             http_auth: ZEN_CONFIG.http_auth.load(Ordering::Relaxed),
@@ -6,8 +6,10 @@ use zen_types::variable::Variable;
 pub use adapter::{
     CustomDecisionNode, CustomNodeAdapter, CustomNodeRequest, DynamicCustomNode, NoopCustomNode,
 };
+pub use registry::{CustomNodeDefinition, CustomNodeRegistry};
 
 mod adapter;
+mod registry;
 
 #[derive(Debug, Clone)]
 pub struct CustomNodeHandler;
@@ -0,0 +1,154 @@
+use crate::nodes::custom::adapter::{CustomNodeAdapter, CustomNodeRequest, DynamicCustomNode};
+use crate::nodes::result::{NodeError, NodeResult};
+use ahash::HashMap;
+use jsonschema::Validator;
+use serde_json::Value;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use zen_expression::variable::VariableType;
+
+/// Handler of a custom node kind together with its declared contract
+#[derive(Clone)]
+pub struct CustomNodeDefinition {
+    handler: DynamicCustomNode,
+    config_schema: Option<Arc<Value>>,
+    /// Serialized [`VariableType`], which itself is not `Send`
+    output: Option<Arc<Value>>,
+    validator: Arc<OnceLock<Result<Validator, String>>>,
+}
+
+impl Debug for CustomNodeDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomNodeDefinition")
+            .field("handler", &self.handler)
+            .field("config_schema", &self.config_schema)
+            .field("output", &self.output)
+            .finish()
+    }
+}
+
+impl CustomNodeDefinition {
+    pub fn new(handler: DynamicCustomNode) -> Self {
+        Self {
+            handler,
+            config_schema: None,
+            output: None,
+            validator: Default::default(),
+        }
+    }
+
+    /// JSON schema the node `config` must satisfy, checked when decisions are compiled
+    pub fn with_config_schema(mut self, schema: Value) -> Self {
+        self.config_schema = Some(Arc::new(schema));
+        self.validator = Default::default();
+        self
+    }
+
+    /// Type of the node output, used by workspace analysis to check downstream nodes
+    pub fn with_output(mut self, output: &VariableType) -> Self {
+        self.output = serde_json::to_value(output).ok().map(Arc::new);
+        self
+    }
+
+    pub fn handler(&self) -> &DynamicCustomNode {
+        &self.handler
+    }
+
+    pub fn config_schema(&self) -> Option<&Arc<Value>> {
+        self.config_schema.as_ref()
+    }
+
+    pub fn output(&self) -> Option<VariableType> {
+        serde_json::from_value(self.output.as_deref()?.clone()).ok()
+    }
+
+    /// Messages of every violation of the config schema, empty when the config is valid
+    pub fn config_errors(&self, config: &Value) -> Vec<String> {
+        let Some(schema) = &self.config_schema else {
+            return Vec::new();
+        };
+
+        let validator = self.validator.get_or_init(|| {
+            jsonschema::options()
+                .with_draft(jsonschema::Draft::Draft7)
+                .build(schema)
+                .map_err(|err| err.to_string())
+        });
+
+        match validator {
+            Ok(validator) => validator
+                .iter_errors(config)
+                .map(|err| match err.instance_path().as_str() {
+                    "" => err.to_string(),
+                    path => format!("{path}: {err}"),
+                })
+                .collect(),
+            Err(err) => vec![format!("invalid config schema: {err}")],
+        }
+    }
+}
+
+/// Custom node adapter dispatching on the node `kind`
+#[derive(Debug, Clone, Default)]
+pub struct CustomNodeRegistry {
+    definitions: HashMap<Arc<str>, CustomNodeDefinition>,
+}
+
+impl CustomNodeRegistry {
+    pub fn with_node(
+        mut self,
+        kind: impl Into<Arc<str>>,
+        definition: CustomNodeDefinition,
+    ) -> Self {
+        self.register(kind, definition);
+        self
+    }
+
+    pub fn register(&mut self, kind: impl Into<Arc<str>>, definition: CustomNodeDefinition) {
+        self.definitions.insert(kind.into(), definition);
+    }
+
+    pub fn get(&self, kind: &str) -> Option<&CustomNodeDefinition> {
+        self.definitions.get(kind)
+    }
+
+    pub fn kinds(&self) -> Vec<Arc<str>> {
+        self.definitions.keys().cloned().collect()
+    }
+
+    /// Problems with a node of `kind` configured with `config`, empty when it can be evaluated
+    pub fn check(&self, kind: &str, config: &Value) -> Vec<String> {
+        match self.get(kind) {
+            Some(definition) => definition
+                .config_errors(config)
+                .into_iter()
+                .map(|err| format!("invalid config of custom node kind '{kind}': {err}"))
+                .collect(),
+            None => vec![format!(
+                "no handler is registered for custom node kind '{kind}'"
+            )],
+        }
+    }
+}
+
+impl CustomNodeAdapter for CustomNodeRegistry {
+    fn handle(&self, request: CustomNodeRequest) -> Pin<Box<dyn Future<Output = NodeResult> + '_>> {
+        Box::pin(async move {
+            let Some(definition) = self.get(request.node.kind.as_ref()) else {
+                return Err(NodeError {
+                    trace: None,
+                    node_id: request.node.id.clone(),
+                    source: format!(
+                        "No handler registered for custom node kind '{}'",
+                        request.node.kind
+                    )
+                    .into(),
+                });
+            };
+
+            definition.handler.handle(request).await
+        })
+    }
+}
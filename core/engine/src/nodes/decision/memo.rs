@@ -0,0 +1,327 @@
+use crate::observer::MemoOutcome;
+use ahash::{AHasher, HashMap};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use zen_expression::variable::Variable;
+
+/// Reuse of sub-decision results, keyed by decision key, input and the parent `$nodes` the
+/// sub-decision can read.
+///
+/// Applies to decision nodes and `zen.evaluate` calls. Memoized sub-decisions must be
+/// deterministic, a sub-decision calling HTTP endpoints or reading the current time returns the
+/// result of its first evaluation for as long as that result is kept.
+#[derive(Debug, Clone, Default)]
+pub enum Memoization {
+    #[default]
+    Disabled,
+    /// Results are reused for the remainder of the evaluation computing them
+    Evaluation,
+    /// Results are additionally kept in `SubDecisionMemo` for later evaluations
+    Shared(SubDecisionMemo),
+}
+
+impl Memoization {
+    pub(crate) fn evaluation_memo(&self) -> Option<EvaluationMemo> {
+        let shared = match self {
+            Memoization::Disabled => return None,
+            Memoization::Evaluation => None,
+            Memoization::Shared(memo) => Some(memo.clone()),
+        };
+
+        Some(EvaluationMemo(Rc::new(EvaluationEntries {
+            entries: Default::default(),
+            shared,
+        })))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+/// Bounded sub-decision results shared across evaluations, the least recently used entry is
+/// dropped once `capacity` is reached.
+///
+/// Entries are keyed by decision key, not by decision content, so they outlive changes made
+/// through the loader; call [`SubDecisionMemo::clear`] after the loaded decisions change.
+#[derive(Clone)]
+pub struct SubDecisionMemo {
+    inner: Arc<Mutex<SharedEntries>>,
+}
+
+impl Debug for SubDecisionMemo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubDecisionMemo")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct SharedEntries {
+    capacity: usize,
+    entries: HashMap<MemoKey, (Value, u64)>,
+    recency: BTreeMap<u64, MemoKey>,
+    tick: u64,
+    stats: MemoStats,
+}
+
+impl SubDecisionMemo {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SharedEntries {
+                capacity,
+                ..Default::default()
+            })),
+        }
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.inner
+            .lock()
+            .map(|shared| MemoStats {
+                entries: shared.entries.len(),
+                ..shared.stats
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut shared) = self.inner.lock() {
+            shared.entries.clear();
+            shared.recency.clear();
+        }
+    }
+
+    fn get(&self, key: &MemoKey) -> Option<Value> {
+        let mut shared = self.inner.lock().ok()?;
+        shared.tick += 1;
+        let tick = shared.tick;
+
+        let Some((value, used)) = shared.entries.get_mut(key) else {
+            shared.stats.misses += 1;
+            return None;
+        };
+
+        let previous = std::mem::replace(used, tick);
+        let value = value.clone();
+        shared.recency.remove(&previous);
+        shared.recency.insert(tick, key.clone());
+        shared.stats.hits += 1;
+        Some(value)
+    }
+
+    fn insert(&self, key: MemoKey, value: Value) {
+        let Ok(mut shared) = self.inner.lock() else {
+            return;
+        };
+        if shared.capacity == 0 {
+            return;
+        }
+
+        shared.tick += 1;
+        let tick = shared.tick;
+        if let Some((_, previous)) = shared.entries.insert(key.clone(), (value, tick)) {
+            shared.recency.remove(&previous);
+        }
+        shared.recency.insert(tick, key);
+
+        while shared.entries.len() > shared.capacity {
+            let Some((_, oldest)) = shared.recency.pop_first() else {
+                break;
+            };
+            shared.entries.remove(&oldest);
+            shared.stats.evictions += 1;
+        }
+    }
+}
+
+/// Sub-decision, input and parent `$nodes` a result was computed for.
+///
+/// The structural hash only picks the bucket; keys are equal when decision, goals, input and
+/// nodes are, so inputs with colliding hashes never share a result.
+#[derive(Debug, Clone)]
+pub(crate) struct MemoKey {
+    decision: Arc<str>,
+    hash: u64,
+    input: Arc<Value>,
+    nodes: Option<Arc<Value>>,
+    goals: Arc<[Arc<str>]>,
+}
+
+impl MemoKey {
+    /// Inputs or nodes holding dynamic values have no structural hash and are never memoized
+    pub(crate) fn new(
+        decision: &str,
+        input: &Variable,
+        nodes: Option<&Variable>,
+        goals: &[Arc<str>],
+    ) -> Option<Self> {
+        let mut hasher = AHasher::default();
+        hash_variable(input, &mut hasher)?;
+        match nodes {
+            Some(nodes) => hash_variable(nodes, &mut hasher)?,
+            None => hasher.write_u8(0),
+        }
+        goals.hash(&mut hasher);
+
+        Some(Self {
+            decision: Arc::from(decision),
+            hash: hasher.finish(),
+            input: Arc::new(input.to_value()),
+            nodes: nodes.map(|nodes| Arc::new(nodes.to_value())),
+            goals: Arc::from(goals),
+        })
+    }
+}
+
+impl PartialEq for MemoKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.decision == other.decision
+            && self.goals == other.goals
+            && self.input == other.input
+            && self.nodes == other.nodes
+    }
+}
+
+impl Eq for MemoKey {}
+
+impl Hash for MemoKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.decision.hash(state);
+        state.write_u64(self.hash);
+    }
+}
+
+fn hash_variable(variable: &Variable, hasher: &mut AHasher) -> Option<()> {
+    match variable {
+        Variable::Null => hasher.write_u8(0),
+        Variable::Bool(b) => {
+            hasher.write_u8(1);
+            b.hash(hasher);
+        }
+        Variable::Number(n) => {
+            hasher.write_u8(2);
+            n.hash(hasher);
+        }
+        Variable::String(s) => {
+            hasher.write_u8(3);
+            s.as_str().hash(hasher);
+        }
+        Variable::Array(arr) => {
+            let arr = arr.borrow();
+            hasher.write_u8(4);
+            hasher.write_usize(arr.len());
+            for item in arr.iter() {
+                hash_variable(item, hasher)?;
+            }
+        }
+        Variable::Object(obj) => {
+            let obj = obj.borrow();
+            hasher.write_u8(5);
+            hasher.write_usize(obj.len());
+
+            // Entries are combined in an order independent way, matching object equality
+            let mut entries = 0u64;
+            for (key, value) in obj.iter() {
+                let mut entry = AHasher::default();
+                key.as_str().hash(&mut entry);
+                hash_variable(value, &mut entry)?;
+                entries = entries.wrapping_add(entry.finish());
+            }
+            hasher.write_u64(entries);
+        }
+        Variable::Dynamic(_) => return None,
+    }
+
+    Some(())
+}
+
+/// Trace recorded in place of the sub-decision trace when its result comes from the memo
+pub(crate) fn memoized_trace() -> Variable {
+    let trace = Variable::empty_object();
+    trace.dot_insert("memoized", Variable::Bool(true));
+    trace
+}
+
+/// Sub-decision results of one evaluation, shared by all graphs it evaluates
+#[derive(Clone)]
+pub(crate) struct EvaluationMemo(Rc<EvaluationEntries>);
+
+struct EvaluationEntries {
+    entries: RefCell<HashMap<MemoKey, Variable>>,
+    shared: Option<SubDecisionMemo>,
+}
+
+impl Debug for EvaluationMemo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvaluationMemo")
+            .field("entries", &self.0.entries.borrow().len())
+            .field("shared", &self.0.shared)
+            .finish()
+    }
+}
+
+impl EvaluationMemo {
+    pub(crate) fn get(&self, key: &MemoKey) -> (Option<Variable>, MemoOutcome) {
+        if let Some(result) = self.0.entries.borrow().get(key) {
+            return (Some(result.deep_clone()), MemoOutcome::Hit);
+        }
+
+        let Some(value) = self.0.shared.as_ref().and_then(|shared| shared.get(key)) else {
+            return (None, MemoOutcome::Miss);
+        };
+
+        let result = Variable::from(value);
+        self.0
+            .entries
+            .borrow_mut()
+            .insert(key.clone(), result.deep_clone());
+        (Some(result), MemoOutcome::SharedHit)
+    }
+
+    pub(crate) fn insert(&self, key: MemoKey, result: &Variable) {
+        if let Some(shared) = &self.0.shared {
+            shared.insert(key.clone(), result.to_value());
+        }
+
+        self.0.entries.borrow_mut().insert(key, result.deep_clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn colliding_inputs_do_not_share_results() {
+        let key = |input: Value| MemoKey::new("table", &Variable::from(input), None, &[]).unwrap();
+        let first = key(json!({ "amount": 1 }));
+        let mut second = key(json!({ "amount": 2 }));
+        second.hash = first.hash;
+
+        let memo = SubDecisionMemo::new(8);
+        memo.insert(first.clone(), json!("first"));
+        assert_eq!(memo.get(&second), None);
+        assert_eq!(memo.get(&key(json!({ "amount": 1 }))), Some(json!("first")));
+
+        let evaluation = Memoization::Shared(memo).evaluation_memo().unwrap();
+        evaluation.insert(second.clone(), &Variable::from(json!("second")));
+        let (result, outcome) = evaluation.get(&first);
+        assert_eq!(result.map(|v| v.to_value()), Some(json!("first")));
+        assert_eq!(outcome, MemoOutcome::SharedHit);
+        let (result, _) = evaluation.get(&second);
+        assert_eq!(result.map(|v| v.to_value()), Some(json!("second")));
+    }
+}
@@ -1,15 +1,28 @@
-use crate::decision_graph::graph::{DecisionGraph, DecisionGraphConfig};
+use crate::decision_graph::graph::{
+    check_depth, DecisionGraph, DecisionGraphConfig, EvaluationTrace,
+};
+use crate::model::DecisionContent;
+use crate::nodes::decision::memo::{memoized_trace, EvaluationMemo, MemoKey};
 use crate::nodes::{NodeContext, NodeContextExt, NodeError, NodeHandler, NodeResult};
-use crate::EvaluationError;
+use crate::observer::{MemoLookup, SubDecision};
+use crate::policy::runtime::PolicyRuntime;
 use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 use zen_types::decision::{DecisionNodeContent, TransformAttributes};
 use zen_types::variable::{ToVariable, Variable};
 
+pub mod memo;
+
 #[derive(Debug, Clone, Default)]
 pub struct DecisionNodeHandler {
-    decision_graph: Rc<RefCell<Option<DecisionGraph>>>,
+    sub_decision: Rc<RefCell<Option<SubDecisionRuntime>>>,
+}
+
+#[derive(Debug)]
+enum SubDecisionRuntime {
+    Graph(Box<DecisionGraph>),
+    Policy(Box<PolicyRuntime>),
 }
 
 pub type DecisionNodeData = DecisionNodeContent;
@@ -26,11 +39,16 @@ impl NodeHandler for DecisionNodeHandler {
         Some(ctx.node.transform_attributes.clone())
     }
 
+    /// Loop items evaluated concurrently each need a sub-decision of their own
+    fn fork(&self) -> Self {
+        Self::default()
+    }
+
     async fn after_transform_attributes(
         &self,
         _ctx: &NodeContext<Self::NodeData, Self::TraceData>,
     ) -> Result<(), NodeError> {
-        if let Some(graph) = self.decision_graph.borrow_mut().as_mut() {
+        if let Some(SubDecisionRuntime::Graph(graph)) = self.sub_decision.borrow_mut().as_mut() {
             graph.reset_graph();
         };
 
@@ -38,70 +56,75 @@ impl NodeHandler for DecisionNodeHandler {
     }
 
     async fn handle(&self, ctx: NodeContext<Self::NodeData, Self::TraceData>) -> NodeResult {
-        let mut decision_graph_ref = self.decision_graph.borrow_mut();
-
-        if decision_graph_ref.is_none() {
-            let loader = ctx.extensions.loader();
-            let sub_decision = loader.load(ctx.node.key.deref()).await.node_context(&ctx)?;
-            let sub_kind = sub_decision.kind();
-            let Some(sub_graph) = sub_decision.into_graph_arc() else {
-                return ctx.error(format!(
-                    "sub-decision '{}' is a {sub_kind}, expected graph",
-                    ctx.node.key
-                ));
-            };
+        let (memoized, memo) = Self::lookup_memo(&ctx);
+        if let Some(result) = memoized {
+            ctx.trace(|trace| *trace = memoized_trace());
+            return ctx.success(result);
+        }
 
-            let sub_graph =
-                if sub_graph.compiled_cache.is_some() && sub_graph.resolved_schemas.is_some() {
-                    sub_graph
-                } else {
-                    let mut owned = (*sub_graph).clone();
-                    owned.compile();
-                    let _ = owned.resolve_schemas(loader).await;
-                    std::sync::Arc::new(owned)
-                };
-
-            let mut extensions = ctx.extensions.clone();
-            extensions.compiled_cache = sub_graph.compiled_cache.clone();
-            extensions.dt_indexes = sub_graph.dt_indexes.clone();
-            extensions.validator_cache =
-                std::sync::Arc::new(std::cell::OnceCell::from(sub_graph.validator_cache.clone()));
-
-            let dg = DecisionGraph::try_new(DecisionGraphConfig {
-                content: sub_graph,
-                extensions,
-                trace: ctx.config.trace,
-                iteration: ctx.iteration + 1,
-                max_depth: ctx.config.max_depth,
-            })
-            .node_context(&ctx)?;
-
-            *decision_graph_ref = Some(dg);
+        let mut sub_decision_ref = self.sub_decision.borrow_mut();
+
+        if sub_decision_ref.is_none() {
+            *sub_decision_ref = Some(Self::load_sub_decision(&ctx).await?);
         }
 
-        let Some(decision_graph) = decision_graph_ref.as_mut() else {
-            return ctx.error("Failed to initialize decision graph".to_string());
+        let Some(sub_decision) = sub_decision_ref.as_mut() else {
+            return ctx.error("Failed to initialize sub-decision".to_string());
         };
 
-        decision_graph.set_parent_nodes(ctx.nodes.clone());
+        if let Some(observer) = &ctx.extensions.observer {
+            observer.on_sub_decision(&SubDecision {
+                node_id: ctx.id.deref(),
+                key: ctx.node.key.deref(),
+                depth: ctx.iteration + 1,
+            });
+        }
+
+        let evaluate_result = match sub_decision {
+            SubDecisionRuntime::Graph(decision_graph) => {
+                decision_graph.set_parent_nodes(ctx.nodes.clone());
+                Box::pin(decision_graph.evaluate(ctx.input.clone())).await
+            }
+            SubDecisionRuntime::Policy(policy) => {
+                match check_depth(ctx.iteration + 1, ctx.config.max_depth) {
+                    Ok(()) => {
+                        policy
+                            .evaluate(
+                                ctx.input.clone(),
+                                ctx.node.goals.clone(),
+                                ctx.config.trace,
+                                &ctx.extensions.limits,
+                            )
+                            .await
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+        };
 
-        let evaluate_result = Box::pin(decision_graph.evaluate(ctx.input.clone())).await;
         match evaluate_result {
             Ok(result) => {
                 ctx.trace(|trace| {
-                    *trace = result
-                        .trace
-                        .and_then(|t| t.into_graph())
-                        .as_ref()
-                        .map(|m| m.to_variable())
-                        .unwrap_or(Variable::Null);
+                    *trace = match result.trace {
+                        Some(EvaluationTrace::Graph(graph_trace)) => graph_trace.to_variable(),
+                        Some(EvaluationTrace::Policy(policy_trace)) => {
+                            serde_json::to_value(policy_trace)
+                                .map(Variable::from)
+                                .unwrap_or(Variable::Null)
+                        }
+                        None => Variable::Null,
+                    };
                 });
 
+                if let Some((memo, key)) = memo {
+                    memo.insert(key, &result.result);
+                }
+
                 ctx.success(result.result)
             }
             Err(err) => {
-                if let EvaluationError::NodeError { trace, .. } = err.deref() {
-                    ctx.trace(|t| *t = trace.to_variable());
+                if let Some(trace) = err.trace() {
+                    ctx.trace(|t| *t = trace.clone());
                 }
 
                 ctx.error(err.to_string())
@@ -109,3 +132,88 @@ impl NodeHandler for DecisionNodeHandler {
         }
     }
 }
+
+impl DecisionNodeHandler {
+    /// Memoized result for the node input, along with the memo entry to fill when there is none
+    fn lookup_memo(
+        ctx: &NodeContext<DecisionNodeData, DecisionNodeTrace>,
+    ) -> (Option<Variable>, Option<(EvaluationMemo, MemoKey)>) {
+        let Some(memo) = ctx.extensions.memo.clone() else {
+            return (None, None);
+        };
+        let Some(key) = MemoKey::new(
+            ctx.node.key.deref(),
+            &ctx.input,
+            ctx.nodes.as_ref(),
+            &ctx.node.goals,
+        ) else {
+            return (None, None);
+        };
+
+        let (result, outcome) = memo.get(&key);
+        if let Some(observer) = &ctx.extensions.observer {
+            observer.on_memo(&MemoLookup {
+                key: ctx.node.key.deref(),
+                depth: ctx.iteration + 1,
+                outcome,
+            });
+        }
+
+        match result {
+            Some(result) => (Some(result), None),
+            None => (None, Some((memo, key))),
+        }
+    }
+
+    async fn load_sub_decision(
+        ctx: &NodeContext<DecisionNodeData, DecisionNodeTrace>,
+    ) -> Result<SubDecisionRuntime, NodeError> {
+        let loader = ctx.extensions.loader();
+        let sub_decision = loader.load(ctx.node.key.deref()).await.node_context(ctx)?;
+        if let DecisionContent::Policy(_) = sub_decision.as_ref() {
+            let policy = PolicyRuntime::load(
+                loader,
+                ctx.node.key.deref(),
+                sub_decision,
+                &ctx.extensions.property_resolver,
+                &ctx.extensions.expression_context,
+            )
+            .await
+            .node_context(ctx)?;
+
+            return Ok(SubDecisionRuntime::Policy(Box::new(policy)));
+        }
+
+        let Some(sub_graph) = sub_decision.into_graph_arc() else {
+            return Err(ctx.make_error("Failed to load sub-decision graph".to_string()));
+        };
+
+        let sub_graph =
+            if sub_graph.compiled_cache.is_some() && sub_graph.resolved_schemas.is_some() {
+                sub_graph
+            } else {
+                let mut owned = (*sub_graph).clone();
+                owned.compile();
+                let _ = owned.resolve_schemas(loader).await;
+                std::sync::Arc::new(owned)
+            };
+
+        let mut extensions = ctx.extensions.clone();
+        extensions.compiled_cache = sub_graph.compiled_cache.clone();
+        extensions.dt_indexes = sub_graph.dt_indexes.clone();
+        extensions.validator_cache =
+            std::sync::Arc::new(std::cell::OnceCell::from(sub_graph.validator_cache.clone()));
+
+        let dg = DecisionGraph::try_new(DecisionGraphConfig {
+            content: sub_graph,
+            extensions,
+            trace: ctx.config.trace,
+            concurrent: ctx.config.concurrent,
+            iteration: ctx.iteration + 1,
+            max_depth: ctx.config.max_depth,
+        })
+        .node_context(ctx)?;
+
+        Ok(SubDecisionRuntime::Graph(Box::new(dg)))
+    }
+}
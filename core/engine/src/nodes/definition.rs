@@ -32,6 +32,11 @@ pub trait NodeHandler: Clone {
         Box::pin(async { Ok(()) })
     }
 
+    /// Handler evaluating loop items next to this one, it must not share state `handle` changes
+    fn fork(&self) -> Self {
+        self.clone()
+    }
+
     fn handle(
         &self,
         ctx: NodeContext<Self::NodeData, Self::TraceData>,
@@ -1,12 +1,20 @@
+use crate::cancellation::EvaluationLimits;
+use crate::expression_context::ExpressionContext;
 use crate::loader::{DynamicLoader, NoopLoader};
 use crate::nodes::custom::{DynamicCustomNode, NoopCustomNode};
+use crate::nodes::decision::memo::EvaluationMemo;
 use crate::nodes::decision_table::index::TableIndex;
 use crate::nodes::function::http_handler::DynamicHttpHandler;
+use crate::nodes::function::pool::{FunctionLease, FunctionRuntimeCell, PooledFunction};
 use crate::nodes::function::v2::function::{Function, FunctionConfig};
+use crate::nodes::function::v2::listener::RuntimeListener;
 use crate::nodes::function::v2::module::console::ConsoleListener;
 use crate::nodes::function::v2::module::http::listener::HttpListener;
 use crate::nodes::function::v2::module::zen::ZenListener;
 use crate::nodes::validator_cache::ValidatorCache;
+use crate::nodes::wasm::WasmRuntime;
+use crate::observer::DynamicEvaluationObserver;
+use crate::policy::resolver::DynamicPropertyResolver;
 use anyhow::Context;
 use std::cell::OnceCell;
 use std::sync::Arc;
@@ -15,14 +23,20 @@ use zen_expression::OpcodeCache;
 /// This is created on every graph evaluation
 #[derive(Debug, Clone)]
 pub struct NodeHandlerExtensions {
-    pub(crate) function_runtime: Arc<tokio::sync::OnceCell<Function>>,
+    pub(crate) function_runtime: Arc<FunctionRuntimeCell>,
     pub(crate) validator_cache: Arc<OnceCell<ValidatorCache>>,
+    pub(crate) wasm_runtime: Arc<OnceCell<WasmRuntime>>,
     pub(crate) loader: DynamicLoader,
     pub(crate) custom_node: DynamicCustomNode,
     pub(crate) http_handler: DynamicHttpHandler,
     pub(crate) compiled_cache: Option<Arc<OpcodeCache>>,
     pub(crate) stripped_functions: Option<Arc<ahash::HashMap<Arc<str>, Arc<str>>>>,
     pub(crate) dt_indexes: Option<Arc<ahash::HashMap<Arc<str>, TableIndex>>>,
+    pub(crate) limits: EvaluationLimits,
+    pub(crate) observer: DynamicEvaluationObserver,
+    pub(crate) property_resolver: DynamicPropertyResolver,
+    pub(crate) expression_context: ExpressionContext,
+    pub(crate) memo: Option<EvaluationMemo>,
 }
 
 impl Default for NodeHandlerExtensions {
@@ -30,6 +44,7 @@ impl Default for NodeHandlerExtensions {
         Self {
             function_runtime: Default::default(),
             validator_cache: Default::default(),
+            wasm_runtime: Default::default(),
 
             loader: Arc::new(NoopLoader::default()),
             custom_node: Arc::new(NoopCustomNode::default()),
@@ -37,30 +52,55 @@ impl Default for NodeHandlerExtensions {
             stripped_functions: None,
             dt_indexes: None,
             http_handler: None,
+            limits: Default::default(),
+            observer: None,
+            property_resolver: None,
+            expression_context: Default::default(),
+            memo: None,
         }
     }
 }
 
 impl NodeHandlerExtensions {
-    pub async fn function_runtime(&self) -> anyhow::Result<&Function> {
-        self.function_runtime
-            .get_or_try_init(|| {
-                Function::create(FunctionConfig {
-                    listeners: Some(vec![
-                        Box::new(ConsoleListener),
-                        Box::new(HttpListener {
-                            http_handler: self.http_handler.clone(),
-                        }),
-                        Box::new(ZenListener {
-                            loader: self.loader.clone(),
-                            custom_node: self.custom_node.clone(),
-                            http_handler: self.http_handler.clone(),
-                        }),
-                    ]),
+    pub async fn function_runtime(&self) -> anyhow::Result<FunctionLease<'_>> {
+        let runtime = &self.function_runtime;
+        let function = match runtime.take_idle() {
+            Some(function) => function,
+            None => match &runtime.pool {
+                // Boxed so the renew and isolate futures do not grow every node future
+                Some(pool) => Box::pin(pool.acquire(|| self.function_listeners())).await,
+                None => Function::create(FunctionConfig {
+                    listeners: Some(self.function_listeners()),
                 })
-            })
-            .await
-            .context("Failed to create function")
+                .await
+                .map(PooledFunction::from),
+            }
+            .context("Failed to create function")?,
+        };
+
+        Ok(runtime.lease(function))
+    }
+
+    fn function_listeners(&self) -> Vec<Box<dyn RuntimeListener>> {
+        vec![
+            Box::new(ConsoleListener),
+            Box::new(HttpListener {
+                http_handler: self.http_handler.clone(),
+                observer: self.observer.clone(),
+            }),
+            Box::new(ZenListener {
+                loader: self.loader.clone(),
+                custom_node: self.custom_node.clone(),
+                http_handler: self.http_handler.clone(),
+                limits: self.limits.clone(),
+                observer: self.observer.clone(),
+                property_resolver: self.property_resolver.clone(),
+                expression_context: self.expression_context.clone(),
+                function_pool: self.function_runtime.pool.clone(),
+                wasm_runtime: self.wasm_runtime.clone(),
+                memo: self.memo.clone(),
+            }),
+        ]
     }
 
     pub fn validator_cache(&self) -> &ValidatorCache {
@@ -68,6 +108,10 @@ impl NodeHandlerExtensions {
             .get_or_init(|| ValidatorCache::default())
     }
 
+    pub fn wasm_runtime(&self) -> &WasmRuntime {
+        self.wasm_runtime.get_or_init(WasmRuntime::default)
+    }
+
     pub fn custom_node(&self) -> &DynamicCustomNode {
         &self.custom_node
     }
@@ -0,0 +1,82 @@
+use super::{HttpHandler, HttpMock, MockHttpHandler, ReplayHttpHandler};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Serializable description of the built-in handlers, used by language bindings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "mode",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum HttpHandlerConfig {
+    /// Serves `mocks` followed by the mocks stored in the JSON file at `path`
+    Mock {
+        #[serde(default)]
+        mocks: Vec<HttpMock>,
+        #[serde(default)]
+        path: Option<String>,
+    },
+    /// Performs real requests and records them to `path`, with the values of
+    /// `redacted_headers` (default: [`DEFAULT_REDACTED_HEADERS`]) and `redacted_query_params`
+    /// (default: [`DEFAULT_REDACTED_QUERY_PARAMS`]) replaced
+    ///
+    /// [`DEFAULT_REDACTED_HEADERS`]: super::DEFAULT_REDACTED_HEADERS
+    /// [`DEFAULT_REDACTED_QUERY_PARAMS`]: super::DEFAULT_REDACTED_QUERY_PARAMS
+    Record {
+        path: String,
+        #[serde(default)]
+        redacted_headers: Option<Vec<String>>,
+        #[serde(default)]
+        redacted_query_params: Option<Vec<String>>,
+    },
+    /// Serves the exchanges recorded to `path`
+    Replay { path: String },
+}
+
+impl HttpHandlerConfig {
+    pub fn into_http_handler(self) -> anyhow::Result<Arc<dyn HttpHandler + Send + Sync>> {
+        match self {
+            HttpHandlerConfig::Mock { mut mocks, path } => {
+                if let Some(path) = path {
+                    let contents = std::fs::read(path)?;
+                    mocks.extend(serde_json::from_slice::<Vec<HttpMock>>(&contents)?);
+                }
+
+                Ok(Arc::new(MockHttpHandler::new(mocks)))
+            }
+            HttpHandlerConfig::Record {
+                path,
+                redacted_headers,
+                redacted_query_params,
+            } => Self::recording_handler(path, redacted_headers, redacted_query_params),
+            HttpHandlerConfig::Replay { path } => Ok(Arc::new(ReplayHttpHandler::from_file(path)?)),
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn recording_handler(
+        path: String,
+        redacted_headers: Option<Vec<String>>,
+        redacted_query_params: Option<Vec<String>>,
+    ) -> anyhow::Result<Arc<dyn HttpHandler + Send + Sync>> {
+        let mut handler =
+            super::RecordingHttpHandler::new(Arc::new(super::NativeHttpHandler), path);
+        if let Some(headers) = redacted_headers {
+            handler = handler.with_redacted_headers(headers);
+        }
+        if let Some(params) = redacted_query_params {
+            handler = handler.with_redacted_query_params(params);
+        }
+        Ok(Arc::new(handler))
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn recording_handler(
+        _path: String,
+        _redacted_headers: Option<Vec<String>>,
+        _redacted_query_params: Option<Vec<String>>,
+    ) -> anyhow::Result<Arc<dyn HttpHandler + Send + Sync>> {
+        anyhow::bail!("Recording HTTP requests is not available in WASM environment")
+    }
+}
@@ -0,0 +1,178 @@
+use super::{HttpHandler, HttpHandlerRequest, HttpHandlerResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+
+/// Request predicate of an [`HttpMock`], fields left unset match any request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRequestMatcher {
+    /// Compared case-insensitively
+    #[serde(default)]
+    pub method: Option<String>,
+    /// URL without `params`, `*` matches any sequence of characters
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Expected body, objects match when every listed field matches
+    #[serde(default)]
+    pub body: Option<Value>,
+}
+
+impl HttpRequestMatcher {
+    pub fn matches(&self, request: &HttpHandlerRequest) -> bool {
+        if let Some(method) = &self.method {
+            if !method.eq_ignore_ascii_case(&request.method) {
+                return false;
+            }
+        }
+
+        if let Some(url) = &self.url {
+            if !wildcard_match(url, &request.url) {
+                return false;
+            }
+        }
+
+        match (&self.body, &request.body) {
+            (None, _) => true,
+            (Some(expected), Some(actual)) => body_matches(expected, actual),
+            (Some(expected), None) => expected.is_null(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpMock {
+    #[serde(rename = "match", default)]
+    pub matcher: HttpRequestMatcher,
+    pub response: HttpHandlerResponse,
+}
+
+/// Serves fixed responses, the first mock matching a request wins
+#[derive(Debug, Default)]
+pub struct MockHttpHandler {
+    mocks: Vec<HttpMock>,
+}
+
+impl MockHttpHandler {
+    pub fn new(mocks: Vec<HttpMock>) -> Self {
+        Self { mocks }
+    }
+
+    /// Reads mocks from a JSON array of `{ "match": ..., "response": ... }` objects
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let contents = std::fs::read(path)?;
+        Ok(Self::new(serde_json::from_slice(&contents)?))
+    }
+
+    pub fn with_mock(mut self, matcher: HttpRequestMatcher, response: HttpHandlerResponse) -> Self {
+        self.mocks.push(HttpMock { matcher, response });
+        self
+    }
+}
+
+impl HttpHandler for MockHttpHandler {
+    fn handle(
+        &self,
+        request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        let response = self
+            .mocks
+            .iter()
+            .find(|mock| mock.matcher.matches(&request))
+            .map(|mock| mock.response.clone())
+            .ok_or_else(|| format!("No HTTP mock matches {} {}", request.method, request.url));
+
+        Box::pin(async move { response })
+    }
+}
+
+fn body_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| body_matches(value, a))),
+        _ => expected == actual,
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == value;
+    };
+
+    let Some(mut remaining) = value.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    remaining.len() >= suffix.len() && remaining.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(method: &str, url: &str, body: Option<Value>) -> HttpHandlerRequest {
+        HttpHandlerRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            body,
+            headers: Default::default(),
+            params: Default::default(),
+            auth: None,
+        }
+    }
+
+    #[test]
+    fn wildcard_urls() {
+        assert!(wildcard_match(
+            "https://api.test/*",
+            "https://api.test/users/1"
+        ));
+        assert!(wildcard_match(
+            "https://*.test/users/*",
+            "https://api.test/users/1"
+        ));
+        assert!(wildcard_match("*/users/1", "https://api.test/users/1"));
+        assert!(!wildcard_match(
+            "https://api.test/*/orders",
+            "https://api.test/users/1"
+        ));
+        assert!(!wildcard_match(
+            "https://api.test/users",
+            "https://api.test/users/1"
+        ));
+        assert!(!wildcard_match(
+            "https://api.test/*1*1",
+            "https://api.test/1"
+        ));
+    }
+
+    #[test]
+    fn matcher_checks_method_and_body_subset() {
+        let matcher = HttpRequestMatcher {
+            method: Some("post".to_string()),
+            url: Some("https://api.test/*".to_string()),
+            body: Some(json!({ "customer": { "tier": "gold" } })),
+        };
+
+        let body = json!({ "customer": { "tier": "gold", "id": 1 }, "total": 10 });
+        assert!(matcher.matches(&request("POST", "https://api.test/quote", Some(body))));
+        assert!(!matcher.matches(&request("GET", "https://api.test/quote", None)));
+
+        let body = json!({ "customer": { "tier": "silver" } });
+        assert!(!matcher.matches(&request("POST", "https://api.test/quote", Some(body))));
+        assert!(HttpRequestMatcher::default().matches(&request("GET", "https://other", None)));
+    }
+}
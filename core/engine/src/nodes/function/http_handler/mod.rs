@@ -6,6 +6,21 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+mod config;
+mod mock;
+#[cfg(not(target_family = "wasm"))]
+mod native;
+mod recording;
+
+pub use config::HttpHandlerConfig;
+pub use mock::{HttpMock, HttpRequestMatcher, MockHttpHandler};
+#[cfg(not(target_family = "wasm"))]
+pub use native::NativeHttpHandler;
+pub use recording::{
+    HttpExchange, RecordingHttpHandler, ReplayHttpHandler, DEFAULT_REDACTED_HEADERS,
+    DEFAULT_REDACTED_QUERY_PARAMS, REDACTED,
+};
+
 pub trait HttpHandler: Debug + Send + Sync {
     fn handle(
         &self,
@@ -32,7 +47,9 @@ pub struct HttpHandlerRequest {
 #[serde(rename_all = "camelCase")]
 pub struct HttpHandlerResponse {
     pub status: u16,
+    #[serde(default)]
     pub headers: Value,
+    #[serde(default)]
     pub data: Value,
 }
 
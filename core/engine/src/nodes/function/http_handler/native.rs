@@ -0,0 +1,21 @@
+use super::{HttpHandler, HttpHandlerRequest, HttpHandlerResponse};
+use crate::nodes::function::v2::module::http::backend::native::execute_handler_request;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Performs requests over the network, the same way function nodes do when no handler is set
+#[derive(Debug, Default)]
+pub struct NativeHttpHandler;
+
+impl HttpHandler for NativeHttpHandler {
+    fn handle(
+        &self,
+        request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        Box::pin(async move {
+            execute_handler_request(request)
+                .await
+                .map_err(|err| err.to_string())
+        })
+    }
+}
@@ -0,0 +1,341 @@
+use super::{HttpHandler, HttpHandlerRequest, HttpHandlerResponse};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Request and response pair as stored in recording files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpExchange {
+    pub request: HttpHandlerRequest,
+    pub response: HttpHandlerResponse,
+}
+
+impl HttpExchange {
+    /// Recorded query parameters holding [`REDACTED`] match any value
+    fn replays(&self, request: &HttpHandlerRequest) -> bool {
+        let params = self.request.params.len() == request.params.len()
+            && self.request.params.iter().all(|(name, recorded)| {
+                request
+                    .params
+                    .get(name)
+                    .is_some_and(|value| recorded == REDACTED || recorded == value)
+            });
+
+        self.request.method.eq_ignore_ascii_case(&request.method)
+            && url_replays(&self.request.url, &request.url)
+            && params
+            && self.request.body == request.body
+    }
+}
+
+fn url_replays(recorded: &str, url: &str) -> bool {
+    if recorded == url {
+        return true;
+    }
+
+    let (recorded_base, recorded_query) = split_query(recorded);
+    let (base, query) = split_query(url);
+    let (Some(recorded_query), Some(query)) = (recorded_query, query) else {
+        return false;
+    };
+
+    let recorded_pairs: Vec<&str> = recorded_query.split('&').collect();
+    let pairs: Vec<&str> = query.split('&').collect();
+    recorded_base == base
+        && recorded_pairs.len() == pairs.len()
+        && recorded_pairs.iter().zip(&pairs).all(|(recorded, pair)| {
+            recorded == pair
+                || match (recorded.split_once('='), pair.split_once('=')) {
+                    (Some((recorded_name, REDACTED)), Some((name, _))) => recorded_name == name,
+                    _ => false,
+                }
+        })
+}
+
+/// Splits `url` into the part before the query, the fragment included, and its query
+fn split_query(url: &str) -> (String, Option<&str>) {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let (base, query) = match rest.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (rest, None),
+    };
+
+    let base = match fragment {
+        Some(fragment) => format!("{base}#{fragment}"),
+        None => base.to_string(),
+    };
+    (base, query)
+}
+
+/// Headers whose values are replaced by [`REDACTED`] in recordings unless configured otherwise
+pub const DEFAULT_REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// Query parameters whose values are replaced by [`REDACTED`] in recordings unless configured
+/// otherwise
+pub const DEFAULT_REDACTED_QUERY_PARAMS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "access_token",
+    "token",
+    "key",
+    "secret",
+    "client_secret",
+    "password",
+    "signature",
+];
+
+/// Value recorded in place of redacted header and query parameter values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Forwards requests to `inner` and keeps every successful exchange, which [`flush`] writes to
+/// a JSON file that [`ReplayHttpHandler`] can serve back later. Exchanges not flushed yet are
+/// written when the handler is dropped.
+///
+/// Recordings usually end up committed next to the decisions they exercise, so credentials
+/// are left out: request `auth` is dropped and the values of sensitive request and response
+/// headers and of sensitive query parameters, in the URL and in `params`, are redacted.
+///
+/// [`flush`]: RecordingHttpHandler::flush
+#[derive(Debug)]
+pub struct RecordingHttpHandler {
+    inner: Arc<dyn HttpHandler + Send + Sync>,
+    path: PathBuf,
+    redacted_headers: Vec<String>,
+    redacted_query_params: Vec<String>,
+    exchanges: Mutex<Recording>,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    exchanges: Vec<HttpExchange>,
+    flushed: bool,
+}
+
+impl RecordingHttpHandler {
+    pub fn new<P: Into<PathBuf>>(inner: Arc<dyn HttpHandler + Send + Sync>, path: P) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            redacted_headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(|header| header.to_string())
+                .collect(),
+            redacted_query_params: DEFAULT_REDACTED_QUERY_PARAMS
+                .iter()
+                .map(|param| param.to_string())
+                .collect(),
+            exchanges: Default::default(),
+        }
+    }
+
+    /// Replaces the headers redacted in recordings, matched case-insensitively
+    pub fn with_redacted_headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.redacted_headers = headers
+            .into_iter()
+            .map(|header| header.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Replaces the query parameters redacted in recordings, matched case-insensitively
+    pub fn with_redacted_query_params<I, S>(mut self, params: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.redacted_query_params = params
+            .into_iter()
+            .map(|param| param.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    pub fn exchanges(&self) -> Vec<HttpExchange> {
+        self.exchanges
+            .lock()
+            .map(|recording| recording.exchanges.clone())
+            .unwrap_or_default()
+    }
+
+    /// Writes the exchanges recorded so far to the recording file
+    pub fn flush(&self) -> anyhow::Result<()> {
+        let mut recording = self
+            .exchanges
+            .lock()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+
+        let contents = serde_json::to_vec_pretty(&recording.exchanges)?;
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write recording {}", self.path.display()))?;
+
+        recording.flushed = true;
+        Ok(())
+    }
+
+    fn record(&self, mut exchange: HttpExchange) -> Result<(), String> {
+        exchange.request.auth = None;
+        exchange.request.url = self.redact_url(&exchange.request.url);
+        for (name, value) in exchange.request.params.iter_mut() {
+            if self.is_redacted_param(name) {
+                *value = REDACTED.to_string();
+            }
+        }
+        for (name, value) in exchange.request.headers.iter_mut() {
+            if self.is_redacted(name) {
+                *value = REDACTED.to_string();
+            }
+        }
+        if let Value::Object(headers) = &mut exchange.response.headers {
+            for (name, value) in headers.iter_mut() {
+                if self.is_redacted(name) {
+                    *value = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+
+        let mut recording = self.exchanges.lock().map_err(|err| err.to_string())?;
+        recording.exchanges.push(exchange);
+        recording.flushed = false;
+        Ok(())
+    }
+
+    fn is_redacted(&self, header: &str) -> bool {
+        self.redacted_headers
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(header))
+    }
+
+    fn is_redacted_param(&self, param: &str) -> bool {
+        self.redacted_query_params
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(param))
+    }
+
+    fn redact_url(&self, url: &str) -> String {
+        let (base, Some(query)) = split_query(url) else {
+            return url.to_string();
+        };
+
+        let query: Vec<String> = query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.is_redacted_param(name) => format!("{name}={REDACTED}"),
+                _ => pair.to_string(),
+            })
+            .collect();
+        match base.split_once('#') {
+            Some((base, fragment)) => format!("{base}?{}#{fragment}", query.join("&")),
+            None => format!("{base}?{}", query.join("&")),
+        }
+    }
+}
+
+impl Drop for RecordingHttpHandler {
+    fn drop(&mut self) {
+        let pending = self
+            .exchanges
+            .get_mut()
+            .is_ok_and(|recording| !recording.flushed && !recording.exchanges.is_empty());
+        if pending {
+            // Nothing is left to report the failure to, recordings that matter are flushed
+            let _ = self.flush();
+        }
+    }
+}
+
+impl HttpHandler for RecordingHttpHandler {
+    fn handle(
+        &self,
+        request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        Box::pin(async move {
+            let response = self.inner.handle(request.clone()).await?;
+            self.record(HttpExchange {
+                request,
+                response: response.clone(),
+            })?;
+
+            Ok(response)
+        })
+    }
+}
+
+/// Serves exchanges captured by [`RecordingHttpHandler`] and fails on requests that were not
+/// recorded. Identical requests are served in recording order, repeating the last one once
+/// all have been used. Query parameters recorded as [`REDACTED`] match any value.
+#[derive(Debug)]
+pub struct ReplayHttpHandler {
+    exchanges: Vec<HttpExchange>,
+    served: Mutex<Vec<bool>>,
+}
+
+impl ReplayHttpHandler {
+    pub fn new(exchanges: Vec<HttpExchange>) -> Self {
+        Self {
+            served: Mutex::new(vec![false; exchanges.len()]),
+            exchanges,
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let contents = std::fs::read(path)?;
+        Ok(Self::new(serde_json::from_slice(&contents)?))
+    }
+
+    fn replay(&self, request: &HttpHandlerRequest) -> Result<HttpHandlerResponse, String> {
+        let mut served = self.served.lock().map_err(|err| err.to_string())?;
+        let mut candidates = self
+            .exchanges
+            .iter()
+            .enumerate()
+            .filter(|(_, exchange)| exchange.replays(request))
+            .map(|(index, _)| index)
+            .peekable();
+
+        let Some(&first) = candidates.peek() else {
+            return Err(format!(
+                "No recorded exchange matches {} {}",
+                request.method, request.url
+            ));
+        };
+
+        let mut last = first;
+        for index in candidates {
+            if !served[index] {
+                served[index] = true;
+                return Ok(self.exchanges[index].response.clone());
+            }
+
+            last = index;
+        }
+
+        Ok(self.exchanges[last].response.clone())
+    }
+}
+
+impl HttpHandler for ReplayHttpHandler {
+    fn handle(
+        &self,
+        request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        let response = self.replay(&request);
+        Box::pin(async move { response })
+    }
+}
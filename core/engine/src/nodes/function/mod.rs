@@ -1,4 +1,5 @@
 pub mod http_handler;
+pub mod pool;
 pub(crate) mod v1;
 pub(crate) mod v2;
 
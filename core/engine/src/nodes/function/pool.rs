@@ -0,0 +1,252 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ahash::HashMap;
+
+use crate::nodes::function::v2::error::FunctionResult;
+use crate::nodes::function::v2::function::{Function, FunctionConfig};
+use crate::nodes::function::v2::listener::RuntimeListener;
+
+static NEXT_POOL_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // QuickJS runtimes cannot move between threads, so each thread keeps its own idle runtimes
+    static IDLE: RefCell<HashMap<u64, Vec<IdleFunction>>> = RefCell::default();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionPoolConfig {
+    /// Idle runtimes kept per thread
+    pub max_idle: usize,
+    /// Evaluations a runtime serves before it is replaced, bounding the memory held by the
+    /// modules each evaluation declares
+    pub max_uses: u32,
+    /// Idle runtimes unused for longer are dropped instead of reused
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for FunctionPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle: 4,
+            max_uses: 256,
+            idle_timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// Function node runtimes kept warm across graph evaluations.
+///
+/// A runtime is started, its bundled modules loaded, once; each evaluation then gets it with a
+/// soft reset:
+/// - node modules are compiled once per node and source and instantiated again for every
+///   evaluation, library modules are declared again, so module-level variables start fresh
+/// - globals, built-ins and bundled modules, down to every object reachable from them through
+///   properties and prototypes, are compared against their state after startup, and a runtime
+///   whose previous evaluation changed them, or left jobs pending, is dropped instead of reused
+///
+/// The comparison sees properties only. State in closures, other than the dayjs locale and zod
+/// error map, and in internal slots, such as the contents of a `Map` or `Set` reachable from a
+/// global, goes unnoticed and can carry over to later evaluations on the same thread; use no
+/// pool where that matters. The comparison walks the whole graph on every reuse, which costs
+/// far less than starting a runtime but is not free.
+///
+/// QuickJS never unloads a module, which is why runtimes are retired after `max_uses`
+/// evaluations.
+#[derive(Clone)]
+pub struct FunctionPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    id: u64,
+    config: FunctionPoolConfig,
+}
+
+impl Debug for FunctionPool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FunctionPool")
+            .field("config", &self.inner.config)
+            .finish()
+    }
+}
+
+impl FunctionPool {
+    pub fn new(config: FunctionPoolConfig) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                id: NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed),
+                config,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &FunctionPoolConfig {
+        &self.inner.config
+    }
+
+    /// Idle runtimes this pool keeps on the current thread
+    pub fn idle(&self) -> usize {
+        IDLE.with_borrow(|idle| idle.get(&self.inner.id).map_or(0, Vec::len))
+    }
+
+    pub(crate) async fn acquire<F>(&self, listeners: F) -> FunctionResult<PooledFunction>
+    where
+        F: Fn() -> Vec<Box<dyn RuntimeListener>>,
+    {
+        while let Some(mut idle) = self.take_idle() {
+            if idle.function.renew(listeners()).await? {
+                return Ok(PooledFunction {
+                    function: Some(idle.function),
+                    pool: Some((self.clone(), idle.uses + 1)),
+                });
+            }
+        }
+
+        let mut function = Function::create(FunctionConfig {
+            listeners: Some(listeners()),
+        })
+        .await?;
+        function.isolate().await?;
+
+        Ok(PooledFunction {
+            function: Some(function),
+            pool: Some((self.clone(), 1)),
+        })
+    }
+
+    fn take_idle(&self) -> Option<IdleFunction> {
+        IDLE.with_borrow_mut(|idle| {
+            let functions = idle.get_mut(&self.inner.id)?;
+            if let Some(timeout) = self.inner.config.idle_timeout {
+                functions.retain(|f| f.since.elapsed() <= timeout);
+            }
+
+            functions.pop()
+        })
+    }
+
+    fn release(&self, function: Function, uses: u32) {
+        if uses >= self.inner.config.max_uses {
+            return;
+        }
+
+        // Fails only while the thread shuts down, dropping the runtime is all that is left then
+        let _ = IDLE.try_with(|idle| {
+            let Ok(mut idle) = idle.try_borrow_mut() else {
+                return;
+            };
+            let functions = idle.entry(self.inner.id).or_default();
+            if functions.len() < self.inner.config.max_idle {
+                functions.push(IdleFunction {
+                    function,
+                    uses,
+                    since: Instant::now(),
+                });
+            }
+        });
+    }
+}
+
+struct IdleFunction {
+    function: Function,
+    uses: u32,
+    since: Instant,
+}
+
+/// Function runtimes of one evaluation, taken from `pool` when there is one.
+///
+/// Every handler call leases a runtime of its own and hands it back once done, so concurrent
+/// branches never share globals, console or interrupt handler while sequential calls keep
+/// reusing the same runtime.
+#[derive(Debug, Default)]
+pub(crate) struct FunctionRuntimeCell {
+    idle: RefCell<Vec<PooledFunction>>,
+    pub(crate) pool: Option<FunctionPool>,
+}
+
+impl FunctionRuntimeCell {
+    // Shared by the graphs of one evaluation, all on the same thread, like the other extensions
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub(crate) fn shared(pool: Option<FunctionPool>) -> Arc<Self> {
+        Arc::new(Self {
+            idle: Default::default(),
+            pool,
+        })
+    }
+
+    pub(crate) fn take_idle(&self) -> Option<PooledFunction> {
+        self.idle.borrow_mut().pop()
+    }
+
+    pub(crate) fn lease(&self, function: PooledFunction) -> FunctionLease<'_> {
+        FunctionLease {
+            function: Some(function),
+            cell: self,
+        }
+    }
+}
+
+/// Runtime held by a single handler call, returned to the evaluation on drop
+pub struct FunctionLease<'a> {
+    function: Option<PooledFunction>,
+    cell: &'a FunctionRuntimeCell,
+}
+
+impl Deref for FunctionLease<'_> {
+    type Target = Function;
+
+    fn deref(&self) -> &Self::Target {
+        // Taken only on drop
+        self.function
+            .as_ref()
+            .expect("function present until dropped")
+    }
+}
+
+impl Drop for FunctionLease<'_> {
+    fn drop(&mut self) {
+        if let Some(function) = self.function.take() {
+            self.cell.idle.borrow_mut().push(function);
+        }
+    }
+}
+
+/// Runtime used by one evaluation, returned to its pool once the evaluation ends
+#[derive(Debug)]
+pub(crate) struct PooledFunction {
+    function: Option<Function>,
+    pool: Option<(FunctionPool, u32)>,
+}
+
+impl From<Function> for PooledFunction {
+    fn from(function: Function) -> Self {
+        Self {
+            function: Some(function),
+            pool: None,
+        }
+    }
+}
+
+impl Deref for PooledFunction {
+    type Target = Function;
+
+    fn deref(&self) -> &Self::Target {
+        // Taken only on drop
+        self.function
+            .as_ref()
+            .expect("function present until dropped")
+    }
+}
+
+impl Drop for PooledFunction {
+    fn drop(&mut self) {
+        if let (Some(function), Some((pool, uses))) = (self.function.take(), self.pool.take()) {
+            pool.release(function, uses);
+        }
+    }
+}
@@ -3,12 +3,15 @@ use std::fmt::{Display, Formatter};
 use rquickjs::{CaughtError, Ctx, Error, Exception};
 use thiserror::Error;
 
+use crate::loader::LoaderError;
+
 pub type FunctionResult<Ok = ()> = Result<Ok, FunctionError>;
 
 #[derive(Debug, Error)]
 pub enum FunctionError {
     Caught(String),
     Runtime(Error),
+    Library(LoaderError),
 }
 
 impl<'js> From<CaughtError<'js>> for FunctionError {
@@ -28,6 +31,7 @@ impl Display for FunctionError {
         match self {
             FunctionError::Caught(c) => f.write_str(c.as_str()),
             FunctionError::Runtime(rt) => rt.fmt(f),
+            FunctionError::Library(err) => write!(f, "Failed to load library module: {err}"),
         }
     }
 }
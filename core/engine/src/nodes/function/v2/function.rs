@@ -1,14 +1,23 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 
+use crate::loader::DynamicLoader;
 use crate::nodes::function::v2::error::{FunctionError, FunctionResult, ResultExt};
 use crate::nodes::function::v2::listener::{RuntimeEvent, RuntimeListener};
 use crate::nodes::function::v2::module::console::{Console, Log};
-use crate::nodes::function::v2::module::ModuleLoader;
+use crate::nodes::function::v2::module::library;
+use crate::nodes::function::v2::module::{
+    compiled_module, insert_compiled_module, lease_scoped, ModuleLoader,
+};
 use crate::nodes::function::v2::serde::{JsValue, JsValueWithNodes};
+use crate::nodes::function::v2::strip::TypeStripper;
 use rquickjs::promise::MaybePromise;
-use rquickjs::{async_with, AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Module};
+use rquickjs::{
+    async_with, AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Module, Object, Persistent,
+    WriteOptions,
+};
 use serde::{Deserialize, Serialize};
 use zen_expression::variable::{ToVariable, Variable};
 
@@ -17,10 +26,13 @@ pub struct FunctionConfig {
 }
 
 pub struct Function {
+    // Declared first so the value is released before the runtime
+    isolation: Option<Persistent<rquickjs::Function<'static>>>,
     rt: Arc<AsyncRuntime>,
     ctx: AsyncContext,
     listeners: Vec<Box<dyn RuntimeListener>>,
     module_loader: ModuleLoader,
+    lease: u64,
 }
 
 impl Debug for Function {
@@ -39,10 +51,12 @@ impl Function {
 
         let ctx = AsyncContext::full(&rt).await?;
         let this = Self {
+            isolation: None,
             rt,
             ctx,
             module_loader,
             listeners: config.listeners.unwrap_or_default(),
+            lease: 0,
         };
 
         this.dispatch_event(RuntimeEvent::Startup).await?;
@@ -66,6 +80,61 @@ impl Function {
         .await
     }
 
+    /// Prepares a runtime for reuse across evaluations: bundled modules are loaded up front and
+    /// the state every evaluation could change is recorded for [`renew`](Self::renew).
+    pub(crate) async fn isolate(&mut self) -> FunctionResult {
+        let isolation = async_with!(&self.ctx => |ctx| {
+            let mut roots = vec![ctx.globals().into_value()];
+            for name in BUNDLED_MODULES {
+                let namespace: Object = Module::import(&ctx, *name)
+                    .catch(&ctx)?
+                    .into_future()
+                    .await
+                    .catch(&ctx)?;
+                roots.push(namespace.into_value());
+            }
+
+            let snapshot: rquickjs::Function = ctx.eval(ISOLATION_SNAPSHOT).catch(&ctx)?;
+            let check: rquickjs::Function = snapshot
+                .call((roots, LEASE_GLOBALS.to_vec()))
+                .catch(&ctx)?;
+            FunctionResult::Ok(Persistent::save(&ctx, check))
+        })
+        .await?;
+
+        self.isolation = Some(isolation);
+        Ok(())
+    }
+
+    /// Hands an isolated runtime to a new evaluation with `listeners` bound to it, returning
+    /// `false` when the previous evaluation left state behind and the runtime must be dropped.
+    pub(crate) async fn renew(
+        &mut self,
+        listeners: Vec<Box<dyn RuntimeListener>>,
+    ) -> FunctionResult<bool> {
+        let Some(isolation) = self.isolation.clone() else {
+            return Ok(false);
+        };
+        if self.rt.is_job_pending().await {
+            return Ok(false);
+        }
+
+        let unchanged: bool = async_with!(&self.ctx => |ctx| {
+            let check = isolation.restore(&ctx).catch(&ctx)?;
+            FunctionResult::Ok(check.call(()).catch(&ctx)?)
+        })
+        .await?;
+        if !unchanged {
+            return Ok(false);
+        }
+
+        self.listeners = listeners;
+        self.lease += 1;
+        self.module_loader.set_lease(self.lease);
+        self.dispatch_event(RuntimeEvent::SoftReset).await?;
+        Ok(true)
+    }
+
     pub fn context(&self) -> &AsyncContext {
         &self.ctx
     }
@@ -75,11 +144,11 @@ impl Function {
     }
 
     pub fn suggest_module_name<'a>(&self, name: &str, source: &str) -> String {
-        let declarative_name = format!("node:{name}");
+        let declarative_name = lease_scoped(&format!("node:{name}"), self.lease);
 
         if self.module_loader.has_module(&declarative_name) {
             let content_hash = create_content_hash(source);
-            format!("node:{name}.{content_hash:x}")
+            lease_scoped(&format!("node:{name}.{content_hash:x}"), self.lease)
         } else {
             declarative_name
         }
@@ -101,6 +170,90 @@ impl Function {
         Ok(())
     }
 
+    /// Makes `name` import a new instance of the node module `source`, compiling it only the first
+    /// time any runtime sees that node and source.
+    pub(crate) async fn register_node_module(
+        &self,
+        node_id: &str,
+        name: &str,
+        source: &str,
+    ) -> FunctionResult {
+        let key = (node_id.to_string(), create_content_hash(source));
+        if let Some(compiled) = compiled_module(&key) {
+            self.module_loader.add_compiled(name.to_string(), compiled);
+            return Ok(());
+        }
+
+        let bytecode: Vec<u8> = async_with!(&self.ctx => |ctx| {
+            let module = Module::declare(ctx.clone(), format!("node:{node_id}"), source)
+                .catch(&ctx)?;
+            FunctionResult::Ok(module.write(WriteOptions::default()).catch(&ctx)?)
+        })
+        .await?;
+
+        match insert_compiled_module(key, bytecode) {
+            Some(compiled) => self.module_loader.add_compiled(name.to_string(), compiled),
+            None => self.register_module(name, source).await?,
+        }
+        Ok(())
+    }
+
+    /// Declares the library modules imported by `source`, and transitively by those, from the
+    /// loader. Each library is fetched and stripped once per runtime.
+    pub(crate) async fn register_libraries(
+        &self,
+        loader: &DynamicLoader,
+        name: &str,
+        source: &str,
+    ) -> FunctionResult {
+        let roots = library::library_imports(name, source);
+        let mut libraries: HashMap<String, (Arc<str>, Vec<String>)> = HashMap::new();
+        let mut pending = roots.clone();
+        while let Some(key) = pending.pop() {
+            if libraries.contains_key(&key)
+                || self
+                    .module_loader
+                    .has_module(&lease_scoped(&key, self.lease))
+            {
+                continue;
+            }
+
+            let raw = loader
+                .load_module(&key)
+                .await
+                .map_err(FunctionError::Library)?;
+            let stripped = TypeStripper::strip(&raw);
+            let imports = library::library_imports(&key, &stripped);
+            pending.extend(imports.iter().cloned());
+            libraries.insert(key, (stripped, imports));
+        }
+
+        // Imports are resolved on declaration, so dependencies are declared first
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(String, bool)> =
+            roots.into_iter().rev().map(|key| (key, false)).collect();
+        while let Some((key, expanded)) = stack.pop() {
+            let Some((source, imports)) = libraries.get(&key) else {
+                continue;
+            };
+            if expanded {
+                let name = lease_scoped(&key, self.lease);
+                if !self.module_loader.has_module(&name) {
+                    self.register_module(&name, source).await?;
+                }
+                continue;
+            }
+            if !visited.insert(key.clone()) {
+                continue;
+            }
+
+            stack.push((key, true));
+            stack.extend(imports.iter().rev().map(|import| (import.clone(), false)));
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn call_handler(
         &self,
         name: &str,
@@ -142,6 +295,79 @@ pub struct HandlerResponse {
     pub data: Variable,
 }
 
+/// Bundled modules shared by every lease of a pooled runtime
+const BUNDLED_MODULES: &[&str] = &["dayjs", "big.js", "zod"];
+
+/// Globals the listeners and node handler replace for every lease and node
+const LEASE_GLOBALS: &[&str] = &[
+    "console",
+    "config",
+    "__getNodesData",
+    "__evaluate",
+    "__executeHttp",
+];
+
+/// Records own properties of the roots and of every object reachable from them through property
+/// values, accessors and prototypes, returning a check that they are unchanged.
+///
+/// State held in closures or internal slots is invisible to that walk. Of the bundled modules
+/// only the dayjs global locale and the zod error map live in closures, so they are read through
+/// their getters and compared on their own; see [`FunctionPool`](crate::nodes::pool::FunctionPool)
+/// for what goes unnoticed.
+const ISOLATION_SNAPSHOT: &str = r#"
+(roots, ignored) => {
+  const skipped = new Set(ignored);
+  const { getOwnPropertyDescriptor, getPrototypeOf, isExtensible, ownKeys } = Reflect;
+  const exported = (name) => roots.map((root) => root[name] ?? root.default?.[name]).find((f) => typeof f === 'function');
+  const readers = [exported('locale'), exported('getErrorMap')].filter(Boolean);
+  const closures = readers.map((read) => read());
+  const keysOf = (object) => object === globalThis ? ownKeys(object).filter((key) => !skipped.has(key)) : ownKeys(object);
+  const flags = (d) => (d.writable ? 1 : 0) | (d.enumerable ? 2 : 0) | (d.configurable ? 4 : 0);
+
+  // [object, extensible, prototype, key count, ...[key, value, get, set, flags]] per object
+  const snapshot = [];
+  const seen = new Set();
+  const pending = [...roots];
+  while (pending.length > 0) {
+    const object = pending.pop();
+    if (object === null || (typeof object !== 'object' && typeof object !== 'function')) continue;
+    if (seen.has(object)) continue;
+    seen.add(object);
+
+    const keys = keysOf(object);
+    const prototype = getPrototypeOf(object);
+    snapshot.push(object, isExtensible(object), prototype, keys.length);
+    for (const key of keys) {
+      const d = getOwnPropertyDescriptor(object, key);
+      snapshot.push(key, d.value, d.get, d.set, flags(d));
+      pending.push(d.value, d.get, d.set);
+    }
+    pending.push(prototype);
+  }
+
+  return () => {
+    for (let i = 0; i < readers.length; i++) {
+      if (!Object.is(readers[i](), closures[i])) return false;
+    }
+    let i = 0;
+    while (i < snapshot.length) {
+      const object = snapshot[i];
+      if (isExtensible(object) !== snapshot[i + 1] || getPrototypeOf(object) !== snapshot[i + 2]) return false;
+      const keys = keysOf(object);
+      if (keys.length !== snapshot[i + 3]) return false;
+      i += 4;
+      for (const key of keys) {
+        const d = getOwnPropertyDescriptor(object, key);
+        if (key !== snapshot[i] || !Object.is(d.value, snapshot[i + 1]) || d.get !== snapshot[i + 2] ||
+          d.set !== snapshot[i + 3] || flags(d) !== snapshot[i + 4]) return false;
+        i += 5;
+      }
+    }
+    return true;
+  };
+}
+"#;
+
 fn create_content_hash(content: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     content.hash(&mut hasher);
@@ -29,9 +29,9 @@ impl NodeHandler for FunctionV2NodeHandler {
     type TraceData = FunctionV2Trace;
 
     async fn handle(&self, ctx: NodeContext<Self::NodeData, Self::TraceData>) -> NodeResult {
+        let function = ctx.function_runtime().await?;
         let start = Instant::now();
 
-        let function = ctx.function_runtime().await?;
         let source = ctx
             .extensions
             .stripped_functions
@@ -41,7 +41,9 @@ impl NodeHandler for FunctionV2NodeHandler {
         let module_name = function.suggest_module_name(ctx.id.deref(), source.as_ref());
 
         let max_duration = Duration::from_millis(ctx.config.function_timeout_millis);
-        let interrupt_handler = Box::new(move || start.elapsed() > max_duration);
+        let limits = ctx.extensions.limits.clone();
+        let interrupt_handler =
+            Box::new(move || start.elapsed() > max_duration || limits.check().is_err());
 
         function
             .runtime()
@@ -54,13 +56,19 @@ impl NodeHandler for FunctionV2NodeHandler {
             function: &function,
         };
 
-        self.attach_globals(function, &ctx)
+        self.attach_globals(&function, &ctx)
+            .await
+            .function_context(&function_context)
+            .await?;
+
+        function
+            .register_libraries(ctx.extensions.loader(), &module_name, source.as_ref())
             .await
             .function_context(&function_context)
             .await?;
 
         function
-            .register_module(&module_name, source.as_ref())
+            .register_node_module(ctx.id.deref(), &module_name, source.as_ref())
             .await
             .function_context(&function_context)
             .await?;
@@ -92,6 +100,7 @@ impl FunctionV2NodeHandler {
             config.prop("iteration", node_ctx.iteration).catch(&ctx)?;
             config.prop("maxDepth", node_ctx.config.max_depth).catch(&ctx)?;
             config.prop("trace", node_ctx.config.trace).catch(&ctx)?;
+            config.prop("concurrent", node_ctx.config.concurrent).catch(&ctx)?;
 
             ctx.globals().set("config", config).catch(&ctx)?;
 
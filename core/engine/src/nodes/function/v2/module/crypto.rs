@@ -0,0 +1,145 @@
+use crate::nodes::function::v2::error::ResultExt;
+use crate::nodes::function::v2::module::encoding::{bytes_from_js, bytes_into_js, encode_digest};
+use crate::nodes::function::v2::module::export_default;
+use hmac::{Hmac, Mac};
+use rquickjs::module::{Declarations, Exports, ModuleDef};
+use rquickjs::prelude::{Func, Opt};
+use rquickjs::{Ctx, Exception, Value};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
+use uuid::Builder;
+
+/// Same quota as `crypto.getRandomValues` in browsers
+const MAX_RANDOM_BYTES: usize = 65_536;
+
+fn hash<'js, D: Digest>(
+    ctx: Ctx<'js>,
+    data: Value<'js>,
+    encoding: Opt<String>,
+) -> rquickjs::Result<String> {
+    let bytes = bytes_from_js(&ctx, data)?;
+    encode_digest(&ctx, &D::digest(bytes), encoding.0)
+}
+
+fn sha1<'js>(ctx: Ctx<'js>, data: Value<'js>, encoding: Opt<String>) -> rquickjs::Result<String> {
+    hash::<Sha1>(ctx, data, encoding)
+}
+
+fn sha256<'js>(ctx: Ctx<'js>, data: Value<'js>, encoding: Opt<String>) -> rquickjs::Result<String> {
+    hash::<Sha256>(ctx, data, encoding)
+}
+
+fn sha512<'js>(ctx: Ctx<'js>, data: Value<'js>, encoding: Opt<String>) -> rquickjs::Result<String> {
+    hash::<Sha512>(ctx, data, encoding)
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(
+    ctx: &Ctx<'_>,
+    key: &[u8],
+    data: &[u8],
+) -> rquickjs::Result<Vec<u8>> {
+    let mut mac = <M as Mac>::new_from_slice(key).or_throw(ctx)?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn hmac<'js>(
+    ctx: Ctx<'js>,
+    algorithm: String,
+    key: Value<'js>,
+    data: Value<'js>,
+    encoding: Opt<String>,
+) -> rquickjs::Result<String> {
+    let key = bytes_from_js(&ctx, key)?;
+    let data = bytes_from_js(&ctx, data)?;
+    let signature = match algorithm.as_str() {
+        "sha1" => mac::<Hmac<Sha1>>(&ctx, &key, &data)?,
+        "sha256" => mac::<Hmac<Sha256>>(&ctx, &key, &data)?,
+        "sha512" => mac::<Hmac<Sha512>>(&ctx, &key, &data)?,
+        other => {
+            return Err(Exception::throw_type(
+                &ctx,
+                &format!("Unknown algorithm '{other}', expected sha1, sha256 or sha512"),
+            ))
+        }
+    };
+
+    encode_digest(&ctx, &signature, encoding.0)
+}
+
+fn timing_safe_equal<'js>(ctx: Ctx<'js>, a: Value<'js>, b: Value<'js>) -> rquickjs::Result<bool> {
+    let a = bytes_from_js(&ctx, a)?;
+    let b = bytes_from_js(&ctx, b)?;
+    Ok(a.ct_eq(&b).into())
+}
+
+fn fill_random<const N: usize>(ctx: &Ctx<'_>) -> rquickjs::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    getrandom::fill(&mut bytes).or_throw(ctx)?;
+    Ok(bytes)
+}
+
+fn random_bytes<'js>(ctx: Ctx<'js>, length: usize) -> rquickjs::Result<Value<'js>> {
+    if length > MAX_RANDOM_BYTES {
+        return Err(Exception::throw_range(
+            &ctx,
+            &format!("At most {MAX_RANDOM_BYTES} random bytes can be requested"),
+        ));
+    }
+
+    let mut bytes = vec![0u8; length];
+    getrandom::fill(&mut bytes).or_throw(&ctx)?;
+    bytes_into_js(&ctx, bytes)
+}
+
+fn uuid_v4(ctx: Ctx<'_>) -> rquickjs::Result<String> {
+    let random = fill_random::<16>(&ctx)?;
+    Ok(Builder::from_random_bytes(random).into_uuid().to_string())
+}
+
+fn uuid_v7(ctx: Ctx<'_>) -> rquickjs::Result<String> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .or_throw(&ctx)?
+        .as_millis() as u64;
+    let random = fill_random::<10>(&ctx)?;
+    Ok(Builder::from_unix_timestamp_millis(millis, &random)
+        .into_uuid()
+        .to_string())
+}
+
+pub struct CryptoModule;
+
+impl ModuleDef for CryptoModule {
+    fn declare<'js>(decl: &Declarations<'js>) -> rquickjs::Result<()> {
+        decl.declare("sha1")?;
+        decl.declare("sha256")?;
+        decl.declare("sha512")?;
+        decl.declare("hmac")?;
+        decl.declare("timingSafeEqual")?;
+        decl.declare("randomBytes")?;
+        decl.declare("uuidV4")?;
+        decl.declare("uuidV7")?;
+
+        decl.declare("default")?;
+
+        Ok(())
+    }
+
+    fn evaluate<'js>(ctx: &Ctx<'js>, exports: &Exports<'js>) -> rquickjs::Result<()> {
+        export_default(ctx, exports, |default| {
+            default.set("sha1", Func::from(sha1))?;
+            default.set("sha256", Func::from(sha256))?;
+            default.set("sha512", Func::from(sha512))?;
+            default.set("hmac", Func::from(hmac))?;
+            default.set("timingSafeEqual", Func::from(timing_safe_equal))?;
+            default.set("randomBytes", Func::from(random_bytes))?;
+            default.set("uuidV4", Func::from(uuid_v4))?;
+            default.set("uuidV7", Func::from(uuid_v7))?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use crate::nodes::function::v2::error::ResultExt;
+use crate::nodes::function::v2::module::export_default;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use rquickjs::module::{Declarations, Exports, ModuleDef};
+use rquickjs::prelude::Func;
+use rquickjs::{ArrayBuffer, Ctx, Exception, TypedArray, Value};
+
+/// Bytes of a string (UTF-8), `Uint8Array` or `ArrayBuffer` argument
+pub(crate) fn bytes_from_js<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Vec<u8>> {
+    if let Some(text) = value.as_string() {
+        return Ok(text.to_string()?.into_bytes());
+    }
+
+    if let Ok(array) = TypedArray::<u8>::from_value(value.clone()) {
+        return array.as_bytes().map(<[u8]>::to_vec).or_throw(ctx);
+    }
+
+    if let Some(buffer) = value
+        .as_object()
+        .and_then(|o| ArrayBuffer::from_object(o.clone()))
+    {
+        return buffer.as_bytes().map(<[u8]>::to_vec).or_throw(ctx);
+    }
+
+    Err(Exception::throw_type(
+        ctx,
+        "Expected a string, Uint8Array or ArrayBuffer",
+    ))
+}
+
+pub(crate) fn bytes_into_js<'js>(ctx: &Ctx<'js>, bytes: Vec<u8>) -> rquickjs::Result<Value<'js>> {
+    Ok(TypedArray::<u8>::new(ctx.clone(), bytes)?.into_value())
+}
+
+/// Text form of a digest, `hex` unless another encoding is requested
+pub(crate) fn encode_digest(
+    ctx: &Ctx<'_>,
+    bytes: &[u8],
+    encoding: Option<String>,
+) -> rquickjs::Result<String> {
+    match encoding.as_deref().unwrap_or("hex") {
+        "hex" => Ok(hex::encode(bytes)),
+        "base64" => Ok(STANDARD.encode(bytes)),
+        "base64url" => Ok(URL_SAFE_NO_PAD.encode(bytes)),
+        other => Err(Exception::throw_type(
+            ctx,
+            &format!("Unknown encoding '{other}', expected hex, base64 or base64url"),
+        )),
+    }
+}
+
+fn encode_base64<'js>(ctx: Ctx<'js>, data: Value<'js>) -> rquickjs::Result<String> {
+    Ok(STANDARD.encode(bytes_from_js(&ctx, data)?))
+}
+
+fn decode_base64<'js>(ctx: Ctx<'js>, text: String) -> rquickjs::Result<Value<'js>> {
+    let bytes = STANDARD.decode(text.trim()).or_throw(&ctx)?;
+    bytes_into_js(&ctx, bytes)
+}
+
+fn encode_base64_url<'js>(ctx: Ctx<'js>, data: Value<'js>) -> rquickjs::Result<String> {
+    Ok(URL_SAFE_NO_PAD.encode(bytes_from_js(&ctx, data)?))
+}
+
+fn decode_base64_url<'js>(ctx: Ctx<'js>, text: String) -> rquickjs::Result<Value<'js>> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(text.trim().trim_end_matches('='))
+        .or_throw(&ctx)?;
+    bytes_into_js(&ctx, bytes)
+}
+
+fn encode_hex<'js>(ctx: Ctx<'js>, data: Value<'js>) -> rquickjs::Result<String> {
+    Ok(hex::encode(bytes_from_js(&ctx, data)?))
+}
+
+fn decode_hex<'js>(ctx: Ctx<'js>, text: String) -> rquickjs::Result<Value<'js>> {
+    let bytes = hex::decode(text.trim()).or_throw(&ctx)?;
+    bytes_into_js(&ctx, bytes)
+}
+
+fn encode_utf8<'js>(ctx: Ctx<'js>, text: String) -> rquickjs::Result<Value<'js>> {
+    bytes_into_js(&ctx, text.into_bytes())
+}
+
+fn decode_utf8<'js>(ctx: Ctx<'js>, data: Value<'js>) -> rquickjs::Result<String> {
+    let bytes = bytes_from_js(&ctx, data)?;
+    String::from_utf8(bytes).or_throw(&ctx)
+}
+
+pub struct EncodingModule;
+
+impl ModuleDef for EncodingModule {
+    fn declare<'js>(decl: &Declarations<'js>) -> rquickjs::Result<()> {
+        decl.declare("encodeBase64")?;
+        decl.declare("decodeBase64")?;
+        decl.declare("encodeBase64Url")?;
+        decl.declare("decodeBase64Url")?;
+        decl.declare("encodeHex")?;
+        decl.declare("decodeHex")?;
+        decl.declare("encodeUtf8")?;
+        decl.declare("decodeUtf8")?;
+
+        decl.declare("default")?;
+
+        Ok(())
+    }
+
+    fn evaluate<'js>(ctx: &Ctx<'js>, exports: &Exports<'js>) -> rquickjs::Result<()> {
+        export_default(ctx, exports, |default| {
+            default.set("encodeBase64", Func::from(encode_base64))?;
+            default.set("decodeBase64", Func::from(decode_base64))?;
+            default.set("encodeBase64Url", Func::from(encode_base64_url))?;
+            default.set("decodeBase64Url", Func::from(decode_base64_url))?;
+            default.set("encodeHex", Func::from(encode_hex))?;
+            default.set("decodeHex", Func::from(decode_hex))?;
+            default.set("encodeUtf8", Func::from(encode_utf8))?;
+            default.set("decodeUtf8", Func::from(decode_utf8))?;
+
+            Ok(())
+        })
+    }
+}
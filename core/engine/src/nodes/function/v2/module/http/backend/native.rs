@@ -3,6 +3,7 @@ use crate::nodes::function::v2::error::ResultExt;
 use crate::nodes::function::v2::module::http::auth::{HttpConfigAuth, IamAuth};
 use crate::nodes::function::v2::module::http::{HttpMethod, HttpRequestConfig};
 use crate::nodes::function::v2::serde::JsValue;
+use crate::nodes::http_handler::{HttpHandlerRequest, HttpHandlerResponse};
 use crate::ZEN_CONFIG;
 use ::http::Request as HttpRequest;
 use reqwest::{Body, Method, Request, Url};
@@ -44,63 +45,120 @@ async fn execute_http_native<'js>(
     url: String,
     config: HttpRequestConfig,
 ) -> rquickjs::Result<HttpResponse<'js>> {
+    let response = send_request(
+        method,
+        &url,
+        config
+            .params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.0.as_str())),
+        config
+            .headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.0.as_str())),
+        config.data,
+        config.auth,
+    )
+    .await
+    .or_throw(&ctx)?;
+
+    let status = response.status().as_u16();
+    let header_object = Object::new(ctx.clone()).catch(&ctx).or_throw(&ctx)?;
+    for (key, value) in response.headers() {
+        header_object.set(
+            key.as_str().into_atom(&ctx)?,
+            value.to_str().or_throw(&ctx).into_js(&ctx),
+        )?;
+    }
+
+    let data: Variable = response.json().await.or_throw(&ctx)?;
+
+    Ok(HttpResponse {
+        data: JsValue(data).into_js(&ctx)?,
+        headers: header_object.into_value(),
+        status,
+    })
+}
+
+/// Executes a handler request over the network, used by [`NativeHttpHandler`](crate::nodes::http_handler::NativeHttpHandler)
+pub(crate) async fn execute_handler_request(
+    request: HttpHandlerRequest,
+) -> anyhow::Result<HttpHandlerResponse> {
+    let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
+    let auth = request
+        .auth
+        .map(serde_json::from_value::<HttpConfigAuth>)
+        .transpose()?;
+
+    let response = send_request(
+        method,
+        &request.url,
+        request.params.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+        request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str())),
+        request.body,
+        auth,
+    )
+    .await?;
+
+    let status = response.status().as_u16();
+    let mut headers = serde_json::Map::new();
+    for (key, value) in response.headers() {
+        headers.insert(key.to_string(), value.to_str()?.into());
+    }
+
+    let data: serde_json::Value = response.json().await?;
+    Ok(HttpHandlerResponse {
+        status,
+        headers: headers.into(),
+        data,
+    })
+}
+
+async fn send_request<'a>(
+    method: Method,
+    url: &str,
+    params: impl Iterator<Item = (&'a str, &'a str)>,
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+    data: Option<serde_json::Value>,
+    auth: Option<HttpConfigAuth>,
+) -> anyhow::Result<reqwest::Response> {
     static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
     let client = HTTP_CLIENT.get_or_init(|| reqwest::Client::new()).clone();
 
-    let mut url = Url::parse(&url).or_throw(&ctx)?;
-    for (k, v) in &config.params {
-        url.query_pairs_mut().append_pair(k.as_str(), v.0.as_str());
+    let mut url = Url::parse(url)?;
+    for (k, v) in params {
+        url.query_pairs_mut().append_pair(k, v);
     }
 
     let mut request_builder = HttpRequest::builder().method(method).uri(url.as_str());
-    for (k, v) in &config.headers {
-        request_builder = request_builder.header(k.as_str(), v.0.as_str());
+    for (k, v) in headers {
+        request_builder = request_builder.header(k, v);
     }
 
-    let auth_method = config
-        .auth
-        .clone()
-        .filter(|_| ZEN_CONFIG.http_auth.load(Ordering::Relaxed));
-
-    let request_data_opt = config.data;
-    let http_request = match request_data_opt {
-        None => request_builder.body(Body::default()).or_throw(&ctx)?,
+    let auth_method = auth.filter(|_| ZEN_CONFIG.http_auth.load(Ordering::Relaxed));
+    let http_request = match data {
+        None => request_builder.body(Body::default())?,
         Some(request_data) => {
-            let request_body_json = serde_json::to_vec(&request_data).or_throw(&ctx)?;
-            request_builder
-                .body(Body::from(request_body_json))
-                .or_throw(&ctx)?
+            let request_body_json = serde_json::to_vec(&request_data)?;
+            request_builder.body(Body::from(request_body_json))?
         }
     };
 
     let request = match auth_method {
         Some(HttpConfigAuth::Iam(IamAuth::Aws(config))) => {
-            config.build_request(http_request).await.or_throw(&ctx)?
+            config.build_request(http_request).await?
         }
         Some(HttpConfigAuth::Iam(IamAuth::Azure(config))) => {
-            config.build_request(http_request).await.or_throw(&ctx)?
+            config.build_request(http_request).await?
         }
         Some(HttpConfigAuth::Iam(IamAuth::Gcp(config))) => {
-            config.build_request(http_request).await.or_throw(&ctx)?
+            config.build_request(http_request).await?
         }
-        None => Request::try_from(http_request).or_throw(&ctx)?,
+        None => Request::try_from(http_request)?,
     };
 
-    let response = client.execute(request).await.or_throw(&ctx)?;
-    let status = response.status().as_u16();
-    let header_object = Object::new(ctx.clone()).catch(&ctx).or_throw(&ctx)?;
-    for (key, value) in response.headers() {
-        header_object.set(
-            key.as_str().into_atom(&ctx)?,
-            value.to_str().or_throw(&ctx).into_js(&ctx),
-        )?;
-    }
-
-    let data: Variable = response.json().await.or_throw(&ctx)?;
-
-    Ok(HttpResponse {
-        data: JsValue(data).into_js(&ctx)?,
-        headers: header_object.into_value(),
-        status,
-    })
+    Ok(client.execute(request).await?)
 }
@@ -2,29 +2,45 @@ use crate::nodes::function::http_handler::{DynamicHttpHandler, HttpHandlerReques
 use crate::nodes::function::v2::error::{FunctionResult, ResultExt};
 use crate::nodes::function::v2::listener::{RuntimeEvent, RuntimeListener};
 use crate::nodes::function::v2::serde::rquickjs_conv;
+use crate::observer::{DynamicEvaluationObserver, EvaluationObserver};
 use rquickjs::prelude::{Async, Func};
 use rquickjs::{CatchResultExt, Ctx};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 pub(crate) struct HttpListener {
     pub http_handler: DynamicHttpHandler,
+    pub observer: DynamicEvaluationObserver,
 }
 
+/// Observer reachable from the `http` module through the context userdata
+#[derive(rquickjs::JsLifetime)]
+pub(crate) struct HttpObserver(pub Arc<dyn EvaluationObserver>);
+
 impl RuntimeListener for HttpListener {
     fn on_event<'js>(
         &self,
         ctx: Ctx<'js>,
-        event: RuntimeEvent,
+        _event: RuntimeEvent,
     ) -> Pin<Box<dyn Future<Output = FunctionResult> + 'js>> {
         let http_handler = self.http_handler.clone();
+        let observer = self.observer.clone();
 
         Box::pin(async move {
-            if event != RuntimeEvent::Startup {
-                return Ok(());
+            // Installed again on soft reset, as a pooled runtime serves evaluations with
+            // different handlers
+            match observer {
+                Some(observer) => {
+                    ctx.store_userdata(HttpObserver(observer)).or_throw(&ctx)?;
+                }
+                None => {
+                    ctx.remove_userdata::<HttpObserver>().or_throw(&ctx)?;
+                }
             }
 
             let Some(http_handler) = http_handler.clone() else {
+                ctx.globals().remove("__executeHttp").catch(&ctx)?;
                 return Ok(());
             };
 
@@ -6,12 +6,15 @@ use crate::nodes::function::v2::module::export_default;
 use crate::nodes::function::v2::module::http::auth::HttpConfigAuth;
 use crate::nodes::function::v2::module::http::backend::callback::CallbackHttpBackend;
 use crate::nodes::function::v2::module::http::backend::{HttpBackend, HttpResponse};
+use crate::nodes::function::v2::module::http::listener::HttpObserver;
 use crate::nodes::function::v2::serde::{rquickjs_conv, JsValue};
+use crate::observer::HttpCall;
 use ahash::HashMap;
 use rquickjs::module::{Declarations, Exports, ModuleDef};
 use rquickjs::prelude::{Async, Func, Opt};
 use rquickjs::{Ctx, FromJs, Value};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Instant;
 use strum::Display;
 
 async fn execute_http<'js>(
@@ -26,6 +29,43 @@ async fn execute_http<'js>(
         request.data = Some(data);
     }
 
+    let observer = ctx.userdata::<HttpObserver>().map(|o| o.0.clone());
+    let method_name = method.to_string();
+    let request_url = url.clone();
+
+    let call = dispatch_http(ctx, method, url, request);
+    #[cfg(feature = "tracing")]
+    let call = tracing::Instrument::instrument(
+        call,
+        tracing::debug_span!(
+            "zen.http",
+            method = method_name.as_str(),
+            url = request_url.as_str()
+        ),
+    );
+
+    let start = Instant::now();
+    let response = call.await;
+    if let Some(observer) = observer {
+        let error = response.as_ref().err().map(|err| err.to_string());
+        observer.on_http_call(&HttpCall {
+            method: &method_name,
+            url: &request_url,
+            status: response.as_ref().ok().map(|r| r.status),
+            duration: start.elapsed(),
+            error: error.as_deref(),
+        });
+    }
+
+    response
+}
+
+async fn dispatch_http<'js>(
+    ctx: Ctx<'js>,
+    method: HttpMethod,
+    url: String,
+    request: HttpRequestConfig,
+) -> rquickjs::Result<HttpResponse<'js>> {
     if ctx.globals().contains_key("__executeHttp").unwrap_or(false) {
         let backend = CallbackHttpBackend;
         let backend_result = backend.execute_http(ctx, method, url, request).await;
@@ -0,0 +1,135 @@
+use swc_common::input::StringInput;
+use swc_common::source_map::SmallPos;
+use swc_common::BytePos;
+use swc_ecma_ast::{ModuleDecl, ModuleItem};
+use swc_ecma_parser::{lexer::Lexer, Parser, Syntax, TsSyntax};
+
+/// Loader keys of shared library modules are rooted here
+pub(crate) const LIBRARY_ROOT: &str = "lib";
+
+/// Resolves an import specifier to the loader key of a library module.
+///
+/// `lib:scoring.js` and `lib/scoring.js` both resolve to `lib/scoring.js`. Relative specifiers
+/// resolve against the importing library module, or against `lib/` when imported from a
+/// function node. Returns `None` for other specifiers and for paths escaping `lib/`.
+pub(crate) fn resolve_library(base: &str, name: &str) -> Option<String> {
+    let path = if let Some(rest) = name.strip_prefix("lib:") {
+        format!("{LIBRARY_ROOT}/{rest}")
+    } else if name.starts_with("lib/") {
+        name.to_string()
+    } else if name.starts_with("./") || name.starts_with("../") {
+        let directory = match base.rsplit_once('/') {
+            Some((directory, _)) if is_library(base) => directory,
+            _ => LIBRARY_ROOT,
+        };
+        format!("{directory}/{name}")
+    } else {
+        return None;
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    if segments.len() < 2 || segments[0] != LIBRARY_ROOT {
+        return None;
+    }
+
+    Some(segments.join("/"))
+}
+
+pub(crate) fn is_library(name: &str) -> bool {
+    name.strip_prefix(LIBRARY_ROOT)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Specifiers of static `import` and `export ... from` declarations, in source order
+pub(crate) fn import_specifiers(source: &str) -> Vec<String> {
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax::default()),
+        Default::default(),
+        StringInput::new(
+            source,
+            BytePos::from_usize(0),
+            BytePos::from_usize(source.len()),
+        ),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let Ok(module) = parser.parse_typescript_module() else {
+        return Vec::new();
+    };
+
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(&import.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => Some(&export.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => export.src.as_ref(),
+            _ => None,
+        })
+        .filter_map(|src| src.value.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Loader keys of the library modules imported by `source`
+pub(crate) fn library_imports(base: &str, source: &str) -> Vec<String> {
+    import_specifiers(source)
+        .into_iter()
+        .filter_map(|specifier| resolve_library(base, &specifier))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_library_specifiers() {
+        assert_eq!(
+            resolve_library("node:a", "lib:scoring.js").as_deref(),
+            Some("lib/scoring.js")
+        );
+        assert_eq!(
+            resolve_library("node:a", "lib/scoring.js").as_deref(),
+            Some("lib/scoring.js")
+        );
+        assert_eq!(
+            resolve_library("node:a", "./scoring.js").as_deref(),
+            Some("lib/scoring.js")
+        );
+        assert_eq!(
+            resolve_library("lib/risk/score.js", "../math.js").as_deref(),
+            Some("lib/math.js")
+        );
+        assert_eq!(
+            resolve_library("lib/risk/score.js", "./weights.js").as_deref(),
+            Some("lib/risk/weights.js")
+        );
+    }
+
+    #[test]
+    fn ignores_other_specifiers() {
+        assert_eq!(resolve_library("node:a", "zen"), None);
+        assert_eq!(resolve_library("node:a", "dayjs"), None);
+        assert_eq!(resolve_library("node:a", "../secrets.js"), None);
+        assert_eq!(resolve_library("lib/a.js", "../../b.js"), None);
+    }
+
+    #[test]
+    fn collects_static_imports() {
+        let source = "import { score } from 'lib:scoring.js';\nimport dayjs from 'dayjs';\nexport * from './shared';\nexport const handler = (input: { a: number }) => score(input.a);";
+        assert_eq!(
+            library_imports("node:a", source),
+            vec!["lib/scoring.js".to_string(), "lib/shared".to_string()]
+        );
+    }
+}
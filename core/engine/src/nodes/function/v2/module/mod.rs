@@ -1,15 +1,21 @@
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::DerefMut;
 use std::rc::Rc;
+use std::sync::{LazyLock, Mutex};
 
+use crate::nodes::function::v2::module::crypto::CryptoModule;
+use crate::nodes::function::v2::module::encoding::EncodingModule;
 use crate::nodes::function::v2::module::zen::ZenModule;
-use rquickjs::loader::{Bundle, Loader, ModuleLoader as MDLoader, Resolver};
+use rquickjs::loader::{bundle, Bundle, Loader, ModuleLoader as MDLoader, Resolver};
 use rquickjs::module::{Declared, Exports};
 use rquickjs::{embed, Ctx, Error, Module, Object};
 
 pub(crate) mod console;
+pub(crate) mod crypto;
+pub(crate) mod encoding;
 pub(crate) mod http;
+pub(crate) mod library;
 pub(crate) mod zen;
 
 static JS_BUNDLE: Bundle = embed! {
@@ -18,6 +24,45 @@ static JS_BUNDLE: Bundle = embed! {
     "zod": "js/zod.mjs"
 };
 
+/// Node modules kept compiled for the lifetime of the process
+const MAX_COMPILED_MODULES: usize = 512;
+
+/// Name node module bytecode is looked up by in its single entry bundle
+const COMPILED_MODULE: &str = "node";
+
+/// Node module bytecode as a single entry bundle, loadable without `unsafe`
+pub(crate) type CompiledModule = &'static [(&'static str, &'static [u8])];
+
+/// Node module bytecode shared by all runtimes, keyed by node id and source hash.
+///
+/// Bundles only load `'static` bytecode, so entries are never freed; once the cache is full,
+/// further node modules are compiled from source on every lease instead.
+static COMPILED_MODULES: LazyLock<Mutex<HashMap<(String, u64), CompiledModule>>> =
+    LazyLock::new(Default::default);
+
+pub(crate) fn compiled_module(key: &(String, u64)) -> Option<CompiledModule> {
+    COMPILED_MODULES.lock().ok()?.get(key).copied()
+}
+
+/// Keeps `bytecode` for `key`, returning `None` once the cache is full
+pub(crate) fn insert_compiled_module(
+    key: (String, u64),
+    bytecode: Vec<u8>,
+) -> Option<CompiledModule> {
+    let mut modules = COMPILED_MODULES.lock().ok()?;
+    if let Some(module) = modules.get(&key) {
+        return Some(module);
+    }
+    if modules.len() >= MAX_COMPILED_MODULES {
+        return None;
+    }
+
+    let bytecode: &'static [u8] = Box::leak(bytecode.into_boxed_slice());
+    let module: CompiledModule = Box::leak(Box::new([(COMPILED_MODULE, bytecode)]));
+    modules.insert(key, module);
+    Some(module)
+}
+
 #[derive(Clone)]
 pub struct ModuleLoader(Rc<RefCell<BaseModuleLoader>>);
 
@@ -35,6 +80,35 @@ impl ModuleLoader {
         let reference = self.0.borrow();
         reference.has_module(module)
     }
+
+    /// Makes `module` load a new instance of `compiled` whenever it is imported
+    pub(crate) fn add_compiled(&self, module: String, compiled: CompiledModule) {
+        let mut reference = self.0.borrow_mut();
+        reference.add_module(module.clone());
+        reference.compiled.insert(module, compiled);
+    }
+
+    /// Library modules resolve to their copy declared for `lease`, see [`lease_scoped`]
+    pub(crate) fn set_lease(&self, lease: u64) {
+        let mut reference = self.0.borrow_mut();
+        reference.lease = lease;
+        reference.compiled.clear();
+    }
+}
+
+/// Name of module `name` declared for `lease` of a pooled runtime.
+///
+/// QuickJS keeps one instance per module name, so each lease gets its own instance of node and
+/// library modules; module-level state never outlives the evaluation that created it. Node
+/// modules are compiled once, see [`COMPILED_MODULES`], and instantiated from bytecode, while
+/// library modules, which import each other by name, are declared from source again. Instances
+/// stay loaded until the runtime is dropped, see
+/// [`FunctionPoolConfig::max_uses`](crate::nodes::function::pool::FunctionPoolConfig::max_uses).
+pub(crate) fn lease_scoped(name: &str, lease: u64) -> String {
+    match lease {
+        0 => name.to_string(),
+        lease => format!("{name}@{lease}"),
+    }
 }
 
 impl Resolver for ModuleLoader {
@@ -46,6 +120,12 @@ impl Resolver for ModuleLoader {
 
 impl Loader for ModuleLoader {
     fn load<'js>(&mut self, ctx: &Ctx<'js>, name: &str) -> rquickjs::Result<Module<'js, Declared>> {
+        // Loading bytecode resolves its imports, which borrows the loader again
+        let compiled = self.0.borrow().compiled.get(name).copied();
+        if let Some(compiled) = compiled {
+            return bundle::Bundle(compiled).load(ctx, COMPILED_MODULE);
+        }
+
         let mut inner = self.0.borrow_mut();
         inner.deref_mut().load(ctx, name)
     }
@@ -54,12 +134,19 @@ impl Loader for ModuleLoader {
 struct BaseModuleLoader {
     bundle: Bundle,
     defined_modules: RefCell<HashSet<String>>,
+    compiled: HashMap<String, CompiledModule>,
     md_loader: MDLoader,
+    lease: u64,
 }
 
 impl BaseModuleLoader {
     pub fn new() -> Self {
-        let mut hs = HashSet::from(["zen".to_string(), "http".to_string()]);
+        let mut hs = HashSet::from([
+            "zen".to_string(),
+            "http".to_string(),
+            "crypto".to_string(),
+            "encoding".to_string(),
+        ]);
 
         JS_BUNDLE.iter().for_each(|(key, _)| {
             hs.insert(key.to_string());
@@ -67,12 +154,16 @@ impl BaseModuleLoader {
 
         let md_loader = MDLoader::default()
             .with_module("zen", ZenModule)
-            .with_module("http", http::HttpModule);
+            .with_module("http", http::HttpModule)
+            .with_module("crypto", CryptoModule)
+            .with_module("encoding", EncodingModule);
 
         Self {
             bundle: JS_BUNDLE,
             defined_modules: RefCell::new(hs),
+            compiled: HashMap::new(),
             md_loader,
+            lease: 0,
         }
     }
 
@@ -98,6 +189,13 @@ impl Resolver for &mut BaseModuleLoader {
             return Ok(name.to_string());
         }
 
+        if let Some(key) = library::resolve_library(base, name) {
+            let key = lease_scoped(&key, self.lease);
+            if defined_modules.contains(&key) {
+                return Ok(key);
+            }
+        }
+
         Err(Error::new_resolving(base, name))
     }
 }
@@ -1,40 +1,72 @@
-use crate::decision_graph::graph::{DecisionGraph, DecisionGraphConfig};
+use crate::cancellation::EvaluationLimits;
+use crate::decision_graph::graph::{check_depth, DecisionGraph, DecisionGraphConfig};
+use crate::expression_context::ExpressionContext;
 use crate::loader::DynamicLoader;
+use crate::model::DecisionContent;
 use crate::nodes::custom::DynamicCustomNode;
+use crate::nodes::decision::memo::{EvaluationMemo, MemoKey};
 use crate::nodes::function::v2::error::{FunctionResult, ResultExt};
 use crate::nodes::function::v2::listener::{RuntimeEvent, RuntimeListener};
 use crate::nodes::function::v2::module::export_default;
 use crate::nodes::function::v2::serde::JsValue;
+use crate::nodes::wasm::WasmRuntime;
 use crate::nodes::NodeHandlerExtensions;
+use crate::observer::{DynamicEvaluationObserver, MemoLookup};
+use crate::policy::resolver::DynamicPropertyResolver;
+use crate::policy::runtime::PolicyRuntime;
 use rquickjs::module::{Declarations, Exports, ModuleDef};
 use rquickjs::prelude::{Async, Func, Opt};
 use rquickjs::{CatchResultExt, Ctx, Function, Object};
+use std::cell::OnceCell;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use zen_expression::variable::Variable;
+use zen_expression::Isolate;
 
 use crate::nodes::function::http_handler::DynamicHttpHandler;
+use crate::nodes::function::pool::{FunctionPool, FunctionRuntimeCell};
 
 pub(crate) struct ZenListener {
     pub loader: DynamicLoader,
     pub custom_node: DynamicCustomNode,
     pub http_handler: DynamicHttpHandler,
+    pub limits: EvaluationLimits,
+    pub observer: DynamicEvaluationObserver,
+    pub property_resolver: DynamicPropertyResolver,
+    pub expression_context: ExpressionContext,
+    pub function_pool: Option<FunctionPool>,
+    pub wasm_runtime: Arc<OnceCell<WasmRuntime>>,
+    pub memo: Option<EvaluationMemo>,
 }
 
+/// Expression settings of the evaluation, reachable from the `zen` module through the context
+/// userdata
+#[derive(rquickjs::JsLifetime)]
+pub(crate) struct ZenExpressionContext(pub ExpressionContext);
+
 impl RuntimeListener for ZenListener {
     fn on_event<'js>(
         &self,
         ctx: Ctx<'js>,
-        event: RuntimeEvent,
+        _event: RuntimeEvent,
     ) -> Pin<Box<dyn Future<Output = FunctionResult> + 'js>> {
         let loader = self.loader.clone();
         let custom_node = self.custom_node.clone();
         let http_handler = self.http_handler.clone();
+        let limits = self.limits.clone();
+        let observer = self.observer.clone();
+        let property_resolver = self.property_resolver.clone();
+        let expression_context = self.expression_context.clone();
+        let function_pool = self.function_pool.clone();
+        let wasm_runtime = self.wasm_runtime.clone();
+        let memo = self.memo.clone();
 
         Box::pin(async move {
-            if event != RuntimeEvent::Startup {
-                return Ok(());
-            };
-
+            // Installed again on soft reset, binding the handles of the evaluation now using
+            // a pooled runtime
+            ctx.store_userdata(ZenExpressionContext(expression_context.clone()))
+                .or_throw(&ctx)?;
             ctx.globals()
                 .set(
                     "__evaluate",
@@ -46,41 +78,117 @@ impl RuntimeListener for ZenListener {
                             let loader = loader.clone();
                             let custom_node = custom_node.clone();
                             let http_handler = http_handler.clone();
+                            let limits = limits.clone();
+                            let observer = observer.clone();
+                            let property_resolver = property_resolver.clone();
+                            let expression_context = expression_context.clone();
+                            let function_pool = function_pool.clone();
+                            let wasm_runtime = wasm_runtime.clone();
+                            let memo = memo.clone();
 
                             async move {
                                 let config: Object = ctx.globals().get("config").or_throw(&ctx)?;
 
                                 let iteration: u8 = config.get("iteration").or_throw(&ctx)?;
                                 let max_depth: u8 = config.get("maxDepth").or_throw(&ctx)?;
-                                let trace = opts
-                                    .0
-                                    .map(|opt| opt.get::<_, bool>("trace").unwrap_or_default())
-                                    .unwrap_or_default();
+                                let concurrent: bool = config.get("concurrent").unwrap_or_default();
+                                let (trace, goals) = match opts.0 {
+                                    Some(opt) => (
+                                        opt.get::<_, bool>("trace").unwrap_or_default(),
+                                        opt.get::<_, Option<Vec<String>>>("goals")
+                                            .ok()
+                                            .flatten()
+                                            .unwrap_or_default(),
+                                    ),
+                                    None => Default::default(),
+                                };
+                                let goals: Vec<Arc<str>> =
+                                    goals.into_iter().map(Arc::from).collect();
+
+                                let memo_lookup = memo.clone().and_then(|memo| {
+                                    let memo_key = MemoKey::new(&key, &context.0, None, &goals)?;
+                                    Some((memo, memo_key))
+                                });
+                                if let Some((memo, memo_key)) = &memo_lookup {
+                                    let (result, outcome) = memo.get(memo_key);
+                                    if let Some(observer) = &observer {
+                                        observer.on_memo(&MemoLookup {
+                                            key: &key,
+                                            depth: iteration + 1,
+                                            outcome,
+                                        });
+                                    }
+
+                                    if let Some(result) = result {
+                                        let response = Variable::empty_object();
+                                        response.dot_insert("result", result);
+                                        response.dot_insert("memoized", Variable::Bool(true));
+                                        return rquickjs::Result::Ok(JsValue(response));
+                                    }
+                                }
 
                                 let load_result = loader.load(key.as_str()).await;
                                 let decision_content = load_result.or_throw(&ctx)?;
-                                let kind = decision_content.kind();
-                                let Some(graph_content) = decision_content.into_graph_arc() else {
-                                    return Err(rquickjs::Exception::throw_message(
-                                        &ctx,
-                                        &format!("decision '{key}' is a {kind}, expected graph"),
-                                    ));
+                                let response = match decision_content.as_ref() {
+                                    DecisionContent::Policy(_) => {
+                                        check_depth(iteration + 1, max_depth).or_throw(&ctx)?;
+                                        let policy = PolicyRuntime::load(
+                                            &loader,
+                                            &key,
+                                            decision_content,
+                                            &property_resolver,
+                                            &expression_context,
+                                        )
+                                        .await
+                                        .or_throw(&ctx)?;
+
+                                        policy
+                                            .evaluate(context.0, goals, trace, &limits)
+                                            .await
+                                            .or_throw(&ctx)?
+                                    }
+                                    DecisionContent::Graph(_) => {
+                                        let Some(graph_content) = decision_content.into_graph_arc()
+                                        else {
+                                            return Err(rquickjs::Exception::throw_message(
+                                                &ctx,
+                                                &format!("decision '{key}' could not be loaded"),
+                                            ));
+                                        };
+
+                                        let mut sub_tree =
+                                            DecisionGraph::try_new(DecisionGraphConfig {
+                                                content: graph_content,
+                                                max_depth,
+                                                iteration: iteration + 1,
+                                                trace,
+                                                concurrent,
+                                                extensions: NodeHandlerExtensions {
+                                                    loader: loader.clone(),
+                                                    custom_node: custom_node.clone(),
+                                                    http_handler: http_handler.clone(),
+                                                    limits,
+                                                    observer,
+                                                    property_resolver,
+                                                    expression_context,
+                                                    memo,
+                                                    function_runtime: FunctionRuntimeCell::shared(
+                                                        function_pool,
+                                                    ),
+                                                    wasm_runtime,
+                                                    ..Default::default()
+                                                },
+                                            })
+                                            .or_throw(&ctx)?;
+
+                                        sub_tree.evaluate(context.0).await.or_throw(&ctx)?
+                                    }
                                 };
-                                let mut sub_tree = DecisionGraph::try_new(DecisionGraphConfig {
-                                    content: graph_content,
-                                    max_depth,
-                                    iteration: iteration + 1,
-                                    trace,
-                                    extensions: NodeHandlerExtensions {
-                                        loader: loader.clone(),
-                                        custom_node: custom_node.clone(),
-                                        http_handler: http_handler.clone(),
-                                        ..Default::default()
-                                    },
-                                })
-                                .or_throw(&ctx)?;
-
-                                let response = sub_tree.evaluate(context.0).await.or_throw(&ctx)?;
+
+                                if let Some((memo, memo_key)) = memo_lookup {
+                                    memo.insert(memo_key, &response.result);
+                                }
+
                                 let k = serde_json::to_value(response).or_throw(&ctx)?.into();
 
                                 return rquickjs::Result::Ok(JsValue(k));
@@ -95,12 +203,23 @@ impl RuntimeListener for ZenListener {
     }
 }
 
+/// Isolate over `context` configured with the expression settings of the evaluation
+fn make_isolate(ctx: &Ctx<'_>, context: Variable) -> Isolate {
+    let mut isolate = Isolate::with_environment(context);
+    if let Some(expression_context) = ctx.userdata::<ZenExpressionContext>() {
+        expression_context.0.apply(&mut isolate);
+    }
+    isolate
+}
+
 fn evaluate_expression<'js>(
     ctx: Ctx<'js>,
     expression: String,
     context: JsValue,
 ) -> rquickjs::Result<JsValue> {
-    let s = zen_expression::evaluate_expression(expression.as_str(), context.0).or_throw(&ctx)?;
+    let s = make_isolate(&ctx, context.0)
+        .run_standard(expression.as_str())
+        .or_throw(&ctx)?;
 
     Ok(JsValue(s))
 }
@@ -110,8 +229,17 @@ fn evaluate_unary_expression<'js>(
     expression: String,
     context: JsValue,
 ) -> rquickjs::Result<bool> {
-    let s =
-        zen_expression::evaluate_unary_expression(expression.as_str(), context.0).or_throw(&ctx)?;
+    let has_reference = context
+        .0
+        .as_object()
+        .is_some_and(|object| object.borrow().contains_key(&Variable::dollar_key()));
+    if !has_reference {
+        return Err(zen_expression::IsolateError::MissingContextReference).or_throw(&ctx);
+    }
+
+    let s = make_isolate(&ctx, context.0)
+        .run_unary(expression.as_str())
+        .or_throw(&ctx)?;
 
     Ok(s)
 }
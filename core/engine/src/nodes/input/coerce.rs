@@ -0,0 +1,327 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use zen_expression::variable::{ToVariable, Variable};
+use zen_types::symbol::Symbol;
+
+const MAX_DEPTH: usize = 64;
+
+/// Change applied to the input before it was validated
+#[derive(Debug, Clone, Serialize, ToVariable)]
+#[serde(rename_all = "camelCase")]
+pub struct InputCoercion {
+    pub path: String,
+    pub kind: CoercionKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Variable>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Variable>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToVariable)]
+#[serde(rename_all = "camelCase")]
+pub enum CoercionKind {
+    /// Missing property filled from the schema `default`
+    Default,
+    /// Scalar converted to the declared type or date format
+    Coerce,
+    /// Property removed because the schema disallows additional properties
+    Strip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScalarType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Date,
+    DateTime,
+}
+
+impl ScalarType {
+    fn from_schema(ty: &str, format: Option<&str>) -> Option<Self> {
+        match (ty, format) {
+            ("string", Some("date")) => Some(Self::Date),
+            ("string", Some("date-time")) => Some(Self::DateTime),
+            ("string", _) => Some(Self::String),
+            ("number", _) => Some(Self::Number),
+            ("integer", _) => Some(Self::Integer),
+            ("boolean", _) => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+
+    fn accepts(&self, value: &Variable) -> bool {
+        match (self, value) {
+            (Self::Number, Variable::Number(_)) => true,
+            (Self::Integer, Variable::Number(n)) => n.fract().is_zero(),
+            (Self::Boolean, Variable::Bool(_)) => true,
+            (Self::String, Variable::String(_)) => true,
+            (Self::Date | Self::DateTime, Variable::String(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Converts a scalar to `target`, `None` when it already conforms or cannot be converted.
+///
+/// Date strings conform only in their canonical form, `YYYY-MM-DD` for dates and RFC 3339
+/// for date-times; other ISO 8601 forms and unix timestamps are rewritten.
+pub(crate) fn coerce_scalar(value: &Variable, target: ScalarType) -> Option<Variable> {
+    match (target, value) {
+        (ScalarType::String, Variable::Number(n)) => {
+            Some(Variable::String(n.normalize().to_string().into()))
+        }
+        (ScalarType::String, Variable::Bool(b)) => Some(Variable::String(b.to_string().into())),
+        (ScalarType::Number, Variable::String(s)) => parse_decimal(s).map(Variable::Number),
+        (ScalarType::Integer, Variable::String(s)) => parse_decimal(s)
+            .filter(|n| n.fract().is_zero())
+            .map(Variable::Number),
+        (ScalarType::Boolean, Variable::String(s)) => {
+            match str::trim(s).to_ascii_lowercase().as_str() {
+                "true" => Some(Variable::Bool(true)),
+                "false" => Some(Variable::Bool(false)),
+                _ => None,
+            }
+        }
+        (ScalarType::Date, Variable::String(s)) => {
+            if NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() {
+                return None;
+            }
+
+            let date = DateTime::parse_from_rfc3339(str::trim(s))
+                .map(|dt| dt.date_naive())
+                .ok()
+                .or_else(|| parse_naive_date_time(s).map(|dt| dt.date()))?;
+            Some(Variable::String(date.format("%Y-%m-%d").to_string().into()))
+        }
+        (ScalarType::Date, Variable::Number(n)) => {
+            let date = from_timestamp(n)?.date_naive();
+            Some(Variable::String(date.format("%Y-%m-%d").to_string().into()))
+        }
+        (ScalarType::DateTime, Variable::String(s)) => {
+            if DateTime::parse_from_rfc3339(s).is_ok() {
+                return None;
+            }
+
+            let date_time = parse_naive_date_time(s)?.and_utc();
+            Some(Variable::String(rfc3339(date_time).into()))
+        }
+        (ScalarType::DateTime, Variable::Number(n)) => {
+            Some(Variable::String(rfc3339(from_timestamp(n)?).into()))
+        }
+        _ => None,
+    }
+}
+
+fn parse_decimal(s: &str) -> Option<Decimal> {
+    let s = s.trim();
+    Decimal::from_str(s)
+        .or_else(|_| Decimal::from_scientific(s))
+        .ok()
+}
+
+fn parse_naive_date_time(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    })
+}
+
+fn from_timestamp(n: &Decimal) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(n.trunc().to_i64()?, 0)
+}
+
+fn rfc3339(date_time: DateTime<Utc>) -> String {
+    date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Applies `default`s, scalar coercion and `additionalProperties: false` of `schema` to
+/// `value` in place, returning every change made.
+///
+/// Local `$ref`s and `allOf` are followed; `anyOf` and `oneOf` are left to validation since
+/// the branch a value is meant for is ambiguous.
+pub(crate) fn coerce_input(schema: &Value, value: &mut Variable) -> Vec<InputCoercion> {
+    let mut coercer = SchemaCoercer {
+        root: schema,
+        changes: Vec::new(),
+        depth: 0,
+    };
+    coercer.walk(schema, value, "");
+    coercer.changes
+}
+
+struct SchemaCoercer<'a> {
+    root: &'a Value,
+    changes: Vec<InputCoercion>,
+    depth: usize,
+}
+
+impl<'a> SchemaCoercer<'a> {
+    fn resolve(&self, mut schema: &'a Value) -> &'a Value {
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+                break;
+            };
+            let Some(target) = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            else {
+                break;
+            };
+            schema = target;
+        }
+
+        schema
+    }
+
+    fn walk(&mut self, schema: &'a Value, value: &mut Variable, path: &str) {
+        if self.depth >= MAX_DEPTH {
+            return;
+        }
+        let Some(schema) = self.resolve(schema).as_object() else {
+            return;
+        };
+
+        self.depth += 1;
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            for sub_schema in all_of {
+                self.walk(sub_schema, value, path);
+            }
+        }
+
+        match value {
+            Variable::Null => {}
+            Variable::Object(_) => self.walk_object(schema, value, path),
+            Variable::Array(_) => self.walk_array(schema, value, path),
+            _ => self.walk_scalar(schema, value, path),
+        }
+        self.depth -= 1;
+    }
+
+    fn walk_object(&mut self, schema: &'a Map<String, Value>, value: &Variable, path: &str) {
+        let Some(object) = value.as_object() else {
+            return;
+        };
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let mut map = object.borrow_mut();
+
+        for (key, property_schema) in properties.into_iter().flatten() {
+            let child_path = join_key(path, key);
+            let symbol = Symbol::from(key.as_str());
+            if let Some(child) = map.get_mut(&symbol) {
+                self.walk(property_schema, child, &child_path);
+                continue;
+            }
+
+            let Some(default) = self.resolve(property_schema).get("default") else {
+                continue;
+            };
+            let mut child = Variable::from(default);
+            self.changes.push(InputCoercion {
+                path: child_path.clone(),
+                kind: CoercionKind::Default,
+                from: None,
+                to: Some(child.clone()),
+            });
+            self.walk(property_schema, &mut child, &child_path);
+            map.insert(symbol, child);
+        }
+
+        let is_declared = |key: &Symbol| properties.is_some_and(|p| p.contains_key(key.as_str()));
+        let undeclared: Vec<Symbol> = map.keys().filter(|k| !is_declared(k)).cloned().collect();
+        match schema.get("additionalProperties") {
+            Some(Value::Bool(false)) if !schema.contains_key("patternProperties") => {
+                for key in undeclared {
+                    let removed = map.remove(&key);
+                    self.changes.push(InputCoercion {
+                        path: join_key(path, key.as_str()),
+                        kind: CoercionKind::Strip,
+                        from: removed,
+                        to: None,
+                    });
+                }
+            }
+            Some(additional @ Value::Object(_)) => {
+                for key in undeclared {
+                    if let Some(child) = map.get_mut(&key) {
+                        self.walk(additional, child, &join_key(path, key.as_str()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn walk_array(&mut self, schema: &'a Map<String, Value>, value: &Variable, path: &str) {
+        let Some(array) = value.as_array() else {
+            return;
+        };
+        let mut items = array.borrow_mut();
+
+        match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (i, (item, item_schema)) in items.iter_mut().zip(tuple).enumerate() {
+                    self.walk(item_schema, item, &format!("{path}[{i}]"));
+                }
+            }
+            Some(item_schema) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.walk(item_schema, item, &format!("{path}[{i}]"));
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn walk_scalar(&mut self, schema: &Map<String, Value>, value: &mut Variable, path: &str) {
+        let format = schema.get("format").and_then(Value::as_str);
+        let targets: Vec<ScalarType> = match schema.get("type") {
+            Some(Value::String(ty)) => ScalarType::from_schema(ty, format).into_iter().collect(),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .filter_map(|ty| ScalarType::from_schema(ty, format))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let coerced = match targets.iter().find(|target| target.accepts(value)) {
+            Some(target) => coerce_scalar(value, *target),
+            None => targets
+                .iter()
+                .find_map(|target| coerce_scalar(value, *target)),
+        };
+        let Some(coerced) = coerced else {
+            return;
+        };
+
+        let from = std::mem::replace(value, coerced.clone());
+        self.changes.push(InputCoercion {
+            path: path.to_string(),
+            kind: CoercionKind::Coerce,
+            from: Some(from),
+            to: Some(coerced),
+        });
+    }
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    match path {
+        "" => key.to_string(),
+        _ => format!("{path}.{key}"),
+    }
+}
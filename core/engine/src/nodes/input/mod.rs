@@ -1,24 +1,42 @@
 use crate::nodes::definition::NodeHandler;
 use crate::nodes::result::NodeResult;
 use crate::nodes::NodeContext;
+use zen_expression::variable::ToVariable;
 use zen_types::decision::InputNodeContent;
 use zen_types::variable::Variable;
 
+pub(crate) mod coerce;
+
+pub use coerce::{CoercionKind, InputCoercion};
+
 #[derive(Debug, Clone)]
 pub struct InputNodeHandler;
 
 pub type InputNodeData = InputNodeContent;
 pub type InputNodeTrace = Variable;
 
+#[derive(Debug, Clone, ToVariable)]
+#[serde(rename_all = "camelCase")]
+struct InputCoercionTrace {
+    coercions: Vec<InputCoercion>,
+}
+
 impl NodeHandler for InputNodeHandler {
     type NodeData = InputNodeData;
     type TraceData = InputNodeTrace;
 
     async fn handle(&self, ctx: NodeContext<Self::NodeData, Self::TraceData>) -> NodeResult {
+        let mut input = ctx.input.clone();
         if let Some(json_schema) = &ctx.node.schema {
-            ctx.validate(json_schema, &ctx.input)?;
+            if ctx.node.coerce {
+                input = input.deep_clone();
+                let coercions = coerce::coerce_input(json_schema, &mut input);
+                ctx.trace(|t| *t = InputCoercionTrace { coercions }.to_variable());
+            }
+
+            ctx.validate(json_schema, &input)?;
         };
 
-        ctx.success(ctx.input.clone())
+        ctx.success(input)
     }
 }
@@ -12,10 +12,12 @@ mod result;
 pub(crate) mod transform_attributes;
 pub(crate) mod validator_cache;
 pub mod variable_json;
+pub mod wasm;
 
 pub use context::{NodeContext, NodeContextBase, NodeContextConfig, NodeContextExt};
 pub use definition::NodeHandler;
 pub(crate) use definition::{NodeDataType, TraceDataType};
 pub use extensions::NodeHandlerExtensions;
 pub use function::http_handler;
+pub use function::pool;
 pub use result::{NodeError, NodeRequest, NodeResponse, NodeResult};
@@ -1,20 +1,27 @@
 use crate::model::{TransformAttributes, TransformExecutionMode};
 use crate::nodes::result::NodeResult;
-use crate::nodes::{NodeContextBase, NodeContextExt};
+use crate::nodes::{NodeContextBase, NodeContextExt, NodeError};
+use futures::future::join_all;
+use std::cell::{Cell, RefCell};
 use std::future::Future;
 use std::ops::Deref;
 use zen_expression::Variable;
+use zen_types::decision::LoopOptions;
 
 pub(crate) trait TransformAttributesExecution {
-    async fn run_with<F, Fut>(&self, ctx: NodeContextBase, evaluate: F) -> NodeResult
+    /// Evaluates the node input, `worker` is called once for every handler needed to evaluate
+    /// loop items concurrently
+    async fn run_with<W, F, Fut>(&self, ctx: NodeContextBase, worker: W) -> NodeResult
     where
+        W: Fn() -> F,
         F: Fn(Variable, bool) -> Fut,
         Fut: Future<Output = NodeResult>;
 }
 
 impl TransformAttributesExecution for TransformAttributes {
-    async fn run_with<F, Fut>(&self, ctx: NodeContextBase, evaluate: F) -> NodeResult
+    async fn run_with<W, F, Fut>(&self, ctx: NodeContextBase, worker: W) -> NodeResult
     where
+        W: Fn() -> F,
         F: Fn(Variable, bool) -> Fut,
         Fut: Future<Output = NodeResult>,
     {
@@ -29,8 +36,9 @@ impl TransformAttributesExecution for TransformAttributes {
         };
 
         // let mut trace_data: Option<Variable> = None;
-        let mut output = match self.execution_mode {
-            TransformExecutionMode::Single => {
+        let mut output = match (&self.execution_mode, &self.loop_options) {
+            (TransformExecutionMode::Single, _) => {
+                let evaluate = worker();
                 let response = evaluate(input, false).await?;
                 if let Some(td) = response.trace_data {
                     ctx.trace(|t| {
@@ -40,17 +48,30 @@ impl TransformAttributesExecution for TransformAttributes {
 
                 response.output
             }
-            TransformExecutionMode::Loop => {
+            (TransformExecutionMode::Loop, Some(options)) => {
+                let items = input
+                    .as_array()
+                    .node_context_message(&ctx, "Expected an array")?
+                    .borrow()
+                    .clone();
+
+                // Boxed to keep the stack of nested sub-decisions shallow
+                Box::pin(run_items(&ctx, &items, self.pass_through, options, worker)).await?
+            }
+            (TransformExecutionMode::Loop, None) => {
                 let input_array_ref = input
                     .as_array()
                     .node_context_message(&ctx, "Expected an array")?;
                 let input_array = input_array_ref.borrow();
+                let evaluate = worker();
                 ctx.trace(|t| {
                     *t = Variable::from_array(Vec::with_capacity(input_array.len()));
                 });
 
                 let mut output_array = Vec::with_capacity(input_array.len());
                 for (index, input) in input_array.iter().enumerate() {
+                    ctx.extensions.limits.check().node_context(&ctx)?;
+
                     let has_more = index < input_array.len() - 1;
                     let mut response = evaluate(input.clone(), has_more).await?;
                     if let Some(td) = response.trace_data {
@@ -88,3 +109,104 @@ impl TransformAttributesExecution for TransformAttributes {
         ctx.success(output)
     }
 }
+
+/// Evaluates loop items with `options.concurrency` handlers taking the next pending item in
+/// turns, returning outputs in item order
+async fn run_items<W, F, Fut>(
+    ctx: &NodeContextBase,
+    items: &[Variable],
+    pass_through: bool,
+    options: &LoopOptions,
+    worker: W,
+) -> Result<Variable, NodeError>
+where
+    W: Fn() -> F,
+    F: Fn(Variable, bool) -> Fut,
+    Fut: Future<Output = NodeResult>,
+{
+    let next = &Cell::new(0);
+    let aborted: &RefCell<Option<NodeError>> = &RefCell::new(None);
+    let outcomes: &RefCell<Vec<Option<NodeResult>>> =
+        &RefCell::new(items.iter().map(|_| None).collect());
+
+    let run = move |evaluate: F| async move {
+        while aborted.borrow().is_none() {
+            let index = next.get();
+            let Some(input) = items.get(index) else {
+                break;
+            };
+            next.set(index + 1);
+
+            // Interruptions stop the loop even when item errors are collected
+            if let Err(err) = ctx.extensions.limits.check().node_context(ctx) {
+                aborted.borrow_mut().get_or_insert(err);
+                break;
+            }
+
+            let has_more = index < items.len() - 1;
+            match evaluate(input.clone(), has_more).await {
+                Err(err) if !options.collect_errors => {
+                    aborted.borrow_mut().get_or_insert(err);
+                }
+                outcome => outcomes.borrow_mut()[index] = Some(outcome),
+            }
+        }
+    };
+
+    let workers = usize::from(options.concurrency.max(1)).min(items.len());
+    join_all((0..workers).map(|_| run(worker()))).await;
+    if let Some(err) = aborted.take() {
+        return Err(err);
+    }
+
+    ctx.trace(|t| {
+        *t = Variable::from_array(Vec::with_capacity(items.len()));
+    });
+
+    let mut results = Vec::with_capacity(items.len());
+    let mut errors = Vec::with_capacity(items.len());
+    for (input, outcome) in items.iter().zip(outcomes.take()) {
+        let item_trace = Variable::empty_object();
+        match outcome {
+            Some(Ok(mut response)) => {
+                item_trace.dot_insert("status", Variable::String("success".into()));
+                item_trace.dot_insert("trace", response.trace_data.unwrap_or(Variable::Null));
+
+                if pass_through {
+                    response.output = input.clone().merge_clone(&response.output);
+                }
+
+                results.push(response.output);
+                errors.push(Variable::Null);
+            }
+            Some(Err(err)) => {
+                let message = Variable::String(err.to_string().into());
+                item_trace.dot_insert("status", Variable::String("error".into()));
+                item_trace.dot_insert("error", message.clone());
+                item_trace.dot_insert("trace", err.trace.unwrap_or(Variable::Null));
+
+                let error = Variable::empty_object();
+                error.dot_insert("message", message);
+                results.push(Variable::Null);
+                errors.push(error);
+            }
+            // Every item has an outcome once the loop completes without aborting
+            None => continue,
+        }
+
+        ctx.trace(|var| {
+            if let Variable::Array(arr) = var {
+                arr.borrow_mut().push(item_trace);
+            };
+        });
+    }
+
+    if !options.collect_errors {
+        return Ok(Variable::from_array(results));
+    }
+
+    let output = Variable::empty_object();
+    output.dot_insert("results", Variable::from_array(results));
+    output.dot_insert("errors", Variable::from_array(errors));
+    Ok(output)
+}
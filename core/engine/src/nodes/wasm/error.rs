@@ -0,0 +1,44 @@
+use crate::cancellation::InterruptReason;
+use crate::loader::LoaderError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WasmError {
+    #[error("Node has neither an embedded module nor a loader key")]
+    MissingModule,
+
+    #[error("Embedded module is not valid base64: {0}")]
+    Encoding(#[from] base64::DecodeError),
+
+    #[error(transparent)]
+    Loader(#[from] LoaderError),
+
+    #[error("Failed to compile module: {0}")]
+    Compile(wasmi::Error),
+
+    #[error("Failed to instantiate module: {0}")]
+    Instantiate(wasmi::Error),
+
+    #[error("Module does not export `{0}`")]
+    MissingExport(&'static str),
+
+    #[error("Fuel limit of {0} exhausted")]
+    OutOfFuel(u64),
+
+    #[error("Memory limit of {0} bytes exceeded")]
+    MemoryLimit(u64),
+
+    #[error("Module trapped: {0}")]
+    Trap(wasmi::Error),
+
+    #[error(transparent)]
+    Interrupted(InterruptReason),
+
+    #[error("Module accessed memory out of bounds")]
+    OutOfBounds,
+
+    #[error("Module produced invalid JSON: {0}")]
+    Output(#[from] serde_json::Error),
+}
+
+pub type WasmResult<T> = Result<T, WasmError>;
@@ -0,0 +1,94 @@
+use crate::nodes::definition::NodeHandler;
+use crate::nodes::function::v2::module::console::Log;
+use crate::nodes::result::NodeResult;
+use crate::nodes::wasm::error::{WasmError, WasmResult};
+use crate::nodes::wasm::runtime::WasmLimits;
+use crate::nodes::NodeContext;
+use crate::ZEN_CONFIG;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use zen_expression::variable::ToVariable;
+use zen_types::decision::WasmNodeContent;
+use zen_types::variable::Variable;
+
+pub(crate) mod error;
+pub(crate) mod runtime;
+
+pub use error::WasmError as WasmNodeError;
+pub use runtime::WasmRuntime;
+
+/// Runs the `handle` export of a WebAssembly module on the node input.
+///
+/// The guest runs synchronously on the thread polling the evaluation, so it blocks that executor
+/// thread until it returns, runs out of fuel or is interrupted at a host call. Concurrent graph
+/// branches do not progress meanwhile; lower the fuel of a node to bound how long it can block,
+/// or evaluate on a runtime whose other tasks can tolerate it.
+#[derive(Debug, Clone)]
+pub struct WasmNodeHandler;
+
+pub type WasmNodeData = WasmNodeContent;
+
+#[derive(Debug, Clone, Default, ToVariable)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmNodeTrace {
+    pub log: Vec<Log>,
+    pub fuel_consumed: u64,
+}
+
+impl NodeHandler for WasmNodeHandler {
+    type NodeData = WasmNodeData;
+    type TraceData = WasmNodeTrace;
+
+    async fn handle(&self, ctx: NodeContext<Self::NodeData, Self::TraceData>) -> NodeResult {
+        let bytes = self
+            .module_bytes(&ctx)
+            .await
+            .map_err(|err| ctx.make_error(err))?;
+        let runtime = ctx.extensions.wasm_runtime();
+        let module = runtime.module(&bytes).map_err(|err| ctx.make_error(err))?;
+
+        // Documents may lower the engine limits but never raise them
+        let max_fuel = ZEN_CONFIG.wasm_fuel.load(Ordering::Relaxed);
+        let max_memory_bytes = ZEN_CONFIG.wasm_max_memory_bytes.load(Ordering::Relaxed);
+        let limits = WasmLimits {
+            fuel: ctx.node.fuel.map_or(max_fuel, |fuel| fuel.min(max_fuel)),
+            max_memory_bytes: ctx
+                .node
+                .max_memory_bytes
+                .map_or(max_memory_bytes, |bytes| bytes.min(max_memory_bytes)),
+        };
+
+        let input = serde_json::to_vec(&ctx.input).map_err(|err| ctx.make_error(err))?;
+        let invocation = runtime.invoke(&module, &input, limits, &ctx.extensions.limits);
+        ctx.trace(|t| {
+            t.log = invocation.log;
+            t.fuel_consumed = invocation.fuel_consumed;
+        });
+
+        let output = invocation
+            .result
+            .and_then(|output| Ok(serde_json::from_slice::<Variable>(&output)?))
+            .map_err(|err| ctx.make_error(err))?;
+
+        ctx.success(output)
+    }
+}
+
+impl WasmNodeHandler {
+    async fn module_bytes(
+        &self,
+        ctx: &NodeContext<WasmNodeData, WasmNodeTrace>,
+    ) -> WasmResult<Arc<[u8]>> {
+        if let Some(module) = &ctx.node.module {
+            return Ok(Arc::from(STANDARD.decode(module.trim())?));
+        }
+
+        let Some(key) = &ctx.node.key else {
+            return Err(WasmError::MissingModule);
+        };
+
+        Ok(ctx.extensions.loader().load_binary(key).await?)
+    }
+}
@@ -0,0 +1,231 @@
+use crate::cancellation::{EvaluationLimits, InterruptReason};
+use crate::nodes::function::v2::module::console::Log;
+use crate::nodes::wasm::error::{WasmError, WasmResult};
+use ahash::HashMap;
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use wasmi::core::TrapCode;
+use wasmi::{CallHook, Caller, Config, Engine, Extern, Linker, Memory, Module, Store};
+use wasmi::{StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+/// Compiled modules kept by a runtime, the cache is emptied once it is full
+const MAX_CACHED_MODULES: usize = 64;
+
+/// Resource limits of a single invocation
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WasmLimits {
+    pub fuel: u64,
+    pub max_memory_bytes: u64,
+}
+
+/// Result of a `handle` call together with what the guest reported while running
+pub(crate) struct WasmInvocation {
+    pub result: WasmResult<Vec<u8>>,
+    pub log: Vec<Log>,
+    pub fuel_consumed: u64,
+}
+
+struct HostState {
+    limits: StoreLimits,
+    evaluation: EvaluationLimits,
+    interrupted: Option<InterruptReason>,
+    log: Vec<Log>,
+    start: Instant,
+}
+
+/// Compiled modules shared by the evaluations of an engine, keyed by the SHA-256 digest of
+/// their bytes
+#[derive(Clone)]
+pub struct WasmRuntime {
+    engine: Engine,
+    modules: Arc<RwLock<HashMap<[u8; 32], Module>>>,
+}
+
+impl Debug for WasmRuntime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmRuntime").finish_non_exhaustive()
+    }
+}
+
+impl Default for WasmRuntime {
+    fn default() -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+
+        Self {
+            engine: Engine::new(&config),
+            modules: Default::default(),
+        }
+    }
+}
+
+impl WasmRuntime {
+    pub(crate) fn module(&self, bytes: &[u8]) -> WasmResult<Module> {
+        let key: [u8; 32] = Sha256::digest(bytes).into();
+
+        if let Some(module) = self.modules.read().ok().and_then(|m| m.get(&key).cloned()) {
+            return Ok(module);
+        }
+
+        let module = Module::new(&self.engine, bytes).map_err(WasmError::Compile)?;
+        if let Ok(mut modules) = self.modules.write() {
+            if modules.len() >= MAX_CACHED_MODULES {
+                modules.clear();
+            }
+            modules.insert(key, module.clone());
+        }
+
+        Ok(module)
+    }
+
+    /// Calls `handle` of the module with JSON `input`, returning the JSON it produced.
+    ///
+    /// Guests export `memory`, `alloc(len) -> ptr` and `handle(ptr, len) -> i64` where the result
+    /// packs the output pointer in the upper and its length in the lower 32 bits. They may import
+    /// `env.log(ptr, len)` to write UTF-8 lines into the trace.
+    ///
+    /// The evaluation deadline and cancellation are checked whenever control crosses between
+    /// host and guest: on entering `alloc` and `handle` and around every `env.log` call. wasmi
+    /// cannot resume a call once its fuel is exhausted, so a guest computing without host calls
+    /// is bounded by its fuel alone. For the same reason the call cannot yield to the executor in
+    /// fuel slices and blocks the calling thread until it ends.
+    pub(crate) fn invoke(
+        &self,
+        module: &Module,
+        input: &[u8],
+        limits: WasmLimits,
+        evaluation: &EvaluationLimits,
+    ) -> WasmInvocation {
+        let memory_limit = usize::try_from(limits.max_memory_bytes).unwrap_or(usize::MAX);
+        let mut store = Store::new(
+            &self.engine,
+            HostState {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(memory_limit)
+                    .trap_on_grow_failure(true)
+                    .build(),
+                evaluation: evaluation.clone(),
+                interrupted: None,
+                log: Vec::new(),
+                start: Instant::now(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        if !evaluation.is_unbounded() {
+            store.call_hook(check_evaluation);
+        }
+
+        let result = store
+            .set_fuel(limits.fuel)
+            .map_err(WasmError::Trap)
+            .and_then(|_| self.call_handle(&mut store, module, input, limits));
+        let result = match store.data().interrupted {
+            Some(reason) => Err(WasmError::Interrupted(reason)),
+            None => result,
+        };
+
+        let fuel_consumed = limits.fuel - store.get_fuel().unwrap_or(0);
+        WasmInvocation {
+            result,
+            log: std::mem::take(&mut store.data_mut().log),
+            fuel_consumed,
+        }
+    }
+
+    fn call_handle(
+        &self,
+        store: &mut Store<HostState>,
+        module: &Module,
+        input: &[u8],
+        limits: WasmLimits,
+    ) -> WasmResult<Vec<u8>> {
+        let mut linker = Linker::<HostState>::new(&self.engine);
+        linker
+            .func_wrap("env", "log", host_log)
+            .map_err(|err| WasmError::Instantiate(err.into()))?;
+
+        let instance = linker
+            .instantiate(&mut *store, module)
+            .and_then(|pre| pre.start(&mut *store))
+            .map_err(|err| trap_error(err, limits).unwrap_or_else(WasmError::Instantiate))?;
+
+        let memory = instance
+            .get_memory(&*store, "memory")
+            .ok_or(WasmError::MissingExport("memory"))?;
+        let alloc: TypedFunc<i32, i32> = instance
+            .get_typed_func(&*store, "alloc")
+            .map_err(|_| WasmError::MissingExport("alloc"))?;
+        let handle: TypedFunc<(i32, i32), i64> = instance
+            .get_typed_func(&*store, "handle")
+            .map_err(|_| WasmError::MissingExport("handle"))?;
+
+        let input_len = i32::try_from(input.len()).map_err(|_| WasmError::OutOfBounds)?;
+        let input_ptr = alloc
+            .call(&mut *store, input_len)
+            .map_err(|err| trap_error(err, limits).unwrap_or_else(WasmError::Trap))?;
+        memory
+            .write(&mut *store, input_ptr as u32 as usize, input)
+            .map_err(|_| WasmError::OutOfBounds)?;
+
+        let packed = handle
+            .call(&mut *store, (input_ptr, input_len))
+            .map_err(|err| trap_error(err, limits).unwrap_or_else(WasmError::Trap))?;
+        let output_ptr = (packed as u64 >> 32) as usize;
+        let output_len = (packed as u64 & 0xFFFF_FFFF) as usize;
+
+        read_memory(&memory, &*store, output_ptr, output_len)
+    }
+}
+
+fn read_memory(
+    memory: &Memory,
+    store: &Store<HostState>,
+    ptr: usize,
+    len: usize,
+) -> WasmResult<Vec<u8>> {
+    let data = memory.data(store);
+    let end = ptr.checked_add(len).ok_or(WasmError::OutOfBounds)?;
+    data.get(ptr..end)
+        .map(<[u8]>::to_vec)
+        .ok_or(WasmError::OutOfBounds)
+}
+
+fn host_log(mut caller: Caller<'_, HostState>, ptr: i32, len: i32) -> Result<(), wasmi::Error> {
+    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+        return Err(wasmi::Error::new("log requires an exported memory"));
+    };
+
+    let data = memory.data(&caller);
+    let (ptr, len) = (ptr as u32 as usize, len as u32 as usize);
+    let Some(bytes) = ptr.checked_add(len).and_then(|end| data.get(ptr..end)) else {
+        return Err(TrapCode::MemoryOutOfBounds.into());
+    };
+
+    let line = serde_json::Value::String(String::from_utf8_lossy(bytes).into_owned()).to_string();
+    let state = caller.data_mut();
+    state.log.push(Log {
+        lines: vec![line],
+        ms_since_run: state.start.elapsed().as_millis() as usize,
+    });
+
+    Ok(())
+}
+
+fn check_evaluation(state: &mut HostState, _: CallHook) -> Result<(), wasmi::Error> {
+    state.evaluation.check().map_err(|reason| {
+        state.interrupted = Some(reason);
+        wasmi::Error::new(reason.to_string())
+    })
+}
+
+fn trap_error(err: wasmi::Error, limits: WasmLimits) -> Result<WasmError, wasmi::Error> {
+    match err.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => Ok(WasmError::OutOfFuel(limits.fuel)),
+        Some(TrapCode::GrowthOperationLimited) => {
+            Ok(WasmError::MemoryLimit(limits.max_memory_bytes))
+        }
+        _ => Err(err),
+    }
+}
@@ -0,0 +1,216 @@
+//! Instrumentation hooks for collecting evaluation metrics without enabling traces.
+//!
+//! Register an [`EvaluationObserver`] through [`DecisionEngine::with_observer`](crate::DecisionEngine::with_observer).
+//! With the `tracing` feature enabled the engine additionally emits `zen.evaluate`, `zen.node`,
+//! `zen.load` and `zen.http` spans.
+use crate::loader::{
+    BinaryResponse, DecisionLoader, DynamicLoader, LoaderError, LoaderResponse, LoaderResult,
+    ModuleResponse,
+};
+use crate::EvaluationError;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Callbacks invoked while decisions are evaluated, every method defaults to a no-op.
+///
+/// Callbacks run inline on the evaluating task and should return quickly.
+pub trait EvaluationObserver: Debug + Send + Sync {
+    fn on_evaluation_start(&self, _event: &EvaluationStart) {}
+
+    fn on_evaluation_end(&self, _event: &EvaluationEnd<'_>) {}
+
+    fn on_node_start(&self, _event: &NodeStart<'_>) {}
+
+    fn on_node_end(&self, _event: &NodeEnd<'_>) {}
+
+    fn on_load(&self, _event: &LoadEvent<'_>) {}
+
+    fn on_sub_decision(&self, _event: &SubDecision<'_>) {}
+
+    fn on_memo(&self, _event: &MemoLookup<'_>) {}
+
+    fn on_http_call(&self, _event: &HttpCall<'_>) {}
+}
+
+pub type DynamicEvaluationObserver = Option<Arc<dyn EvaluationObserver>>;
+
+/// Graph evaluation started, `depth` is 0 for the root graph and grows with each sub-decision
+#[derive(Debug, Clone)]
+pub struct EvaluationStart {
+    pub depth: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct EvaluationEnd<'a> {
+    pub depth: u8,
+    pub duration: Duration,
+    pub error: Option<&'a EvaluationError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeStart<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    /// JDM node type, e.g. `decisionTableNode`
+    pub kind: &'static str,
+    pub depth: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeEnd<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub kind: &'static str,
+    pub depth: u8,
+    pub duration: Duration,
+    pub error: Option<&'a (dyn std::error::Error + 'static)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadOutcome {
+    /// Served from the set built by [`DecisionEngine::compile`](crate::DecisionEngine::compile)
+    Precompiled,
+    Hit,
+    Miss,
+    Failed,
+}
+
+impl<T> From<&LoaderResult<T>> for LoadOutcome {
+    fn from(value: &LoaderResult<T>) -> Self {
+        match value {
+            Ok(_) => LoadOutcome::Hit,
+            Err(LoaderError::NotFound(_)) => LoadOutcome::Miss,
+            Err(LoaderError::Internal { .. }) => LoadOutcome::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadEvent<'a> {
+    pub key: &'a str,
+    pub outcome: LoadOutcome,
+    pub duration: Duration,
+}
+
+/// Decision node is about to evaluate a nested graph at `depth`
+#[derive(Debug, Clone)]
+pub struct SubDecision<'a> {
+    pub node_id: &'a str,
+    pub key: &'a str,
+    pub depth: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoOutcome {
+    /// Computed earlier in the same evaluation
+    Hit,
+    /// Kept from an earlier evaluation by a [`SubDecisionMemo`](crate::nodes::decision::memo::SubDecisionMemo)
+    SharedHit,
+    Miss,
+}
+
+/// Sub-decision result looked up in the memo, a miss is followed by the sub-decision evaluating
+#[derive(Debug, Clone)]
+pub struct MemoLookup<'a> {
+    pub key: &'a str,
+    pub depth: u8,
+    pub outcome: MemoOutcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpCall<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub status: Option<u16>,
+    pub duration: Duration,
+    pub error: Option<&'a str>,
+}
+
+/// Reports loads of the wrapped loader, used whenever there is something to report to
+pub(crate) fn observe_loader(
+    loader: DynamicLoader,
+    observer: &DynamicEvaluationObserver,
+) -> DynamicLoader {
+    if observer.is_none() && !cfg!(feature = "tracing") {
+        return loader;
+    }
+
+    Arc::new(ObservedLoader {
+        inner: loader,
+        observer: observer.clone(),
+    })
+}
+
+#[derive(Debug)]
+struct ObservedLoader {
+    inner: DynamicLoader,
+    observer: DynamicEvaluationObserver,
+}
+
+impl ObservedLoader {
+    fn report<T>(&self, key: &str, response: &LoaderResult<T>, start: Instant) {
+        if let Some(observer) = &self.observer {
+            observer.on_load(&LoadEvent {
+                key,
+                outcome: response.into(),
+                duration: start.elapsed(),
+            });
+        }
+    }
+
+    /// Times `load`, reports it and wraps it in the `zen.load` span
+    fn observe<'a, T: Send + 'a>(
+        &'a self,
+        key: &'a str,
+        load: Pin<Box<dyn Future<Output = LoaderResult<T>> + 'a + Send>>,
+    ) -> Pin<Box<dyn Future<Output = LoaderResult<T>> + 'a + Send>> {
+        let load = async move {
+            let start = Instant::now();
+            let response = load.await;
+            self.report(key, &response, start);
+            response
+        };
+
+        #[cfg(feature = "tracing")]
+        let load = tracing::Instrument::instrument(load, tracing::debug_span!("zen.load", key));
+
+        Box::pin(load)
+    }
+}
+
+impl DecisionLoader for ObservedLoader {
+    fn load<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = LoaderResponse> + 'a + Send>> {
+        self.observe(key, self.inner.load(key))
+    }
+
+    fn keys(&self) -> Option<Vec<Arc<str>>> {
+        self.inner.keys()
+    }
+
+    fn load_sync(&self, key: &str) -> Option<LoaderResponse> {
+        let start = Instant::now();
+        let response = self.inner.load_sync(key)?;
+        self.report(key, &response, start);
+        Some(response)
+    }
+
+    fn load_module<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ModuleResponse> + 'a + Send>> {
+        self.observe(key, self.inner.load_module(key))
+    }
+
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = BinaryResponse> + 'a + Send>> {
+        self.observe(key, self.inner.load_binary(key))
+    }
+}
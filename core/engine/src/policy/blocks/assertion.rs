@@ -15,8 +15,8 @@ use crate::policy::ArcStrTrim;
 
 use super::context::{AnalysisContext, ExecutionContext, ExecutionError};
 use super::{
-    Block, BlockKind, BlockReadPlan, ConditionalReads, ExpressionLocation, ParseContext,
-    ReadFlattenFn, WriteSite, WriteTarget,
+    Block, BlockKind, BlockReadPlan, ConditionReads, ConditionalReads, ExpressionLocation,
+    ParseContext, ReadFlattenFn, WriteSite, WriteTarget,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -196,12 +196,20 @@ impl AssertionIr {
 
     pub(super) fn read_plan(&self, flatten: &mut ReadFlattenFn) -> BlockReadPlan {
         let mut unconditional = Vec::new();
+        let mut conditions = Vec::with_capacity(self.conditions.len());
         for condition in &self.conditions {
-            unconditional.extend(flatten(&condition.expression, ExpressionKind::Standard));
+            let reads = flatten(&condition.expression, ExpressionKind::Standard);
+            unconditional.extend(reads.iter().cloned());
+            conditions.push(ConditionReads {
+                id: condition.id.clone(),
+                expression: condition.expression.clone(),
+                reads: BlockReadPlan::dedup(reads),
+            });
         }
         BlockReadPlan {
             unconditional: BlockReadPlan::dedup(unconditional),
             conditional: ConditionalReads::None,
+            conditions: Arc::from(conditions),
         }
     }
 
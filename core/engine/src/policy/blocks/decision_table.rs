@@ -7,7 +7,7 @@ use zen_expression::intellisense::{ArmTest, IntelliSense, NumberCover};
 use zen_expression::variable::{Variable, VariableType};
 use zen_expression::Isolate;
 use zen_types::decision::{
-    DecisionTableHitPolicy, DecisionTableInputField, DecisionTableOutputField,
+    DecisionTableContent, DecisionTableHitPolicy, DecisionTableInputField, DecisionTableOutputField,
 };
 
 use base64::Engine as _;
@@ -93,6 +93,30 @@ impl DecisionTableDoc {
     }
 }
 
+impl From<&DecisionTableContent> for DecisionTableDoc {
+    fn from(content: &DecisionTableContent) -> Self {
+        Self {
+            hit_policy: content.hit_policy.clone(),
+            inputs: content.inputs.as_ref().clone(),
+            outputs: content.outputs.as_ref().clone(),
+            rules: content.rules.as_ref().clone(),
+        }
+    }
+}
+
+/// Graph node content with default transform attributes
+impl From<DecisionTableDoc> for DecisionTableContent {
+    fn from(doc: DecisionTableDoc) -> Self {
+        Self {
+            rules: Arc::new(doc.rules),
+            inputs: Arc::new(doc.inputs),
+            outputs: Arc::new(doc.outputs),
+            hit_policy: doc.hit_policy,
+            transform_attributes: Default::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DecisionTableIr {
     pub inputs: Vec<DecisionTableInputField>,
@@ -687,6 +711,7 @@ impl DecisionTableIr {
         BlockReadPlan {
             unconditional: BlockReadPlan::dedup(unconditional),
             conditional: ConditionalReads::DecisionTable(Arc::from(cells)),
+            conditions: Arc::from([]),
         }
     }
 
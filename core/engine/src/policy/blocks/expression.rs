@@ -114,6 +114,7 @@ impl ExpressionIr {
         BlockReadPlan {
             unconditional: BlockReadPlan::dedup(flatten(&self.value, ExpressionKind::Standard)),
             conditional: ConditionalReads::None,
+            conditions: Arc::from([]),
         }
     }
 
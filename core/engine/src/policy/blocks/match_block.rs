@@ -16,8 +16,8 @@ use crate::policy::ArcStrTrim;
 
 use super::context::{AnalysisContext, ExecutionContext, ExecutionError, InstanceSource};
 use super::{
-    ArmReads, Block, BlockKind, BlockReadPlan, ConditionalReads, ExpressionLocation, ParseContext,
-    ReadFlattenFn, WriteSite, WriteTarget,
+    ArmReads, Block, BlockKind, BlockReadPlan, ConditionReads, ConditionalReads,
+    ExpressionLocation, ParseContext, ReadFlattenFn, WriteSite, WriteTarget,
 };
 
 pub(crate) struct MatchSelection {
@@ -164,10 +164,19 @@ impl MatchIr {
     pub(super) fn read_plan(&self, flatten: &mut ReadFlattenFn) -> BlockReadPlan {
         let mut unconditional = Vec::new();
         let mut arms = Vec::new();
+        let mut conditions = Vec::with_capacity(self.arms.len());
         for arm in &self.arms {
-            if !arm.condition.is_empty() {
-                unconditional.extend(flatten(&arm.condition, ExpressionKind::Standard));
-            }
+            let condition_reads = if arm.condition.is_empty() {
+                Vec::new()
+            } else {
+                flatten(&arm.condition, ExpressionKind::Standard)
+            };
+            unconditional.extend(condition_reads.iter().cloned());
+            conditions.push(ConditionReads {
+                id: arm.id.clone(),
+                expression: arm.condition.clone(),
+                reads: BlockReadPlan::dedup(condition_reads),
+            });
             let value_reads = if arm.value.is_empty() {
                 Vec::new()
             } else {
@@ -181,6 +190,7 @@ impl MatchIr {
         BlockReadPlan {
             unconditional: BlockReadPlan::dedup(unconditional),
             conditional: ConditionalReads::Match(Arc::from(arms)),
+            conditions: Arc::from(conditions),
         }
     }
 
@@ -25,18 +25,18 @@ pub struct WriteSite {
     pub contributing_expr_ids: HashSet<Arc<str>>,
 }
 
-pub use assertion::{AssertionDoc, AssertionIr};
+pub use assertion::{AssertionDoc, AssertionIr, ConditionOperatorDoc};
 pub(crate) use context::IntelliSenseSource;
 pub use context::{
     AnalysisContext, AnalysisSummary, ExecutionContext, ExecutionError, ExpressionLocation,
     InstanceSource, PropertyRead, SharedDictionaryTypes, SharedIntelliSense, SharedPoisonedPaths,
     WriteTarget,
 };
-pub use decision_table::{DecisionTableDoc, DecisionTableIr, DeclaredType};
+pub use decision_table::{DecisionTableDoc, DecisionTableIr, DeclaredBase, DeclaredType};
 pub(crate) use decision_table::{DictionaryCandidate, TableSelection};
 pub use expression::{ExpressionDoc, ExpressionIr};
 pub(crate) use match_block::MatchSelection;
-pub use match_block::{MatchDoc, MatchIr};
+pub use match_block::{MatchArmDoc, MatchDoc, MatchIr};
 pub(crate) use property_read::ReadFlattener;
 
 impl ExpressionLocation {
@@ -156,6 +156,8 @@ impl ParseContext<'_> {
 pub(crate) struct BlockReadPlan {
     pub(crate) unconditional: Arc<[Arc<str>]>,
     pub(crate) conditional: ConditionalReads,
+    /// Reads of each match arm or assertion condition, in declaration order
+    pub(crate) conditions: Arc<[ConditionReads]>,
 }
 
 pub(crate) enum ConditionalReads {
@@ -169,6 +171,12 @@ pub(crate) struct ArmReads {
     pub(crate) value_reads: Arc<[Arc<str>]>,
 }
 
+pub(crate) struct ConditionReads {
+    pub(crate) id: Arc<str>,
+    pub(crate) expression: Arc<str>,
+    pub(crate) reads: Arc<[Arc<str>]>,
+}
+
 pub(crate) struct CellReads {
     pub(crate) row_idx: u32,
     pub(crate) col_id: Arc<str>,
@@ -184,6 +192,22 @@ impl BlockReadPlan {
         Arc::from(paths)
     }
 
+    pub(crate) fn conditional_reads(&self, out: &mut Vec<Arc<str>>) {
+        match &self.conditional {
+            ConditionalReads::None => {}
+            ConditionalReads::Match(arms) => {
+                for arm in arms.iter() {
+                    out.extend(arm.value_reads.iter().cloned());
+                }
+            }
+            ConditionalReads::DecisionTable(cells) => {
+                for cell in cells.iter() {
+                    out.extend(cell.cell_reads.iter().cloned());
+                }
+            }
+        }
+    }
+
     pub(crate) fn match_arm_reads(&self, arm_id: &str) -> Option<&[Arc<str>]> {
         match &self.conditional {
             ConditionalReads::Match(arms) => arms
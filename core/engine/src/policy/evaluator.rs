@@ -1,15 +1,19 @@
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Instant;
 use zen_types::symbol::Symbol;
 
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use futures::future::join_all;
 use zen_expression::variable::Variable;
 use zen_types::rccell::RcCell;
 
 use zen_expression::{Isolate, OpcodeCache};
 
+use crate::cancellation::EvaluationLimits;
+use crate::expression_context::ExpressionContext;
 use crate::policy::blocks::{
     Block, BlockKind, BlockReadPlan, ExecutionContext, ExecutionError, MatchSelection,
     PropertyRead, TableSelection,
@@ -19,10 +23,13 @@ use crate::policy::queries::dependency::{DataModelPaths, EvalGraph, WriteScope};
 use crate::policy::queries::path::PathClassifier;
 use crate::policy::queries::scope::{EntitySources, ReferenceField};
 use crate::policy::refs::RefPoolIndex;
+use crate::policy::residual::{Residual, ResidualSimplifier};
+use crate::policy::resolver::{DynamicPropertyResolver, PropertyRequest, PropertyResolver};
 use crate::policy::validator::InputSchema;
 use crate::workspace::db::Db;
 use crate::workspace::types::{
-    BlockExecution, BlockRef, BlockTrace, EvaluateRequest, EvaluationError, EvaluationResult, Trace,
+    BlockExecution, BlockRef, BlockTrace, EvaluateRequest, EvaluationError, EvaluationResult,
+    PartialEvaluation, PendingGoal, ResidualCondition, ResolvedRead, Trace,
 };
 
 pub(crate) struct EvalArtifact {
@@ -41,31 +48,66 @@ pub(crate) struct EvalArtifact {
 }
 
 impl Db {
-    pub fn evaluate(&self, req: &EvaluateRequest) -> Result<EvaluationResult, EvaluationError> {
-        if self.is_graph(&req.policy_path) {
-            return Err(EvaluationError::GraphNotEvaluable(req.policy_path.clone()));
-        }
-        if self.raw_policy(&req.policy_path).is_none() {
-            return Err(EvaluationError::PolicyNotFound(req.policy_path.clone()));
-        }
-        self.check_imports_resolved(&req.policy_path)?;
-        self.eval_artifact(&req.policy_path).evaluate(req, false)
+    pub fn evaluate(
+        &self,
+        req: &EvaluateRequest,
+        limits: &EvaluationLimits,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        self.evaluable_artifact(&req.policy_path)?.evaluate(
+            req,
+            false,
+            self.expression_context(),
+            limits,
+        )
+    }
+
+    pub async fn evaluate_resolving(
+        &self,
+        req: &EvaluateRequest,
+        resolver: &dyn PropertyResolver,
+        limits: &EvaluationLimits,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        self.evaluable_artifact(&req.policy_path)?
+            .evaluate_resolving(req, false, resolver, self.expression_context(), limits)
+            .await
+    }
+
+    pub fn partial_evaluate(
+        &self,
+        req: &EvaluateRequest,
+        residuals: bool,
+    ) -> Result<PartialEvaluation, EvaluationError> {
+        self.evaluable_artifact(&req.policy_path)?.partial_evaluate(
+            req,
+            residuals,
+            self.expression_context(),
+        )
     }
 
     pub fn enhance_trace(
         &self,
         req: &EvaluateRequest,
     ) -> Result<EvaluationResult, EvaluationError> {
-        if self.is_graph(&req.policy_path) {
-            return Err(EvaluationError::GraphNotEvaluable(req.policy_path.clone()));
-        }
-        if self.raw_policy(&req.policy_path).is_none() {
-            return Err(EvaluationError::PolicyNotFound(req.policy_path.clone()));
-        }
-        self.check_imports_resolved(&req.policy_path)?;
+        let artifact = self.evaluable_artifact(&req.policy_path)?;
         let mut req = req.clone();
         req.trace = true;
-        self.eval_artifact(&req.policy_path).evaluate(&req, true)
+        artifact.evaluate(
+            &req,
+            true,
+            self.expression_context(),
+            &EvaluationLimits::default(),
+        )
+    }
+
+    fn evaluable_artifact(&self, path: &Arc<str>) -> Result<Arc<EvalArtifact>, EvaluationError> {
+        if self.is_graph(path) {
+            return Err(EvaluationError::GraphNotEvaluable(path.clone()));
+        }
+        if self.raw_policy(path).is_none() {
+            return Err(EvaluationError::PolicyNotFound(path.clone()));
+        }
+        self.check_imports_resolved(path)?;
+        Ok(self.eval_artifact(path))
     }
 
     fn check_imports_resolved(&self, entry: &Arc<str>) -> Result<(), EvaluationError> {
@@ -94,11 +136,14 @@ impl Db {
 }
 
 impl EvalArtifact {
-    pub(crate) fn evaluate_entry(
+    pub(crate) async fn evaluate_entry(
         &self,
         key: &str,
         input: Variable,
         trace: bool,
+        property_resolver: &DynamicPropertyResolver,
+        expression_context: &ExpressionContext,
+        limits: &EvaluationLimits,
     ) -> Result<EvaluationResult, EvaluationError> {
         let request = EvaluateRequest {
             policy_path: Arc::from(key),
@@ -106,21 +151,253 @@ impl EvalArtifact {
             goals: Vec::new(),
             trace,
         };
-        self.evaluate(&request, false)
+        match property_resolver {
+            Some(resolver) => {
+                self.evaluate_resolving(
+                    &request,
+                    false,
+                    resolver.as_ref(),
+                    expression_context,
+                    limits,
+                )
+                .await
+            }
+            None => self.evaluate(&request, false, expression_context, limits),
+        }
     }
 
     pub(crate) fn evaluate(
         &self,
         req: &EvaluateRequest,
         extras: bool,
+        expression_context: &ExpressionContext,
+        limits: &EvaluationLimits,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        self.run(
+            req,
+            extras,
+            MissingInputs::Ignore,
+            expression_context,
+            limits,
+        )
+    }
+
+    /// Evaluates with inputs missing from the request fetched through `resolver` on demand.
+    ///
+    /// The driver suspends at the first declared input it cannot find, the value is resolved
+    /// and the evaluation restarts from a fresh copy of the input. Resolved values, including
+    /// misses, are cached for the remainder of the evaluation.
+    pub(crate) async fn evaluate_resolving(
+        &self,
+        req: &EvaluateRequest,
+        extras: bool,
+        resolver: &dyn PropertyResolver,
+        expression_context: &ExpressionContext,
+        limits: &EvaluationLimits,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        let start = Instant::now();
+        let input = req.input.deep_clone();
+        let mut resolution = Resolution::default();
+
+        loop {
+            let attempt = EvaluateRequest {
+                policy_path: req.policy_path.clone(),
+                input: input.deep_clone(),
+                goals: req.goals.clone(),
+                trace: req.trace,
+            };
+            let result = self.run(
+                &attempt,
+                extras,
+                MissingInputs::Resolve(&mut resolution),
+                expression_context,
+                limits,
+            );
+            let pending = std::mem::take(&mut resolution.pending);
+            if pending.is_empty() {
+                return result.map(|mut r| {
+                    r.duration = start.elapsed();
+                    r
+                });
+            }
+
+            limits
+                .check()
+                .map_err(|reason| EvaluationError::Interrupted {
+                    policy_path: req.policy_path.clone(),
+                    reason,
+                })?;
+            let values = join_all(pending.iter().map(|missing| {
+                resolver.resolve(PropertyRequest {
+                    policy_path: req.policy_path.clone(),
+                    property: missing.property.clone(),
+                    path: missing.path.clone(),
+                    parent: missing.parent.shallow_clone().into(),
+                })
+            }))
+            .await;
+
+            for (missing, value) in pending.into_iter().zip(values) {
+                let value = value.map_err(|message| EvaluationError::PropertyResolutionFailed {
+                    policy_path: req.policy_path.clone(),
+                    path: missing.path.clone(),
+                    message,
+                })?;
+
+                resolution.attempted.insert(missing.path.clone());
+                if let Some(value) = value {
+                    let value = Variable::from(value);
+                    input.dot_insert(&missing.path, value.shallow_clone());
+                    resolution.resolved.push(ResolvedRead {
+                        path: missing.path,
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Evaluates as far as the supplied input allows, reporting the goals that are already
+    /// determined and the missing inputs each remaining goal still depends on.
+    ///
+    /// Missing inputs taint every block that reads them, directly or through computed
+    /// properties. Conditions of tainted match and assertion blocks are simplified with the
+    /// properties already known: arms ruled out that way are dropped, and only the inputs read
+    /// by the remaining conditions and the arms they may select are reported. Decision table
+    /// selections that are tainted demand all of their branches.
+    pub(crate) fn partial_evaluate(
+        &self,
+        req: &EvaluateRequest,
+        residuals: bool,
+        expression_context: &ExpressionContext,
+    ) -> Result<PartialEvaluation, EvaluationError> {
+        let mut unknowns = Unknowns::default();
+        let attempt = EvaluateRequest {
+            policy_path: req.policy_path.clone(),
+            input: req.input.shallow_clone(),
+            goals: req.goals.clone(),
+            trace: true,
+        };
+        let result = self.run(
+            &attempt,
+            residuals,
+            MissingInputs::Collect(&mut unknowns),
+            expression_context,
+            &EvaluationLimits::default(),
+        )?;
+        let executions = result
+            .trace
+            .map(|trace| trace.executions)
+            .unwrap_or_default();
+
+        let goals: Vec<Arc<str>> = if req.goals.is_empty() {
+            self.eval_graph.terminal_sinks(&self.members)
+        } else {
+            req.goals.clone()
+        };
+
+        let env = result.output.depth_clone(1);
+        if let Some(fields) = env.as_object() {
+            fields.borrow_mut().remove(&Variable::dollar_key());
+        }
+        let mut isolate = Isolate::with_environment(env);
+        expression_context.apply(&mut isolate);
+        let is_unknown = |path: &str| self.is_unknown(path, &unknowns);
+        let mut analysis = PendingAnalysis {
+            artifact: self,
+            unknowns: &unknowns,
+            executions: &executions,
+            entry: &req.policy_path,
+            simplifier: ResidualSimplifier::new(isolate, &is_unknown),
+            writers: HashMap::new(),
+            visiting: HashSet::new(),
+        };
+
+        let mut determined: HashMap<Arc<str>, Variable> = HashMap::new();
+        let mut pending: Vec<PendingGoal> = Vec::new();
+        for goal in goals {
+            let writers = self.demand_writers(&goal);
+            if !writers.iter().any(|w| unknowns.by_block.contains_key(w)) {
+                let value = result.output.dot(&goal).unwrap_or(Variable::Null);
+                determined.insert(goal, value);
+                continue;
+            }
+
+            if let [owner] = writers {
+                if let Some(value) = analysis.writer(owner).value.clone() {
+                    determined.insert(goal, value);
+                    continue;
+                }
+            }
+
+            let mut missing = BTreeSet::new();
+            let mut residual = Vec::new();
+            for owner in writers {
+                let writer = analysis.writer(owner);
+                missing.extend(writer.missing.iter().cloned());
+                if residuals {
+                    residual.extend(writer.residual.iter().cloned());
+                }
+            }
+            pending.push(PendingGoal {
+                goal,
+                missing: missing.into_iter().collect(),
+                residual,
+            });
+        }
+
+        Ok(PartialEvaluation {
+            determined,
+            pending,
+        })
+    }
+
+    fn is_unknown(&self, path: &str, unknowns: &Unknowns) -> bool {
+        unknowns.inputs.contains(path)
+            || self
+                .demand_writers(path)
+                .iter()
+                .any(|w| unknowns.by_block.contains_key(w))
+    }
+
+    fn demand_writers(&self, prop: &str) -> &[BlockRef] {
+        let graph = &self.eval_graph;
+        let direct = graph.demand_writers_for(prop);
+        if !direct.is_empty() {
+            return direct;
+        }
+        let mut end = prop.len();
+        while let Some(dot) = prop[..end].rfind('.') {
+            let owners = graph.demand_writers_for(&prop[..dot]);
+            if !owners.is_empty() {
+                return owners;
+            }
+            end = dot;
+        }
+        &[]
+    }
+
+    fn run(
+        &self,
+        req: &EvaluateRequest,
+        extras: bool,
+        mut missing: MissingInputs<'_>,
+        expression_context: &ExpressionContext,
+        limits: &EvaluationLimits,
     ) -> Result<EvaluationResult, EvaluationError> {
         let start = Instant::now();
 
-        self.validate_request(req)?;
+        let mut input = req.input.clone();
+        let mut coercions = Vec::new();
+        if expression_context.coerce_input {
+            input = input.deep_clone();
+            coercions = self.input_schema.coerce(&input);
+        }
+        self.validate_request(req, &input)?;
 
-        let order_to_run = self.compute_order_to_run(req)?;
+        let order_to_run = self.compute_order_to_run(req, &missing)?;
 
-        let store = req.input.depth_clone(1);
+        let store = input.depth_clone(1);
         let ref_targets: HashSet<Arc<str>> = self
             .reference_fields
             .iter()
@@ -134,13 +411,28 @@ impl EvalArtifact {
         } else {
             req.goals.clone()
         };
-        let mut driver = Driver::new(self, &store, &req.policy_path, req.trace, extras);
+        let mut driver = Driver::new(
+            self,
+            &store,
+            &req.policy_path,
+            req.trace,
+            extras,
+            missing.reborrow(),
+            expression_context,
+        )
+        .with_limits(limits);
         let outcome = roots.iter().try_for_each(|root| driver.demand(root));
+        let executions = driver.executions;
 
         let trace = req.trace.then(|| Trace {
             engine_version: Arc::from(crate::ENGINE_VERSION),
             properties: store.snapshot(&order_to_run),
-            executions: driver.executions,
+            executions,
+            resolved: match &missing {
+                MissingInputs::Resolve(resolution) => resolution.resolved.clone(),
+                _ => Vec::new(),
+            },
+            coercions,
         });
 
         if let Err(error) = outcome {
@@ -154,13 +446,17 @@ impl EvalArtifact {
         })
     }
 
-    fn validate_request(&self, req: &EvaluateRequest) -> Result<(), EvaluationError> {
+    fn validate_request(
+        &self,
+        req: &EvaluateRequest,
+        input: &Variable,
+    ) -> Result<(), EvaluationError> {
         for goal in &req.goals {
             if !self.eval_graph.contains(goal) {
                 return Err(EvaluationError::GoalNotFound(goal.clone()));
             }
         }
-        let validation_errors = self.input_schema.validate(&req.input);
+        let validation_errors = self.input_schema.validate(input);
         if !validation_errors.is_empty() {
             return Err(EvaluationError::InputValidationFailed {
                 errors: validation_errors,
@@ -172,6 +468,7 @@ impl EvalArtifact {
     fn compute_order_to_run(
         &self,
         req: &EvaluateRequest,
+        missing_inputs: &MissingInputs<'_>,
     ) -> Result<Vec<PropertyPath>, EvaluationError> {
         let visible = &self.members;
         let visible_order: Vec<PropertyPath> = self
@@ -190,6 +487,15 @@ impl EvalArtifact {
         }
 
         let reachable = self.eval_graph.reachable_from(&req.goals);
+        let reachable_order: Vec<PropertyPath> = visible_order
+            .iter()
+            .filter(|p| reachable.contains(*p))
+            .cloned()
+            .collect();
+        if matches!(missing_inputs, MissingInputs::Collect(_)) {
+            return Ok(reachable_order);
+        }
+
         let mut missing: Vec<PropertyPath> = self
             .eval_graph
             .reachable_input_paths(&req.goals, visible)
@@ -197,6 +503,12 @@ impl EvalArtifact {
             .filter(|p| {
                 !self.data_model_paths.is_optional(p) && !self.input_satisfied(&req.input, p)
             })
+            .filter(|p| match missing_inputs {
+                MissingInputs::Resolve(resolution) => self
+                    .missing_input(&req.input, p)
+                    .is_none_or(|m| resolution.attempted.contains(&m.path)),
+                _ => true,
+            })
             .collect();
         if !missing.is_empty() {
             missing.sort();
@@ -206,11 +518,7 @@ impl EvalArtifact {
             });
         }
 
-        Ok(visible_order
-            .iter()
-            .filter(|p| reachable.contains(*p))
-            .cloned()
-            .collect())
+        Ok(reachable_order)
     }
 
     fn input_satisfied(&self, input: &Variable, path: &str) -> bool {
@@ -229,6 +537,35 @@ impl EvalArtifact {
         }
     }
 
+    fn missing_input(&self, input: &Variable, property: &str) -> Option<MissingInput> {
+        let path = match property
+            .split_once('.')
+            .and_then(|(entity, rest)| Some((self.entity_sources.get(entity)?, rest)))
+        {
+            Some((src, rest)) => format!("{}.{}", src.path, rest),
+            None => property.to_string(),
+        };
+
+        let mut current = input.shallow_clone();
+        let mut end = 0;
+        for segment in path.split('.') {
+            end += segment.len();
+            current.as_object()?;
+            match current.dot(segment) {
+                Some(v) => current = v,
+                None => {
+                    return Some(MissingInput {
+                        property: Arc::from(property),
+                        path: Arc::from(&path[..end]),
+                        parent: current,
+                    })
+                }
+            }
+            end += 1;
+        }
+        None
+    }
+
     fn input_path_satisfied(input: &Variable, path: &str) -> bool {
         let mut current = input.shallow_clone();
         for segment in path.split('.') {
@@ -244,6 +581,239 @@ impl EvalArtifact {
     }
 }
 
+/// Residual conditions and missing inputs of the tainted writers of pending goals
+#[derive(Default)]
+struct PendingWriter {
+    residual: Vec<ResidualCondition>,
+    missing: BTreeSet<Arc<str>>,
+    /// Value of a match whose arm is decided by the known properties alone
+    value: Option<Variable>,
+}
+
+struct PendingAnalysis<'a> {
+    artifact: &'a EvalArtifact,
+    unknowns: &'a Unknowns,
+    executions: &'a [BlockExecution],
+    entry: &'a Arc<str>,
+    simplifier: ResidualSimplifier<'a>,
+    writers: HashMap<BlockRef, Rc<PendingWriter>>,
+    visiting: HashSet<BlockRef>,
+}
+
+impl PendingAnalysis<'_> {
+    fn writer(&mut self, owner: &BlockRef) -> Rc<PendingWriter> {
+        if let Some(writer) = self.writers.get(owner) {
+            return writer.clone();
+        }
+        let Some(tainted) = self.unknowns.by_block.get(owner) else {
+            return Default::default();
+        };
+        if !self.visiting.insert(owner.clone()) {
+            return Rc::new(PendingWriter {
+                missing: tainted.clone(),
+                ..Default::default()
+            });
+        }
+
+        let mut writer = self.analyze(owner).unwrap_or_default();
+        if writer.missing.is_empty() && writer.value.is_none() {
+            // Whatever left the block tainted was not traced back to a read, report every input
+            writer.missing = tainted.clone();
+        }
+
+        self.visiting.remove(owner);
+        let writer = Rc::new(writer);
+        self.writers.insert(owner.clone(), writer.clone());
+        writer
+    }
+
+    fn analyze(&mut self, owner: &BlockRef) -> Option<PendingWriter> {
+        let artifact = self.artifact;
+        let rule = artifact.rule_by_ref.get(owner)?;
+        let plan = artifact.read_plans.get(owner)?;
+        if let WriteScope::Entity(entity) = rule.write_scope(&artifact.classifier) {
+            if artifact.entity_sources.get(entity.as_ref()).is_some() {
+                return None;
+            }
+        }
+
+        let mut writer = PendingWriter::default();
+        match &rule.kind {
+            BlockKind::Match(_) | BlockKind::Assertion(_) => {
+                let is_match = matches!(rule.kind, BlockKind::Match(_));
+                let mut candidates: Vec<&Arc<str>> = Vec::new();
+                for condition in plan.conditions.iter() {
+                    if is_match && condition.expression.is_empty() {
+                        candidates.push(&condition.id);
+                        break;
+                    }
+
+                    match self.simplifier.simplify(&condition.expression) {
+                        Residual::Decided(true) if is_match => {
+                            candidates.push(&condition.id);
+                            break;
+                        }
+                        Residual::Decided(_) => {}
+                        Residual::Pending { expression, reads }
+                            if condition
+                                .reads
+                                .iter()
+                                .any(|read| artifact.is_unknown(read, self.unknowns)) =>
+                        {
+                            reads
+                                .iter()
+                                .for_each(|read| self.inputs(read, &mut writer.missing));
+                            writer.residual.push(ResidualCondition {
+                                block: owner.clone(),
+                                condition_id: condition.id.clone(),
+                                expression,
+                            });
+                            candidates.push(&condition.id);
+                        }
+                        Residual::Pending { .. } => {
+                            if is_match && self.matched(owner, &condition.id) {
+                                candidates.push(&condition.id);
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                for arm in &candidates {
+                    for read in plan.match_arm_reads(arm).unwrap_or_default() {
+                        self.inputs(read, &mut writer.missing);
+                    }
+                }
+
+                if let (BlockKind::Match(ir), [arm]) = (&rule.kind, candidates.as_slice()) {
+                    let value = ir.arms.iter().find(|a| &a.id == *arm).map(|a| &a.value);
+                    if let Some(value) = value.filter(|_| writer.missing.is_empty()) {
+                        writer.value = self.simplifier.evaluate(value);
+                    }
+                }
+            }
+            BlockKind::Expression(_) => {
+                let mut reads = plan.unconditional.to_vec();
+                plan.conditional_reads(&mut reads);
+                reads
+                    .iter()
+                    .for_each(|read| self.inputs(read, &mut writer.missing));
+            }
+            BlockKind::DecisionTable(_) => return None,
+        }
+
+        Some(writer)
+    }
+
+    /// Missing inputs behind `read`, either the input itself or those of its tainted writers
+    fn inputs(&mut self, read: &Arc<str>, out: &mut BTreeSet<Arc<str>>) {
+        let unknowns = self.unknowns;
+        let nested = format!("{read}.");
+        out.extend(
+            unknowns
+                .inputs
+                .iter()
+                .filter(|input| *input == read || input.starts_with(&nested))
+                .cloned(),
+        );
+        for owner in self.artifact.demand_writers(read) {
+            out.extend(self.writer(owner).missing.iter().cloned());
+        }
+    }
+
+    fn matched(&self, owner: &BlockRef, condition_id: &str) -> bool {
+        self.executions
+            .iter()
+            .filter(|e| {
+                e.block_id == owner.block_id
+                    && e.instance_path.is_none()
+                    && e.policy_path.as_ref().unwrap_or(self.entry) == &owner.policy_path
+            })
+            .flat_map(|e| match &e.trace {
+                BlockTrace::Match { arms, .. } => arms.as_slice(),
+                BlockTrace::Assertion { conditions, .. } => conditions.as_slice(),
+                _ => &[],
+            })
+            .any(|o| o.id.as_ref() == condition_id && o.result)
+    }
+}
+
+/// How the driver treats declared inputs that are absent from the store
+enum MissingInputs<'a> {
+    Ignore,
+    Resolve(&'a mut Resolution),
+    Collect(&'a mut Unknowns),
+}
+
+impl MissingInputs<'_> {
+    fn reborrow(&mut self) -> MissingInputs<'_> {
+        match self {
+            MissingInputs::Ignore => MissingInputs::Ignore,
+            MissingInputs::Resolve(resolution) => MissingInputs::Resolve(resolution),
+            MissingInputs::Collect(unknowns) => MissingInputs::Collect(unknowns),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Resolution {
+    attempted: HashSet<Arc<str>>,
+    resolved: Vec<ResolvedRead>,
+    /// Inputs found missing during the current pass, resolved together before the next one
+    pending: Vec<MissingInput>,
+}
+
+struct MissingInput {
+    property: Arc<str>,
+    path: Arc<str>,
+    parent: Variable,
+}
+
+#[derive(Default)]
+struct Unknowns {
+    inputs: HashSet<Arc<str>>,
+    by_block: HashMap<BlockRef, BTreeSet<Arc<str>>>,
+    running: Vec<BlockRef>,
+}
+
+impl Unknowns {
+    fn record(&mut self, prop: &str) {
+        let prop: Arc<str> = Arc::from(prop);
+        self.inputs.insert(prop.clone());
+        if let Some(current) = self.running.last() {
+            self.by_block
+                .entry(current.clone())
+                .or_default()
+                .insert(prop);
+        }
+    }
+
+    fn inherit(&mut self, writers: &[BlockRef]) {
+        let Some(current) = self.running.last() else {
+            return;
+        };
+        let inherited: Vec<Arc<str>> = writers
+            .iter()
+            .filter(|w| *w != current)
+            .filter_map(|w| self.by_block.get(w))
+            .flatten()
+            .cloned()
+            .collect();
+        if !inherited.is_empty() {
+            self.by_block
+                .entry(current.clone())
+                .or_default()
+                .extend(inherited);
+        }
+    }
+
+    fn current_tainted(&self) -> bool {
+        self.running
+            .last()
+            .is_some_and(|current| self.by_block.contains_key(current))
+    }
+}
+
 struct Driver<'a> {
     artifact: &'a EvalArtifact,
     store: &'a Variable,
@@ -255,6 +825,10 @@ struct Driver<'a> {
     ran: HashSet<BlockRef>,
     in_progress: HashSet<BlockRef>,
     executions: Vec<BlockExecution>,
+    missing: MissingInputs<'a>,
+    /// Blocks whose unconditional reads were visited after the pass suspended
+    scanned: HashSet<BlockRef>,
+    limits: EvaluationLimits,
 }
 
 enum Pick {
@@ -404,11 +978,14 @@ impl<'a> Driver<'a> {
         entry: &'a Arc<str>,
         trace: bool,
         extras: bool,
+        missing: MissingInputs<'a>,
+        expression_context: &ExpressionContext,
     ) -> Self {
+        let mut isolate = Isolate::new().with_cache(Some(artifact.opcode_cache.clone()));
+        expression_context.apply(&mut isolate);
+
         Self {
-            isolate: Rc::new(RefCell::new(
-                Isolate::new().with_cache(Some(artifact.opcode_cache.clone())),
-            )),
+            isolate: Rc::new(RefCell::new(isolate)),
             artifact,
             store,
             env: store.depth_clone(1),
@@ -418,9 +995,22 @@ impl<'a> Driver<'a> {
             ran: HashSet::new(),
             in_progress: HashSet::new(),
             executions: Vec::new(),
+            missing,
+            scanned: HashSet::new(),
+            limits: Default::default(),
         }
     }
 
+    /// Interrupts the evaluation between blocks and inside expressions once `limits` are
+    /// exceeded
+    fn with_limits(mut self, limits: &EvaluationLimits) -> Self {
+        self.isolate
+            .borrow_mut()
+            .set_interrupt_handler(limits.interrupt_handler());
+        self.limits = limits.clone();
+        self
+    }
+
     fn bind_env(&self, isolate: &RefCell<Isolate>) {
         if let Some(fields) = self.env.as_object() {
             fields.borrow_mut().remove(&Variable::dollar_key());
@@ -431,33 +1021,85 @@ impl<'a> Driver<'a> {
     }
 
     fn demand(&mut self, prop: &str) -> Result<(), EvaluationError> {
-        self.writers_of_longest_prefix(prop)
-            .iter()
-            .try_for_each(|owner| self.run_block(owner))
+        let writers = self.artifact.demand_writers(prop);
+        if writers.is_empty() {
+            self.demand_input(prop);
+            return Ok(());
+        }
+        writers.iter().try_for_each(|owner| self.run_block(owner))?;
+        if let MissingInputs::Collect(unknowns) = &mut self.missing {
+            unknowns.inherit(writers);
+        }
+        Ok(())
     }
 
-    fn writers_of_longest_prefix(&self, prop: &str) -> &'a [BlockRef] {
-        let graph = &self.artifact.eval_graph;
-        let direct = graph.demand_writers_for(prop);
-        if !direct.is_empty() {
-            return direct;
+    fn check_limits(&self) -> Result<(), EvaluationError> {
+        self.limits
+            .check()
+            .map_err(|reason| EvaluationError::Interrupted {
+                policy_path: self.entry.clone(),
+                reason,
+            })
+    }
+
+    fn suspended(&self) -> bool {
+        matches!(&self.missing, MissingInputs::Resolve(r) if !r.pending.is_empty())
+    }
+
+    fn tainted(&self) -> bool {
+        matches!(&self.missing, MissingInputs::Collect(u) if u.current_tainted())
+    }
+
+    fn demand_input(&mut self, prop: &str) {
+        let (artifact, store) = (self.artifact, self.store);
+        if !artifact.data_model_paths.declares(prop) {
+            return;
         }
-        let mut end = prop.len();
-        while let Some(dot) = prop[..end].rfind('.') {
-            let owners = graph.demand_writers_for(&prop[..dot]);
-            if !owners.is_empty() {
-                return owners;
+        match &mut self.missing {
+            MissingInputs::Ignore => {}
+            MissingInputs::Resolve(resolution) => {
+                let Some(missing) = artifact.missing_input(store, prop) else {
+                    return;
+                };
+                let seen = resolution.attempted.contains(&missing.path)
+                    || resolution.pending.iter().any(|p| p.path == missing.path);
+                if !seen {
+                    resolution.pending.push(missing);
+                }
+            }
+            MissingInputs::Collect(unknowns) => {
+                if !artifact.data_model_paths.is_optional(prop)
+                    && artifact.missing_input(store, prop).is_some()
+                {
+                    unknowns.record(prop);
+                }
             }
-            end = dot;
         }
-        &[]
     }
 
     fn run_block(&mut self, owner: &BlockRef) -> Result<(), EvaluationError> {
+        if self.suspended() {
+            return self.scan_block(owner);
+        }
+        self.check_limits()?;
         if self.ran.contains(owner) || !self.in_progress.insert(owner.clone()) {
             return Ok(());
         }
-        let result = self.run_block_inner(owner);
+        if let MissingInputs::Collect(unknowns) = &mut self.missing {
+            unknowns.running.push(owner.clone());
+        }
+        let mut result = self.run_block_inner(owner);
+        if result.is_err() {
+            // Expressions aborted by an interrupt fail with whichever error surfaced first
+            self.check_limits()?;
+        }
+        if result.is_err() && self.tainted() {
+            // Failures on placeholder values leave the block undetermined
+            result = Ok(());
+        }
+        if let MissingInputs::Collect(unknowns) = &mut self.missing {
+            unknowns.running.pop();
+        }
         self.in_progress.remove(owner);
         if result.is_ok() {
             self.ran.insert(owner.clone());
@@ -465,6 +1107,21 @@ impl<'a> Driver<'a> {
         result
     }
 
+    /// Once a pass is suspended on missing inputs its blocks no longer run, but the inputs they
+    /// read unconditionally are still demanded so that a single pass collects every input the
+    /// evaluation is certain to need
+    fn scan_block(&mut self, owner: &BlockRef) -> Result<(), EvaluationError> {
+        if self.ran.contains(owner) || !self.scanned.insert(owner.clone()) {
+            return Ok(());
+        }
+        let Some(plan) = self.artifact.read_plans.get(owner) else {
+            return Ok(());
+        };
+        plan.unconditional
+            .iter()
+            .try_for_each(|path| self.demand(path))
+    }
+
     fn run_block_inner(&mut self, owner: &BlockRef) -> Result<(), EvaluationError> {
         let artifact = self.artifact;
         let Some(rule) = artifact.rule_by_ref.get(owner) else {
@@ -477,6 +1134,9 @@ impl<'a> Driver<'a> {
                 self.demand(path)?;
             }
         }
+        if self.suspended() {
+            return Ok(());
+        }
 
         let iterated = match rule.write_scope(&artifact.classifier) {
             WriteScope::Entity(entity) => artifact
@@ -533,11 +1193,31 @@ impl<'a> Driver<'a> {
         if matches!(rule.kind, BlockKind::Match(_) | BlockKind::DecisionTable(_)) {
             self.bind_env(&isolate);
         }
-        let pick = Self::select_pick(rule, &ctx)?;
+        let selected = Self::select_pick(rule, &ctx);
+        let tainted = self.tainted();
         let mut demanded: Vec<Arc<str>> = Vec::new();
         if let Some(plan) = artifact.read_plans.get(owner) {
-            pick.collect_reads(plan, &mut demanded);
+            match &selected {
+                Ok(_) if tainted => plan.conditional_reads(&mut demanded),
+                Ok(pick) => pick.collect_reads(plan, &mut demanded),
+                Err(_) if tainted => {
+                    plan.conditional_reads(&mut demanded);
+                    demanded.sort();
+                    demanded.dedup();
+                }
+                Err(_) => {}
+            }
         }
+        let pick = match selected {
+            Ok(pick) => pick,
+            Err(_) if tainted => {
+                for path in &demanded {
+                    self.demand(path)?;
+                }
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
         for path in &demanded {
             self.demand(path)?;
         }
@@ -610,7 +1290,11 @@ impl<'a> Driver<'a> {
                         env_mirror: None,
                         isolate: &isolate,
                     };
-                    picks.push(Self::select_pick(rule, &ctx)?);
+                    match Self::select_pick(rule, &ctx) {
+                        Ok(pick) => picks.push(pick),
+                        Err(_) if self.tainted() => picks.push(Pick::Unconditional),
+                        Err(e) => return Err(e.into()),
+                    }
                 }
                 picks
             } else {
@@ -619,8 +1303,12 @@ impl<'a> Driver<'a> {
 
         let mut demanded: Vec<Arc<str>> = Vec::new();
         if let Some(plan) = artifact.read_plans.get(owner) {
-            for pick in &picks {
-                pick.collect_reads(plan, &mut demanded);
+            if self.tainted() {
+                plan.conditional_reads(&mut demanded);
+            } else {
+                for pick in &picks {
+                    pick.collect_reads(plan, &mut demanded);
+                }
             }
         }
         demanded.sort();
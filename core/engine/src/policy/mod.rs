@@ -9,21 +9,28 @@ pub(crate) mod linter;
 pub(crate) mod queries;
 pub(crate) mod raw;
 pub(crate) mod refs;
+pub(crate) mod residual;
+pub(crate) mod resolver;
 pub(crate) mod runtime;
 pub(crate) mod validator;
 
 pub use crate::workspace::{
-    BlockExecution, BlockRef, BlockTrace, Completion, ConditionTrace, ConditionalSchema, Cursor,
-    CursorTarget, DecisionTableExtras, DependencyNode, Diagnostic, DiagnosticCode,
-    DiagnosticLocation, Dictionary, DictionaryEntryInfo, DiscriminantVariant, DiscriminatedUnion,
-    EngineEdit, Entity, EntityField, EvaluateRequest, EvaluationError, EvaluationResult,
-    ExpressionKind, FieldOrigin, FunctionResolutionRequest, FunctionTypeResolver, GraphAnalysis,
-    GraphNodeAnalysis, GraphSignature, GraphTraceMap, GuardedProperty, InputProperty,
-    InputValidationError, InspectResult, NlExpression, OutputProperty, PrepareRename, PropertyKind,
-    ReferenceKind, ReferenceSite, RenameTarget, SchemaFieldKind, SchemaGroup, ScopeRequest,
-    Severity, Span, Trace, Workspace, WriteConflict, WriteTrace,
+    BlockExecution, BlockRef, BlockTrace, Completion, ConditionTrace, ConditionalSchema,
+    ConversionDiagnostic, Cursor, CursorTarget, DecisionContract, DecisionTableExtras,
+    DependencyNode, Diagnostic, DiagnosticCode, DiagnosticLocation, DiagnosticsDelta, Dictionary,
+    DictionaryEntryInfo, DiscriminantVariant, DiscriminatedUnion, EngineEdit, Entity, EntityField,
+    EvaluateRequest, EvaluationError, EvaluationResult, ExpressionKind, FieldOrigin,
+    FunctionLibrary, FunctionResolutionRequest, FunctionTypeResolver, GraphAnalysis,
+    GraphConversion, GraphEdit, GraphEditError, GraphEditor, GraphNodeAnalysis, GraphSignature,
+    GraphTraceMap, GuardedProperty, InputProperty, InputValidationError, InspectResult,
+    NlExpression, OpenApiInfo, OutputProperty, PartialEvaluation, PendingGoal, PolicyConversion,
+    PrepareRename, PropertyKind, ReferenceKind, ReferenceSite, RenameTarget, ResidualCondition,
+    ResolvedRead, SchemaFieldKind, SchemaGroup, ScopeRequest, Severity, Span, Trace, Workspace,
+    WorkspaceEditError, WriteConflict, WriteTrace, FUNCTION_MODULE_DECLARATIONS,
 };
+pub use blocks::DecisionTableDoc;
 pub use raw::{BlockDoc, PolicyDocument};
+pub use resolver::{DynamicPropertyResolver, PropertyRequest, PropertyResolver};
 
 pub type PolicyWorkspace = Workspace;
 
@@ -701,6 +701,10 @@ impl DataModelPaths {
             .find(|p| PathPrefix::extends(p, write_path) || PathPrefix::extends(write_path, p))
     }
 
+    pub fn declares(&self, path: &str) -> bool {
+        self.all.contains(path)
+    }
+
     pub fn is_optional(&self, path: &str) -> bool {
         self.optional.contains(path) || self.optional.iter().any(|p| PathPrefix::extends(p, path))
     }
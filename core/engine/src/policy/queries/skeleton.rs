@@ -53,6 +53,7 @@ impl SkeletonBuilder {
             VariableType::String | VariableType::Date | VariableType::Interval => {
                 Value::String(String::new())
             }
+            VariableType::Duration => Value::String("PT0S".to_string()),
             VariableType::Number => Value::Number(0u64.into()),
             VariableType::Bool => Value::Bool(false),
             VariableType::Null | VariableType::Any => Value::Null,
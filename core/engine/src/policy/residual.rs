@@ -0,0 +1,297 @@
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use zen_expression::intellisense::{AstMetadata, IntelliSense};
+use zen_expression::lexer::{LogicalOperator, Operator};
+use zen_expression::parser::Node;
+use zen_expression::variable::Variable;
+use zen_expression::Isolate;
+
+use crate::policy::linter::AstOps;
+use crate::workspace::types::Span;
+
+/// Condition after substituting the properties known so far
+pub(crate) enum Residual {
+    Decided(bool),
+    Pending {
+        expression: Arc<str>,
+        /// Unknown properties the simplified expression still reads
+        reads: BTreeSet<Arc<str>>,
+    },
+}
+
+/// Rewrites conditions that read unknown properties: every subexpression that reads only known
+/// properties is evaluated and replaced by its value, and `and` / `or` operands decided that
+/// way are dropped or short-circuit the whole operation.
+pub(crate) struct ResidualSimplifier<'a> {
+    isolate: Isolate,
+    intellisense: IntelliSense,
+    is_unknown: &'a dyn Fn(&str) -> bool,
+}
+
+enum Folded {
+    Const(Variable),
+    Text(String, BTreeSet<Arc<str>>),
+}
+
+impl<'a> ResidualSimplifier<'a> {
+    pub(crate) fn new(isolate: Isolate, is_unknown: &'a dyn Fn(&str) -> bool) -> Self {
+        Self {
+            isolate,
+            intellisense: IntelliSense::new(),
+            is_unknown,
+        }
+    }
+
+    pub(crate) fn simplify(&mut self, expression: &Arc<str>) -> Residual {
+        let Self {
+            isolate,
+            intellisense,
+            is_unknown,
+        } = self;
+        let folded = intellisense.with_ast(expression, false, |root, metadata| {
+            let mut fold = Fold {
+                source: expression,
+                metadata,
+                isolate,
+                is_unknown: *is_unknown,
+            };
+            if fold.has_assignments(root) {
+                // Locals would be evaluated out of context, leave the expression as written
+                return None;
+            }
+            fold.visit(root)
+        });
+
+        match folded.flatten() {
+            Some(Folded::Const(Variable::Bool(value))) => Residual::Decided(value),
+            Some(Folded::Text(text, reads)) => Residual::Pending {
+                expression: Arc::from(text),
+                reads,
+            },
+            _ => Residual::Pending {
+                expression: expression.clone(),
+                reads: self.unknown_reads(expression),
+            },
+        }
+    }
+
+    /// Evaluates an expression that reads only known properties
+    pub(crate) fn evaluate(&mut self, expression: &str) -> Option<Variable> {
+        self.isolate.run_standard(expression).ok()
+    }
+
+    fn unknown_reads(&mut self, expression: &str) -> BTreeSet<Arc<str>> {
+        let is_unknown = self.is_unknown;
+        self.intellisense
+            .with_ast(expression, false, |root, _| {
+                let mut reads = BTreeSet::new();
+                Fold::collect_unknown(root, is_unknown, &mut reads);
+                reads
+            })
+            .unwrap_or_default()
+    }
+}
+
+struct Fold<'s, 'm> {
+    source: &'s str,
+    metadata: &'m AstMetadata,
+    isolate: &'m mut Isolate,
+    is_unknown: &'m dyn Fn(&str) -> bool,
+}
+
+impl Fold<'_, '_> {
+    fn visit(&mut self, node: &Node) -> Option<Folded> {
+        let span = AstOps::span(self.metadata, node)?;
+        let text = self.source.get(span.0 as usize..span.1 as usize)?;
+
+        let mut unknown = BTreeSet::new();
+        Self::collect_unknown(node, self.is_unknown, &mut unknown);
+        if unknown.is_empty() {
+            return Some(self.evaluate(node, text));
+        }
+
+        match node {
+            Node::Parenthesized(inner) => Some(match self.visit(inner)? {
+                Folded::Text(inner, reads) => Folded::Text(format!("({inner})"), reads),
+                constant => constant,
+            }),
+            Node::Binary {
+                left,
+                operator: Operator::Logical(operator @ (LogicalOperator::And | LogicalOperator::Or)),
+                right,
+            } => {
+                let absorbing = matches!(operator, LogicalOperator::Or);
+                match (self.visit(left)?, self.visit(right)?) {
+                    (Folded::Const(Variable::Bool(value)), _)
+                    | (_, Folded::Const(Variable::Bool(value)))
+                        if value == absorbing =>
+                    {
+                        Some(Folded::Const(Variable::Bool(absorbing)))
+                    }
+                    (Folded::Const(Variable::Bool(_)), other)
+                    | (other @ Folded::Text(..), Folded::Const(Variable::Bool(_))) => Some(other),
+                    (left, right) => {
+                        let mut reads = BTreeSet::new();
+                        let left = self.render(left, &mut reads)?;
+                        let right = self.render(right, &mut reads)?;
+                        Some(Folded::Text(format!("{left} {operator} {right}"), reads))
+                    }
+                }
+            }
+            Node::Conditional {
+                condition,
+                on_true,
+                on_false,
+            } => match self.visit(condition)? {
+                Folded::Const(Variable::Bool(value)) => {
+                    let branch = if value { on_true } else { on_false };
+                    Some(match self.visit(branch)? {
+                        Folded::Text(text, reads) => Folded::Text(format!("({text})"), reads),
+                        constant => constant,
+                    })
+                }
+                _ => self.rebuild(node, span),
+            },
+            Node::Closure { .. } | Node::Error { .. } => {
+                Some(Folded::Text(text.to_string(), unknown))
+            }
+            _ if AstOps::dotted_path(node).is_some() => {
+                Some(Folded::Text(text.to_string(), unknown))
+            }
+            _ => self.rebuild(node, span),
+        }
+    }
+
+    /// Replaces the folded children of `node` within its source text
+    fn rebuild(&mut self, node: &Node, span: Span) -> Option<Folded> {
+        let mut children = Self::children(node);
+        children.sort_by_key(|child| AstOps::span(self.metadata, child).map(|span| span.0));
+
+        let mut out = String::new();
+        let mut reads = BTreeSet::new();
+        let mut cursor = span.0;
+        for child in children {
+            let child_span = AstOps::span(self.metadata, child)?;
+            if child_span.0 < cursor || child_span.1 > span.1 {
+                return None;
+            }
+            out.push_str(self.source.get(cursor as usize..child_span.0 as usize)?);
+            let folded = self.visit(child)?;
+            out.push_str(&self.render(folded, &mut reads)?);
+            cursor = child_span.1;
+        }
+        out.push_str(self.source.get(cursor as usize..span.1 as usize)?);
+
+        Some(Folded::Text(out, reads))
+    }
+
+    fn evaluate(&mut self, node: &Node, text: &str) -> Folded {
+        let literal = matches!(
+            node,
+            Node::Null | Node::Bool(_) | Node::Number(_) | Node::String(_)
+        );
+        if !literal && !matches!(node, Node::Closure { .. }) {
+            if let Ok(value) = self.isolate.run_standard(text) {
+                if Self::literal(&value).is_some() {
+                    return Folded::Const(value);
+                }
+            }
+        }
+
+        Folded::Text(text.to_string(), BTreeSet::new())
+    }
+
+    fn render(&self, folded: Folded, reads: &mut BTreeSet<Arc<str>>) -> Option<String> {
+        match folded {
+            Folded::Const(value) => Self::literal(&value),
+            Folded::Text(text, text_reads) => {
+                reads.extend(text_reads);
+                Some(text)
+            }
+        }
+    }
+
+    /// Expression source producing `value`, for the values that have one
+    fn literal(value: &Variable) -> Option<String> {
+        match value {
+            Variable::Null => Some("null".to_string()),
+            Variable::Bool(b) => Some(b.to_string()),
+            Variable::Number(n) if n.is_sign_negative() => Some(format!("({})", n.normalize())),
+            Variable::Number(n) => Some(n.normalize().to_string()),
+            Variable::String(s) if !s.contains('\'') => Some(format!("'{s}'")),
+            Variable::String(s) if !s.contains('"') => Some(format!("\"{s}\"")),
+            Variable::Array(items) => {
+                let items = items
+                    .borrow()
+                    .iter()
+                    .map(Self::literal)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("[{}]", items.join(", ")))
+            }
+            _ => None,
+        }
+    }
+
+    fn has_assignments(&self, node: &Node) -> bool {
+        let found = Cell::new(false);
+        node.walk(|n| {
+            if matches!(n, Node::Assignments { .. }) {
+                found.set(true);
+            }
+        });
+        found.get()
+    }
+
+    fn collect_unknown(
+        node: &Node,
+        is_unknown: &dyn Fn(&str) -> bool,
+        out: &mut BTreeSet<Arc<str>>,
+    ) {
+        if let Some(path) = AstOps::dotted_path(node) {
+            if is_unknown(&path) {
+                out.insert(Arc::from(path));
+            }
+            return;
+        }
+        match node {
+            Node::Closure { body, .. } => Self::collect_unknown(body, is_unknown, out),
+            _ => Self::children(node)
+                .into_iter()
+                .for_each(|child| Self::collect_unknown(child, is_unknown, out)),
+        }
+    }
+
+    fn children<'n>(node: &'n Node<'n>) -> Vec<&'n Node<'n>> {
+        match node {
+            Node::TemplateString(parts) | Node::Array(parts) => parts.to_vec(),
+            Node::Object(pairs) => pairs.iter().flat_map(|(k, v)| [*k, *v]).collect(),
+            Node::Assignments { list, output } => list
+                .iter()
+                .flat_map(|(k, v)| [*k, *v])
+                .chain(*output)
+                .collect(),
+            Node::Parenthesized(inner) => vec![*inner],
+            Node::Member { node, property } => vec![*node, *property],
+            Node::Slice { node, from, to } => {
+                [Some(*node), *from, *to].into_iter().flatten().collect()
+            }
+            Node::Interval { left, right, .. } => vec![*left, *right],
+            Node::Conditional {
+                condition,
+                on_true,
+                on_false,
+            } => vec![*condition, *on_true, *on_false],
+            Node::Unary { node, .. } => vec![*node],
+            Node::Binary { left, right, .. } => vec![*left, *right],
+            Node::FunctionCall { arguments, .. } => arguments.to_vec(),
+            Node::MethodCall {
+                this, arguments, ..
+            } => std::iter::once(*this)
+                .chain(arguments.iter().copied())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Fetches input properties that were not supplied with the request, e.g. a credit bureau
+/// score, at the moment a policy block needs them.
+///
+/// Only properties declared in a data model are requested, and only when evaluation of the
+/// requested goals actually reads them. Each path is requested at most once per evaluation.
+pub trait PropertyResolver: Debug + Send + Sync {
+    /// Returns `None` when the property is unavailable; evaluation continues without it
+    fn resolve(
+        &self,
+        request: PropertyRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Value>, String>> + Send + '_>>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyRequest {
+    pub policy_path: Arc<str>,
+    /// Declared property being read, e.g. `bureau.score`
+    pub property: Arc<str>,
+    /// Shallowest missing input path leading to `property`, so a missing relationship such as
+    /// `customer.bureau` is requested as a whole
+    pub path: Arc<str>,
+    /// Object holding `path`, which is the whole input for top-level paths
+    pub parent: Value,
+}
+
+pub type DynamicPropertyResolver = Option<Arc<dyn PropertyResolver>>;
@@ -1,18 +1,24 @@
 use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
 use ahash::{HashMap, HashSet, HashSetExt};
 use zen_expression::variable::Variable;
 
+use crate::cancellation::EvaluationLimits;
 use crate::decision::Decision;
 use crate::decision_graph::graph::{DecisionGraphResponse, EvaluationTrace};
 use crate::engine::EvaluationOptions;
+use crate::expression_context::ExpressionContext;
 use crate::loader::DynamicLoader;
-use crate::model::{DecisionContent, GraphContent};
+use crate::model::{DecisionContent, DecisionNodeKind, GraphContent};
+use crate::nodes::custom::CustomNodeRegistry;
 use crate::policy::evaluator::EvalArtifact;
 use crate::policy::raw::PolicyDocument;
+use crate::policy::resolver::DynamicPropertyResolver;
 use crate::workspace::types::{
-    Diagnostic, EvaluateRequest, EvaluationError as PolicyEvaluationError, Severity,
+    Diagnostic, DiagnosticCode, DiagnosticLocation, EvaluateRequest,
+    EvaluationError as PolicyEvaluationError, Severity,
 };
 use crate::workspace::Workspace;
 use crate::{CompileFailure, EvaluationError};
@@ -23,45 +29,101 @@ pub(crate) async fn evaluate_policy(
     entry_content: Arc<DecisionContent>,
     input: Variable,
     options: EvaluationOptions,
+    property_resolver: &DynamicPropertyResolver,
+    expression_context: &ExpressionContext,
 ) -> Result<DecisionGraphResponse, Box<EvaluationError>> {
-    let entry_path: Arc<str> = Arc::from(entry_key);
+    PolicyRuntime::load(
+        loader,
+        entry_key,
+        entry_content,
+        property_resolver,
+        expression_context,
+    )
+    .await?
+    .evaluate(input, Vec::new(), options.trace, &options.limits())
+    .await
+}
+
+/// Policy together with its transitive imports, loaded once and evaluated repeatedly
+/// by decision nodes and `zen.evaluate`
+pub(crate) struct PolicyRuntime {
+    entry_path: Arc<str>,
+    workspace: Workspace,
+}
+
+impl PolicyRuntime {
+    pub(crate) async fn load(
+        loader: &DynamicLoader,
+        entry_key: &str,
+        entry_content: Arc<DecisionContent>,
+        property_resolver: &DynamicPropertyResolver,
+        expression_context: &ExpressionContext,
+    ) -> Result<Self, Box<EvaluationError>> {
+        let entry_path: Arc<str> = Arc::from(entry_key);
 
-    let documents = collect_transitive_policies(loader, entry_path.clone(), entry_content).await?;
+        let documents =
+            collect_transitive_policies(loader, entry_path.clone(), entry_content).await?;
+
+        let mut workspace = Workspace::new();
+        workspace.set_property_resolver(property_resolver.clone());
+        workspace.set_expression_context(expression_context.clone());
+        for (path, doc) in documents {
+            workspace.set_policy_arc(path, doc);
+        }
+
+        let blocking_errors = workspace
+            .all_diagnostics()
+            .into_iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        if blocking_errors > 0 {
+            return Err(Box::new(EvaluationError::Policy(
+                PolicyEvaluationError::CompilationErrors {
+                    policy_path: entry_path.clone(),
+                },
+            )));
+        }
 
-    let mut workspace = Workspace::new();
-    for (path, doc) in documents {
-        workspace.set_policy_arc(path, doc);
+        Ok(Self {
+            entry_path,
+            workspace,
+        })
     }
 
-    let blocking_errors = workspace
-        .all_diagnostics()
-        .into_iter()
-        .filter(|d| d.severity == Severity::Error)
-        .count();
-    if blocking_errors > 0 {
-        return Err(Box::new(EvaluationError::Policy(
-            PolicyEvaluationError::CompilationErrors {
-                policy_path: entry_path.clone(),
-            },
-        )));
+    pub(crate) async fn evaluate(
+        &self,
+        input: Variable,
+        goals: Vec<Arc<str>>,
+        trace: bool,
+        limits: &EvaluationLimits,
+    ) -> Result<DecisionGraphResponse, Box<EvaluationError>> {
+        let request = EvaluateRequest {
+            policy_path: self.entry_path.clone(),
+            input,
+            goals,
+            trace,
+        };
+
+        let result = self
+            .workspace
+            .evaluate_limited(&request, limits)
+            .await
+            .map_err(EvaluationError::from_policy)?;
+
+        Ok(DecisionGraphResponse {
+            performance: format!("{:.1?}", result.duration),
+            result: result.output,
+            trace: result.trace.map(EvaluationTrace::Policy),
+        })
     }
+}
 
-    let request = EvaluateRequest {
-        policy_path: entry_path,
-        input,
-        goals: vec![],
-        trace: options.trace,
-    };
-
-    let result = workspace
-        .evaluate(&request)
-        .map_err(|e| Box::new(EvaluationError::Policy(e)))?;
-
-    Ok(DecisionGraphResponse {
-        performance: format!("{:.1?}", result.duration),
-        result: result.output,
-        trace: result.trace.map(EvaluationTrace::Policy),
-    })
+impl Debug for PolicyRuntime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyRuntime")
+            .field("entry_path", &self.entry_path)
+            .finish_non_exhaustive()
+    }
 }
 
 async fn collect_transitive_policies(
@@ -117,7 +179,11 @@ pub(crate) struct CompiledSet {
 }
 
 impl CompiledSet {
-    pub(crate) fn build_sync(loader: &DynamicLoader, keys: &[Arc<str>]) -> CompiledSet {
+    pub(crate) fn build_sync(
+        loader: &DynamicLoader,
+        keys: &[Arc<str>],
+        custom_nodes: Option<&CustomNodeRegistry>,
+    ) -> CompiledSet {
         let mut workspace = Workspace::new();
         let mut policy_keys: Vec<Arc<str>> = Vec::new();
         let mut failures: Vec<CompileFailure> = Vec::new();
@@ -152,6 +218,19 @@ impl CompiledSet {
                         error: Some(error.to_string()),
                     }),
                     Ok(()) => {
+                        let diagnostics = custom_nodes
+                            .map(|registry| Self::custom_node_diagnostics(registry, key, graph))
+                            .unwrap_or_default();
+                        if !diagnostics.is_empty() {
+                            failures.push(CompileFailure {
+                                key: key.clone(),
+                                kind: "graph",
+                                diagnostics,
+                                error: None,
+                            });
+                            continue;
+                        }
+
                         let mut compiled = graph.clone();
                         Arc::make_mut(&mut compiled).compile();
                         entries.insert(key.clone(), CompiledEntry::Graph(compiled));
@@ -180,6 +259,33 @@ impl CompiledSet {
         CompiledSet { entries, failures }
     }
 
+    fn custom_node_diagnostics(
+        registry: &CustomNodeRegistry,
+        key: &Arc<str>,
+        graph: &GraphContent,
+    ) -> Vec<Diagnostic> {
+        graph
+            .nodes
+            .iter()
+            .filter_map(|node| match &node.kind {
+                DecisionNodeKind::CustomNode { content } => Some((node, content)),
+                _ => None,
+            })
+            .flat_map(|(node, content)| {
+                registry
+                    .check(content.kind.as_ref(), content.config.as_ref())
+                    .into_iter()
+                    .map(|message| {
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidNodeConfig,
+                            DiagnosticLocation::block(key.clone(), node.id.clone()),
+                            message,
+                        )
+                    })
+            })
+            .collect()
+    }
+
     fn closure_error_diagnostics(workspace: &Workspace, key: &Arc<str>) -> Vec<Diagnostic> {
         let mut diagnostics: Vec<Diagnostic> = Vec::new();
         let mut enqueued: HashSet<Arc<str>> = HashSet::new();
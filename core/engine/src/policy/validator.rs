@@ -3,6 +3,8 @@ use std::sync::Arc;
 use ahash::{HashMap, HashMapExt, HashSet};
 use zen_expression::variable::Variable;
 
+use crate::nodes::input::coerce::{coerce_scalar, ScalarType};
+use crate::nodes::input::{CoercionKind, InputCoercion};
 use crate::policy::ir::{DataModelIr, DictionaryIr, Property, PropertyTypeIr};
 use crate::policy::refs::RefPoolIndex;
 use crate::policy::MAX_RECURSION_DEPTH;
@@ -114,6 +116,113 @@ impl InputSchema {
     }
 }
 
+impl InputSchema {
+    /// Converts scalars of `input` in place to the types their data model properties declare.
+    ///
+    /// Data models declare neither defaults nor closed entities, so unlike graph input nodes
+    /// nothing is added or removed.
+    pub(crate) fn coerce(&self, input: &Variable) -> Vec<InputCoercion> {
+        let mut coercer = InputCoercer {
+            entities: &self.entities,
+            dictionaries: &self.dictionaries,
+            changes: Vec::new(),
+            depth: 0,
+        };
+        let Some(input_obj) = input.as_object() else {
+            return coercer.changes;
+        };
+
+        for (key, val) in input_obj.borrow_mut().iter_mut() {
+            let key_str: &str = key.as_str();
+            if self.ref_targets.contains(key_str) {
+                coercer.coerce_array_of_entity(val, key_str, key_str);
+            } else if self.roots.contains(key_str) {
+                coercer.coerce_entity(val, key_str, key_str);
+            } else if let Some(prop) = self.globals.get(key_str) {
+                coercer.coerce_property(val, prop, key_str.to_string());
+            }
+        }
+
+        coercer.changes
+    }
+}
+
+struct InputCoercer<'a> {
+    entities: &'a HashMap<Arc<str>, Arc<DataModelIr>>,
+    dictionaries: &'a HashMap<Arc<str>, Arc<DictionaryIr>>,
+    changes: Vec<InputCoercion>,
+    depth: usize,
+}
+
+impl InputCoercer<'_> {
+    fn coerce_entity(&mut self, value: &Variable, entity_name: &str, path: &str) {
+        if self.depth >= MAX_RECURSION_DEPTH {
+            return;
+        }
+        let (Some(obj), Some(dm)) = (value.as_object(), self.entities.get(entity_name)) else {
+            return;
+        };
+
+        self.depth += 1;
+        let dm = dm.clone();
+        for (key, val) in obj.borrow_mut().iter_mut() {
+            if let Some(prop) = dm.properties.iter().find(|p| *p.name == *key.as_str()) {
+                self.coerce_property(val, prop, format!("{path}.{}", prop.name));
+            }
+        }
+        self.depth -= 1;
+    }
+
+    fn coerce_array_of_entity(&mut self, value: &Variable, entity_name: &str, path: &str) {
+        let Some(arr) = value.as_array() else {
+            return;
+        };
+        for (i, item) in arr.borrow().iter().enumerate() {
+            self.coerce_entity(item, entity_name, &format!("{path}[{i}]"));
+        }
+    }
+
+    fn coerce_property(&mut self, value: &mut Variable, prop: &Property, path: String) {
+        if !prop.array {
+            return self.coerce_kind(value, &prop.kind, path);
+        }
+        let Some(arr) = value.as_array() else {
+            return;
+        };
+        for (i, item) in arr.borrow_mut().iter_mut().enumerate() {
+            self.coerce_kind(item, &prop.kind, format!("{path}[{i}]"));
+        }
+    }
+
+    fn coerce_kind(&mut self, value: &mut Variable, kind: &PropertyTypeIr, path: String) {
+        let target = match kind {
+            PropertyTypeIr::String | PropertyTypeIr::Enum(_) | PropertyTypeIr::Reference { .. } => {
+                ScalarType::String
+            }
+            PropertyTypeIr::Number => ScalarType::Number,
+            PropertyTypeIr::Boolean => ScalarType::Boolean,
+            PropertyTypeIr::Date => ScalarType::Date,
+            PropertyTypeIr::Relationship { target } => {
+                if self.entities.contains_key(target) || !self.dictionaries.contains_key(target) {
+                    return self.coerce_entity(value, target, &path);
+                }
+                ScalarType::String
+            }
+        };
+
+        let Some(coerced) = coerce_scalar(value, target) else {
+            return;
+        };
+        let from = std::mem::replace(value, coerced.clone());
+        self.changes.push(InputCoercion {
+            path,
+            kind: CoercionKind::Coerce,
+            from: Some(from),
+            to: Some(coerced),
+        });
+    }
+}
+
 struct InputValidator<'a> {
     entities: &'a HashMap<Arc<str>, Arc<DataModelIr>>,
     dictionaries: &'a HashMap<Arc<str>, Arc<DictionaryIr>>,
@@ -0,0 +1,80 @@
+use crate::spreadsheet::SpreadsheetError;
+
+/// RFC 4180 reader and writer, fields are quoted only when needed
+pub(crate) struct Csv;
+
+impl Csv {
+    pub(crate) fn parse(text: &str) -> Result<Vec<Vec<String>>, SpreadsheetError> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut chars = text.chars().peekable();
+        let mut quoted_since = None;
+
+        while let Some(c) = chars.next() {
+            if let Some(start) = quoted_since {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' => {
+                        quoted_since = None;
+                        if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                            return Err(SpreadsheetError::Csv {
+                                row: start,
+                                message: "unexpected character after closing quote".to_string(),
+                            });
+                        }
+                    }
+                    c => field.push(c),
+                }
+                continue;
+            }
+
+            match c {
+                '"' if field.is_empty() => quoted_since = Some(rows.len() + 1),
+                ',' => row.push(std::mem::take(&mut field)),
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\r' | '\n' => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                c => field.push(c),
+            }
+        }
+
+        if let Some(start) = quoted_since {
+            return Err(SpreadsheetError::Csv {
+                row: start,
+                message: "unterminated quoted field".to_string(),
+            });
+        }
+        if !field.is_empty() || !row.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    pub(crate) fn write(rows: &[Vec<String>]) -> String {
+        let mut out = String::new();
+        for row in rows {
+            for (i, field) in row.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                if field.contains([',', '"', '\r', '\n']) {
+                    out.push('"');
+                    out.push_str(&field.replace('"', "\"\""));
+                    out.push('"');
+                } else {
+                    out.push_str(field);
+                }
+            }
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
@@ -0,0 +1,332 @@
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use std::sync::Arc;
+
+use zen_expression::intellisense::{ArmTest, IntelliSense};
+use zen_expression::validate::{validate_expression, validate_unary_expression};
+
+use crate::model::{DecisionTableHitPolicy, DecisionTableInputField, DecisionTableOutputField};
+use crate::policy::blocks::{DeclaredBase, DeclaredType};
+use crate::policy::DecisionTableDoc;
+use crate::spreadsheet::{CellError, SpreadsheetError, TableImport};
+
+pub(crate) const HEADER_ROWS: usize = 5;
+
+const KIND_ROW: usize = 0;
+const ID_ROW: usize = 1;
+const FIELD_ROW: usize = 2;
+const NAME_ROW: usize = 3;
+const TYPE_ROW: usize = 4;
+
+const HIT_POLICY_KIND: &str = "hitPolicy";
+/// Column type the editor writes for plain expression columns, it names no value type
+const EXPRESSION_COLUMN: &str = "expression";
+
+const RULE_ID_KEY: &str = "_id";
+const RULE_DESCRIPTION_KEY: &str = "_description";
+
+enum Column {
+    Input {
+        id: Arc<str>,
+        declared: Option<DeclaredType>,
+    },
+    Output {
+        id: Arc<str>,
+    },
+    RuleId,
+    Description,
+}
+
+/// Maps between decision tables and sheet rows
+pub(crate) struct Layout;
+
+impl Layout {
+    pub(crate) fn rows(table: &DecisionTableDoc) -> Vec<Vec<String>> {
+        let has_key = |key: &str| {
+            table
+                .rules
+                .iter()
+                .any(|rule| rule.get(key).is_some_and(|v| !v.is_empty()))
+        };
+        let mut header: Vec<[String; HEADER_ROWS]> = Vec::new();
+        let mut keys: Vec<&str> = Vec::new();
+
+        if table.hit_policy != DecisionTableHitPolicy::default() {
+            header.push([
+                HIT_POLICY_KIND.to_string(),
+                Self::hit_policy_name(&table.hit_policy).to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+            keys.push("");
+        }
+
+        if has_key(RULE_ID_KEY) {
+            header.push([
+                "id".to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+            keys.push(RULE_ID_KEY);
+        }
+        for input in &table.inputs {
+            header.push([
+                "input".to_string(),
+                input.id.to_string(),
+                input.field.as_deref().unwrap_or_default().to_string(),
+                input.name.to_string(),
+                input.column_type.as_deref().unwrap_or_default().to_string(),
+            ]);
+            keys.push(&input.id);
+        }
+        for output in &table.outputs {
+            header.push([
+                "output".to_string(),
+                output.id.to_string(),
+                output.field.to_string(),
+                output.name.to_string(),
+                output
+                    .column_type
+                    .as_deref()
+                    .unwrap_or_default()
+                    .to_string(),
+            ]);
+            keys.push(&output.id);
+        }
+        if has_key(RULE_DESCRIPTION_KEY) {
+            header.push([
+                "description".to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+            keys.push(RULE_DESCRIPTION_KEY);
+        }
+
+        let mut rows: Vec<Vec<String>> = (0..HEADER_ROWS)
+            .map(|r| header.iter().map(|column| column[r].clone()).collect())
+            .collect();
+        for rule in &table.rules {
+            rows.push(
+                keys.iter()
+                    .map(|key| rule.get(*key).map(|v| v.to_string()).unwrap_or_default())
+                    .collect(),
+            );
+        }
+        rows
+    }
+
+    fn hit_policy_name(hit_policy: &DecisionTableHitPolicy) -> &'static str {
+        match hit_policy {
+            DecisionTableHitPolicy::First => "first",
+            DecisionTableHitPolicy::Collect => "collect",
+        }
+    }
+
+    pub(crate) fn table(rows: Vec<Vec<String>>) -> Result<TableImport, SpreadsheetError> {
+        if rows.len() < HEADER_ROWS {
+            return Err(SpreadsheetError::MissingHeader(rows.len()));
+        }
+
+        let mut reader = SheetReader {
+            table: DecisionTableDoc {
+                hit_policy: Default::default(),
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                rules: Vec::new(),
+            },
+            errors: Vec::new(),
+            intellisense: IntelliSense::new(),
+        };
+
+        let width = rows[..HEADER_ROWS].iter().map(Vec::len).max().unwrap_or(0);
+        let columns: Vec<(usize, Column)> = reader.columns(&rows[..HEADER_ROWS], width);
+        for (r, row) in rows.iter().enumerate().skip(HEADER_ROWS) {
+            if row.iter().all(|cell| cell.trim().is_empty()) {
+                continue;
+            }
+            reader.rule(r, row, &columns);
+        }
+
+        reader.errors.sort_by_key(|e| (e.row, e.column));
+        Ok(TableImport {
+            table: reader.table,
+            errors: reader.errors,
+        })
+    }
+}
+
+struct SheetReader {
+    table: DecisionTableDoc,
+    errors: Vec<CellError>,
+    intellisense: IntelliSense,
+}
+
+impl SheetReader {
+    fn hit_policy(&mut self, c: usize, value: &str, seen: &mut bool) {
+        if std::mem::replace(seen, true) {
+            self.report(KIND_ROW, c, "duplicate hit policy column");
+            return;
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "" | "first" => self.table.hit_policy = DecisionTableHitPolicy::First,
+            "collect" => self.table.hit_policy = DecisionTableHitPolicy::Collect,
+            other => self.report(
+                ID_ROW,
+                c,
+                format!("unknown hit policy '{other}', expected first or collect"),
+            ),
+        }
+    }
+
+    fn columns(&mut self, header: &[Vec<String>], width: usize) -> Vec<(usize, Column)> {
+        let cell =
+            |r: usize, c: usize| -> &str { header[r].get(c).map(|v| v.trim()).unwrap_or_default() };
+        let mut columns = Vec::new();
+        let mut ids: HashSet<Arc<str>> = HashSet::new();
+        let mut hit_policy = false;
+
+        for c in 0..width {
+            if cell(KIND_ROW, c).eq_ignore_ascii_case(HIT_POLICY_KIND) {
+                self.hit_policy(c, cell(ID_ROW, c), &mut hit_policy);
+                continue;
+            }
+
+            let kind = cell(KIND_ROW, c).to_ascii_lowercase();
+            if kind.is_empty() {
+                if (ID_ROW..HEADER_ROWS).any(|r| !cell(r, c).is_empty()) {
+                    self.report(KIND_ROW, c, "missing column kind");
+                }
+                continue;
+            }
+
+            match kind.as_str() {
+                "id" => columns.push((c, Column::RuleId)),
+                "description" => columns.push((c, Column::Description)),
+                "input" | "output" => {
+                    let id: Arc<str> = match cell(ID_ROW, c) {
+                        "" => Arc::from(format!("{kind}-{}", c + 1)),
+                        id => Arc::from(id),
+                    };
+                    if !ids.insert(id.clone()) {
+                        self.report(ID_ROW, c, format!("duplicate column id '{id}'"));
+                        continue;
+                    }
+
+                    let field = cell(FIELD_ROW, c);
+                    let name: Arc<str> = Arc::from(cell(NAME_ROW, c));
+                    let raw_type = cell(TYPE_ROW, c);
+                    let declared = match DeclaredType::parse(raw_type) {
+                        Ok(declared) => declared,
+                        Err(message) => {
+                            self.report(TYPE_ROW, c, message);
+                            None
+                        }
+                    };
+
+                    if kind == "input" {
+                        self.table.inputs.push(DecisionTableInputField {
+                            id: id.clone(),
+                            name,
+                            field: (!field.is_empty()).then(|| Arc::from(field)),
+                            column_type: declared.is_some().then(|| Arc::from(raw_type)),
+                        });
+                        let declared = declared.filter(|_| raw_type != EXPRESSION_COLUMN);
+                        columns.push((c, Column::Input { id, declared }));
+                    } else {
+                        if field.is_empty() {
+                            self.report(FIELD_ROW, c, "output column requires a field");
+                        }
+                        self.table.outputs.push(DecisionTableOutputField {
+                            id: id.clone(),
+                            name,
+                            field: Arc::from(field),
+                            column_type: declared.is_some().then(|| Arc::from(raw_type)),
+                        });
+                        columns.push((c, Column::Output { id }));
+                    }
+                }
+                other => self.report(
+                    KIND_ROW,
+                    c,
+                    format!(
+                        "unknown column kind '{other}', expected input, output, id, description or hitPolicy"
+                    ),
+                ),
+            }
+        }
+        columns
+    }
+
+    fn rule(&mut self, r: usize, row: &[String], columns: &[(usize, Column)]) {
+        let mut rule: HashMap<Arc<str>, Arc<str>> = HashMap::with_capacity(columns.len());
+        for (c, column) in columns {
+            let text = row.get(*c).map(|v| v.trim()).unwrap_or_default();
+            match column {
+                Column::RuleId | Column::Description if text.is_empty() => {}
+                Column::RuleId => {
+                    rule.insert(Arc::from(RULE_ID_KEY), Arc::from(text));
+                }
+                Column::Description => {
+                    rule.insert(Arc::from(RULE_DESCRIPTION_KEY), Arc::from(text));
+                }
+                Column::Input { id, declared } => {
+                    if let Err(message) = self.check_input(text, declared.as_ref()) {
+                        self.report(r, *c, message);
+                    }
+                    rule.insert(id.clone(), Arc::from(text));
+                }
+                Column::Output { id } => {
+                    if !text.is_empty() {
+                        if let Err(err) = validate_expression(text) {
+                            self.report(r, *c, err.to_string());
+                        }
+                    }
+                    rule.insert(id.clone(), Arc::from(text));
+                }
+            }
+        }
+        self.table.rules.push(rule);
+    }
+
+    fn check_input(&mut self, text: &str, declared: Option<&DeclaredType>) -> Result<(), String> {
+        let test = self.intellisense.cell_test(text);
+        let found = match test {
+            ArmTest::Default => return Ok(()),
+            ArmTest::Unrecognized => {
+                return validate_unary_expression(text).map_err(|err| err.to_string());
+            }
+            ArmTest::Enum { .. } => DeclaredBase::String,
+            ArmTest::Bool { .. } => DeclaredBase::Bool,
+            ArmTest::Number { .. } => DeclaredBase::Number,
+        };
+        let Some(declared) = declared.filter(|declared| !declared.array) else {
+            return Ok(());
+        };
+
+        let compatible = match (&declared.base, &found) {
+            (DeclaredBase::Date, _) => true,
+            (DeclaredBase::Dictionary(_), DeclaredBase::String) => true,
+            (expected, found) => expected == found,
+        };
+        if compatible {
+            return Ok(());
+        }
+        let found = DeclaredType {
+            base: found,
+            array: false,
+        };
+        Err(format!("expected a {declared} test, found a {found} test"))
+    }
+
+    fn report(&mut self, r: usize, c: usize, message: impl Into<String>) {
+        self.errors.push(CellError {
+            row: r + 1,
+            column: c + 1,
+            message: message.into(),
+        });
+    }
+}
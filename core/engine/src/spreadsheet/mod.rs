@@ -0,0 +1,110 @@
+//! CSV and XLSX round trip for decision tables.
+//!
+//! A sheet starts with five header rows describing the columns:
+//!
+//! | row | content                                                              |
+//! |-----|----------------------------------------------------------------------|
+//! | 1   | column kind: `input`, `output`, `id` (rule id) or `description`      |
+//! | 2   | column id, generated when left empty                                 |
+//! | 3   | field                                                                |
+//! | 4   | name                                                                 |
+//! | 5   | type: `string`, `number`, `boolean`, `date` or a dictionary name     |
+//!
+//! A `hitPolicy` column carries the table hit policy (`first` or `collect`) in its second row
+//! and is only written for tables that do not use the default
+//! [`DecisionTableHitPolicy::First`](crate::model::DecisionTableHitPolicy::First).
+//!
+//! Every following non-empty row is a rule. Input cells are checked as unary tests against the
+//! column type and output cells as expressions; problems are reported per cell as a
+//! [`CellError`] rather than failing the import.
+//!
+//! XLSX imports are bounded to the 1,048,576 rows and 16,384 columns Excel supports; sheets
+//! referencing cells past them, or archive entries that inflate past 64 MiB, are rejected.
+
+mod csv;
+mod layout;
+mod xlsx;
+
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+use crate::model::DecisionTableContent;
+use crate::policy::DecisionTableDoc;
+
+use self::csv::Csv;
+use self::layout::{Layout, HEADER_ROWS};
+use self::xlsx::Xlsx;
+
+/// Writes the table as CSV
+pub fn export_csv(table: &DecisionTableDoc) -> String {
+    Csv::write(&Layout::rows(table))
+}
+
+/// Reads a table from CSV
+pub fn import_csv(text: &str) -> Result<TableImport, SpreadsheetError> {
+    Layout::table(Csv::parse(text)?)
+}
+
+/// Writes the table as a single-sheet XLSX workbook
+pub fn export_xlsx(table: &DecisionTableDoc) -> Result<Vec<u8>, SpreadsheetError> {
+    Xlsx::write(&Layout::rows(table))
+}
+
+/// Reads a table from the first sheet of an XLSX workbook
+pub fn import_xlsx(bytes: &[u8]) -> Result<TableImport, SpreadsheetError> {
+    Layout::table(Xlsx::read(bytes)?)
+}
+
+#[derive(Debug, Clone)]
+pub struct TableImport {
+    pub table: DecisionTableDoc,
+    pub errors: Vec<CellError>,
+}
+
+impl TableImport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Imported table as decision table node content
+    pub fn into_content(self) -> DecisionTableContent {
+        self.table.into()
+    }
+}
+
+/// Problem with a single cell, `row` and `column` are 1-based sheet coordinates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellError {
+    pub row: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl CellError {
+    /// Spreadsheet reference of the cell, e.g. `C7`
+    pub fn reference(&self) -> String {
+        format!(
+            "{}{}",
+            Xlsx::column_name(self.column.saturating_sub(1)),
+            self.row
+        )
+    }
+}
+
+impl Display for CellError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.reference(), self.message)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SpreadsheetError {
+    #[error("Invalid CSV at row {row}: {message}")]
+    Csv { row: usize, message: String },
+    #[error("Invalid XLSX workbook: {0}")]
+    Xlsx(String),
+    #[error("Expected {HEADER_ROWS} header rows (kind, id, field, name, type), found {0}")]
+    MissingHeader(usize),
+}
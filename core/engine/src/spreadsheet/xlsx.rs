@@ -0,0 +1,316 @@
+use std::io::{Cursor, Read, Write};
+
+use roxmltree::{Document, Node};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::spreadsheet::SpreadsheetError;
+
+const SHEET_NAME: &str = "Decision table";
+const MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const RELATIONSHIP_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Sheet dimensions supported by Excel
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+/// Upper bound of the cells a sheet may allocate, gaps between referenced cells included
+const MAX_CELLS: usize = 4_194_304;
+/// Upper bound of a decompressed archive entry
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Minimal SpreadsheetML support: cell text of the first sheet on read, inline strings on write
+pub(crate) struct Xlsx;
+
+impl Xlsx {
+    pub(crate) fn read(bytes: &[u8]) -> Result<Vec<Vec<String>>, SpreadsheetError> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(Self::error)?;
+        let shared_strings = match Self::entry(&mut archive, "xl/sharedStrings.xml") {
+            Ok(xml) => Self::shared_strings(&xml)?,
+            Err(_) => Vec::new(),
+        };
+        let sheet_path = Self::first_sheet_path(&mut archive)?;
+        let sheet = Self::entry(&mut archive, &sheet_path)?;
+        Self::sheet_rows(&sheet, &shared_strings)
+    }
+
+    pub(crate) fn write(rows: &[Vec<String>]) -> Result<Vec<u8>, SpreadsheetError> {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = ZipWriter::new(&mut cursor);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let parts = [
+            ("[Content_Types].xml", Self::content_types()),
+            ("_rels/.rels", Self::package_relationships()),
+            ("xl/workbook.xml", Self::workbook()),
+            ("xl/_rels/workbook.xml.rels", Self::workbook_relationships()),
+            ("xl/worksheets/sheet1.xml", Self::worksheet(rows)),
+        ];
+        for (name, xml) in parts {
+            writer.start_file(name, options).map_err(Self::error)?;
+            writer.write_all(xml.as_bytes()).map_err(Self::error)?;
+        }
+        writer.finish().map_err(Self::error)?;
+        Ok(cursor.into_inner())
+    }
+
+    fn entry(
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+        name: &str,
+    ) -> Result<String, SpreadsheetError> {
+        let entry = archive.by_name(name).map_err(Self::error)?;
+        if entry.size() > MAX_ENTRY_BYTES {
+            return Err(Self::too_large(name));
+        }
+
+        // The declared size is not trusted, the read stops one byte past the limit
+        let mut xml = String::new();
+        entry
+            .take(MAX_ENTRY_BYTES + 1)
+            .read_to_string(&mut xml)
+            .map_err(Self::error)?;
+        if xml.len() as u64 > MAX_ENTRY_BYTES {
+            return Err(Self::too_large(name));
+        }
+        Ok(xml)
+    }
+
+    fn first_sheet_path(
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+    ) -> Result<String, SpreadsheetError> {
+        let workbook = Self::entry(archive, "xl/workbook.xml")?;
+        let workbook = Document::parse(&workbook).map_err(Self::error)?;
+        let relationship_id = workbook
+            .descendants()
+            .find(|node| node.has_tag_name("sheet"))
+            .and_then(|sheet| sheet.attribute((RELATIONSHIP_NAMESPACE, "id")))
+            .ok_or_else(|| SpreadsheetError::Xlsx("workbook has no sheets".to_string()))?;
+
+        let relationships = Self::entry(archive, "xl/_rels/workbook.xml.rels")?;
+        let relationships = Document::parse(&relationships).map_err(Self::error)?;
+        let target = relationships
+            .descendants()
+            .find(|node| {
+                node.has_tag_name("Relationship") && node.attribute("Id") == Some(relationship_id)
+            })
+            .and_then(|node| node.attribute("Target"))
+            .ok_or_else(|| {
+                SpreadsheetError::Xlsx(format!("missing relationship '{relationship_id}'"))
+            })?;
+
+        Ok(match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("xl/{target}"),
+        })
+    }
+
+    fn shared_strings(xml: &str) -> Result<Vec<String>, SpreadsheetError> {
+        let document = Document::parse(xml).map_err(Self::error)?;
+        Ok(document
+            .root_element()
+            .children()
+            .filter(|node| node.has_tag_name("si"))
+            .map(Self::text)
+            .collect())
+    }
+
+    fn sheet_rows(xml: &str, shared: &[String]) -> Result<Vec<Vec<String>>, SpreadsheetError> {
+        let document = Document::parse(xml).map_err(Self::error)?;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut allocated = 0usize;
+
+        for row in document.descendants().filter(|n| n.has_tag_name("row")) {
+            let index = match row.attribute("r") {
+                Some(r) => match r.trim().parse::<usize>() {
+                    Ok(r) if (1..=MAX_ROWS).contains(&r) => r - 1,
+                    _ => return Err(Self::out_of_bounds("row", r)),
+                },
+                None => rows.len(),
+            };
+            if index >= MAX_ROWS {
+                return Err(Self::out_of_bounds("row", &(index + 1).to_string()));
+            }
+            if rows.len() <= index {
+                rows.resize(index + 1, Vec::new());
+            }
+
+            let cells = &mut rows[index];
+            for cell in row.children().filter(|n| n.has_tag_name("c")) {
+                let column = match cell.attribute("r") {
+                    Some(reference) => Self::column_index(reference)
+                        .ok_or_else(|| Self::out_of_bounds("cell reference", reference))?,
+                    None => cells.len(),
+                };
+                if column >= MAX_COLUMNS {
+                    return Err(Self::out_of_bounds("column", &Self::column_name(column)));
+                }
+                if cells.len() <= column {
+                    allocated += column + 1 - cells.len();
+                    if allocated > MAX_CELLS {
+                        return Err(SpreadsheetError::Xlsx(format!(
+                            "sheet has more than {MAX_CELLS} cells"
+                        )));
+                    }
+                    cells.resize(column + 1, String::new());
+                }
+                cells[column] = Self::cell_value(cell, shared)?;
+            }
+        }
+        Ok(rows)
+    }
+
+    fn cell_value(cell: Node, shared: &[String]) -> Result<String, SpreadsheetError> {
+        let value = || {
+            cell.children()
+                .find(|n| n.has_tag_name("v"))
+                .and_then(|v| v.text())
+                .unwrap_or_default()
+        };
+        Ok(match cell.attribute("t") {
+            Some("s") => {
+                let index: usize = value().trim().parse().map_err(Self::error)?;
+                shared.get(index).cloned().ok_or_else(|| {
+                    SpreadsheetError::Xlsx(format!("shared string {index} is out of range"))
+                })?
+            }
+            Some("inlineStr") => cell
+                .children()
+                .find(|n| n.has_tag_name("is"))
+                .map(Self::text)
+                .unwrap_or_default(),
+            Some("b") => match value().trim() {
+                "1" => "true".to_string(),
+                _ => "false".to_string(),
+            },
+            _ => value().to_string(),
+        })
+    }
+
+    /// Rich text runs joined, phonetic hints skipped
+    fn text(node: Node) -> String {
+        node.descendants()
+            .filter(|n| n.has_tag_name("t") && !n.ancestors().any(|a| a.has_tag_name("rPh")))
+            .filter_map(|n| n.text())
+            .collect()
+    }
+
+    /// Zero-based column of an `A1` reference, `None` past the last column Excel supports
+    fn column_index(reference: &str) -> Option<usize> {
+        let column = reference
+            .bytes()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .try_fold(0usize, |acc, b| {
+                acc.checked_mul(26)?
+                    .checked_add((b.to_ascii_uppercase() - b'A') as usize + 1)
+                    .filter(|column| *column <= MAX_COLUMNS)
+            })?;
+        column.checked_sub(1)
+    }
+
+    pub(crate) fn column_name(index: usize) -> String {
+        let mut letters = Vec::new();
+        let mut column = index + 1;
+        while column > 0 {
+            letters.push(b'A' + ((column - 1) % 26) as u8);
+            column = (column - 1) / 26;
+        }
+        letters.reverse();
+        String::from_utf8_lossy(&letters).into_owned()
+    }
+
+    fn worksheet(rows: &[Vec<String>]) -> String {
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<worksheet xmlns=\"{MAIN_NAMESPACE}\"><sheetData>"
+        );
+        for (r, row) in rows.iter().enumerate() {
+            xml.push_str(&format!("<row r=\"{}\">", r + 1));
+            for (c, value) in row.iter().enumerate() {
+                if value.is_empty() {
+                    continue;
+                }
+                let space = if value.trim() != value {
+                    " xml:space=\"preserve\""
+                } else {
+                    ""
+                };
+                xml.push_str(&format!(
+                    "<c r=\"{}{}\" t=\"inlineStr\"><is><t{space}>{}</t></is></c>",
+                    Self::column_name(c),
+                    r + 1,
+                    Self::escape(value),
+                ));
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>\n");
+        xml
+    }
+
+    fn content_types() -> String {
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+         <Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+         </Types>\n"
+            .to_string()
+    }
+
+    fn package_relationships() -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"{RELATIONSHIP_NAMESPACE}/officeDocument\" Target=\"xl/workbook.xml\"/>\
+             </Relationships>\n"
+        )
+    }
+
+    fn workbook() -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <workbook xmlns=\"{MAIN_NAMESPACE}\" xmlns:r=\"{RELATIONSHIP_NAMESPACE}\">\
+             <sheets><sheet name=\"{SHEET_NAME}\" sheetId=\"1\" r:id=\"rId1\"/></sheets>\
+             </workbook>\n"
+        )
+    }
+
+    fn workbook_relationships() -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"{RELATIONSHIP_NAMESPACE}/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+             </Relationships>\n"
+        )
+    }
+
+    fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    fn error(err: impl std::fmt::Display) -> SpreadsheetError {
+        SpreadsheetError::Xlsx(err.to_string())
+    }
+
+    fn out_of_bounds(what: &str, value: &str) -> SpreadsheetError {
+        SpreadsheetError::Xlsx(format!(
+            "{what} '{value}' is outside the sheet, at most {MAX_ROWS} rows and {MAX_COLUMNS} columns are supported"
+        ))
+    }
+
+    fn too_large(name: &str) -> SpreadsheetError {
+        SpreadsheetError::Xlsx(format!(
+            "entry '{name}' exceeds {MAX_ENTRY_BYTES} bytes when decompressed"
+        ))
+    }
+}
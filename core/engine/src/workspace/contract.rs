@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+use zen_expression::variable::VariableType;
+
+use crate::error::CompileFailure;
+use crate::workspace::db::Db;
+use crate::workspace::types::{
+    ConditionalSchema, DecisionContract, GuardedProperty, OpenApiInfo, ScopeRequest,
+};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+const OPENAPI_VERSION: &str = "3.1.0";
+
+impl Db {
+    /// JSON Schema of the input and output of a policy or graph, `None` for unknown paths
+    pub fn json_schema(&self, req: &ScopeRequest) -> Option<DecisionContract> {
+        let mut writer = SchemaWriter::new("#/$defs/");
+        let (input, output) = self.contract_schemas(req, &mut writer)?;
+        let defs = writer.into_defs();
+        Some(DecisionContract {
+            input: SchemaWriter::document(input, &defs),
+            output: SchemaWriter::document(output, &defs),
+        })
+    }
+
+    /// OpenAPI document with one `POST /{key}` operation per policy and graph, along with the
+    /// documents left out because their contract could not be derived
+    pub fn openapi(&self, info: &OpenApiInfo) -> (Value, Vec<CompileFailure>) {
+        let mut writer = SchemaWriter::new("#/components/schemas/");
+        let mut paths = Map::new();
+        let mut schemas: BTreeMap<String, Value> = BTreeMap::new();
+        let mut skipped = Vec::new();
+
+        let mut keys = self.document_paths();
+        keys.sort();
+        for key in keys {
+            let Some((input, output)) =
+                self.contract_schemas(&ScopeRequest::for_policy(key.clone()), &mut writer)
+            else {
+                let (kind, error) = if self.is_graph(&key) {
+                    ("graph", "Graph could not be analyzed")
+                } else {
+                    ("policy", "Policy contract could not be derived")
+                };
+                skipped.push(CompileFailure {
+                    key,
+                    kind,
+                    diagnostics: Vec::new(),
+                    error: Some(error.to_string()),
+                });
+                continue;
+            };
+            let name = writer.component_name(&key);
+            let input_name = format!("{name}Input");
+            let output_name = format!("{name}Output");
+            schemas.insert(input_name.clone(), input);
+            schemas.insert(output_name.clone(), output);
+
+            paths.insert(
+                format!("/{}", key.trim_start_matches('/')),
+                json!({
+                    "post": {
+                        "operationId": format!("evaluate{name}"),
+                        "summary": format!("Evaluate {key}"),
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": {
+                                "schema": { "$ref": format!("#/components/schemas/{input_name}") }
+                            } }
+                        },
+                        "responses": { "200": {
+                            "description": "Evaluation result",
+                            "content": { "application/json": { "schema": {
+                                "type": "object",
+                                "properties": {
+                                    "performance": { "type": "string" },
+                                    "result": { "$ref": format!("#/components/schemas/{output_name}") },
+                                    "trace": { "type": "object" }
+                                },
+                                "required": ["performance", "result"]
+                            } } }
+                        } }
+                    }
+                }),
+            );
+        }
+        schemas.extend(writer.into_defs());
+
+        let document = json!({
+            "openapi": OPENAPI_VERSION,
+            "jsonSchemaDialect": JSON_SCHEMA_DIALECT,
+            "info": { "title": info.title.as_ref(), "version": info.version.as_ref() },
+            "paths": paths,
+            "components": { "schemas": schemas },
+        });
+        (document, skipped)
+    }
+
+    fn contract_schemas(
+        &self,
+        req: &ScopeRequest,
+        writer: &mut SchemaWriter,
+    ) -> Option<(Value, Value)> {
+        if self.is_graph(&req.policy_path) {
+            let analysis = self.graph_analysis(&req.policy_path)?;
+            return Some((
+                writer.schema(&analysis.signature.input),
+                writer.schema(&analysis.signature.output),
+            ));
+        }
+        self.raw_policy(&req.policy_path)?;
+        let unit = self.unit(&req.policy_path);
+        let optional = |path: &str| unit.data_model_paths.is_optional(path);
+
+        let input = match self.conditional_schema(req) {
+            ConditionalSchema::Union { common, union } => {
+                let values: Vec<Value> = union
+                    .variants
+                    .iter()
+                    .filter_map(|variant| variant.value.as_deref())
+                    .map(|value| SchemaWriter::discriminant(&union.resolved_type, value))
+                    .collect();
+                // Arms testing several values are told apart from the single-value arms by
+                // excluding their values; two such arms, or a repeated value, may overlap
+                let open = union.variants.len() - values.len();
+                let exclusive = open <= 1
+                    && values
+                        .iter()
+                        .all(|v| values.iter().filter(|w| *w == v).count() == 1);
+                let variants: Vec<Value> = union
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let mut tree = PropertyTree::default();
+                        tree.extend(&common.inputs, true, &optional, writer);
+                        tree.extend(&variant.group.inputs, true, &optional, writer);
+                        let discriminant = match &variant.value {
+                            Some(value) => Some(json!({
+                                "const": SchemaWriter::discriminant(&union.resolved_type, value)
+                            })),
+                            None if exclusive && !values.is_empty() => {
+                                let mut schema = writer.schema(&union.resolved_type);
+                                schema["not"] = json!({ "enum": values });
+                                Some(schema)
+                            }
+                            None => None,
+                        };
+                        if let Some(schema) = discriminant {
+                            tree.insert(&union.property, schema, true);
+                        }
+                        tree.into_schema()
+                    })
+                    .collect();
+                match exclusive {
+                    true => json!({ "oneOf": variants }),
+                    false => json!({ "anyOf": variants }),
+                }
+            }
+            ConditionalSchema::Flat {
+                common,
+                conditional,
+            } => {
+                let mut tree = PropertyTree::default();
+                tree.extend(&common.inputs, true, &optional, writer);
+                tree.extend(&conditional.inputs, false, &optional, writer);
+                tree.into_schema()
+            }
+        };
+
+        let mut output = PropertyTree::default();
+        for property in self.outputs(req) {
+            let schema = writer.schema(&property.resolved_type);
+            output.insert(&property.path, schema, false);
+        }
+
+        Some((input, output.into_schema()))
+    }
+}
+
+/// Converts resolved types to JSON Schema, collecting named dictionaries as shared definitions
+struct SchemaWriter {
+    ref_prefix: &'static str,
+    defs: BTreeMap<String, Value>,
+    /// Component names handed out for operation schemas, kept apart from definitions
+    reserved: HashSet<String>,
+}
+
+impl SchemaWriter {
+    fn new(ref_prefix: &'static str) -> Self {
+        Self {
+            ref_prefix,
+            defs: BTreeMap::new(),
+            reserved: HashSet::new(),
+        }
+    }
+
+    fn into_defs(self) -> BTreeMap<String, Value> {
+        self.defs
+    }
+
+    fn document(schema: Value, defs: &BTreeMap<String, Value>) -> Value {
+        let mut document = Map::new();
+        document.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+        match schema {
+            Value::Object(object) => document.extend(object),
+            Value::Bool(true) => {}
+            other => {
+                document.insert("allOf".to_string(), json!([other]));
+            }
+        }
+        if !defs.is_empty() {
+            document.insert("$defs".to_string(), json!(defs));
+        }
+        Value::Object(document)
+    }
+
+    fn schema(&mut self, ty: &VariableType) -> Value {
+        match ty {
+            VariableType::Any => json!({}),
+            VariableType::Null => json!({ "type": "null" }),
+            VariableType::Bool => json!({ "type": "boolean" }),
+            VariableType::String | VariableType::Interval => json!({ "type": "string" }),
+            VariableType::Number => json!({ "type": "number" }),
+            VariableType::Date => json!({ "type": "string", "format": "date-time" }),
+            VariableType::Duration => json!({ "type": "string", "format": "duration" }),
+            VariableType::Const(value) => json!({ "const": value.as_ref() }),
+            VariableType::Array(inner) => json!({ "type": "array", "items": self.schema(inner) }),
+            VariableType::Enum(name, values) => {
+                let schema = json!({ "type": "string", "enum": values.iter().map(|v| v.as_ref()).collect::<Vec<_>>() });
+                match name {
+                    Some(name) => {
+                        let name = self.define(name, schema);
+                        json!({ "$ref": format!("{}{name}", self.ref_prefix) })
+                    }
+                    None => schema,
+                }
+            }
+            VariableType::Nullable(inner) => {
+                let mut inner = self.schema(inner);
+                match inner
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                {
+                    Some(kind) if inner.get("enum").is_none() => {
+                        inner["type"] = json!([kind, "null"]);
+                        inner
+                    }
+                    _ => json!({ "anyOf": [inner, { "type": "null" }] }),
+                }
+            }
+            VariableType::Object(object) => {
+                let mut properties: Vec<(String, Value, bool)> = object
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), self.schema(value), !value.is_nullable()))
+                    .collect();
+                properties.sort_by(|a, b| a.0.cmp(&b.0));
+                let required: Vec<&String> = properties
+                    .iter()
+                    .filter(|(_, _, required)| *required)
+                    .map(|(key, _, _)| key)
+                    .collect();
+                let mut schema = json!({ "type": "object" });
+                if !required.is_empty() {
+                    schema["required"] = json!(required);
+                }
+                schema["properties"] = Value::Object(
+                    properties
+                        .iter()
+                        .map(|(key, value, _)| (key.clone(), value.clone()))
+                        .collect(),
+                );
+                schema
+            }
+        }
+    }
+
+    fn discriminant(ty: &VariableType, value: &str) -> Value {
+        match ty {
+            VariableType::Bool => json!(value == "true"),
+            _ => json!(value),
+        }
+    }
+
+    /// Stores a definition under `name`, reusing an identical one and numbering the name when
+    /// a different schema already holds it
+    fn define(&mut self, name: &str, schema: Value) -> String {
+        let mut candidate = name.to_string();
+        for n in 2.. {
+            match self.defs.get(&candidate) {
+                Some(existing) if *existing == schema => break,
+                None if !self.reserved.contains(&candidate) => {
+                    self.defs.insert(candidate.clone(), schema);
+                    break;
+                }
+                _ => candidate = format!("{name}{n}"),
+            }
+        }
+        candidate
+    }
+
+    /// `loans/approval.json` becomes `LoansApprovalJson`, numbered when another key or a
+    /// definition already uses the `Input` or `Output` schema name
+    fn component_name(&mut self, key: &str) -> String {
+        let base = Self::pascal_case(key);
+        let mut name = base.clone();
+        for n in 2.. {
+            let taken = ["Input", "Output"].iter().any(|suffix| {
+                let schema = format!("{name}{suffix}");
+                self.reserved.contains(&schema) || self.defs.contains_key(&schema)
+            });
+            if !taken {
+                break;
+            }
+            name = format!("{base}{n}");
+        }
+        self.reserved.insert(format!("{name}Input"));
+        self.reserved.insert(format!("{name}Output"));
+        name
+    }
+
+    fn pascal_case(key: &str) -> String {
+        key.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+/// Object schema assembled from dotted property paths
+#[derive(Default)]
+struct PropertyTree {
+    children: BTreeMap<String, PropertyNode>,
+}
+
+enum PropertyNode {
+    Leaf { schema: Value, required: bool },
+    Branch(PropertyTree),
+}
+
+impl PropertyTree {
+    fn extend(
+        &mut self,
+        properties: &[GuardedProperty],
+        required: bool,
+        optional: &dyn Fn(&str) -> bool,
+        writer: &mut SchemaWriter,
+    ) {
+        for property in properties {
+            let mut schema = writer.schema(&property.resolved_type);
+            if let Some(guard) = &property.required_when {
+                schema["description"] = json!(format!("Required when {guard}"));
+            }
+            self.insert(
+                &property.path,
+                schema,
+                required && !property.resolved_type.is_nullable() && !optional(&property.path),
+            );
+        }
+    }
+
+    fn insert(&mut self, path: &Arc<str>, schema: Value, required: bool) {
+        let mut segments = path.split('.').peekable();
+        let mut current = self;
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                current
+                    .children
+                    .insert(segment.to_string(), PropertyNode::Leaf { schema, required });
+                return;
+            }
+            let node = current
+                .children
+                .entry(segment.to_string())
+                .or_insert_with(|| PropertyNode::Branch(PropertyTree::default()));
+            if matches!(node, PropertyNode::Leaf { .. }) {
+                *node = PropertyNode::Branch(PropertyTree::default());
+            }
+            let PropertyNode::Branch(tree) = node else {
+                unreachable!("just ensured branch");
+            };
+            current = tree;
+        }
+    }
+
+    fn is_required(&self) -> bool {
+        self.children.values().any(|node| match node {
+            PropertyNode::Leaf { required, .. } => *required,
+            PropertyNode::Branch(tree) => tree.is_required(),
+        })
+    }
+
+    fn into_schema(self) -> Value {
+        let mut required: Vec<String> = Vec::new();
+        let mut properties = Map::new();
+        for (key, node) in self.children {
+            let (schema, is_required) = match node {
+                PropertyNode::Leaf { schema, required } => (schema, required),
+                PropertyNode::Branch(tree) => {
+                    let is_required = tree.is_required();
+                    (tree.into_schema(), is_required)
+                }
+            };
+            if is_required {
+                required.push(key.clone());
+            }
+            properties.insert(key, schema);
+        }
+
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = json!(required);
+        }
+        schema
+    }
+}
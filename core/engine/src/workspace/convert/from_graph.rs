@@ -0,0 +1,502 @@
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use std::sync::Arc;
+
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::model::{
+    DecisionNode, DecisionNodeKind, DecisionTableContent, DecisionTableHitPolicy,
+    ExpressionNodeContent, GraphContent, SwitchNodeContent, SwitchStatementHitPolicy,
+    TransformAttributes, TransformExecutionMode,
+};
+use crate::policy::blocks::{DecisionTableDoc, ExpressionDoc, MatchArmDoc, MatchDoc};
+use crate::policy::raw::{
+    BlockDoc, DataModelDoc, PolicyDocument, PropertyDoc, PropertyTypeDoc, ScopeDoc,
+};
+use crate::workspace::convert::SelfReference;
+use crate::workspace::db::Db;
+use crate::workspace::types::{ConversionDiagnostic, PolicyConversion};
+
+const GLOBALS_MODEL: &str = "globals";
+
+impl Db {
+    /// Policy document with the graph's expression, decision table and switch nodes as blocks.
+    ///
+    /// Node outputs land in the policy's shared property store, so keys keep their graph paths
+    /// (prefixed with the node's output path). A switch whose branches each lead to one
+    /// expression node becomes one match block per written key; other switches are reported and
+    /// their branches converted unconditionally. Data models are inferred from input schemas.
+    pub fn graph_to_policy(&self, graph: &GraphContent) -> PolicyConversion {
+        let mut importer = GraphImporter {
+            graph,
+            blocks: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        importer.run();
+        PolicyConversion {
+            document: PolicyDocument {
+                imports: graph.imports.clone(),
+                blocks: importer.blocks,
+            },
+            diagnostics: importer.diagnostics,
+        }
+    }
+}
+
+struct GraphImporter<'a> {
+    graph: &'a GraphContent,
+    blocks: Vec<BlockDoc>,
+    diagnostics: Vec<ConversionDiagnostic>,
+}
+
+impl<'a> GraphImporter<'a> {
+    fn run(&mut self) {
+        for node in &self.graph.nodes {
+            if let DecisionNodeKind::InputNode { content } = &node.kind {
+                match &content.schema {
+                    Some(schema) => {
+                        let mut models = ModelInference {
+                            node_id: &node.id,
+                            models: Vec::new(),
+                            names: HashSet::new(),
+                            diagnostics: &mut self.diagnostics,
+                        };
+                        models.infer(schema);
+                        self.blocks.extend(models.models);
+                    }
+                    None => self.report(
+                        node,
+                        "input node has no schema, no data models were inferred",
+                    ),
+                }
+            }
+        }
+
+        let mut folded: HashMap<&str, Vec<BlockDoc>> = HashMap::new();
+        let mut absorbed: HashSet<&str> = HashSet::new();
+        for node in &self.graph.nodes {
+            let DecisionNodeKind::SwitchNode { content } = &node.kind else {
+                continue;
+            };
+            match self.fold_switch(node, content) {
+                Ok((blocks, targets)) => {
+                    folded.insert(node.id.as_ref(), blocks);
+                    absorbed.extend(targets);
+                }
+                Err(reason) => self.report(
+                    node,
+                    format!("{reason}; its branches were converted unconditionally"),
+                ),
+            }
+        }
+
+        for node in &self.graph.nodes {
+            if absorbed.contains(node.id.as_ref()) {
+                continue;
+            }
+            match &node.kind {
+                DecisionNodeKind::InputNode { .. } | DecisionNodeKind::OutputNode { .. } => {}
+                DecisionNodeKind::ExpressionNode { content } => self.expression(node, content),
+                DecisionNodeKind::DecisionTableNode { content } => {
+                    self.decision_table(node, content)
+                }
+                DecisionNodeKind::SwitchNode { .. } => {
+                    if let Some(blocks) = folded.remove(node.id.as_ref()) {
+                        self.blocks.extend(blocks);
+                    }
+                }
+                kind => self.report(
+                    node,
+                    format!(
+                        "{} has no policy equivalent and was skipped",
+                        kind.type_name()
+                    ),
+                ),
+            }
+        }
+    }
+
+    fn expression(&mut self, node: &DecisionNode, content: &ExpressionNodeContent) {
+        let Some(prefix) = self.output_prefix(node, &content.transform_attributes) else {
+            return;
+        };
+        for expression in content.expressions.iter() {
+            match SelfReference::rebase(&expression.value, &prefix) {
+                Ok(value) => self.blocks.push(BlockDoc::Expression {
+                    id: expression.id.clone(),
+                    data: ExpressionDoc {
+                        key: Arc::from(format!("{prefix}{}", expression.key)),
+                        value: Arc::from(value),
+                    },
+                }),
+                Err(message) => self.report(
+                    node,
+                    format!("expression '{}' skipped: {message}", expression.key),
+                ),
+            }
+        }
+    }
+
+    fn decision_table(&mut self, node: &DecisionNode, content: &DecisionTableContent) {
+        let Some(prefix) = self.output_prefix(node, &content.transform_attributes) else {
+            return;
+        };
+        if content.hit_policy == DecisionTableHitPolicy::Collect {
+            self.report(
+                node,
+                "collect tables write one array per output field in policies instead of an array of rows",
+            );
+        }
+        let mut data = DecisionTableDoc::from(content);
+        for output in &mut data.outputs {
+            output.field = Arc::from(format!("{prefix}{}", output.field));
+        }
+        self.blocks.push(BlockDoc::DecisionTable {
+            id: node.id.clone(),
+            data,
+        });
+    }
+
+    /// Match blocks when every branch leads to a single expression node writing the same keys
+    fn fold_switch(
+        &self,
+        node: &DecisionNode,
+        content: &SwitchNodeContent,
+    ) -> Result<(Vec<BlockDoc>, Vec<&'a str>), String> {
+        if content.hit_policy == SwitchStatementHitPolicy::Collect {
+            return Err("switch with collect hit policy may take several branches".to_string());
+        }
+
+        let mut branches: Vec<(&Arc<str>, &Arc<str>, &ExpressionNodeContent)> = Vec::new();
+        for statement in content.statements.iter() {
+            let targets: Vec<&Arc<str>> = self
+                .graph
+                .edges
+                .iter()
+                .filter(|edge| {
+                    edge.source_id == node.id && edge.source_handle.as_ref() == Some(&statement.id)
+                })
+                .map(|edge| &edge.target_id)
+                .collect();
+            let [target] = targets.as_slice() else {
+                return Err(format!(
+                    "switch branch '{}' does not lead to exactly one node",
+                    statement.id
+                ));
+            };
+            let incoming = self
+                .graph
+                .edges
+                .iter()
+                .filter(|edge| edge.target_id == **target)
+                .count();
+            let expression = self
+                .graph
+                .nodes
+                .iter()
+                .find(|n| n.id == **target)
+                .and_then(|n| match &n.kind {
+                    DecisionNodeKind::ExpressionNode { content }
+                        if incoming == 1
+                            && content.transform_attributes == TransformAttributes::default() =>
+                    {
+                        Some(content)
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "switch branch '{}' does not lead to a plain expression node",
+                        statement.id
+                    )
+                })?;
+            branches.push((&statement.id, &statement.condition, expression));
+        }
+
+        let Some((_, _, first)) = branches.first() else {
+            return Err("switch has no statements".to_string());
+        };
+        let keys: Vec<&Arc<str>> = first.expressions.iter().map(|e| &e.key).collect();
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        for (_, _, expression) in &branches {
+            let mut branch_keys: Vec<&Arc<str>> =
+                expression.expressions.iter().map(|e| &e.key).collect();
+            branch_keys.sort();
+            if branch_keys != sorted_keys {
+                return Err("switch branches write different keys".to_string());
+            }
+        }
+
+        let mut blocks = Vec::with_capacity(keys.len());
+        for key in keys {
+            let mut arms = Vec::with_capacity(branches.len());
+            for (statement_id, condition, expression) in &branches {
+                let value = expression
+                    .expressions
+                    .iter()
+                    .find(|e| e.key == *key)
+                    .map(|e| e.value.clone())
+                    .unwrap_or_default();
+                let value = SelfReference::rebase(&value, "").map_err(|message| {
+                    format!("switch branch '{statement_id}' cannot be folded: {message}")
+                })?;
+                arms.push(MatchArmDoc {
+                    id: (*statement_id).clone(),
+                    condition: (*condition).clone(),
+                    value: Arc::from(value),
+                });
+            }
+            blocks.push(BlockDoc::Match {
+                id: Arc::from(format!("{}:{key}", node.id)),
+                data: MatchDoc {
+                    key: key.clone(),
+                    arms,
+                },
+            });
+        }
+
+        let targets = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.source_id == node.id)
+            .map(|edge| edge.target_id.as_ref())
+            .collect();
+        Ok((blocks, targets))
+    }
+
+    /// Prefix for written keys, `None` when the node's transform has no policy equivalent
+    fn output_prefix(
+        &mut self,
+        node: &DecisionNode,
+        attributes: &TransformAttributes,
+    ) -> Option<String> {
+        if attributes.execution_mode == TransformExecutionMode::Loop {
+            self.report(
+                node,
+                "loop execution mode has no policy equivalent, node skipped",
+            );
+            return None;
+        }
+        if attributes.input_field.is_some() {
+            self.report(
+                node,
+                "input field scoping has no policy equivalent, node skipped",
+            );
+            return None;
+        }
+        Some(
+            attributes
+                .output_path
+                .as_ref()
+                .map(|path| format!("{path}."))
+                .unwrap_or_default(),
+        )
+    }
+
+    fn report(&mut self, node: &DecisionNode, message: impl Into<String>) {
+        self.diagnostics
+            .push(ConversionDiagnostic::new(Some(&node.id), message));
+    }
+}
+
+/// Data models from a JSON Schema: object properties of the root become entities, everything
+/// else a property of the global model
+struct ModelInference<'d> {
+    node_id: &'d str,
+    models: Vec<BlockDoc>,
+    names: HashSet<String>,
+    diagnostics: &'d mut Vec<ConversionDiagnostic>,
+}
+
+impl ModelInference<'_> {
+    fn infer(&mut self, schema: &Value) {
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            self.report("input schema has no root properties, no data models were inferred");
+            return;
+        };
+        let required = Self::required(schema);
+
+        let mut globals = Vec::new();
+        for (name, property) in properties {
+            let (object, _) = Self::non_null(property);
+            if object.get("properties").is_some() {
+                self.entity(name, None, &object);
+                continue;
+            }
+            let optional = !required.contains(&name.as_str());
+            if let Some(doc) = self.property(GLOBALS_MODEL, name, property, optional) {
+                globals.push(doc);
+            }
+        }
+
+        if !globals.is_empty() {
+            self.names.insert(GLOBALS_MODEL.to_string());
+            self.models.push(BlockDoc::DataModel {
+                id: Arc::from(format!("model-{GLOBALS_MODEL}")),
+                data: DataModelDoc {
+                    name: Arc::from(GLOBALS_MODEL),
+                    scope: ScopeDoc::Global,
+                    properties: globals,
+                },
+            });
+        }
+    }
+
+    fn entity(&mut self, preferred: &str, parent: Option<&str>, schema: &Value) -> Arc<str> {
+        let name = match parent {
+            Some(parent) if self.names.contains(preferred) => {
+                let mut chars = preferred.chars();
+                let capitalised: String = chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default();
+                format!("{parent}{capitalised}")
+            }
+            _ => preferred.to_string(),
+        };
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while !self.names.insert(unique.clone()) {
+            unique = format!("{name}{suffix}");
+            suffix += 1;
+        }
+
+        let required = Self::required(schema);
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let properties: Vec<PropertyDoc> = properties
+            .iter()
+            .filter_map(|(key, property)| {
+                let optional = !required.contains(&key.as_str());
+                self.property(&unique, key, property, optional)
+            })
+            .collect();
+
+        let name: Arc<str> = Arc::from(unique);
+        self.models.push(BlockDoc::DataModel {
+            id: Arc::from(format!("model-{name}")),
+            data: DataModelDoc {
+                name: name.clone(),
+                scope: ScopeDoc::Entity,
+                properties,
+            },
+        });
+        name
+    }
+
+    fn property(
+        &mut self,
+        owner: &str,
+        name: &str,
+        schema: &Value,
+        optional: bool,
+    ) -> Option<PropertyDoc> {
+        let (schema, nullable) = Self::non_null(schema);
+        let (schema, array) = match schema.get("type").and_then(Value::as_str) {
+            Some("array") => {
+                let items = schema.get("items").cloned().unwrap_or_default();
+                let (items, _) = Self::non_null(&items);
+                if items.get("type").and_then(Value::as_str) == Some("array") {
+                    self.report(format!(
+                        "nested arrays have no data model equivalent, '{owner}.{name}' skipped"
+                    ));
+                    return None;
+                }
+                (Cow::Owned(items.into_owned()), true)
+            }
+            _ => (schema, false),
+        };
+
+        let property_type =
+            if let Some(dictionary) = schema.get("$dictionary").and_then(Value::as_str) {
+                PropertyTypeDoc::Relationship {
+                    target: Arc::from(dictionary),
+                }
+            } else if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+                PropertyTypeDoc::String {
+                    values: Some(
+                        values
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(Arc::from)
+                            .collect(),
+                    ),
+                }
+            } else {
+                match schema.get("type").and_then(Value::as_str) {
+                    Some("string") => match schema.get("format").and_then(Value::as_str) {
+                        Some("date" | "date-time") => PropertyTypeDoc::Date,
+                        _ => PropertyTypeDoc::String { values: None },
+                    },
+                    Some("number" | "integer") => PropertyTypeDoc::Number,
+                    Some("boolean") => PropertyTypeDoc::Boolean,
+                    Some("object") if schema.get("properties").is_some() => {
+                        PropertyTypeDoc::Relationship {
+                            target: self.entity(name, Some(owner), &schema),
+                        }
+                    }
+                    _ => {
+                        self.report(format!(
+                            "'{owner}.{name}' has no single scalar or object type and was skipped"
+                        ));
+                        return None;
+                    }
+                }
+            };
+
+        Some(PropertyDoc {
+            id: Arc::from(format!("{owner}.{name}")),
+            name: Arc::from(name),
+            property_type,
+            array,
+            optional: optional || nullable,
+        })
+    }
+
+    /// Schema without a `null` alternative, and whether it had one
+    fn non_null(schema: &Value) -> (Cow<'_, Value>, bool) {
+        if let Some(Value::Array(kinds)) = schema.get("type") {
+            let non_null: Vec<&Value> = kinds
+                .iter()
+                .filter(|kind| kind.as_str() != Some("null"))
+                .collect();
+            if let [kind] = non_null.as_slice() {
+                let mut single = schema.clone();
+                single["type"] = (*kind).clone();
+                return (Cow::Owned(single), non_null.len() < kinds.len());
+            }
+        }
+        let cases = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array);
+        if let Some(cases) = cases {
+            let non_null: Vec<&Value> = cases
+                .iter()
+                .filter(|case| case.get("type").and_then(Value::as_str) != Some("null"))
+                .collect();
+            if let [single] = non_null.as_slice() {
+                return (Cow::Borrowed(*single), non_null.len() < cases.len());
+            }
+        }
+        (Cow::Borrowed(schema), false)
+    }
+
+    fn required(schema: &Value) -> Vec<&str> {
+        schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|list| list.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    fn report(&mut self, message: impl Into<String>) {
+        self.diagnostics
+            .push(ConversionDiagnostic::new(Some(self.node_id), message));
+    }
+}
@@ -0,0 +1,53 @@
+//! Conversion between JDM graphs and policy documents.
+
+mod from_graph;
+mod to_graph;
+
+/// Rewrites expression node references to the node's own output (`$.key`) and to the graph
+/// input (`$root.key`) as plain property paths
+pub(crate) struct SelfReference;
+
+impl SelfReference {
+    pub(crate) fn rebase(source: &str, prefix: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(source.len());
+        let mut quote: Option<char> = None;
+        let mut rest = source;
+
+        while let Some(c) = rest.chars().next() {
+            let len = c.len_utf8();
+            match quote {
+                Some(q) => {
+                    if c == '\\' {
+                        let escaped = rest[len..].chars().next().map_or(0, char::len_utf8);
+                        out.push_str(&rest[..len + escaped]);
+                        rest = &rest[len + escaped..];
+                        continue;
+                    }
+                    if c == q {
+                        quote = None;
+                    }
+                }
+                None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+                None if c == '$' => {
+                    if let Some(after) = rest.strip_prefix("$root.") {
+                        rest = after;
+                        continue;
+                    }
+                    if let Some(after) = rest.strip_prefix("$.") {
+                        out.push_str(prefix);
+                        rest = after;
+                        continue;
+                    }
+                    return Err(
+                        "`$` refers to the whole node output, which has no policy equivalent"
+                            .to_string(),
+                    );
+                }
+                None => {}
+            }
+            out.push(c);
+            rest = &rest[len..];
+        }
+        Ok(out)
+    }
+}
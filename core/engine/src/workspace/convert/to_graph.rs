@@ -0,0 +1,343 @@
+use ahash::{HashSet, HashSetExt};
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use crate::model::{
+    DecisionEdge, DecisionNode, DecisionNodeKind, DecisionTableContent, DecisionTableHitPolicy,
+    Expression, ExpressionNodeContent, GraphContent, InputNodeContent, OutputNodeContent,
+    TransformAttributes,
+};
+use crate::policy::blocks::{AssertionDoc, ConditionOperatorDoc, DecisionTableDoc, MatchDoc};
+use crate::policy::raw::BlockDoc;
+use crate::workspace::db::Db;
+use crate::workspace::types::{BlockRef, ConversionDiagnostic, GraphConversion, ScopeRequest};
+
+const INPUT_NODE: &str = "input";
+const OUTPUT_NODE: &str = "output";
+
+impl Db {
+    /// JDM graph running the blocks of a policy and its imports in dependency order, `None`
+    /// for unknown policies.
+    ///
+    /// Blocks become a chain of pass-through nodes so every node sees the properties written
+    /// before it, and the input node schema is the policy's input JSON Schema.
+    pub fn policy_to_graph(&self, policy_path: &str) -> Option<GraphConversion> {
+        if self.is_graph(policy_path) {
+            return None;
+        }
+        self.raw_policy(policy_path)?;
+
+        let unit = self.unit(policy_path);
+        let mut seen: HashSet<BlockRef> = HashSet::new();
+        let mut order: Vec<BlockRef> = Vec::new();
+        for path in &unit.execution_order {
+            if let Some(writer) = unit.dep_graph.writer_for(path) {
+                if seen.insert(writer.clone()) {
+                    order.push(writer.clone());
+                }
+            }
+        }
+        let mut members: Vec<&Arc<str>> = unit.members.iter().collect();
+        members.sort();
+        for member in members {
+            let Some(document) = self.raw_policy(member) else {
+                continue;
+            };
+            for block in &document.blocks {
+                let Some(id) = block.id() else {
+                    continue;
+                };
+                let block_ref = BlockRef {
+                    policy_path: member.clone(),
+                    block_id: Arc::from(id),
+                };
+                if seen.insert(block_ref.clone()) {
+                    order.push(block_ref);
+                }
+            }
+        }
+
+        let schema = self
+            .json_schema(&ScopeRequest::for_policy(policy_path))
+            .map(|contract| GraphExporter::inline_definitions(contract.input));
+        let mut exporter = GraphExporter {
+            nodes: Vec::new(),
+            node_ids: HashSet::new(),
+            diagnostics: Vec::new(),
+        };
+        exporter.push_node(
+            INPUT_NODE,
+            "Request",
+            DecisionNodeKind::InputNode {
+                content: InputNodeContent {
+                    schema: schema.map(Arc::new),
+                    ..Default::default()
+                },
+            },
+        );
+        for block_ref in &order {
+            if let Some(block) = self.block_doc(block_ref) {
+                exporter.block(block_ref, &block);
+            }
+        }
+        exporter.push_node(
+            OUTPUT_NODE,
+            "Response",
+            DecisionNodeKind::OutputNode {
+                content: OutputNodeContent::default(),
+            },
+        );
+
+        let edges = exporter
+            .nodes
+            .windows(2)
+            .map(|pair| {
+                Arc::new(DecisionEdge {
+                    id: Arc::from(format!("{}-{}", pair[0].id, pair[1].id)),
+                    source_id: pair[0].id.clone(),
+                    target_id: pair[1].id.clone(),
+                    source_handle: None,
+                })
+            })
+            .collect();
+
+        let imports = self
+            .raw_policy(policy_path)
+            .map(|document| document.imports.clone())
+            .unwrap_or_default();
+        Some(GraphConversion {
+            graph: GraphContent {
+                nodes: exporter.nodes,
+                edges,
+                imports,
+                ..Default::default()
+            },
+            diagnostics: exporter.diagnostics,
+        })
+    }
+}
+
+struct GraphExporter {
+    nodes: Vec<Arc<DecisionNode>>,
+    node_ids: HashSet<Arc<str>>,
+    diagnostics: Vec<ConversionDiagnostic>,
+}
+
+impl GraphExporter {
+    fn block(&mut self, block_ref: &BlockRef, block: &BlockDoc) {
+        match block {
+            BlockDoc::Expression { id, data } => {
+                self.expression(block_ref, id, data.key.clone(), data.value.clone())
+            }
+            BlockDoc::Match { id, data } => {
+                let value = Self::match_expression(data);
+                self.expression(block_ref, id, data.key.clone(), value);
+            }
+            BlockDoc::Assertion { id, data } => {
+                if data.output.is_empty() {
+                    return;
+                }
+                let value = Self::assertion_expression(data);
+                self.expression(block_ref, id, data.output.clone(), value);
+            }
+            BlockDoc::DecisionTable { id, data } => self.decision_table(block_ref, id, data),
+            BlockDoc::Dictionary { id, .. } => self.report(
+                id,
+                "dictionaries have no graph counterpart, their values are inlined into the input schema",
+            ),
+            BlockDoc::DataModel { .. } | BlockDoc::Ignored(_) => {}
+        }
+    }
+
+    fn expression(&mut self, block_ref: &BlockRef, id: &Arc<str>, key: Arc<str>, value: Arc<str>) {
+        let node_id = self.node_id(block_ref);
+        let name = key.clone();
+        self.push_node(
+            &node_id,
+            &name,
+            DecisionNodeKind::ExpressionNode {
+                content: ExpressionNodeContent {
+                    expressions: Arc::new(vec![Expression {
+                        id: id.clone(),
+                        key,
+                        value,
+                    }]),
+                    transform_attributes: Self::pass_through(),
+                },
+            },
+        );
+    }
+
+    fn decision_table(&mut self, block_ref: &BlockRef, id: &Arc<str>, data: &DecisionTableDoc) {
+        if data.hit_policy == DecisionTableHitPolicy::Collect
+            || data
+                .outputs
+                .iter()
+                .any(|output| output.field.ends_with("[]"))
+        {
+            self.report(
+                id,
+                "collected outputs become an array of rows in graphs instead of one array per output field",
+            );
+        }
+        let mut content: DecisionTableContent = data.clone().into();
+        content.transform_attributes = Self::pass_through();
+        let name = data
+            .outputs
+            .iter()
+            .map(|output| output.field.as_ref())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let node_id = self.node_id(block_ref);
+        self.push_node(
+            &node_id,
+            &name,
+            DecisionNodeKind::DecisionTableNode { content },
+        );
+    }
+
+    /// First arm whose condition holds, `null` when none does, as nested ternaries
+    fn match_expression(data: &MatchDoc) -> Arc<str> {
+        let mut expression = "null".to_string();
+        for arm in data.arms.iter().rev() {
+            let value = match arm.value.trim() {
+                "" => "null",
+                value => value,
+            };
+            expression = match arm.condition.trim() {
+                "" => format!("({value})"),
+                condition => format!("({condition}) ? ({value}) : {expression}"),
+            };
+        }
+        Arc::from(expression)
+    }
+
+    /// Conditions folded left to right, deeper conditions grouped, the way assertions evaluate
+    fn assertion_expression(data: &AssertionDoc) -> Arc<str> {
+        struct Frame {
+            acc: Option<String>,
+            combine: ConditionOperatorDoc,
+        }
+
+        fn apply(acc: Option<String>, operator: ConditionOperatorDoc, value: String) -> String {
+            match acc {
+                None => value,
+                Some(acc) => {
+                    let operator = match operator {
+                        ConditionOperatorDoc::And => "and",
+                        ConditionOperatorDoc::Or => "or",
+                    };
+                    format!("({acc} {operator} {value})")
+                }
+            }
+        }
+
+        if data.conditions.is_empty() {
+            return Arc::from("false");
+        }
+
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut acc: Option<String> = None;
+        let mut combine = ConditionOperatorDoc::And;
+        let mut depth = 0u32;
+        for condition in &data.conditions {
+            while condition.depth > depth {
+                stack.push(Frame {
+                    acc: acc.take(),
+                    combine,
+                });
+                combine = ConditionOperatorDoc::And;
+                depth += 1;
+            }
+            while condition.depth < depth {
+                if let Some(frame) = stack.pop() {
+                    let group = acc.take().unwrap_or_else(|| "false".to_string());
+                    acc = Some(apply(frame.acc, frame.combine, group));
+                }
+                depth -= 1;
+            }
+            let value = format!("({} == true)", condition.expression.trim());
+            acc = Some(apply(acc.take(), combine, value));
+            combine = condition.operator;
+        }
+        while let Some(frame) = stack.pop() {
+            let group = acc.take().unwrap_or_else(|| "false".to_string());
+            acc = Some(apply(frame.acc, frame.combine, group));
+        }
+        Arc::from(acc.unwrap_or_else(|| "false".to_string()))
+    }
+
+    /// Input JSON Schema without `$schema` and with `$defs` references replaced by their target
+    fn inline_definitions(mut schema: Value) -> Value {
+        let definitions = schema
+            .as_object_mut()
+            .and_then(|object| {
+                object.remove("$schema");
+                object.remove("$defs")
+            })
+            .and_then(|defs| match defs {
+                Value::Object(defs) => Some(defs),
+                _ => None,
+            })
+            .unwrap_or_default();
+        Self::replace_refs(&mut schema, &definitions);
+        schema
+    }
+
+    fn replace_refs(schema: &mut Value, definitions: &Map<String, Value>) {
+        match schema {
+            Value::Object(object) => {
+                let target = object
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|reference| reference.strip_prefix("#/$defs/"))
+                    .and_then(|name| definitions.get(name));
+                if let Some(target) = target {
+                    *schema = target.clone();
+                    return;
+                }
+                for value in object.values_mut() {
+                    Self::replace_refs(value, definitions);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    Self::replace_refs(item, definitions);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn pass_through() -> TransformAttributes {
+        TransformAttributes {
+            pass_through: true,
+            ..Default::default()
+        }
+    }
+
+    /// Block id, qualified with the policy path when an imported policy reuses it
+    fn node_id(&self, block_ref: &BlockRef) -> Arc<str> {
+        if self.node_ids.contains(&block_ref.block_id) {
+            Arc::from(format!("{}:{}", block_ref.policy_path, block_ref.block_id))
+        } else {
+            block_ref.block_id.clone()
+        }
+    }
+
+    fn push_node(&mut self, id: &str, name: &str, kind: DecisionNodeKind) {
+        let id: Arc<str> = Arc::from(id);
+        self.node_ids.insert(id.clone());
+        self.nodes.push(Arc::new(DecisionNode {
+            id,
+            name: Arc::from(name),
+            kind,
+        }));
+    }
+
+    fn report(&mut self, id: &str, message: impl Into<String>) {
+        self.diagnostics
+            .push(ConversionDiagnostic::new(Some(id), message));
+    }
+}
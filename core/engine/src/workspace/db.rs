@@ -7,7 +7,9 @@ use zen_expression::intellisense::IntelliSense;
 use zen_expression::variable::VariableType;
 use zen_expression::{Isolate, OpcodeCache};
 
+use crate::expression_context::ExpressionContext;
 use crate::model::{DecisionContent, PolicyContent};
+use crate::nodes::custom::CustomNodeRegistry;
 use crate::policy::blocks::{
     Block, BlockKind, BlockReadPlan, IntelliSenseSource, PropertyRead, ReadFlattener,
     SharedIntelliSense,
@@ -29,6 +31,7 @@ use crate::workspace::graph::function::{
     FunctionKey, FunctionResolutionRequest, FunctionTypeResolver, ResolvedFunction,
 };
 use crate::workspace::graph::GraphAnalysis;
+use crate::workspace::history::History;
 use crate::workspace::types::{BlockRef, Diagnostic, ExpressionKind, InstanceTarget};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -128,6 +131,7 @@ impl PolicyDerivedCache {
 
 struct Inputs {
     documents: HashMap<Arc<str>, Arc<DecisionContent>>,
+    libraries: HashMap<Arc<str>, Arc<str>>,
 }
 
 pub struct Snapshot {
@@ -193,6 +197,9 @@ pub struct Db {
     function_requested: RefCell<HashSet<FunctionKey>>,
     function_resolver: RefCell<Option<Box<FunctionTypeResolver>>>,
     scope_roots: RefCell<Vec<VariableType>>,
+    expression_context: ExpressionContext,
+    custom_nodes: Option<Arc<CustomNodeRegistry>>,
+    pub(crate) history: History,
 }
 
 impl Drop for Db {
@@ -208,6 +215,7 @@ impl Db {
         Self {
             inputs: RefCell::new(Inputs {
                 documents: HashMap::default(),
+                libraries: HashMap::default(),
             }),
             snapshot: RefCell::new(None),
             cache: PolicyDerivedCache::default(),
@@ -221,19 +229,56 @@ impl Db {
             function_requested: RefCell::new(HashSet::default()),
             function_resolver: RefCell::new(None),
             scope_roots: RefCell::new(Vec::new()),
+            expression_context: ExpressionContext::default(),
+            custom_nodes: None,
+            history: History::default(),
         }
     }
 
     pub fn set_document(&mut self, path: Arc<str>, doc: Arc<DecisionContent>) {
+        self.record_load(&path, Some(doc.clone()));
+        self.write_document(path, doc);
+    }
+
+    /// Stores `doc` without touching the history
+    pub(crate) fn write_document(&mut self, path: Arc<str>, doc: Arc<DecisionContent>) {
         self.inputs.borrow_mut().documents.insert(path, doc);
         self.invalidate_snapshot();
     }
 
+    pub(crate) fn expression_context(&self) -> &ExpressionContext {
+        &self.expression_context
+    }
+
+    pub(crate) fn expression_context_mut(&mut self) -> &mut ExpressionContext {
+        &mut self.expression_context
+    }
+
+    pub(crate) fn custom_nodes(&self) -> Option<&CustomNodeRegistry> {
+        self.custom_nodes.as_deref()
+    }
+
+    /// Graph analyses type custom nodes from the registered contracts
+    pub(crate) fn set_custom_nodes(&mut self, custom_nodes: Option<Arc<CustomNodeRegistry>>) {
+        self.custom_nodes = custom_nodes;
+        self.cache.graphs.borrow_mut().clear();
+        self.invalidate_snapshot();
+    }
+
     pub fn set_policy(&mut self, path: Arc<str>, doc: Arc<PolicyDocument>) {
         self.set_document(path, Arc::new(DecisionContent::Policy(PolicyContent(doc))));
     }
 
     pub fn remove_document(&mut self, path: &str) -> bool {
+        let recorded = self
+            .inputs
+            .borrow()
+            .documents
+            .get_key_value(path)
+            .map(|(k, _)| k.clone());
+        if let Some(path) = recorded {
+            self.record_load(&path, None);
+        }
         let existed = self.inputs.borrow_mut().documents.remove(path).is_some();
         if existed {
             self.invalidate_snapshot();
@@ -241,6 +286,49 @@ impl Db {
         existed
     }
 
+    /// Puts documents back to a recorded state, `None` removing the document, without
+    /// recording the change
+    pub(crate) fn restore_documents(&mut self, state: &[(Arc<str>, Option<Arc<DecisionContent>>)]) {
+        {
+            let mut inputs = self.inputs.borrow_mut();
+            for (path, document) in state {
+                match document {
+                    Some(document) => inputs.documents.insert(path.clone(), document.clone()),
+                    None => inputs.documents.remove(path),
+                };
+            }
+        }
+        self.invalidate_snapshot();
+    }
+
+    pub fn set_library(&mut self, key: Arc<str>, source: Arc<str>) {
+        self.inputs.borrow_mut().libraries.insert(key, source);
+        self.invalidate_libraries();
+    }
+
+    pub fn remove_library(&mut self, key: &str) -> bool {
+        let existed = self.inputs.borrow_mut().libraries.remove(key).is_some();
+        if existed {
+            self.invalidate_libraries();
+        }
+        existed
+    }
+
+    pub fn library_keys(&self) -> Vec<Arc<str>> {
+        self.inputs.borrow().libraries.keys().cloned().collect()
+    }
+
+    pub(crate) fn library(&self, key: &str) -> Option<Arc<str>> {
+        self.inputs.borrow().libraries.get(key).cloned()
+    }
+
+    /// Cached graph analyses are keyed by function sources, which do not change with the
+    /// libraries they import
+    fn invalidate_libraries(&self) {
+        self.cache.graphs.borrow_mut().clear();
+        self.invalidate_snapshot();
+    }
+
     pub fn document_paths(&self) -> Vec<Arc<str>> {
         self.inputs.borrow().documents.keys().cloned().collect()
     }
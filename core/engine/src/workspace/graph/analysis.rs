@@ -5,7 +5,7 @@ use std::sync::Arc;
 use ahash::{HashMap, HashMapExt, HashSet};
 use zen_expression::variable::VariableType;
 use zen_types::decision::{
-    DecisionNode, DecisionNodeContent, DecisionNodeKind, DecisionTableContent,
+    CustomNodeContent, DecisionNode, DecisionNodeContent, DecisionNodeKind, DecisionTableContent,
     DecisionTableHitPolicy, DecisionTableOutputField, ExpressionNodeContent, FunctionNodeContent,
     SwitchNodeContent, SwitchStatementHitPolicy, TransformAttributes, TransformExecutionMode,
 };
@@ -514,20 +514,27 @@ impl<'a> GraphAnalyzer<'a> {
                 analysis.output = scope_input;
             }
             DecisionNodeKind::CustomNode { content } => {
-                self.diagnostics.push(Diagnostic::warning(
-                    DiagnosticCode::UncheckedNode,
-                    DiagnosticLocation::block(self.path.clone(), node.id.clone()),
-                    format!(
-                        "unknown node kind '{}' — this node is not type-checked and downstream nodes are unchecked",
-                        content.kind
-                    ),
-                ));
-                analysis.opaque = true;
-                analysis.open = true;
+                self.check_custom_node(node, content, &mut analysis);
             }
             DecisionNodeKind::FunctionNode { content } => {
                 self.check_function(node, content, &scope_input, &mut analysis);
             }
+            DecisionNodeKind::WasmNode { content } => match content.output_schema.as_ref() {
+                Some(schema) => {
+                    self.check_schema_dictionaries(node, schema);
+                    analysis.output =
+                        super::SchemaType::variable_type_with(schema, &self.dictionary_types);
+                }
+                None => {
+                    self.diagnostics.push(Diagnostic::warning(
+                        DiagnosticCode::UncheckedNode,
+                        DiagnosticLocation::block(self.path.clone(), node.id.clone()),
+                        "wasm node declares no output schema — downstream nodes are unchecked; declare the schema of its output",
+                    ));
+                    analysis.opaque = true;
+                    analysis.open = true;
+                }
+            },
             DecisionNodeKind::ExpressionNode { content } => {
                 let (handler_input, output) = self.transformed(
                     node,
@@ -580,6 +587,48 @@ impl<'a> GraphAnalyzer<'a> {
         analysis
     }
 
+    fn check_custom_node(
+        &mut self,
+        node: &DecisionNode,
+        content: &CustomNodeContent,
+        analysis: &mut GraphNodeAnalysis,
+    ) {
+        let registry = self.db.custom_nodes();
+        if let Some(registry) = registry {
+            for message in registry.check(content.kind.as_ref(), content.config.as_ref()) {
+                self.diagnostics.push(Diagnostic::error(
+                    DiagnosticCode::InvalidNodeConfig,
+                    DiagnosticLocation::block(self.path.clone(), node.id.clone()),
+                    message,
+                ));
+            }
+        }
+
+        let definition = registry.and_then(|registry| registry.get(content.kind.as_ref()));
+        if let Some(output) = definition.and_then(|definition| definition.output()) {
+            analysis.output = output;
+            return;
+        }
+
+        let message = match definition {
+            Some(_) => format!(
+                "custom node kind '{}' declares no output type — downstream nodes are unchecked",
+                content.kind
+            ),
+            None => format!(
+                "unknown node kind '{}' — this node is not type-checked and downstream nodes are unchecked",
+                content.kind
+            ),
+        };
+        self.diagnostics.push(Diagnostic::warning(
+            DiagnosticCode::UncheckedNode,
+            DiagnosticLocation::block(self.path.clone(), node.id.clone()),
+            message,
+        ));
+        analysis.opaque = true;
+        analysis.open = true;
+    }
+
     fn check_function(
         &mut self,
         node: &DecisionNode,
@@ -724,7 +773,16 @@ impl<'a> GraphAnalyzer<'a> {
                 if attributes.pass_through {
                     output = Self::merge_patch_type(&element, &output);
                 }
-                (element, output.array())
+                let collect_errors = attributes
+                    .loop_options
+                    .as_ref()
+                    .is_some_and(|options| options.collect_errors);
+                let output = if collect_errors {
+                    Self::collected_loop_type(output)
+                } else {
+                    output.array()
+                };
+                (element, output)
             }
         };
 
@@ -740,6 +798,18 @@ impl<'a> GraphAnalyzer<'a> {
         (handler_scope, output)
     }
 
+    /// Loop output with collected errors, results and errors are parallel arrays where a failed
+    /// item has a null result and a succeeded item a null error.
+    fn collected_loop_type(item: VariableType) -> VariableType {
+        let error = VariableType::empty_object();
+        error.dot_insert("message", VariableType::String);
+
+        let collected = VariableType::empty_object();
+        collected.dot_insert("results", VariableType::Nullable(Rc::new(item)).array());
+        collected.dot_insert("errors", VariableType::Nullable(Rc::new(error)).array());
+        collected
+    }
+
     /// Type-level mirror of the runtime pass-through merge (`Variable::merge_clone`).
     fn merge_patch_type(base: &VariableType, patch: &VariableType) -> VariableType {
         match patch {
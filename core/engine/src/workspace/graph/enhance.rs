@@ -147,6 +147,8 @@ impl Db {
             engine_version: Arc::from(crate::ENGINE_VERSION),
             properties,
             executions: state.executions,
+            resolved: Vec::new(),
+            coercions: Vec::new(),
         })
     }
 }
@@ -3,9 +3,14 @@ use std::sync::Arc;
 
 use zen_expression::variable::VariableType;
 
+use crate::nodes::function::v2::module::library;
 use crate::workspace::db::Db;
 use crate::workspace::graph::ts_type::TsTypeParser;
 
+/// Ambient TypeScript declarations of the native `crypto` and `encoding` modules, for resolvers
+/// type-checking function sources
+pub const FUNCTION_MODULE_DECLARATIONS: &str = include_str!("../../../js/modules.d.ts");
+
 pub type FunctionTypeResolver = dyn Fn(&str, &VariableType) -> Option<String>;
 
 pub(crate) type FunctionKey = (u64, u64);
@@ -14,6 +19,14 @@ pub(crate) type FunctionKey = (u64, u64);
 pub struct FunctionResolutionRequest {
     pub source: Arc<str>,
     pub input: VariableType,
+    /// Library modules imported by the source, directly or transitively
+    pub libraries: Vec<FunctionLibrary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionLibrary {
+    pub key: Arc<str>,
+    pub source: Arc<str>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,7 +47,7 @@ impl Db {
         source: &Arc<str>,
         input: &VariableType,
     ) -> FunctionTypeOutcome {
-        let key = Self::function_key(source, input);
+        let key = self.function_key(source, input);
         let outcome = self.function_outcome(key, source, input);
         self.graph_fn_record(key, self.function_state(key));
         outcome
@@ -76,6 +89,7 @@ impl Db {
                 .push(FunctionResolutionRequest {
                     source: source.clone(),
                     input: input.shallow_clone(),
+                    libraries: self.function_libraries(source),
                 });
         }
         FunctionTypeOutcome::Unknown
@@ -92,7 +106,7 @@ impl Db {
     }
 
     pub fn set_function_type(&self, source: &str, input: &VariableType, ts_type: Option<&str>) {
-        let key = Self::function_key_str(source, input);
+        let key = self.function_key_str(source, input);
         let entry = match ts_type.and_then(TsTypeParser::variable_type) {
             Some(resolved) => ResolvedFunction::Type(resolved),
             None => ResolvedFunction::Unresolved,
@@ -114,13 +128,40 @@ impl Db {
         }
     }
 
-    pub(crate) fn function_key(source: &Arc<str>, input: &VariableType) -> FunctionKey {
-        Self::function_key_str(source.as_ref(), input)
+    /// Library modules registered in the workspace that `source` imports, sorted by key
+    pub(crate) fn function_libraries(&self, source: &str) -> Vec<FunctionLibrary> {
+        let mut libraries: Vec<FunctionLibrary> = Vec::new();
+        let mut pending = library::library_imports("node", source);
+        while let Some(key) = pending.pop() {
+            if libraries.iter().any(|lib| lib.key.as_ref() == key) {
+                continue;
+            }
+            let Some(source) = self.library(&key) else {
+                continue;
+            };
+
+            pending.extend(library::library_imports(&key, &source));
+            libraries.push(FunctionLibrary {
+                key: Arc::from(key),
+                source,
+            });
+        }
+
+        libraries.sort_by(|a, b| a.key.cmp(&b.key));
+        libraries
+    }
+
+    pub(crate) fn function_key(&self, source: &Arc<str>, input: &VariableType) -> FunctionKey {
+        self.function_key_str(source.as_ref(), input)
     }
 
-    fn function_key_str(source: &str, input: &VariableType) -> FunctionKey {
+    fn function_key_str(&self, source: &str, input: &VariableType) -> FunctionKey {
         let mut source_hasher = std::collections::hash_map::DefaultHasher::new();
         source.hash(&mut source_hasher);
+        for library in self.function_libraries(source) {
+            library.key.hash(&mut source_hasher);
+            library.source.hash(&mut source_hasher);
+        }
         let mut input_hasher = std::collections::hash_map::DefaultHasher::new();
         input.hash(&mut input_hasher);
         (source_hasher.finish(), input_hasher.finish())
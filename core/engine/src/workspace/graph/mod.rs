@@ -7,12 +7,16 @@ pub(crate) mod function;
 mod nl;
 mod queries;
 mod schema;
+mod structure;
 mod ts_type;
 
 pub use analysis::{GraphAnalysis, GraphNodeAnalysis, GraphSignature};
 pub use enhance::GraphTraceMap;
-pub use function::{FunctionResolutionRequest, FunctionTypeResolver};
+pub use function::{
+    FunctionLibrary, FunctionResolutionRequest, FunctionTypeResolver, FUNCTION_MODULE_DECLARATIONS,
+};
 pub(crate) use schema::SchemaType;
+pub use structure::GraphEditor;
 
 use std::rc::Rc;
 use std::sync::Arc;
@@ -0,0 +1,343 @@
+use std::sync::Arc;
+
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use serde_json::Value;
+use zen_types::decision::{DecisionEdge, DecisionNode, DecisionNodeKind};
+
+use crate::model::GraphContent;
+use crate::workspace::db::Db;
+use crate::workspace::types::{EngineEdit, GraphEdit, GraphEditError};
+use crate::Decision;
+
+/// Applies [`GraphEdit`]s to a graph, building one from scratch when started from
+/// [`GraphEditor::default`].
+///
+/// Edits are checked against the nodes and edges they refer to as they are applied; the graph
+/// as a whole is validated once by [`finish`](GraphEditor::finish), so intermediate states
+/// such as a graph without an input node are allowed.
+#[derive(Debug, Clone, Default)]
+pub struct GraphEditor {
+    content: GraphContent,
+}
+
+impl GraphEditor {
+    pub fn new(content: &GraphContent) -> Self {
+        Self {
+            content: GraphContent {
+                nodes: content.nodes.clone(),
+                edges: content.edges.clone(),
+                imports: content.imports.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn with_edit(mut self, edit: GraphEdit) -> Result<Self, GraphEditError> {
+        self.apply(edit)?;
+        Ok(self)
+    }
+
+    pub fn content(&self) -> &GraphContent {
+        &self.content
+    }
+
+    pub fn apply(&mut self, edit: GraphEdit) -> Result<(), GraphEditError> {
+        match edit {
+            GraphEdit::AddNode(node) => self.add_node(node),
+            GraphEdit::Connect {
+                source_id,
+                target_id,
+                source_handle,
+            } => self.connect(source_id, target_id, source_handle),
+            GraphEdit::Disconnect { edge_id } => self.disconnect(&edge_id),
+            GraphEdit::MoveSwitchBranch {
+                node_id,
+                statement_id,
+                index,
+            } => self.move_switch_branch(&node_id, &statement_id, index),
+            GraphEdit::RemoveNode { node_id, rewire } => self.remove_node(&node_id, rewire),
+        }
+    }
+
+    /// Validates the edited graph with [`DecisionGraph::validate`](crate::DecisionGraph::validate)
+    pub fn finish(self) -> Result<GraphContent, GraphEditError> {
+        let content = Arc::new(self.content);
+        Decision::from(content.clone()).validate()?;
+        Ok(Arc::unwrap_or_clone(content))
+    }
+
+    fn node(&self, node_id: &str) -> Result<&Arc<DecisionNode>, GraphEditError> {
+        self.content
+            .nodes
+            .iter()
+            .find(|n| n.id.as_ref() == node_id)
+            .ok_or_else(|| GraphEditError::NodeNotFound(node_id.into()))
+    }
+
+    fn add_node(&mut self, node: DecisionNode) -> Result<(), GraphEditError> {
+        if self.node(&node.id).is_ok() {
+            return Err(GraphEditError::DuplicateNode(node.id));
+        }
+
+        self.content.nodes.push(Arc::new(node));
+        Ok(())
+    }
+
+    fn connect(
+        &mut self,
+        source_id: Arc<str>,
+        target_id: Arc<str>,
+        source_handle: Option<Arc<str>>,
+    ) -> Result<(), GraphEditError> {
+        let source = self.node(&source_id)?;
+        match (&source.kind, &source_handle) {
+            (DecisionNodeKind::SwitchNode { .. }, None) => {
+                return Err(GraphEditError::MissingSourceHandle(source_id));
+            }
+            (DecisionNodeKind::SwitchNode { content }, Some(handle)) => {
+                if !content.statements.iter().any(|s| s.id == *handle) {
+                    return Err(GraphEditError::StatementNotFound {
+                        node_id: source_id,
+                        statement_id: handle.clone(),
+                    });
+                }
+            }
+            (_, Some(_)) => return Err(GraphEditError::UnexpectedSourceHandle(source_id)),
+            (_, None) => {}
+        }
+        self.node(&target_id)?;
+
+        self.push_edge(source_id, target_id, source_handle);
+        Ok(())
+    }
+
+    fn push_edge(
+        &mut self,
+        source_id: Arc<str>,
+        target_id: Arc<str>,
+        source_handle: Option<Arc<str>>,
+    ) {
+        let exists = self.content.edges.iter().any(|e| {
+            e.source_id == source_id && e.target_id == target_id && e.source_handle == source_handle
+        });
+        if exists {
+            return;
+        }
+
+        let base = match &source_handle {
+            Some(handle) => format!("{source_id}-{handle}-{target_id}"),
+            None => format!("{source_id}-{target_id}"),
+        };
+        let taken: HashSet<&str> = self.content.edges.iter().map(|e| e.id.as_ref()).collect();
+        let id = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{base}-{n}"),
+            })
+            .find(|id| !taken.contains(id.as_str()))
+            .unwrap_or(base);
+
+        self.content.edges.push(Arc::new(DecisionEdge {
+            id: id.into(),
+            source_id,
+            target_id,
+            source_handle,
+        }));
+    }
+
+    fn disconnect(&mut self, edge_id: &str) -> Result<(), GraphEditError> {
+        let before = self.content.edges.len();
+        self.content.edges.retain(|e| e.id.as_ref() != edge_id);
+        if self.content.edges.len() == before {
+            return Err(GraphEditError::EdgeNotFound(edge_id.into()));
+        }
+
+        Ok(())
+    }
+
+    fn move_switch_branch(
+        &mut self,
+        node_id: &str,
+        statement_id: &str,
+        index: usize,
+    ) -> Result<(), GraphEditError> {
+        let position = self
+            .content
+            .nodes
+            .iter()
+            .position(|n| n.id.as_ref() == node_id)
+            .ok_or_else(|| GraphEditError::NodeNotFound(node_id.into()))?;
+
+        let node = Arc::make_mut(&mut self.content.nodes[position]);
+        let DecisionNodeKind::SwitchNode { content } = &mut node.kind else {
+            return Err(GraphEditError::NotASwitch(node_id.into()));
+        };
+        let statements = Arc::make_mut(&mut content.statements);
+        let from = statements
+            .iter()
+            .position(|s| s.id.as_ref() == statement_id)
+            .ok_or_else(|| GraphEditError::StatementNotFound {
+                node_id: node_id.into(),
+                statement_id: statement_id.into(),
+            })?;
+
+        let statement = statements.remove(from);
+        statements.insert(index.min(statements.len()), statement);
+        Ok(())
+    }
+
+    fn remove_node(&mut self, node_id: &str, rewire: bool) -> Result<(), GraphEditError> {
+        self.node(node_id)?;
+
+        let (touching, edges): (Vec<_>, Vec<_>) = std::mem::take(&mut self.content.edges)
+            .into_iter()
+            .partition(|e| e.source_id.as_ref() == node_id || e.target_id.as_ref() == node_id);
+        self.content.edges = edges;
+        self.content.nodes.retain(|n| n.id.as_ref() != node_id);
+        if !rewire {
+            return Ok(());
+        }
+
+        let incoming = touching.iter().filter(|e| e.target_id.as_ref() == node_id);
+        let successors: Vec<&Arc<str>> = touching
+            .iter()
+            .filter(|e| e.source_id.as_ref() == node_id)
+            .map(|e| &e.target_id)
+            .collect();
+        for edge in incoming {
+            for &successor in &successors {
+                if edge.source_id != *successor {
+                    self.push_edge(
+                        edge.source_id.clone(),
+                        successor.clone(),
+                        edge.source_handle.clone(),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Db {
+    /// Edits turning graph `document` into the result of applying `edits` to it.
+    ///
+    /// Removals come first, then replacements and insertions, so hosts applying them in order
+    /// never see an edge pointing at a missing node.
+    pub(crate) fn edit_graph(
+        &self,
+        document: &Arc<str>,
+        edits: Vec<GraphEdit>,
+    ) -> Result<Vec<EngineEdit>, GraphEditError> {
+        let snap = self.snapshot();
+        let Some(original) = snap.graphs.get(document).and_then(|d| d.as_graph()) else {
+            return Err(GraphEditError::GraphNotFound(document.clone()));
+        };
+
+        let mut editor = GraphEditor::new(original);
+        for edit in edits {
+            editor.apply(edit)?;
+        }
+        let edited = editor.finish()?;
+
+        let nodes = Diff::new(&original.nodes, &edited.nodes, |n| n.id.clone());
+        let edges = Diff::new(&original.edges, &edited.edges, |e| e.id.clone());
+        let mut result = Vec::new();
+        result.extend(
+            edges
+                .removed
+                .into_iter()
+                .map(|edge_id| EngineEdit::DeleteEdge {
+                    document: document.clone(),
+                    edge_id,
+                }),
+        );
+        result.extend(
+            nodes
+                .removed
+                .into_iter()
+                .map(|node_id| EngineEdit::DeleteNode {
+                    document: document.clone(),
+                    node_id,
+                }),
+        );
+        result.extend(nodes.replaced.into_iter().map(|(node_id, new_node)| {
+            EngineEdit::ReplaceNode {
+                document: document.clone(),
+                node_id,
+                new_node,
+            }
+        }));
+        result.extend(
+            nodes
+                .inserted
+                .into_iter()
+                .map(|new_node| EngineEdit::InsertNode {
+                    document: document.clone(),
+                    new_node,
+                }),
+        );
+        result.extend(edges.replaced.into_iter().map(|(edge_id, new_edge)| {
+            EngineEdit::ReplaceEdge {
+                document: document.clone(),
+                edge_id,
+                new_edge,
+            }
+        }));
+        result.extend(
+            edges
+                .inserted
+                .into_iter()
+                .map(|new_edge| EngineEdit::InsertEdge {
+                    document: document.clone(),
+                    new_edge,
+                }),
+        );
+
+        Ok(result)
+    }
+}
+
+struct Diff {
+    removed: Vec<Arc<str>>,
+    replaced: Vec<(Arc<str>, Value)>,
+    inserted: Vec<Value>,
+}
+
+impl Diff {
+    fn new<T: serde::Serialize>(
+        before: &[Arc<T>],
+        after: &[Arc<T>],
+        id: impl Fn(&T) -> Arc<str>,
+    ) -> Self {
+        let mut previous: HashMap<Arc<str>, Value> = HashMap::with_capacity(before.len());
+        for item in before {
+            previous.insert(id(item), serde_json::to_value(item).unwrap_or_default());
+        }
+
+        let mut diff = Diff {
+            removed: Vec::new(),
+            replaced: Vec::new(),
+            inserted: Vec::new(),
+        };
+        let mut kept: HashSet<Arc<str>> = HashSet::with_capacity(after.len());
+        for item in after {
+            let item_id = id(item);
+            let value = serde_json::to_value(item).unwrap_or_default();
+            match previous.get(&item_id) {
+                Some(old) if *old == value => {}
+                Some(_) => diff.replaced.push((item_id.clone(), value)),
+                None => diff.inserted.push(value),
+            }
+            kept.insert(item_id);
+        }
+        diff.removed = before
+            .iter()
+            .map(|item| id(item))
+            .filter(|item_id| !kept.contains(item_id))
+            .collect();
+
+        diff
+    }
+}
@@ -0,0 +1,381 @@
+use std::sync::Arc;
+
+use zen_types::decision::{DecisionEdge, DecisionNode};
+
+use crate::model::{DecisionContent, GraphContent, PolicyContent};
+use crate::policy::raw::{BlockDoc, PolicyDocument};
+use crate::workspace::db::Db;
+use crate::workspace::types::{Diagnostic, DiagnosticsDelta, EngineEdit, WorkspaceEditError};
+
+const MAX_UNDO_STEPS: usize = 100;
+
+type DocumentState = Vec<(Arc<str>, Option<Arc<DecisionContent>>)>;
+
+/// Committed transactions and the one currently open
+#[derive(Default)]
+pub(crate) struct History {
+    open: Option<OpenTransaction>,
+    undo: Vec<ChangeSet>,
+    redo: Vec<ChangeSet>,
+}
+
+impl History {
+    fn push_undo(&mut self, change: ChangeSet) {
+        if self.undo.len() == MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(change);
+        self.redo.clear();
+    }
+
+    /// Drops the steps that would put `path` back to a state it was loaded over
+    fn forget(&mut self, path: &Arc<str>) {
+        self.undo.retain(|change| !change.touches(path));
+        self.redo.retain(|change| !change.touches(path));
+    }
+}
+
+struct OpenTransaction {
+    before: DocumentState,
+    baseline: Vec<Diagnostic>,
+}
+
+struct ChangeSet {
+    before: DocumentState,
+    after: DocumentState,
+}
+
+impl ChangeSet {
+    fn touches(&self, path: &Arc<str>) -> bool {
+        self.before.iter().any(|(p, _)| p == path)
+    }
+}
+
+impl Db {
+    /// Records `path` being set or removed by the host. Inside a transaction this is recorded
+    /// like an edit; outside of one the document is loaded rather than edited, so no undo step
+    /// is added and the steps touching `path` are dropped.
+    pub(crate) fn record_load(&mut self, path: &Arc<str>, next: Option<Arc<DecisionContent>>) {
+        match self.history.open {
+            Some(_) => self.record_change(path, next),
+            None => self.history.forget(path),
+        }
+    }
+
+    /// Records an edit of `path` to `next`. Inside a transaction only the state before its first
+    /// change is kept; outside of one the edit becomes its own undo step.
+    pub(crate) fn record_change(&mut self, path: &Arc<str>, next: Option<Arc<DecisionContent>>) {
+        let previous = match &self.history.open {
+            Some(transaction) if transaction.before.iter().any(|(p, _)| p == path) => return,
+            _ => self.raw_document(path),
+        };
+
+        match &mut self.history.open {
+            Some(transaction) => transaction.before.push((path.clone(), previous)),
+            None => self.history.push_undo(ChangeSet {
+                before: vec![(path.clone(), previous)],
+                after: vec![(path.clone(), next)],
+            }),
+        }
+    }
+
+    pub(crate) fn begin_transaction(&mut self) -> Result<(), WorkspaceEditError> {
+        if self.history.open.is_some() {
+            return Err(WorkspaceEditError::TransactionOpen);
+        }
+
+        let baseline = self.all_diagnostics();
+        self.history.open = Some(OpenTransaction {
+            before: Vec::new(),
+            baseline,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn transaction_delta(&self) -> Option<DiagnosticsDelta> {
+        let transaction = self.history.open.as_ref()?;
+        Some(DiagnosticsDelta::between(
+            &transaction.baseline,
+            &self.all_diagnostics(),
+        ))
+    }
+
+    pub(crate) fn commit_transaction(&mut self) -> Result<DiagnosticsDelta, WorkspaceEditError> {
+        let transaction = self
+            .history
+            .open
+            .take()
+            .ok_or(WorkspaceEditError::NoTransaction)?;
+        let delta = DiagnosticsDelta::between(&transaction.baseline, &self.all_diagnostics());
+        if transaction.before.is_empty() {
+            return Ok(delta);
+        }
+
+        let after = transaction
+            .before
+            .iter()
+            .map(|(path, _)| (path.clone(), self.raw_document(path)))
+            .collect();
+        self.history.push_undo(ChangeSet {
+            before: transaction.before,
+            after,
+        });
+        Ok(delta)
+    }
+
+    pub(crate) fn rollback_transaction(&mut self) -> Result<(), WorkspaceEditError> {
+        let transaction = self
+            .history
+            .open
+            .take()
+            .ok_or(WorkspaceEditError::NoTransaction)?;
+        self.restore_documents(&transaction.before);
+        Ok(())
+    }
+
+    pub(crate) fn undo(&mut self) -> bool {
+        if self.history.open.is_some() {
+            return false;
+        }
+        let Some(change) = self.history.undo.pop() else {
+            return false;
+        };
+
+        self.restore_documents(&change.before);
+        self.history.redo.push(change);
+        true
+    }
+
+    pub(crate) fn redo(&mut self) -> bool {
+        if self.history.open.is_some() {
+            return false;
+        }
+        let Some(change) = self.history.redo.pop() else {
+            return false;
+        };
+
+        self.restore_documents(&change.after);
+        self.history.undo.push(change);
+        true
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        self.history.open.is_none() && !self.history.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        self.history.open.is_none() && !self.history.redo.is_empty()
+    }
+
+    pub(crate) fn apply_edit(&mut self, edit: EngineEdit) -> Result<(), WorkspaceEditError> {
+        match edit {
+            EngineEdit::ReplaceBlock {
+                policy_path,
+                block_id,
+                new_block,
+            } => {
+                let block: BlockDoc = serde_json::from_value(new_block)?;
+                self.edit_policy(policy_path, |path, doc| {
+                    let position = block_position(path, doc, &block_id)?;
+                    doc.blocks[position] = block;
+                    Ok(())
+                })
+            }
+            EngineEdit::DeleteBlock {
+                policy_path,
+                block_id,
+            } => self.edit_policy(policy_path, |path, doc| {
+                let position = block_position(path, doc, &block_id)?;
+                doc.blocks.remove(position);
+                Ok(())
+            }),
+            EngineEdit::InsertBlock {
+                policy_path,
+                after_block_id,
+                new_block,
+            } => {
+                let block: BlockDoc = serde_json::from_value(new_block)?;
+                self.edit_policy(policy_path, |path, doc| {
+                    let position = match &after_block_id {
+                        Some(after) => block_position(path, doc, after)? + 1,
+                        None => doc.blocks.len(),
+                    };
+                    doc.blocks.insert(position, block);
+                    Ok(())
+                })
+            }
+            EngineEdit::ReplaceNode {
+                document,
+                node_id,
+                new_node,
+            } => {
+                let node: DecisionNode = serde_json::from_value(new_node)?;
+                self.edit_graph_document(document, |path, graph| {
+                    let position = node_position(path, graph, &node_id)?;
+                    graph.nodes[position] = Arc::new(node);
+                    Ok(())
+                })
+            }
+            EngineEdit::DeleteNode { document, node_id } => {
+                self.edit_graph_document(document, |path, graph| {
+                    let position = node_position(path, graph, &node_id)?;
+                    graph.nodes.remove(position);
+                    Ok(())
+                })
+            }
+            EngineEdit::InsertNode { document, new_node } => {
+                let node: DecisionNode = serde_json::from_value(new_node)?;
+                self.edit_graph_document(document, |_, graph| {
+                    graph.nodes.push(Arc::new(node));
+                    Ok(())
+                })
+            }
+            EngineEdit::ReplaceEdge {
+                document,
+                edge_id,
+                new_edge,
+            } => {
+                let edge: DecisionEdge = serde_json::from_value(new_edge)?;
+                self.edit_graph_document(document, |path, graph| {
+                    let position = edge_position(path, graph, &edge_id)?;
+                    graph.edges[position] = Arc::new(edge);
+                    Ok(())
+                })
+            }
+            EngineEdit::DeleteEdge { document, edge_id } => {
+                self.edit_graph_document(document, |path, graph| {
+                    let position = edge_position(path, graph, &edge_id)?;
+                    graph.edges.remove(position);
+                    Ok(())
+                })
+            }
+            EngineEdit::InsertEdge { document, new_edge } => {
+                let edge: DecisionEdge = serde_json::from_value(new_edge)?;
+                self.edit_graph_document(document, |_, graph| {
+                    graph.edges.push(Arc::new(edge));
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Runs `edit` on a copy of policy `path`, storing the copy only when `edit` succeeds
+    fn edit_policy(
+        &mut self,
+        path: Arc<str>,
+        edit: impl FnOnce(&Arc<str>, &mut PolicyDocument) -> Result<(), WorkspaceEditError>,
+    ) -> Result<(), WorkspaceEditError> {
+        let Some(current) = self.raw_policy(&path) else {
+            return Err(WorkspaceEditError::DocumentNotFound(path));
+        };
+
+        let mut document = (*current).clone();
+        edit(&path, &mut document)?;
+        let document = Arc::new(DecisionContent::Policy(PolicyContent(Arc::new(document))));
+        self.record_change(&path, Some(document.clone()));
+        self.write_document(path, document);
+        Ok(())
+    }
+
+    /// Graph counterpart of [`edit_policy`](Self::edit_policy)
+    fn edit_graph_document(
+        &mut self,
+        path: Arc<str>,
+        edit: impl FnOnce(&Arc<str>, &mut GraphContent) -> Result<(), WorkspaceEditError>,
+    ) -> Result<(), WorkspaceEditError> {
+        let current = self.raw_document(&path);
+        let Some(current) = current.as_deref().and_then(DecisionContent::as_graph) else {
+            return Err(WorkspaceEditError::DocumentNotFound(path));
+        };
+
+        let mut graph = GraphContent {
+            nodes: current.nodes.clone(),
+            edges: current.edges.clone(),
+            imports: current.imports.clone(),
+            ..Default::default()
+        };
+        edit(&path, &mut graph)?;
+        let document = Arc::new(DecisionContent::Graph(Arc::new(graph)));
+        self.record_change(&path, Some(document.clone()));
+        self.write_document(path, document);
+        Ok(())
+    }
+}
+
+fn block_position(
+    path: &Arc<str>,
+    document: &PolicyDocument,
+    block_id: &Arc<str>,
+) -> Result<usize, WorkspaceEditError> {
+    document
+        .blocks
+        .iter()
+        .position(|b| b.id() == Some(block_id.as_ref()))
+        .ok_or_else(|| WorkspaceEditError::BlockNotFound {
+            policy_path: path.clone(),
+            block_id: block_id.clone(),
+        })
+}
+
+fn node_position(
+    path: &Arc<str>,
+    graph: &GraphContent,
+    node_id: &Arc<str>,
+) -> Result<usize, WorkspaceEditError> {
+    graph
+        .nodes
+        .iter()
+        .position(|n| n.id == *node_id)
+        .ok_or_else(|| WorkspaceEditError::NodeNotFound {
+            document: path.clone(),
+            node_id: node_id.clone(),
+        })
+}
+
+fn edge_position(
+    path: &Arc<str>,
+    graph: &GraphContent,
+    edge_id: &Arc<str>,
+) -> Result<usize, WorkspaceEditError> {
+    graph
+        .edges
+        .iter()
+        .position(|e| e.id == *edge_id)
+        .ok_or_else(|| WorkspaceEditError::EdgeNotFound {
+            document: path.clone(),
+            edge_id: edge_id.clone(),
+        })
+}
+
+impl DiagnosticsDelta {
+    pub(crate) fn between(before: &[Diagnostic], after: &[Diagnostic]) -> Self {
+        let mut resolved: Vec<&Diagnostic> = before.iter().collect();
+        let mut introduced = Vec::new();
+        for diagnostic in after {
+            match resolved.iter().position(|d| same_diagnostic(d, diagnostic)) {
+                Some(position) => {
+                    resolved.swap_remove(position);
+                }
+                None => introduced.push(diagnostic.clone()),
+            }
+        }
+
+        let mut resolved: Vec<Diagnostic> = resolved.into_iter().cloned().collect();
+        resolved.sort_by(|a, b| a.location.policy_path.cmp(&b.location.policy_path));
+        Self {
+            introduced,
+            resolved,
+        }
+    }
+}
+
+/// Spans are left out so diagnostics shifted by an edit elsewhere in the same expression are
+/// not reported as both resolved and introduced
+fn same_diagnostic(a: &Diagnostic, b: &Diagnostic) -> bool {
+    a.code == b.code
+        && a.severity == b.severity
+        && a.message == b.message
+        && a.location.policy_path == b.location.policy_path
+        && a.location.block_id == b.location.block_id
+        && a.location.expression_id == b.location.expression_id
+}
@@ -1,42 +1,58 @@
+pub(crate) mod contract;
+pub(crate) mod convert;
 pub(crate) mod db;
 pub(crate) mod editor;
 pub(crate) mod graph;
+pub(crate) mod history;
 pub(crate) mod search;
 pub(crate) mod types;
 
 use std::sync::Arc;
 
-use crate::model::DecisionContent;
+use crate::cancellation::EvaluationLimits;
+use crate::error::CompileFailure;
+use crate::expression_context::ExpressionContext;
+use crate::model::{DecisionContent, GraphContent};
+use crate::nodes::custom::CustomNodeRegistry;
 use crate::policy::evaluator::EvalArtifact;
 use crate::policy::raw::PolicyDocument;
+use crate::policy::resolver::DynamicPropertyResolver;
 use db::Db;
 use zen_expression::nl::NlResult;
 use zen_expression::variable::VariableType;
+use zen_expression::vm::{CalendarRegistry, NumericContext};
 
 pub use graph::{
-    FunctionResolutionRequest, FunctionTypeResolver, GraphAnalysis, GraphNodeAnalysis,
-    GraphSignature, GraphTraceMap,
+    FunctionLibrary, FunctionResolutionRequest, FunctionTypeResolver, GraphAnalysis, GraphEditor,
+    GraphNodeAnalysis, GraphSignature, GraphTraceMap, FUNCTION_MODULE_DECLARATIONS,
 };
 pub use types::{
-    BlockExecution, BlockRef, BlockTrace, Completion, ConditionTrace, ConditionalSchema, Cursor,
-    CursorTarget, DecisionTableExtras, DependencyNode, Diagnostic, DiagnosticCode,
-    DiagnosticLocation, Dictionary, DictionaryEntryInfo, DiscriminantVariant, DiscriminatedUnion,
-    EngineEdit, Entity, EntityField, EvaluateRequest, EvaluationError, EvaluationResult,
-    ExpressionKind, FieldOrigin, GuardedProperty, InputProperty, InputValidationError,
-    InspectResult, NlExpression, OutputProperty, PrepareRename, PropertyKind, ReferenceKind,
-    ReferenceSite, RenameTarget, SchemaFieldKind, SchemaGroup, ScopeRequest, SearchHit,
-    SearchHitKind, Severity, Span, Trace, WriteConflict, WriteTrace,
+    BlockExecution, BlockRef, BlockTrace, Completion, ConditionTrace, ConditionalSchema,
+    ConversionDiagnostic, Cursor, CursorTarget, DecisionContract, DecisionTableExtras,
+    DependencyNode, Diagnostic, DiagnosticCode, DiagnosticLocation, DiagnosticsDelta, Dictionary,
+    DictionaryEntryInfo, DiscriminantVariant, DiscriminatedUnion, EngineEdit, Entity, EntityField,
+    EvaluateRequest, EvaluationError, EvaluationResult, ExpressionKind, FieldOrigin,
+    GraphConversion, GraphEdit, GraphEditError, GuardedProperty, InputProperty,
+    InputValidationError, InspectResult, NlExpression, OpenApiInfo, OutputProperty,
+    PartialEvaluation, PendingGoal, PolicyConversion, PrepareRename, PropertyKind, ReferenceKind,
+    ReferenceSite, RenameTarget, ResidualCondition, ResolvedRead, SchemaFieldKind, SchemaGroup,
+    ScopeRequest, SearchHit, SearchHitKind, Severity, Span, Trace, WorkspaceEditError,
+    WriteConflict, WriteTrace,
 };
 
 use types::Global;
 
 pub struct Workspace {
     db: Db,
+    property_resolver: DynamicPropertyResolver,
 }
 
 impl Workspace {
     pub fn new() -> Self {
-        Self { db: Db::new() }
+        Self {
+            db: Db::new(),
+            property_resolver: None,
+        }
     }
 
     pub fn set_document(&mut self, path: impl Into<Arc<str>>, document: DecisionContent) {
@@ -63,6 +79,20 @@ impl Workspace {
         self.db.document_paths()
     }
 
+    /// Registers a library module importable from function nodes, e.g. `lib/scoring.ts`.
+    /// Function type requests carry the libraries their source imports.
+    pub fn set_library(&mut self, key: impl Into<Arc<str>>, source: impl Into<Arc<str>>) {
+        self.db.set_library(key.into(), source.into());
+    }
+
+    pub fn remove_library(&mut self, key: &str) -> bool {
+        self.db.remove_library(key)
+    }
+
+    pub fn library_keys(&self) -> Vec<Arc<str>> {
+        self.db.library_keys()
+    }
+
     pub fn get_document(&self, path: &str) -> Option<Arc<DecisionContent>> {
         self.db.raw_document(path)
     }
@@ -76,7 +106,75 @@ impl Workspace {
     }
 
     pub fn evaluate(&self, req: &EvaluateRequest) -> Result<EvaluationResult, EvaluationError> {
-        self.db.evaluate(req)
+        self.db.evaluate(req, &EvaluationLimits::default())
+    }
+
+    /// Same as [`Workspace::evaluate`], fetching inputs missing from the request through the
+    /// registered property resolver
+    pub async fn evaluate_async(
+        &self,
+        req: &EvaluateRequest,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        self.evaluate_limited(req, &EvaluationLimits::default())
+            .await
+    }
+
+    /// Same as [`Workspace::evaluate_async`], interrupted between blocks and inside expressions
+    /// once `limits` are exceeded
+    pub(crate) async fn evaluate_limited(
+        &self,
+        req: &EvaluateRequest,
+        limits: &EvaluationLimits,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        match &self.property_resolver {
+            Some(resolver) => {
+                self.db
+                    .evaluate_resolving(req, resolver.as_ref(), limits)
+                    .await
+            }
+            None => self.db.evaluate(req, limits),
+        }
+    }
+
+    /// Evaluates with whatever input is available, returning determined goals and, for the
+    /// rest, the input paths still required. With `residuals`, also lists the conditions that
+    /// remain undecided for each pending goal.
+    pub fn partial_evaluate(
+        &self,
+        req: &EvaluateRequest,
+        residuals: bool,
+    ) -> Result<PartialEvaluation, EvaluationError> {
+        self.db.partial_evaluate(req, residuals)
+    }
+
+    pub fn set_property_resolver(&mut self, resolver: DynamicPropertyResolver) {
+        self.property_resolver = resolver;
+    }
+
+    /// Calendars used by business-day date methods in evaluated policies
+    pub fn set_calendars(&mut self, calendars: Option<Arc<CalendarRegistry>>) {
+        self.db.expression_context_mut().calendars = calendars;
+    }
+
+    /// Scale and rounding of divisions in evaluated policies
+    pub fn set_numeric_context(&mut self, numeric: NumericContext) {
+        self.db.expression_context_mut().numeric = numeric;
+    }
+
+    /// Coerces scalars of policy input to the types their data models declare before it is
+    /// validated; each change is listed in [`Trace::coercions`](types::Trace::coercions)
+    pub fn set_input_coercion(&mut self, coerce_input: bool) {
+        self.db.expression_context_mut().coerce_input = coerce_input;
+    }
+
+    /// Contracts of custom node kinds; graph analysis checks node configs and types outputs
+    /// of registered kinds
+    pub fn set_custom_nodes(&mut self, custom_nodes: Option<Arc<CustomNodeRegistry>>) {
+        self.db.set_custom_nodes(custom_nodes);
+    }
+
+    pub(crate) fn set_expression_context(&mut self, expression_context: ExpressionContext) {
+        *self.db.expression_context_mut() = expression_context;
     }
 
     pub fn enhance_trace(
@@ -193,6 +291,62 @@ impl Workspace {
         self.db.rename(target, new_name)
     }
 
+    /// Applies structural `edits` to graph `document`, validating the result. The document
+    /// itself is left unchanged; hosts apply the returned edits.
+    pub fn edit_graph(
+        &self,
+        document: &str,
+        edits: Vec<GraphEdit>,
+    ) -> Result<Vec<EngineEdit>, GraphEditError> {
+        self.db.edit_graph(&Arc::from(document), edits)
+    }
+
+    /// Applies an edit such as those returned by [`edit_graph`](Self::edit_graph) or the
+    /// refactorings, recording it as an undo step
+    pub fn apply_edit(&mut self, edit: EngineEdit) -> Result<(), WorkspaceEditError> {
+        self.db.apply_edit(edit)
+    }
+
+    /// Starts grouping document changes into one undoable step. Diagnostics at this point are
+    /// the baseline for [`transaction_delta`](Self::transaction_delta).
+    pub fn begin_transaction(&mut self) -> Result<(), WorkspaceEditError> {
+        self.db.begin_transaction()
+    }
+
+    /// Diagnostics the open transaction introduced and resolved so far, `None` without one
+    pub fn transaction_delta(&self) -> Option<DiagnosticsDelta> {
+        self.db.transaction_delta()
+    }
+
+    pub fn commit_transaction(&mut self) -> Result<DiagnosticsDelta, WorkspaceEditError> {
+        self.db.commit_transaction()
+    }
+
+    /// Restores every document the open transaction changed
+    pub fn rollback_transaction(&mut self) -> Result<(), WorkspaceEditError> {
+        self.db.rollback_transaction()
+    }
+
+    /// Reverts the last committed transaction; an edit applied outside of a transaction is its
+    /// own step. Documents set or removed outside of a transaction are loads rather than edits:
+    /// they are not undone and drop the steps touching them. Returns `false` with nothing to
+    /// undo or while a transaction is open.
+    pub fn undo(&mut self) -> bool {
+        self.db.undo()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.db.redo()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.db.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.db.can_redo()
+    }
+
     pub fn references(&self, target: &RenameTarget) -> Vec<ReferenceSite> {
         self.db.references(target)
     }
@@ -205,6 +359,29 @@ impl Workspace {
         self.db.input_skeleton(req)
     }
 
+    /// JSON Schema of a policy or graph contract. Discriminated unions become `oneOf` and
+    /// dictionary enums shared `$defs`.
+    pub fn json_schema(&self, req: &ScopeRequest) -> Option<DecisionContract> {
+        self.db.json_schema(req)
+    }
+
+    /// OpenAPI 3.1 document covering every policy and graph in the workspace, along with the
+    /// documents whose contract could not be derived
+    pub fn openapi(&self, info: &OpenApiInfo) -> (serde_json::Value, Vec<CompileFailure>) {
+        self.db.openapi(info)
+    }
+
+    /// Policy document equivalent to a JDM graph, reporting nodes without a block counterpart
+    pub fn graph_to_policy(&self, graph: &GraphContent) -> PolicyConversion {
+        self.db.graph_to_policy(graph)
+    }
+
+    /// JDM graph running a policy's blocks in dependency order, for engines without policy
+    /// support
+    pub fn policy_to_graph(&self, policy_path: &str) -> Option<GraphConversion> {
+        self.db.policy_to_graph(policy_path)
+    }
+
     pub fn dependencies(&self, target: &str) -> DependencyNode {
         self.db.dependencies(target)
     }
@@ -173,6 +173,7 @@ pub enum DiagnosticCode {
     UnresolvedFunctionType,
     ImplicitAny,
     UncheckedNode,
+    InvalidNodeConfig,
     NullabilityDivergence,
 
     RedundantNullish,
@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use zen_types::decision::DecisionNode;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(
     tag = "kind",
     rename_all = "camelCase",
@@ -30,4 +31,54 @@ pub enum EngineEdit {
         node_id: Arc<str>,
         new_node: Value,
     },
+    DeleteNode {
+        document: Arc<str>,
+        node_id: Arc<str>,
+    },
+    InsertNode {
+        document: Arc<str>,
+        new_node: Value,
+    },
+    ReplaceEdge {
+        document: Arc<str>,
+        edge_id: Arc<str>,
+        new_edge: Value,
+    },
+    DeleteEdge {
+        document: Arc<str>,
+        edge_id: Arc<str>,
+    },
+    InsertEdge {
+        document: Arc<str>,
+        new_edge: Value,
+    },
+}
+
+/// Structural change to a decision graph, see [`GraphEditor`](crate::workspace::GraphEditor)
+#[derive(Debug, Clone)]
+pub enum GraphEdit {
+    /// Adds a node under an id not used by any other node
+    AddNode(DecisionNode),
+    /// Connects two nodes; edges leaving a switch node name the statement of their branch in
+    /// `source_handle`
+    Connect {
+        source_id: Arc<str>,
+        target_id: Arc<str>,
+        source_handle: Option<Arc<str>>,
+    },
+    Disconnect {
+        edge_id: Arc<str>,
+    },
+    /// Moves a switch statement to `index`, changing the order branches are evaluated in
+    MoveSwitchBranch {
+        node_id: Arc<str>,
+        statement_id: Arc<str>,
+        index: usize,
+    },
+    /// Removes a node with its edges. With `rewire`, every predecessor is connected to every
+    /// successor through the handle it used to reach the removed node.
+    RemoveNode {
+        node_id: Arc<str>,
+        rewire: bool,
+    },
 }
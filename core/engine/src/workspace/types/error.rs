@@ -5,6 +5,9 @@ use serde::ser::SerializeMap;
 use thiserror::Error;
 use zen_expression::IsolateError;
 
+use crate::cancellation::InterruptReason;
+use crate::DecisionGraphValidationError;
+
 #[derive(Debug, Clone)]
 pub struct InputValidationError {
     pub path: String,
@@ -65,6 +68,85 @@ pub enum EvaluationError {
         source: IsolateError,
         partial_trace: Option<Box<crate::workspace::types::Trace>>,
     },
+
+    #[error("resolving '{path}' failed (policy '{policy_path}'): {message}")]
+    PropertyResolutionFailed {
+        policy_path: Arc<str>,
+        path: Arc<str>,
+        message: String,
+    },
+
+    #[error("{reason} (policy '{policy_path}')")]
+    Interrupted {
+        policy_path: Arc<str>,
+        reason: InterruptReason,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum GraphEditError {
+    #[error("graph '{0}' not found in workspace")]
+    GraphNotFound(Arc<str>),
+
+    #[error("node '{0}' not found")]
+    NodeNotFound(Arc<str>),
+
+    #[error("node '{0}' already exists")]
+    DuplicateNode(Arc<str>),
+
+    #[error("edge '{0}' not found")]
+    EdgeNotFound(Arc<str>),
+
+    #[error("node '{0}' is not a switch node")]
+    NotASwitch(Arc<str>),
+
+    #[error("switch node '{node_id}' has no statement '{statement_id}'")]
+    StatementNotFound {
+        node_id: Arc<str>,
+        statement_id: Arc<str>,
+    },
+
+    #[error("edges leaving switch node '{0}' need a source handle naming their branch")]
+    MissingSourceHandle(Arc<str>),
+
+    #[error("node '{0}' has no branches; edges leaving it take no source handle")]
+    UnexpectedSourceHandle(Arc<str>),
+
+    #[error("edited graph is invalid: {0}")]
+    InvalidGraph(#[from] DecisionGraphValidationError),
+}
+
+#[derive(Debug, Error)]
+pub enum WorkspaceEditError {
+    #[error("document '{0}' not found in workspace")]
+    DocumentNotFound(Arc<str>),
+
+    #[error("block '{block_id}' not found in policy '{policy_path}'")]
+    BlockNotFound {
+        policy_path: Arc<str>,
+        block_id: Arc<str>,
+    },
+
+    #[error("node '{node_id}' not found in graph '{document}'")]
+    NodeNotFound {
+        document: Arc<str>,
+        node_id: Arc<str>,
+    },
+
+    #[error("edge '{edge_id}' not found in graph '{document}'")]
+    EdgeNotFound {
+        document: Arc<str>,
+        edge_id: Arc<str>,
+    },
+
+    #[error("edit content is invalid: {0}")]
+    InvalidContent(#[from] serde_json::Error),
+
+    #[error("a transaction is already open")]
+    TransactionOpen,
+
+    #[error("no transaction is open")]
+    NoTransaction,
 }
 
 impl EvaluationError {
@@ -120,6 +202,24 @@ impl EvaluationError {
                     map.serialize_entry("trace", trace)?;
                 }
             }
+            Self::PropertyResolutionFailed {
+                policy_path,
+                path,
+                message,
+            } => {
+                map.serialize_entry("kind", "PropertyResolutionFailed")?;
+                map.serialize_entry("policyPath", policy_path)?;
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("message", message)?;
+            }
+            Self::Interrupted {
+                policy_path,
+                reason,
+            } => {
+                map.serialize_entry("kind", "Interrupted")?;
+                map.serialize_entry("policyPath", policy_path)?;
+                map.serialize_entry("reason", &reason.to_string())?;
+            }
         }
         Ok(())
     }
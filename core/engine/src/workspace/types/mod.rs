@@ -13,15 +13,17 @@ pub use cursor::{
 };
 pub(crate) use diagnostic::SpanOps;
 pub use diagnostic::{Diagnostic, DiagnosticCode, DiagnosticLocation, Severity, Span};
-pub use edit::EngineEdit;
-pub use error::{EvaluationError, InputValidationError};
+pub use edit::{EngineEdit, GraphEdit};
+pub use error::{EvaluationError, GraphEditError, InputValidationError, WorkspaceEditError};
 pub use nl::NlExpression;
-pub use request::{EvaluateRequest, ScopeRequest};
+pub use request::{EvaluateRequest, OpenApiInfo, ScopeRequest};
 pub use result::{
     BlockExecution, BlockRef, BlockTrace, Completion, ConditionTrace, ConditionalSchema,
-    DecisionTableExtras, DependencyNode, Dictionary, DictionaryEntryInfo, DiscriminantVariant,
-    DiscriminatedUnion, Entity, EntityField, EvaluationResult, FieldOrigin, Global,
-    GuardedProperty, InputProperty, InstanceTarget, OutputProperty, PropertyKind, SchemaFieldKind,
-    SchemaGroup, Trace, WriteConflict, WriteTrace,
+    ConversionDiagnostic, DecisionContract, DecisionTableExtras, DependencyNode, DiagnosticsDelta,
+    Dictionary, DictionaryEntryInfo, DiscriminantVariant, DiscriminatedUnion, Entity, EntityField,
+    EvaluationResult, FieldOrigin, Global, GraphConversion, GuardedProperty, InputProperty,
+    InstanceTarget, OutputProperty, PartialEvaluation, PendingGoal, PolicyConversion, PropertyKind,
+    ResidualCondition, ResolvedRead, SchemaFieldKind, SchemaGroup, Trace, WriteConflict,
+    WriteTrace,
 };
 pub use search::{SearchHit, SearchHitKind};
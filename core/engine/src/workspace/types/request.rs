@@ -24,3 +24,9 @@ impl ScopeRequest {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct OpenApiInfo {
+    pub title: Arc<str>,
+    pub version: Arc<str>,
+}
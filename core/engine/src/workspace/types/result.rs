@@ -6,6 +6,11 @@ use serde::Serialize;
 use zen_expression::intellisense::completion::Completion as _Completion;
 use zen_expression::variable::{Variable, VariableType};
 
+use crate::model::GraphContent;
+use crate::nodes::input::InputCoercion;
+use crate::policy::raw::PolicyDocument;
+use crate::workspace::types::Diagnostic;
+
 pub type Completion = _Completion;
 
 #[derive(Debug, Clone, Serialize)]
@@ -33,6 +38,52 @@ pub struct Trace {
     pub engine_version: Arc<str>,
     pub properties: HashMap<Arc<str>, Variable>,
     pub executions: Vec<BlockExecution>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resolved: Vec<ResolvedRead>,
+    /// Changes made to the input against data models before evaluation, see
+    /// [`Workspace::set_input_coercion`](crate::Workspace::set_input_coercion)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub coercions: Vec<InputCoercion>,
+}
+
+/// Outcome of [`Workspace::partial_evaluate`](crate::Workspace::partial_evaluate) on an
+/// incomplete input
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialEvaluation {
+    /// Goals whose value does not depend on any missing input
+    pub determined: HashMap<Arc<str>, Variable>,
+    pub pending: Vec<PendingGoal>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingGoal {
+    pub goal: Arc<str>,
+    /// Missing input properties the goal still depends on, given the inputs supplied so far
+    pub missing: Vec<Arc<str>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub residual: Vec<ResidualCondition>,
+}
+
+/// Condition of the goal's writer that cannot be decided yet; match arms already ruled out by
+/// the supplied inputs, or shadowed by an arm known to match, are left out
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResidualCondition {
+    pub block: BlockRef,
+    pub condition_id: Arc<str>,
+    /// Condition with the known properties substituted and decided `and` / `or` operands
+    /// removed
+    pub expression: Arc<str>,
+}
+
+/// Input property fetched through a [`PropertyResolver`](crate::policy::PropertyResolver)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedRead {
+    pub path: Arc<str>,
+    pub value: Variable,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -276,3 +327,52 @@ pub enum SchemaFieldKind {
     Relationship { target: Arc<str>, array: bool },
     Reference { target: Arc<str>, array: bool },
 }
+
+/// JSON Schema (draft 2020-12) documents describing a decision's input and output
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionContract {
+    pub input: serde_json::Value,
+    pub output: serde_json::Value,
+}
+
+/// Part of a graph or policy that has no exact counterpart on the other side. `id` is the node
+/// id when converting a graph and the block id when converting a policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionDiagnostic {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Arc<str>>,
+    pub message: String,
+}
+
+impl ConversionDiagnostic {
+    pub(crate) fn new(id: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            id: id.map(Arc::from),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConversion {
+    pub document: PolicyDocument,
+    pub diagnostics: Vec<ConversionDiagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphConversion {
+    pub graph: GraphContent,
+    pub diagnostics: Vec<ConversionDiagnostic>,
+}
+
+/// Diagnostics an open transaction introduced or resolved since it began
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsDelta {
+    pub introduced: Vec<Diagnostic>,
+    pub resolved: Vec<Diagnostic>,
+}
@@ -0,0 +1,305 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use zen_engine::dmn::{self, DmnError};
+use zen_engine::model::{DecisionContent, DecisionNodeKind, DecisionTableHitPolicy, GraphContent};
+use zen_engine::DecisionEngine;
+
+const PRICING: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<definitions xmlns="https://www.omg.org/spec/DMN/20191111/MODEL/" id="pricing" name="Pricing" namespace="https://example.com/pricing">
+  <inputData id="customer" name="Customer"/>
+  <decision id="discount" name="Discount">
+    <variable name="discount"/>
+    <informationRequirement><requiredInput href="#customer"/></informationRequirement>
+    <decisionTable id="discountTable" hitPolicy="UNIQUE">
+      <input id="age" label="Age"><inputExpression typeRef="number"><text>customer.age</text></inputExpression></input>
+      <input id="tier" label="Tier"><inputExpression typeRef="string"><text>customer.tier</text></inputExpression></input>
+      <output id="rate" name="discount" typeRef="number"/>
+      <rule id="r1">
+        <description>Seniors</description>
+        <inputEntry><text>&gt;= 65</text></inputEntry>
+        <inputEntry><text>-</text></inputEntry>
+        <outputEntry><text>0.2</text></outputEntry>
+      </rule>
+      <rule id="r2">
+        <inputEntry><text>]17..65[</text></inputEntry>
+        <inputEntry><text>"gold","platinum"</text></inputEntry>
+        <outputEntry><text>0.1</text></outputEntry>
+      </rule>
+      <rule id="r3">
+        <inputEntry><text>? &lt; 18</text></inputEntry>
+        <inputEntry><text>not("banned")</text></inputEntry>
+        <outputEntry><text>0.05</text></outputEntry>
+      </rule>
+      <rule id="r4">
+        <inputEntry><text>-</text></inputEntry>
+        <inputEntry><text>-</text></inputEntry>
+        <outputEntry><text>0</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+  <decision id="price" name="Price">
+    <variable name="price"/>
+    <informationRequirement><requiredDecision href="#discount"/></informationRequirement>
+    <informationRequirement><requiredInput href="#customer"/></informationRequirement>
+    <literalExpression><text>customer.basket * (1 - discount)</text></literalExpression>
+  </decision>
+</definitions>"##;
+
+fn table_rules(import: &dmn::DmnImport, decision: &str) -> Vec<Value> {
+    let table = import.decision_table(decision).expect("decision table");
+    table
+        .rules
+        .iter()
+        .map(|rule| serde_json::to_value(rule).unwrap())
+        .collect()
+}
+
+#[test]
+fn imports_decision_table_entries_as_unary_expressions() {
+    let import = dmn::import(PRICING).expect("valid DMN");
+    assert!(import.diagnostics.is_empty(), "{:?}", import.diagnostics);
+
+    let table = import.decision_table("discount").expect("decision table");
+    assert_eq!(table.hit_policy, DecisionTableHitPolicy::First);
+    assert_eq!(table.inputs[0].field.as_deref(), Some("customer.age"));
+    assert_eq!(table.outputs[0].field.as_ref(), "discount");
+    assert_eq!(table.outputs[0].column_type.as_deref(), Some("number"));
+
+    let rules = table_rules(&import, "discount");
+    assert_eq!(rules[0]["_id"], "r1");
+    assert_eq!(rules[0]["_description"], "Seniors");
+    assert_eq!(rules[0]["age"], ">= 65");
+    assert_eq!(rules[0]["tier"], "");
+    assert_eq!(rules[1]["age"], "(17..65)");
+    assert_eq!(rules[1]["tier"], "\"gold\", \"platinum\"");
+    assert_eq!(rules[2]["age"], "$ < 18");
+    assert_eq!(rules[2]["tier"], "not ($ in [\"banned\"])");
+}
+
+#[tokio::test]
+async fn imported_requirement_graph_evaluates() {
+    let import = dmn::import(PRICING).expect("valid DMN");
+    let edges: Vec<(String, String)> = import
+        .graph
+        .edges
+        .iter()
+        .map(|e| (e.source_id.to_string(), e.target_id.to_string()))
+        .collect();
+    assert!(edges.contains(&("customer".into(), "discount".into())));
+    assert!(edges.contains(&("discount".into(), "price".into())));
+    assert!(edges.contains(&("price".into(), "output".into())));
+    assert!(
+        !edges.contains(&("discount".into(), "output".into())),
+        "required decisions only feed their dependents: {edges:?}"
+    );
+
+    let engine = DecisionEngine::default();
+    let decision = engine
+        .create_decision(Arc::new(DecisionContent::from(import.graph)))
+        .expect("graph content");
+    for (age, tier, price) in [
+        (70, "silver", 80),
+        (30, "gold", 90),
+        (12, "silver", 95),
+        (30, "silver", 100),
+    ] {
+        let response = decision
+            .evaluate(json!({ "customer": { "age": age, "tier": tier, "basket": 100 } }).into())
+            .await
+            .expect("evaluates");
+        let result: Value = response.result.into();
+        assert_eq!(result["price"], json!(price), "age {age}, tier {tier}");
+    }
+}
+
+#[test]
+fn untranslatable_constructs_are_reported() {
+    let xml = r##"<definitions xmlns="https://www.omg.org/spec/DMN/20151101/dmn.xsd" id="d" name="d">
+  <businessKnowledgeModel id="bkm" name="Helper"/>
+  <decision id="total" name="Total">
+    <decisionTable id="t" hitPolicy="COLLECT" aggregation="SUM">
+      <input id="i"><inputExpression><text>amount</text></inputExpression></input>
+      <output id="o" name="total"/>
+      <rule id="r1">
+        <inputEntry><text>&gt; 10</text></inputEntry>
+        <outputEntry><text>if amount &gt; 100 then 2 else 1</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+  <decision id="invoked" name="Invoked">
+    <invocation id="call"/>
+  </decision>
+</definitions>"##;
+    let import = dmn::import(xml).expect("valid XML");
+    let ids: Vec<Option<&str>> = import.diagnostics.iter().map(|d| d.id.as_deref()).collect();
+    assert_eq!(
+        ids,
+        vec![Some("bkm"), Some("t"), Some("r1"), Some("invoked")],
+        "{:?}",
+        import.diagnostics
+    );
+    assert!(import.diagnostics[1].message.contains("SUM"));
+    assert!(import.diagnostics[2].message.contains("if"));
+
+    let table = import.decision_table("total").expect("table kept");
+    assert_eq!(table.hit_policy, DecisionTableHitPolicy::Collect);
+    assert!(
+        import
+            .graph
+            .nodes
+            .iter()
+            .all(|n| n.id.as_ref() != "invoked"),
+        "untranslatable decision skipped"
+    );
+}
+
+#[test]
+fn export_round_trips_through_import() {
+    let import = dmn::import(PRICING).expect("valid DMN");
+    let export = dmn::export(&import.graph, "Pricing");
+    assert!(export.diagnostics.is_empty(), "{:?}", export.diagnostics);
+    assert!(export.xml.contains("hitPolicy=\"FIRST\""));
+    assert!(export.xml.contains("<text>? &lt; 18</text>"));
+    assert!(export.xml.contains("<text>-</text>"));
+
+    let again = dmn::import(&export.xml).expect("exported DMN imports");
+    assert!(again.diagnostics.is_empty(), "{:?}", again.diagnostics);
+    assert_eq!(
+        table_rules(&again, "discount"),
+        table_rules(&import, "discount")
+    );
+    let node_ids = |graph: &GraphContent| -> Vec<String> {
+        graph.nodes.iter().map(|n| n.id.to_string()).collect()
+    };
+    assert_eq!(node_ids(&again.graph), node_ids(&import.graph));
+    assert_eq!(again.graph.edges.len(), import.graph.edges.len());
+}
+
+#[test]
+fn export_reports_nodes_without_dmn_equivalent() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "in", "name": "Request", "type": "inputNode", "content": {} },
+            { "id": "sw", "name": "Route", "type": "switchNode", "content": {
+                "statements": [{ "id": "s1", "condition": "" }]
+            } },
+            { "id": "calc", "name": "Calc", "type": "expressionNode", "content": { "expressions": [
+                { "id": "x1", "key": "a", "value": "1" },
+                { "id": "x2", "key": "b", "value": "a + 1" }
+            ] } }
+        ],
+        "edges": [
+            { "id": "e1", "sourceId": "in", "targetId": "sw", "sourceHandle": null },
+            { "id": "e2", "sourceId": "sw", "targetId": "calc", "sourceHandle": "s1" }
+        ]
+    }))
+    .unwrap();
+
+    let export = dmn::export(&graph, "Routing");
+    assert_eq!(export.diagnostics.len(), 1, "{:?}", export.diagnostics);
+    assert_eq!(export.diagnostics[0].id.as_deref(), Some("sw"));
+
+    let import = dmn::import(&export.xml).expect("exported DMN imports");
+    let calc = import
+        .graph
+        .nodes
+        .iter()
+        .find(|n| n.id.as_ref() == "calc")
+        .expect("expression decision");
+    let DecisionNodeKind::ExpressionNode { content } = &calc.kind else {
+        panic!("expected expression node");
+    };
+    let keys: Vec<&str> = content.expressions.iter().map(|e| e.key.as_ref()).collect();
+    assert_eq!(keys, vec!["a", "b"]);
+}
+
+#[test]
+fn rejects_non_dmn_documents() {
+    assert!(matches!(dmn::import("<definitions"), Err(DmnError::Xml(_))));
+    assert!(matches!(
+        dmn::import("<model/>"),
+        Err(DmnError::NotDefinitions(name)) if name == "model"
+    ));
+}
+
+#[tokio::test]
+async fn feel_equality_and_built_ins_translate_both_ways() {
+    let xml = r##"<definitions xmlns="https://www.omg.org/spec/DMN/20191111/MODEL/" id="d" name="d">
+  <inputData id="request" name="Request"/>
+  <decision id="approved" name="Approved">
+    <variable name="approved"/>
+    <informationRequirement><requiredInput href="#request"/></informationRequirement>
+    <literalExpression><text>status = "approved" and note != "a=b" and score &gt;= 10</text></literalExpression>
+  </decision>
+  <decision id="tier" name="Tier">
+    <variable name="tier"/>
+    <informationRequirement><requiredInput href="#request"/></informationRequirement>
+    <decisionTable id="tierTable" hitPolicy="FIRST">
+      <input id="code"><inputExpression><text>upper case(code)</text></inputExpression></input>
+      <output id="o" name="tier"/>
+      <rule id="r1">
+        <inputEntry><text>starts with(?, "VIP")</text></inputEntry>
+        <outputEntry><text>"vip"</text></outputEntry>
+      </rule>
+      <rule id="r2">
+        <inputEntry><text>-</text></inputEntry>
+        <outputEntry><text>"standard"</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+</definitions>"##;
+    let import = dmn::import(xml).expect("valid DMN");
+    assert!(import.diagnostics.is_empty(), "{:?}", import.diagnostics);
+    let table = import.decision_table("tier").expect("decision table");
+    assert_eq!(table.inputs[0].field.as_deref(), Some("upper(code)"));
+    assert_eq!(
+        table_rules(&import, "tier")[0]["code"],
+        "startsWith($, \"VIP\")"
+    );
+
+    let decision = DecisionEngine::default()
+        .create_decision(Arc::new(DecisionContent::from(import.graph.clone())))
+        .expect("graph content");
+    for (status, code, approved, tier) in [
+        ("approved", "vip-1", true, "vip"),
+        ("pending", "basic", false, "standard"),
+    ] {
+        let input = json!({ "status": status, "note": "", "score": 12, "code": code });
+        let response = decision.evaluate(input.into()).await.expect("evaluates");
+        let result: Value = response.result.into();
+        assert_eq!(result["approved"], json!(approved), "{status}");
+        assert_eq!(result["tier"], json!(tier), "{code}");
+    }
+
+    let export = dmn::export(&import.graph, "d");
+    assert!(export.diagnostics.is_empty(), "{:?}", export.diagnostics);
+    assert!(export
+        .xml
+        .contains("<text>status = &quot;approved&quot; and note != &quot;a=b&quot;"));
+    assert!(export.xml.contains("<text>upper case(code)</text>"));
+    assert!(export
+        .xml
+        .contains("<text>starts with(?, &quot;VIP&quot;)</text>"));
+}
+
+#[test]
+fn export_reports_functions_without_feel_equivalent() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "in", "name": "Request", "type": "inputNode", "content": {} },
+            { "id": "table", "name": "Table", "type": "decisionTableNode", "content": {
+                "hitPolicy": "first",
+                "inputs": [{ "id": "i", "name": "Name", "field": "name" }],
+                "outputs": [{ "id": "o", "name": "Short", "field": "short" }],
+                "rules": [{ "_id": "r1", "i": "len($) < 4", "o": "true" }]
+            } }
+        ],
+        "edges": [{ "id": "e1", "sourceId": "in", "targetId": "table" }]
+    }))
+    .unwrap();
+
+    let export = dmn::export(&graph, "Names");
+    assert_eq!(export.diagnostics.len(), 1, "{:?}", export.diagnostics);
+    assert_eq!(export.diagnostics[0].id.as_deref(), Some("table"));
+    assert!(export.diagnostics[0].message.contains("'len'"));
+}
@@ -1,16 +1,35 @@
 use crate::support::{create_fs_loader, load_raw_test_data, load_test_data, test_data_root};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde_json::json;
 use std::fs;
+use std::future::Future;
 use std::io::Read;
 use std::ops::Deref;
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::runtime::Builder;
 use zen_engine::loader::{LoaderError, MemoryLoader};
-use zen_engine::model::{DecisionNode, DecisionNodeKind, FunctionNodeContent, GraphContent};
+use zen_engine::model::{
+    DecisionContent, DecisionNode, DecisionNodeKind, FunctionNodeContent, GraphContent,
+};
+use zen_engine::nodes::custom::{
+    CustomNodeAdapter, CustomNodeDefinition, CustomNodeRegistry, CustomNodeRequest,
+};
+use zen_engine::nodes::decision::memo::{MemoStats, Memoization, SubDecisionMemo};
+use zen_engine::nodes::http_handler::{HttpHandler, HttpHandlerRequest, HttpHandlerResponse};
+use zen_engine::nodes::pool::{FunctionPool, FunctionPoolConfig};
+use zen_engine::nodes::{NodeResponse, NodeResult};
+use zen_engine::observer::{
+    EvaluationEnd, EvaluationObserver, EvaluationStart, HttpCall, LoadEvent, MemoLookup, NodeEnd,
+    NodeStart, SubDecision,
+};
 use zen_engine::Variable;
-use zen_engine::{DecisionEngine, EvaluationError, EvaluationOptions};
+use zen_engine::{CancellationToken, Decision, DecisionEngine, EvaluationError, EvaluationOptions};
+use zen_expression::vm::{CalendarRegistry, NumericContext, RoundingMode};
 
 mod support;
 
@@ -55,6 +74,122 @@ async fn engine_precompiles_graphs() {
     assert_eq!(table.unwrap().result, json!({"output": 10}).into());
 }
 
+#[derive(Debug)]
+struct ScaleNode;
+
+impl CustomNodeAdapter for ScaleNode {
+    fn handle(&self, request: CustomNodeRequest) -> Pin<Box<dyn Future<Output = NodeResult> + '_>> {
+        Box::pin(async move {
+            let value = request.input.dot("value").and_then(|v| v.as_number());
+            let factor = request.node.config["factor"].as_i64();
+            let output: Variable = json!({}).into();
+            if let (Some(value), Some(factor)) = (value, factor) {
+                output.dot_insert("value", Variable::Number(value * Decimal::from(factor)));
+            }
+
+            Ok(NodeResponse {
+                output: output.into(),
+                trace_data: None,
+            })
+        })
+    }
+}
+
+#[derive(Debug)]
+struct GreetNode;
+
+impl CustomNodeAdapter for GreetNode {
+    fn handle(&self, request: CustomNodeRequest) -> Pin<Box<dyn Future<Output = NodeResult> + '_>> {
+        Box::pin(async move {
+            Ok(NodeResponse {
+                output: json!({ "greeting": format!("hello {}", request.node.name) }).into(),
+                trace_data: None,
+            })
+        })
+    }
+}
+
+fn custom_node_graph(kind: &str, config: serde_json::Value) -> GraphContent {
+    serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "custom", "type": "customNode", "name": "custom", "content": { "kind": kind, "config": config }},
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "custom"},
+            {"id": "e2", "sourceId": "custom", "targetId": "out"}
+        ]
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_custom_node_registry() {
+    let registry = CustomNodeRegistry::default()
+        .with_node(
+            "scale",
+            CustomNodeDefinition::new(Arc::new(ScaleNode)).with_config_schema(json!({
+                "type": "object",
+                "properties": { "factor": { "type": "integer" } },
+                "required": ["factor"]
+            })),
+        )
+        .with_node("greet", CustomNodeDefinition::new(Arc::new(GreetNode)));
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("scale", custom_node_graph("scale", json!({ "factor": 3 })));
+    loader.add("greet", custom_node_graph("greet", json!({})));
+    loader.add(
+        "invalid",
+        custom_node_graph("scale", json!({ "factor": "3" })),
+    );
+    loader.add("unknown", custom_node_graph("missing", json!({})));
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_custom_nodes(registry);
+
+    let mut failures: Vec<_> = engine
+        .compile()
+        .into_iter()
+        .map(|f| (f.key.to_string(), f.kind, f.diagnostics.len()))
+        .collect();
+    failures.sort();
+    assert_eq!(
+        failures,
+        vec![
+            ("invalid".to_string(), "graph", 1),
+            ("unknown".to_string(), "graph", 1)
+        ]
+    );
+
+    let scaled = engine
+        .evaluate("scale", json!({ "value": 4 }).into())
+        .await
+        .unwrap();
+    assert_eq!(scaled.result.to_value(), json!({ "value": 12 }));
+
+    let greeted = engine.evaluate("greet", json!({}).into()).await.unwrap();
+    assert_eq!(
+        greeted.result.to_value(),
+        json!({ "greeting": "hello custom" })
+    );
+
+    match engine
+        .evaluate("unknown", json!({}).into())
+        .await
+        .unwrap_err()
+        .deref()
+    {
+        EvaluationError::NodeError { source, .. } => assert_eq!(
+            source.to_string(),
+            "No handler registered for custom node kind 'missing'"
+        ),
+        _ => assert!(false, "Unknown kind was handled"),
+    }
+}
+
 #[tokio::test]
 #[cfg_attr(miri, ignore)]
 async fn engine_filesystem_loader() {
@@ -237,6 +372,482 @@ async fn engine_function_imports() {
     assert!(result.moment_valid);
 }
 
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_function_library_modules() {
+    let function_node = |id: &str, source: &str| {
+        json!({
+            "id": id,
+            "type": "functionNode",
+            "name": id,
+            "content": { "source": source }
+        })
+    };
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            function_node(
+                "score",
+                "import { score } from 'lib/scoring.ts';\nexport const handler = async (input: { age: number }) => ({ score: score(input.age) });"
+            ),
+            function_node(
+                "grade",
+                "import { grade } from 'lib:grading.js';\nexport const handler = async (input) => ({ ...input, grade: grade(input.score) });"
+            ),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "score"},
+            {"id": "e2", "sourceId": "score", "targetId": "grade"},
+            {"id": "e3", "sourceId": "grade", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("graph.json", graph);
+    loader.add_module(
+        "lib/scoring.ts",
+        "import { weight } from './shared/weights.js';\nexport const score = (age: number): number => age * weight;",
+    );
+    loader.add_module("lib/shared/weights.js", "export const weight = 2;");
+    loader.add_module(
+        "lib/grading.js",
+        "import { weight } from 'lib/shared/weights.js';\nexport const grade = (score) => score >= 50 * weight ? 'A' : 'B';",
+    );
+
+    let engine = DecisionEngine::default().with_loader(loader.clone());
+    let response = engine
+        .evaluate("graph.json", json!({ "age": 60 }).into())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({ "score": 120, "grade": "A" })
+    );
+
+    let missing: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            function_node(
+                "broken",
+                "import { nope } from 'lib:missing.js';\nexport const handler = async () => nope;"
+            ),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "broken"},
+            {"id": "e2", "sourceId": "broken", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+    loader.add("missing.json", missing);
+    let error = engine
+        .evaluate("missing.json", json!({}).into())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("lib/missing.js"), "{error}");
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_function_pool() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "count",
+                "type": "functionNode",
+                "name": "count",
+                "content": { "source": "import { next } from 'lib:counter.js';\nlet calls = 0;\nexport const handler = async (input) => {\n  calls += 1;\n  const Big = (await import('big.js')).default;\n  const { z } = await import('zod');\n  const seen = { leaked: globalThis.leaked ?? false, dp: Big.DP, marked: Big.prototype.toFixed.marked ?? false, errorMap: z.getErrorMap() === z.defaultErrorMap };\n  if (input.leak) globalThis.leaked = true;\n  if (input.precision) Big.DP = input.precision;\n  if (input.mark) Big.prototype.toFixed.marked = true;\n  if (input.errorMap) z.setErrorMap(() => ({ message: 'custom' }));\n  return { calls, shared: next(), ...seen };\n};" }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "count"},
+            {"id": "e2", "sourceId": "count", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add_module(
+        "lib/counter.js",
+        "let count = 0;\nexport const next = () => ++count;",
+    );
+    let pool = FunctionPool::new(FunctionPoolConfig::default());
+    let decision = Decision::from(graph)
+        .with_loader(loader)
+        .with_function_pool(Some(pool.clone()));
+    let evaluate = |input: serde_json::Value| {
+        let decision = decision.clone();
+        async move {
+            decision
+                .evaluate(input.into())
+                .await
+                .unwrap()
+                .result
+                .to_value()
+        }
+    };
+    let fresh = json!({ "calls": 1, "shared": 1, "leaked": false, "dp": 20, "marked": false, "errorMap": true });
+
+    assert_eq!(pool.idle(), 0);
+    assert_eq!(evaluate(json!({})).await, fresh);
+    assert_eq!(pool.idle(), 1, "runtime returns to the pool");
+    assert_eq!(
+        evaluate(json!({})).await,
+        fresh,
+        "module state is per evaluation"
+    );
+    assert_eq!(pool.idle(), 1, "runtime is reused");
+
+    assert_eq!(evaluate(json!({ "leak": true })).await, fresh);
+    assert_eq!(
+        evaluate(json!({})).await,
+        fresh,
+        "leaking runtime is replaced"
+    );
+    assert_eq!(evaluate(json!({ "precision": 2 })).await, fresh);
+    assert_eq!(
+        evaluate(json!({})).await,
+        fresh,
+        "bundled module changes are not reused"
+    );
+    assert_eq!(evaluate(json!({ "mark": true })).await, fresh);
+    assert_eq!(
+        evaluate(json!({})).await,
+        fresh,
+        "nested bundled module changes are not reused"
+    );
+    assert_eq!(evaluate(json!({ "errorMap": true })).await, fresh);
+    assert_eq!(
+        evaluate(json!({})).await,
+        fresh,
+        "closure state changes are not reused"
+    );
+
+    let single_use = FunctionPool::new(FunctionPoolConfig {
+        max_uses: 1,
+        ..Default::default()
+    });
+    let decision = decision.with_function_pool(Some(single_use.clone()));
+    decision.evaluate(json!({}).into()).await.unwrap();
+    assert_eq!(single_use.idle(), 0);
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_function_crypto_and_encoding_modules() {
+    let source = r#"
+import { sha1, sha256, sha512, hmac, timingSafeEqual, randomBytes, uuidV4, uuidV7 } from 'crypto';
+import encoding from 'encoding';
+
+export const handler = async () => ({
+  sha1: sha1('abc'),
+  sha256: sha256('abc'),
+  sha512Length: sha512('abc', 'base64').length,
+  hmac: hmac('sha256', 'key', 'The quick brown fox jumps over the lazy dog'),
+  hmacBase64Url: hmac('sha1', 'key', encoding.encodeUtf8('payload'), 'base64url'),
+  equal: timingSafeEqual('secret', 'secret'),
+  different: timingSafeEqual('secret', 'secreT'),
+  randomLength: randomBytes(16).length,
+  uuidV4: /^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/.test(uuidV4()),
+  uuidV7: /^[0-9a-f]{8}-[0-9a-f]{4}-7[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/.test(uuidV7()),
+  base64: encoding.encodeBase64('hello'),
+  decoded: encoding.decodeUtf8(encoding.decodeBase64('aGVsbG8=')),
+  base64Url: encoding.encodeBase64Url(new Uint8Array([251, 255])),
+  fromBase64Url: Array.from(encoding.decodeBase64Url('-_8=')),
+  hex: encoding.encodeHex(encoding.encodeUtf8('hi')),
+  fromHex: encoding.decodeUtf8(encoding.decodeHex('6869')),
+});
+"#;
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "fn", "type": "functionNode", "name": "fn", "content": { "source": source }},
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "fn"},
+            {"id": "e2", "sourceId": "fn", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+    let decision = DecisionEngine::default()
+        .create_decision(Arc::new(graph.into()))
+        .unwrap();
+
+    let response = decision.evaluate(json!({}).into()).await.unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({
+            "sha1": "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "sha256": "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "sha512Length": 88,
+            "hmac": "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+            "hmacBase64Url": "LzkCzRYm-n_ftn6TEJ9QQSrXFTE",
+            "equal": true,
+            "different": false,
+            "randomLength": 16,
+            "uuidV4": true,
+            "uuidV7": true,
+            "base64": "aGVsbG8=",
+            "decoded": "hello",
+            "base64Url": "-_8",
+            "fromBase64Url": [251, 255],
+            "hex": "6869",
+            "fromHex": "hi"
+        })
+    );
+}
+
+const WASM_ECHO: &str = r#"
+(module
+  (import "env" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func (export "handle") (param $ptr i32) (param $len i32) (result i64)
+    (call $log (local.get $ptr) (local.get $len))
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len)))))
+"#;
+
+const WASM_SPIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "handle") (param i32 i32) (result i64)
+    (loop $spin (br $spin))
+    (unreachable)))
+"#;
+
+const WASM_GROW: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "handle") (param i32 i32) (result i64)
+    (drop (memory.grow (i32.const 64)))
+    (i64.const 0)))
+"#;
+
+fn wasm_graph(content: serde_json::Value) -> Arc<DecisionContent> {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "wasm", "type": "wasmNode", "name": "wasm", "content": content},
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "wasm"},
+            {"id": "e2", "sourceId": "wasm", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+
+    Arc::new(graph.into())
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_wasm_node() {
+    use base64::Engine;
+
+    let echo = wat::parse_str(WASM_ECHO).unwrap();
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add_binary("wasm/spin.wasm", wat::parse_str(WASM_SPIN).unwrap());
+    loader.add_binary("wasm/grow.wasm", wat::parse_str(WASM_GROW).unwrap());
+    let engine = DecisionEngine::default().with_loader(loader);
+
+    let decision = engine
+        .create_decision(wasm_graph(json!({
+            "module": base64::engine::general_purpose::STANDARD.encode(&echo)
+        })))
+        .unwrap();
+    let response = decision
+        .evaluate_with_opts(
+            json!({ "amount": 12 }).into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.result.to_value(), json!({ "amount": 12 }));
+
+    let trace = serde_json::to_value(&response.trace).unwrap();
+    assert_eq!(
+        trace.pointer("/wasm/traceData/log/0/lines/0"),
+        Some(&json!(r#""{\"amount\":12}""#))
+    );
+    assert!(
+        trace
+            .pointer("/wasm/traceData/fuelConsumed")
+            .unwrap()
+            .as_u64()
+            .unwrap()
+            > 0
+    );
+
+    let spin = engine
+        .create_decision(wasm_graph(
+            json!({ "key": "wasm/spin.wasm", "fuel": 10000 }),
+        ))
+        .unwrap();
+    match spin.evaluate(json!({}).into()).await.unwrap_err().deref() {
+        EvaluationError::NodeError { source, .. } => {
+            assert_eq!(source.to_string(), "Fuel limit of 10000 exhausted")
+        }
+        _ => assert!(false, "Fuel limit not exceeded"),
+    }
+
+    let grow = engine
+        .create_decision(wasm_graph(json!({
+            "key": "wasm/grow.wasm",
+            "maxMemoryBytes": 1048576
+        })))
+        .unwrap();
+    match grow.evaluate(json!({}).into()).await.unwrap_err().deref() {
+        EvaluationError::NodeError { source, .. } => {
+            assert_eq!(source.to_string(), "Memory limit of 1048576 bytes exceeded")
+        }
+        _ => assert!(false, "Memory limit not exceeded"),
+    }
+
+    let missing = engine
+        .create_decision(wasm_graph(json!({ "key": "wasm/missing.wasm" })))
+        .unwrap();
+    assert!(missing.evaluate(json!({}).into()).await.is_err());
+}
+
+/// Cancels the evaluation once the node has started and its module is being loaded
+#[derive(Debug)]
+struct CancellingLoader {
+    inner: Arc<MemoryLoader>,
+    token: CancellationToken,
+}
+
+impl zen_engine::loader::DecisionLoader for CancellingLoader {
+    fn load<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = zen_engine::loader::LoaderResponse> + 'a + Send>> {
+        self.inner.load(key)
+    }
+
+    fn load_binary<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = zen_engine::loader::BinaryResponse> + 'a + Send>> {
+        self.token.cancel();
+        self.inner.load_binary(key)
+    }
+}
+
+#[derive(Debug, Default)]
+struct NodeErrorObserver {
+    errors: std::sync::Mutex<Vec<String>>,
+}
+
+impl EvaluationObserver for NodeErrorObserver {
+    fn on_node_end(&self, event: &NodeEnd<'_>) {
+        if let Some(error) = event.error {
+            self.errors.lock().unwrap().push(error.to_string());
+        }
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_wasm_node_limits() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add_binary("wasm/spin.wasm", wat::parse_str(WASM_SPIN).unwrap());
+    loader.add_binary(
+        "wasm/grow.wasm",
+        wat::parse_str(
+            r#"(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "handle") (param i32 i32) (result i64)
+    (drop (memory.grow (i32.const 1024)))
+    (i64.const 0)))"#,
+        )
+        .unwrap(),
+    );
+    let engine = DecisionEngine::default().with_loader(loader.clone());
+
+    // Documents cannot raise the engine limits
+    let max_fuel = zen_engine::ZEN_CONFIG
+        .wasm_fuel
+        .load(std::sync::atomic::Ordering::Relaxed);
+    let max_memory_bytes = zen_engine::ZEN_CONFIG
+        .wasm_max_memory_bytes
+        .load(std::sync::atomic::Ordering::Relaxed);
+    let spin = engine
+        .create_decision(wasm_graph(
+            json!({ "key": "wasm/spin.wasm", "fuel": u64::MAX }),
+        ))
+        .unwrap();
+    let grow = engine
+        .create_decision(wasm_graph(
+            json!({ "key": "wasm/grow.wasm", "maxMemoryBytes": u64::MAX }),
+        ))
+        .unwrap();
+    for (decision, message) in [
+        (&spin, format!("Fuel limit of {max_fuel} exhausted")),
+        (
+            &grow,
+            format!("Memory limit of {max_memory_bytes} bytes exceeded"),
+        ),
+    ] {
+        match decision
+            .evaluate(json!({}).into())
+            .await
+            .unwrap_err()
+            .deref()
+        {
+            EvaluationError::NodeError { source, .. } => assert_eq!(source.to_string(), message),
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    // Cancelled after the node started, the guest is stopped before it runs
+    let token = CancellationToken::new();
+    let observer = Arc::new(NodeErrorObserver::default());
+    let cancelling = DecisionEngine::default()
+        .with_loader(Arc::new(CancellingLoader {
+            inner: loader,
+            token: token.clone(),
+        }))
+        .with_observer(Some(observer.clone()));
+    let result = cancelling
+        .create_decision(wasm_graph(json!({ "key": "wasm/spin.wasm" })))
+        .unwrap()
+        .evaluate_with_opts(
+            json!({}).into(),
+            EvaluationOptions {
+                cancellation: Some(token),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(
+        result.unwrap_err().deref(),
+        EvaluationError::Cancelled { .. }
+    ));
+    assert_eq!(
+        *observer.errors.lock().unwrap(),
+        vec!["Evaluation cancelled".to_string()]
+    );
+}
+
 #[tokio::test]
 async fn engine_switch_node() {
     let engine = DecisionEngine::default().with_loader(Arc::new(create_fs_loader()));
@@ -476,6 +1087,114 @@ async fn test_validation() {
         .is_err());
 }
 
+#[tokio::test]
+async fn test_input_coercion() {
+    let schema = json!({
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["amount", "currency"],
+        "properties": {
+            "amount": { "type": "number" },
+            "currency": { "type": "string", "default": "EUR" },
+            "express": { "type": ["boolean", "null"] },
+            "placedAt": { "type": "string", "format": "date-time" },
+            "items": {
+                "type": "array",
+                "items": { "$ref": "#/definitions/item" }
+            }
+        },
+        "definitions": {
+            "item": {
+                "type": "object",
+                "properties": {
+                    "quantity": { "type": "integer", "default": 1 },
+                    "sku": { "type": "string" }
+                }
+            }
+        }
+    });
+    let graph = |coerce: bool| -> Arc<DecisionContent> {
+        let graph: GraphContent = serde_json::from_value(json!({
+            "nodes": [
+                {
+                    "id": "in",
+                    "type": "inputNode",
+                    "name": "request",
+                    "content": { "schema": schema.to_string(), "coerce": coerce }
+                },
+                {"id": "out", "type": "outputNode", "name": "response"}
+            ],
+            "edges": [
+                {"id": "e1", "sourceId": "in", "targetId": "out"}
+            ]
+        }))
+        .unwrap();
+
+        Arc::new(graph.into())
+    };
+    let input = json!({
+        "amount": "12.50",
+        "express": "TRUE",
+        "placedAt": "2024-03-01 08:30:00",
+        "items": [{ "sku": 1042 }, { "sku": "A-7", "quantity": "3" }],
+        "debug": true
+    });
+
+    let engine = DecisionEngine::default();
+    let strict = engine.create_decision(graph(false)).unwrap();
+    assert!(strict.evaluate(input.clone().into()).await.is_err());
+
+    let coercing = engine.create_decision(graph(true)).unwrap();
+    let response = coercing
+        .evaluate_with_opts(
+            input.into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({
+            "amount": 12.5,
+            "currency": "EUR",
+            "express": true,
+            "placedAt": "2024-03-01T08:30:00Z",
+            "items": [{ "sku": "1042", "quantity": 1 }, { "sku": "A-7", "quantity": 3 }]
+        })
+    );
+
+    let trace = serde_json::to_value(&response.trace).unwrap();
+    let coercions: Vec<(String, String)> = trace
+        .pointer("/in/traceData/coercions")
+        .and_then(|c| c.as_array())
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                c["path"].as_str().unwrap().to_string(),
+                c["kind"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        coercions,
+        [
+            ("amount", "coerce"),
+            ("currency", "default"),
+            ("express", "coerce"),
+            ("items[0].quantity", "default"),
+            ("items[0].sku", "coerce"),
+            ("items[1].quantity", "coerce"),
+            ("placedAt", "coerce"),
+            ("debug", "strip"),
+        ]
+        .map(|(path, kind)| (path.to_string(), kind.to_string()))
+    );
+}
+
 #[tokio::test]
 #[cfg_attr(miri, ignore)]
 async fn test_nodes_reference() {
@@ -569,6 +1288,7 @@ async fn decision_table_index_matches_linear_semantics() {
                     EvaluationOptions {
                         trace: true,
                         max_depth: 5,
+                        ..Default::default()
                     },
                 )
                 .await
@@ -582,3 +1302,1373 @@ async fn decision_table_index_matches_linear_semantics() {
         }
     }
 }
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_concurrent_graph_tests() {
+    mock_datetime();
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct TestCase {
+        input: Variable,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct TestData {
+        tests: Vec<TestCase>,
+        #[serde(flatten)]
+        decision_content: GraphContent,
+    }
+
+    let engine = DecisionEngine::default();
+    let concurrent = EvaluationOptions {
+        concurrent: true,
+        ..Default::default()
+    };
+    let traced = EvaluationOptions {
+        trace: true,
+        ..Default::default()
+    };
+    let concurrent_traced = EvaluationOptions {
+        trace: true,
+        concurrent: true,
+        ..Default::default()
+    };
+
+    let graphs_path = Path::new(test_data_root().as_str()).join("graphs");
+    for maybe_file in fs::read_dir(graphs_path).unwrap() {
+        let file = maybe_file.unwrap();
+        let file_name = file.file_name().to_string_lossy().to_string();
+        let file_contents = fs::read_to_string(file.path()).expect("valid file data");
+        let test_data: TestData = serde_json::from_str(&file_contents).expect("Valid JSON");
+
+        let decision = engine
+            .create_decision(Arc::new(test_data.decision_content.into()))
+            .unwrap();
+
+        for test_case in test_data.tests {
+            let input = test_case.input.clone();
+            let result = decision
+                .evaluate_with_opts(input.clone(), concurrent.clone())
+                .await
+                .unwrap()
+                .result;
+
+            // Compared against sequential evaluation rather than the expected output, which
+            // depends on the decimal precision features enabled
+            let expected = decision.evaluate(input.clone()).await.unwrap().result;
+            assert_eq!(
+                expected, result,
+                "Decision file: {file_name}.\nInput:\n {input:#?}"
+            );
+
+            let trace_summary = |response: zen_engine::DecisionGraphResponse| {
+                let mut summary: Vec<(String, serde_json::Value)> = response
+                    .trace
+                    .and_then(|t| t.into_graph())
+                    .unwrap()
+                    .into_values()
+                    .map(|t| (t.id.to_string(), t.output.to_value()))
+                    .collect();
+                summary.sort_by(|a, b| a.0.cmp(&b.0));
+                (response.result.to_value(), summary)
+            };
+            let trace_order = |response: &zen_engine::DecisionGraphResponse| {
+                let mut order: Vec<(u32, String)> = response
+                    .trace
+                    .as_ref()
+                    .and_then(|t| t.as_graph())
+                    .unwrap()
+                    .values()
+                    .map(|t| (t.order, t.id.to_string()))
+                    .collect();
+                order.sort();
+                order
+            };
+
+            let sequential = decision
+                .evaluate_with_opts(input.clone(), traced.clone())
+                .await
+                .unwrap();
+            let first = decision
+                .evaluate_with_opts(input.clone(), concurrent_traced.clone())
+                .await
+                .unwrap();
+            let second = decision
+                .evaluate_with_opts(input.clone(), concurrent_traced.clone())
+                .await
+                .unwrap();
+
+            assert_eq!(
+                trace_order(&first),
+                trace_order(&second),
+                "Trace order differs for {file_name}"
+            );
+            assert_eq!(
+                trace_summary(sequential),
+                trace_summary(first),
+                "Concurrent trace differs for {file_name}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_concurrent_branches() {
+    let engine = DecisionEngine::default().with_closure_loader(|key| async move {
+        match key.as_str() {
+            "slow" => {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok(Arc::new(load_test_data("table.json").into()))
+            }
+            _ => Err(LoaderError::NotFound(key).into()),
+        }
+    });
+
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "a", "type": "decisionNode", "name": "a", "content": {"key": "slow"}},
+            {"id": "b", "type": "decisionNode", "name": "b", "content": {"key": "slow"}},
+            {
+                "id": "c",
+                "type": "expressionNode",
+                "name": "c",
+                "content": {"expressions": [{"id": "e", "key": "doubled", "value": "output * 2"}]}
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "a"},
+            {"id": "e2", "sourceId": "in", "targetId": "b"},
+            {"id": "e3", "sourceId": "b", "targetId": "c"},
+            {"id": "e4", "sourceId": "a", "targetId": "out"},
+            {"id": "e5", "sourceId": "c", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = engine.create_decision(Arc::new(content.into())).unwrap();
+
+    let start = Instant::now();
+    let sequential = decision
+        .evaluate_with_opts(
+            json!({ "input": 12 }).into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let sequential_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let concurrent = decision
+        .evaluate_with_opts(
+            json!({ "input": 12 }).into(),
+            EvaluationOptions {
+                trace: true,
+                concurrent: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let concurrent_elapsed = start.elapsed();
+
+    assert_eq!(
+        concurrent.result,
+        json!({ "output": 10, "doubled": 20 }).into()
+    );
+    assert_eq!(sequential.result, concurrent.result);
+    assert!(sequential_elapsed >= Duration::from_millis(400));
+    assert!(
+        concurrent_elapsed < Duration::from_millis(400),
+        "branches did not overlap: {concurrent_elapsed:?}"
+    );
+
+    let trace = concurrent.trace.unwrap().into_graph().unwrap();
+    let mut order: Vec<(u32, &str)> = trace.values().map(|t| (t.order, t.id.as_ref())).collect();
+    order.sort();
+    let ids: Vec<&str> = order.into_iter().map(|(_, id)| id).collect();
+    assert_eq!(ids.len(), 5);
+    assert_eq!(ids[0], "in");
+    assert_eq!(ids[4], "out");
+    assert!(ids.iter().position(|&id| id == "b") < ids.iter().position(|&id| id == "c"));
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_concurrent_nodes_start_when_their_dependencies_complete() {
+    let tracked = Arc::new(AtomicUsize::new(0));
+    let loaded = tracked.clone();
+    let engine = DecisionEngine::default().with_closure_loader(move |key| {
+        let loaded = loaded.clone();
+        async move {
+            let delay = match key.as_str() {
+                "slow" => 300,
+                "quick" => 100,
+                "tracked" => {
+                    loaded.fetch_add(1, Ordering::SeqCst);
+                    0
+                }
+                _ => return Err(LoaderError::NotFound(key).into()),
+            };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Ok(Arc::new(load_test_data("table.json").into()))
+        }
+    });
+    let decision_node = |id: &str, key: &str| json!({ "id": id, "type": "decisionNode", "name": id, "content": { "key": key } });
+    let concurrent = EvaluationOptions {
+        concurrent: true,
+        ..Default::default()
+    };
+
+    // The quick chain finishes while the slow node runs, instead of waiting for it per step
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            decision_node("slow", "slow"),
+            decision_node("q1", "quick"),
+            decision_node("q2", "quick"),
+            decision_node("q3", "quick"),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "slow"},
+            {"id": "e2", "sourceId": "in", "targetId": "q1"},
+            {"id": "e3", "sourceId": "q1", "targetId": "q2"},
+            {"id": "e4", "sourceId": "q2", "targetId": "q3"},
+            {"id": "e5", "sourceId": "slow", "targetId": "out"},
+            {"id": "e6", "sourceId": "q3", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = engine.create_decision(Arc::new(content.into())).unwrap();
+
+    let start = Instant::now();
+    let response = decision
+        .evaluate_with_opts(json!({ "input": 12 }).into(), concurrent.clone())
+        .await
+        .unwrap();
+    let elapsed = start.elapsed();
+    assert_eq!(response.result, json!({ "output": 10 }).into());
+    assert!(
+        elapsed < Duration::from_millis(450),
+        "chain waited on the slow node: {elapsed:?}"
+    );
+
+    // Nodes that are not done when the Output node completes are dropped and start nothing new
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            decision_node("slow", "slow"),
+            decision_node("after", "tracked"),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "out"},
+            {"id": "e2", "sourceId": "in", "targetId": "slow"},
+            {"id": "e3", "sourceId": "slow", "targetId": "after"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = engine.create_decision(Arc::new(content.into())).unwrap();
+
+    let start = Instant::now();
+    let response = decision
+        .evaluate_with_opts(json!({ "input": 12 }).into(), concurrent)
+        .await
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(300));
+    assert_eq!(response.result, json!({ "input": 12 }).into());
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(tracked.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_concurrent_function_nodes() {
+    let function_node = |id: &str, factor: u32| {
+        json!({
+            "id": id,
+            "type": "functionNode",
+            "name": id,
+            "content": {
+                "source": format!(
+                    "export const handler = async (input) => {{ await Promise.resolve(); return {{ {id}: input.input * {factor} }}; }}"
+                )
+            }
+        })
+    };
+
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            function_node("double", 2),
+            function_node("triple", 3),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "double"},
+            {"id": "e2", "sourceId": "in", "targetId": "triple"},
+            {"id": "e3", "sourceId": "double", "targetId": "out"},
+            {"id": "e4", "sourceId": "triple", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = DecisionEngine::default()
+        .create_decision(Arc::new(content.into()))
+        .unwrap();
+
+    for _ in 0..20 {
+        let response = decision
+            .evaluate_with_opts(
+                json!({ "input": 5 }).into(),
+                EvaluationOptions {
+                    concurrent: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.result,
+            json!({ "double": 10, "triple": 15 }).into()
+        );
+    }
+
+    // Both branches wait inside their HTTP call until the other one has made its call too
+    let fetch = |id: &str| {
+        json!({
+            "id": id,
+            "type": "functionNode",
+            "name": id,
+            "content": {
+                "source": format!(
+                    "import http from 'http';\nexport const handler = async () => {{ const r = await http.get('https://example.com/{id}'); return {{ {id}: r.data.price }}; }};"
+                )
+            }
+        })
+    };
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            fetch("first"),
+            fetch("second"),
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "first"},
+            {"id": "e2", "sourceId": "in", "targetId": "second"},
+            {"id": "e3", "sourceId": "first", "targetId": "out"},
+            {"id": "e4", "sourceId": "second", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = DecisionEngine::default()
+        .with_http_handler(Some(Arc::new(BarrierHttpHandler(
+            tokio::sync::Barrier::new(2),
+        ))))
+        .create_decision(Arc::new(content.into()))
+        .unwrap();
+
+    let evaluation = decision.evaluate_with_opts(
+        json!({}).into(),
+        EvaluationOptions {
+            concurrent: true,
+            ..Default::default()
+        },
+    );
+    let response = tokio::time::timeout(Duration::from_secs(10), evaluation)
+        .await
+        .expect("function node branches overlap")
+        .unwrap();
+    assert_eq!(response.result, json!({ "first": 10, "second": 10 }).into());
+}
+
+#[derive(Debug)]
+struct BarrierHttpHandler(tokio::sync::Barrier);
+
+impl HttpHandler for BarrierHttpHandler {
+    fn handle(
+        &self,
+        _request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        Box::pin(async {
+            self.0.wait().await;
+            Ok(HttpHandlerResponse {
+                status: 200,
+                headers: json!({}),
+                data: json!({ "price": 10 }),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_evaluation_limits() {
+    let engine = DecisionEngine::default().with_loader(Arc::new(create_fs_loader()));
+
+    let cancellation = CancellationToken::new();
+    cancellation.cancel();
+    let cancelled = engine
+        .evaluate_with_opts(
+            "table.json",
+            json!({ "input": 12 }).into(),
+            EvaluationOptions {
+                cancellation: Some(cancellation),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(
+        cancelled.unwrap_err().deref(),
+        EvaluationError::Cancelled { node_id: None, .. }
+    ));
+
+    let expired = engine
+        .evaluate_with_opts(
+            "table.json",
+            json!({ "input": 12 }).into(),
+            EvaluationOptions {
+                deadline: Some(Instant::now()),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(
+        expired.unwrap_err().deref(),
+        EvaluationError::DeadlineExceeded { node_id: None, .. }
+    ));
+
+    let cancellation = CancellationToken::new();
+    let handle = cancellation.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        handle.cancel();
+    });
+
+    let start = Instant::now();
+    let infinite_fn = engine
+        .evaluate_with_opts(
+            "infinite-function.json",
+            json!({}).into(),
+            EvaluationOptions {
+                cancellation: Some(cancellation),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(start.elapsed() < Duration::from_secs(1));
+    match infinite_fn.unwrap_err().deref() {
+        EvaluationError::Cancelled { node_id, .. } => {
+            assert_eq!(
+                node_id.as_deref(),
+                Some("e0fd96d0-44dc-4f0e-b825-06e56b442d78")
+            );
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_deadline_partial_trace() {
+    let engine = DecisionEngine::default().with_closure_loader(|key| async move {
+        match key.as_str() {
+            "slow" => {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok(Arc::new(load_test_data("table.json").into()))
+            }
+            _ => Err(LoaderError::NotFound(key).into()),
+        }
+    });
+
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "a", "type": "decisionNode", "name": "a", "content": {"key": "slow"}},
+            {"id": "b", "type": "decisionNode", "name": "b", "content": {"key": "slow"}},
+            {"id": "c", "type": "decisionNode", "name": "c", "content": {"key": "slow"}},
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "a"},
+            {"id": "e2", "sourceId": "a", "targetId": "b"},
+            {"id": "e3", "sourceId": "b", "targetId": "c"},
+            {"id": "e4", "sourceId": "c", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = engine.create_decision(Arc::new(content.into())).unwrap();
+
+    let result = decision
+        .evaluate_with_opts(
+            json!({ "input": 12 }).into(),
+            EvaluationOptions {
+                trace: true,
+                deadline: Some(Instant::now() + Duration::from_millis(150)),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    let err = result.unwrap_err();
+    let EvaluationError::DeadlineExceeded { trace, .. } = err.deref() else {
+        panic!("Unexpected error: {err:?}");
+    };
+
+    let trace = trace.clone().unwrap().to_value();
+    assert!(trace.get("in").is_some());
+    assert!(trace.get("a").is_some());
+    assert!(trace.get("c").is_none());
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_deadline_interrupts_expressions() {
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "expr",
+                "type": "expressionNode",
+                "name": "expr",
+                "content": {"expressions": [{"id": "e", "key": "total", "value": "count([0..n], # > n)"}]}
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "expr"},
+            {"id": "e2", "sourceId": "expr", "targetId": "out"}
+        ]
+    });
+    let content: GraphContent = serde_json::from_value(graph).unwrap();
+    let decision = DecisionEngine::default()
+        .create_decision(Arc::new(content.into()))
+        .unwrap();
+
+    let result = decision
+        .evaluate_with_opts(
+            json!({ "n": 5_000_000 }).into(),
+            EvaluationOptions {
+                deadline: Some(Instant::now() + Duration::from_millis(20)),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    match result.unwrap_err().deref() {
+        EvaluationError::DeadlineExceeded { node_id, .. } => {
+            assert_eq!(node_id.as_deref(), Some("expr"));
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecordingObserver {
+    events: std::sync::Mutex<Vec<String>>,
+}
+
+impl RecordingObserver {
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl EvaluationObserver for RecordingObserver {
+    fn on_evaluation_start(&self, event: &EvaluationStart) {
+        self.record(format!("evaluation_start:{}", event.depth));
+    }
+
+    fn on_evaluation_end(&self, event: &EvaluationEnd<'_>) {
+        self.record(format!(
+            "evaluation_end:{}:{}",
+            event.depth,
+            event.error.is_none()
+        ));
+    }
+
+    fn on_node_start(&self, event: &NodeStart<'_>) {
+        self.record(format!("node_start:{}:{}", event.kind, event.id));
+    }
+
+    fn on_node_end(&self, event: &NodeEnd<'_>) {
+        self.record(format!(
+            "node_end:{}:{}:{}",
+            event.kind,
+            event.id,
+            event.error.is_none()
+        ));
+    }
+
+    fn on_load(&self, event: &LoadEvent<'_>) {
+        self.record(format!("load:{}:{:?}", event.key, event.outcome));
+    }
+
+    fn on_sub_decision(&self, event: &SubDecision<'_>) {
+        self.record(format!("sub_decision:{}:{}", event.key, event.depth));
+    }
+
+    fn on_memo(&self, event: &MemoLookup<'_>) {
+        self.record(format!(
+            "memo:{}:{}:{:?}",
+            event.key, event.depth, event.outcome
+        ));
+    }
+
+    fn on_http_call(&self, event: &HttpCall<'_>) {
+        self.record(format!(
+            "http:{}:{}:{:?}",
+            event.method, event.url, event.status
+        ));
+    }
+}
+
+#[derive(Debug)]
+struct StaticHttpHandler;
+
+impl HttpHandler for StaticHttpHandler {
+    fn handle(
+        &self,
+        _request: HttpHandlerRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<HttpHandlerResponse, String>> + Send + '_>> {
+        Box::pin(async {
+            Ok(HttpHandlerResponse {
+                status: 200,
+                headers: json!({}),
+                data: json!({ "price": 10 }),
+            })
+        })
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_observer() {
+    let graph = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {"id": "sub", "type": "decisionNode", "name": "sub", "content": {"key": "table"}},
+            {
+                "id": "fetch",
+                "type": "functionNode",
+                "name": "fetch",
+                "content": {
+                    "source": "import http from 'http';\nexport const handler = async () => { const r = await http.get('https://example.com/price'); return { price: r.data.price }; };"
+                }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "sub"},
+            {"id": "e2", "sourceId": "sub", "targetId": "fetch"},
+            {"id": "e3", "sourceId": "fetch", "targetId": "out"}
+        ]
+    });
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("table", load_test_data("table.json"));
+    loader.add(
+        "root",
+        serde_json::from_value::<GraphContent>(graph).unwrap(),
+    );
+
+    let observer = Arc::new(RecordingObserver::default());
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_http_handler(Some(Arc::new(StaticHttpHandler)))
+        .with_observer(Some(observer.clone()));
+
+    let response = engine
+        .evaluate("root", json!({ "input": 12 }).into())
+        .await
+        .unwrap();
+    assert_eq!(response.result, json!({ "price": 10 }).into());
+
+    let events = std::mem::take(&mut *observer.events.lock().unwrap());
+    let position = |event: &str| {
+        events
+            .iter()
+            .position(|e| e == event)
+            .unwrap_or_else(|| panic!("missing {event} in {events:?}"))
+    };
+
+    assert_eq!(events.first().map(String::as_str), Some("load:root:Hit"));
+    assert_eq!(
+        events.last().map(String::as_str),
+        Some("evaluation_end:0:true")
+    );
+    assert!(position("evaluation_start:0") < position("node_start:inputNode:in"));
+    assert!(position("node_start:decisionNode:sub") < position("load:table:Hit"));
+    assert!(position("load:table:Hit") < position("sub_decision:table:1"));
+    assert!(position("sub_decision:table:1") < position("evaluation_start:1"));
+    assert!(position("evaluation_end:1:true") < position("node_end:decisionNode:sub:true"));
+    assert!(
+        position("http:GET:https://example.com/price:Some(200)")
+            < position("node_end:functionNode:fetch:true")
+    );
+    assert!(position("node_end:outputNode:out:true") < position("evaluation_end:0:true"));
+
+    let missing = engine.evaluate("missing", json!({}).into()).await;
+    assert!(missing.is_err());
+    assert_eq!(
+        observer.events.lock().unwrap().as_slice(),
+        ["load:missing:Miss"]
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_observer_reports_module_and_binary_loads() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "rate",
+                "type": "functionNode",
+                "name": "rate",
+                "content": {
+                    "source": "import { rate } from 'lib:observed-rate.js';\nexport const handler = async (input) => ({ ...input, rate });"
+                }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "rate"},
+            {"id": "e2", "sourceId": "rate", "targetId": "out"}
+        ]
+    }))
+    .unwrap();
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("graph.json", graph);
+    loader.add_module("lib/observed-rate.js", "export const rate = 3;");
+    loader.add_binary("wasm/echo.wasm", wat::parse_str(WASM_ECHO).unwrap());
+
+    let observer = Arc::new(RecordingObserver::default());
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_observer(Some(observer.clone()));
+
+    let response = engine
+        .evaluate("graph.json", json!({ "amount": 12 }).into())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({ "amount": 12, "rate": 3 })
+    );
+    assert!(observer
+        .events
+        .lock()
+        .unwrap()
+        .contains(&"load:lib/observed-rate.js:Hit".to_string()));
+
+    for key in ["wasm/echo.wasm", "wasm/missing.wasm"] {
+        observer.events.lock().unwrap().clear();
+        let decision = engine
+            .create_decision(wasm_graph(json!({ "key": key })))
+            .unwrap();
+        let _ = decision.evaluate(json!({}).into()).await;
+
+        let outcome = if key == "wasm/echo.wasm" {
+            "Hit"
+        } else {
+            "Miss"
+        };
+        assert!(observer
+            .events
+            .lock()
+            .unwrap()
+            .contains(&format!("load:{key}:{outcome}")));
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_sub_decision_memoization() {
+    let double = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "expr",
+                "type": "expressionNode",
+                "name": "double",
+                "content": {"expressions": [{"id": "e", "key": "doubled", "value": "value * 2"}]}
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "expr"},
+            {"id": "e2", "sourceId": "expr", "targetId": "out"}
+        ]
+    });
+    let root = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "items",
+                "type": "decisionNode",
+                "name": "items",
+                "content": {
+                    "key": "double",
+                    "inputField": "items",
+                    "executionMode": "loop",
+                    "outputPath": "results"
+                }
+            },
+            {
+                "id": "js",
+                "type": "functionNode",
+                "name": "js",
+                "content": {
+                    "source": "import zen from 'zen';\nexport const handler = async () => {\n  const first = await zen.evaluate('double', { value: 5 });\n  const second = await zen.evaluate('double', { value: 5 });\n  return { fromJs: second.result.doubled, memoized: second.memoized === true };\n};"
+                }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "items"},
+            {"id": "e2", "sourceId": "in", "targetId": "js"},
+            {"id": "e3", "sourceId": "items", "targetId": "out"},
+            {"id": "e4", "sourceId": "js", "targetId": "out"}
+        ]
+    });
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "double",
+        serde_json::from_value::<GraphContent>(double).unwrap(),
+    );
+    loader.add(
+        "root",
+        serde_json::from_value::<GraphContent>(root).unwrap(),
+    );
+
+    let memo = SubDecisionMemo::new(8);
+    let observer = Arc::new(RecordingObserver::default());
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_observer(Some(observer.clone()))
+        .with_memoization(Memoization::Shared(memo.clone()));
+    let input =
+        json!({ "items": [{ "value": 1 }, { "value": 2 }, { "value": 1 }, { "value": 1 }] });
+    let expected = json!({
+        "results": [{ "doubled": 2 }, { "doubled": 4 }, { "doubled": 2 }, { "doubled": 2 }],
+        "fromJs": 10,
+        "memoized": true
+    });
+    let memo_events = || {
+        let mut events: Vec<String> = std::mem::take(&mut *observer.events.lock().unwrap())
+            .into_iter()
+            .filter(|e| e.starts_with("memo:") || e.starts_with("sub_decision:"))
+            .collect();
+        events.sort();
+        events
+    };
+
+    let response = engine
+        .evaluate_with_opts(
+            "root",
+            input.clone().into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.result, expected.clone().into());
+    assert_eq!(
+        memo_events(),
+        [
+            "memo:double:1:Hit",
+            "memo:double:1:Hit",
+            "memo:double:1:Hit",
+            "memo:double:1:Miss",
+            "memo:double:1:Miss",
+            "memo:double:1:Miss",
+            "sub_decision:double:1",
+            "sub_decision:double:1",
+        ]
+    );
+
+    let trace = serde_json::to_value(&response).unwrap()["trace"]["items"]["traceData"].clone();
+    assert_eq!(trace.as_array().map(Vec::len), Some(4));
+    assert!(trace[0].get("memoized").is_none());
+    assert_eq!(trace[2], json!({ "memoized": true }));
+    assert_eq!(trace[3], json!({ "memoized": true }));
+
+    // Later evaluations start from the shared memo
+    let response = engine.evaluate("root", input.into()).await.unwrap();
+    assert_eq!(response.result, expected.into());
+    assert_eq!(
+        memo_events(),
+        [
+            "memo:double:1:Hit",
+            "memo:double:1:Hit",
+            "memo:double:1:Hit",
+            "memo:double:1:SharedHit",
+            "memo:double:1:SharedHit",
+            "memo:double:1:SharedHit",
+        ]
+    );
+    assert_eq!(
+        memo.stats(),
+        MemoStats {
+            hits: 3,
+            misses: 3,
+            evictions: 0,
+            entries: 3,
+        }
+    );
+
+    memo.clear();
+    assert_eq!(memo.stats().entries, 0);
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_sub_decision_memoization_keys_on_parent_nodes() {
+    let echo = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "expr",
+                "type": "expressionNode",
+                "name": "echo",
+                "content": {"expressions": [{"id": "e", "key": "seen", "value": "$nodes.request['$nodes'].tag.tagged"}]}
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "expr"},
+            {"id": "e2", "sourceId": "expr", "targetId": "out"}
+        ]
+    });
+    let root = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "tag",
+                "type": "expressionNode",
+                "name": "tag",
+                "content": {"expressions": [{"id": "t", "key": "tagged", "value": "label"}]}
+            },
+            {
+                "id": "sub",
+                "type": "decisionNode",
+                "name": "sub",
+                "content": {"key": "echo", "inputField": "payload"}
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "tag"},
+            {"id": "e2", "sourceId": "in", "targetId": "sub"},
+            {"id": "e3", "sourceId": "tag", "targetId": "sub"},
+            {"id": "e4", "sourceId": "sub", "targetId": "out"}
+        ]
+    });
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "echo",
+        serde_json::from_value::<GraphContent>(echo).unwrap(),
+    );
+    loader.add(
+        "root",
+        serde_json::from_value::<GraphContent>(root).unwrap(),
+    );
+
+    let memo = SubDecisionMemo::new(8);
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_memoization(Memoization::Shared(memo.clone()));
+    for label in ["first", "second", "first"] {
+        let input = json!({ "payload": {}, "label": label });
+        let response = engine.evaluate("root", input.into()).await.unwrap();
+        assert_eq!(response.result, json!({ "seen": label }).into(), "{label}");
+    }
+    assert_eq!(memo.stats().hits, 1);
+    assert_eq!(memo.stats().entries, 2);
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_loop_concurrency_and_error_collection() {
+    let check = json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "fn",
+                "type": "functionNode",
+                "name": "check",
+                "content": {
+                    "source": "export const handler = async (input) => {\n  await Promise.resolve();\n  if (input.value < 0) throw new Error('negative value');\n  return { checked: input.value };\n};"
+                }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "fn"},
+            {"id": "e2", "sourceId": "fn", "targetId": "out"}
+        ]
+    });
+    let root = |loop_options: serde_json::Value| {
+        json!({
+            "nodes": [
+                {"id": "in", "type": "inputNode", "name": "request"},
+                {
+                    "id": "items",
+                    "type": "decisionNode",
+                    "name": "items",
+                    "content": {
+                        "key": "check",
+                        "inputField": "items",
+                        "executionMode": "loop",
+                        "outputPath": "checked",
+                        "loopOptions": loop_options
+                    }
+                },
+                {"id": "out", "type": "outputNode", "name": "response"}
+            ],
+            "edges": [
+                {"id": "e1", "sourceId": "in", "targetId": "items"},
+                {"id": "e2", "sourceId": "items", "targetId": "out"}
+            ]
+        })
+    };
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "check",
+        serde_json::from_value::<GraphContent>(check).unwrap(),
+    );
+    loader.add(
+        "collect",
+        serde_json::from_value::<GraphContent>(root(
+            json!({ "concurrency": 3, "collectErrors": true }),
+        ))
+        .unwrap(),
+    );
+    loader.add(
+        "abort",
+        serde_json::from_value::<GraphContent>(root(json!({ "concurrency": 3 }))).unwrap(),
+    );
+
+    let engine = DecisionEngine::default().with_loader(loader);
+    let input = json!({ "items": [
+        { "value": 1 }, { "value": -2 }, { "value": 3 }, { "value": 4 }, { "value": -5 }
+    ] });
+
+    let response = engine
+        .evaluate_with_opts(
+            "collect",
+            input.clone().into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let result = response.result.to_value();
+    assert_eq!(
+        result["checked"]["results"],
+        json!([{ "checked": 1 }, null, { "checked": 3 }, { "checked": 4 }, null])
+    );
+    let errors = result["checked"]["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 5);
+    for (index, error) in errors.iter().enumerate() {
+        let failed = index == 1 || index == 4;
+        assert_eq!(error.is_null(), !failed, "item {index}");
+        if failed {
+            assert!(error["message"]
+                .as_str()
+                .unwrap()
+                .contains("negative value"));
+        }
+    }
+
+    let trace = serde_json::to_value(&response).unwrap()["trace"]["items"]["traceData"].clone();
+    let statuses: Vec<_> = trace
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        statuses,
+        ["success", "error", "success", "success", "error"]
+    );
+    assert!(trace[1]["error"]
+        .as_str()
+        .unwrap()
+        .contains("negative value"));
+
+    // Without error collection the first failing item fails the node
+    let error = engine.evaluate("abort", input.into()).await.unwrap_err();
+    assert!(matches!(*error, EvaluationError::NodeError { .. }));
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_calendars() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "request", "name": "Request", "type": "inputNode" },
+            {
+                "id": "open", "name": "Open", "type": "switchNode",
+                "content": { "statements": [
+                    { "id": "business", "condition": "d(submitted).isBusinessDay('DE')" },
+                    { "id": "closed", "condition": "" }
+                ] }
+            },
+            {
+                "id": "sla", "name": "SLA", "type": "expressionNode",
+                "content": { "expressions": [
+                    { "id": "e1", "key": "due", "value": "d(submitted).addBusinessDays(2, 'DE').format('%Y-%m-%d')" }
+                ] }
+            },
+            {
+                "id": "next", "name": "Next", "type": "expressionNode",
+                "content": { "expressions": [
+                    { "id": "e2", "key": "due", "value": "d(submitted).nextBusinessDay('DE').format('%Y-%m-%d')" }
+                ] }
+            },
+            { "id": "response", "name": "Response", "type": "outputNode" }
+        ],
+        "edges": [
+            { "id": "a", "sourceId": "request", "targetId": "open" },
+            { "id": "b", "sourceId": "open", "targetId": "sla", "sourceHandle": "business" },
+            { "id": "c", "sourceId": "open", "targetId": "next", "sourceHandle": "closed" },
+            { "id": "d", "sourceId": "sla", "targetId": "response" },
+            { "id": "e", "sourceId": "next", "targetId": "response" }
+        ]
+    }))
+    .unwrap();
+    let policy: zen_engine::policy::PolicyDocument = serde_json::from_value(json!({
+        "blocks": [
+            { "id": "dm", "type": "dataModel", "props": { "data": {
+                "name": "claim",
+                "properties": [
+                    { "id": "p1", "name": "opened", "type": "string", "array": false, "optional": false },
+                    { "id": "p2", "name": "closed", "type": "string", "array": false, "optional": false }
+                ]
+            } } },
+            { "id": "e", "type": "expression", "props": { "data": {
+                "key": "claim.businessDays",
+                "value": "d(claim.opened).businessDaysBetween(d(claim.closed), 'DE')"
+            } } }
+        ]
+    }))
+    .unwrap();
+
+    let source = r#"
+import zen from 'zen';
+
+export const handler = async (input) => ({
+  due: zen.evaluateExpression("d(submitted).addBusinessDays(2, 'DE').format('%Y-%m-%d')", input),
+  open: zen.evaluateUnaryExpression("d($).isBusinessDay('DE')", { $: input.submitted }),
+});
+"#;
+    let function: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "request", "name": "Request", "type": "inputNode" },
+            { "id": "fn", "name": "SLA", "type": "functionNode", "content": { "source": source } },
+            { "id": "response", "name": "Response", "type": "outputNode" }
+        ],
+        "edges": [
+            { "id": "a", "sourceId": "request", "targetId": "fn" },
+            { "id": "b", "sourceId": "fn", "targetId": "response" }
+        ]
+    }))
+    .unwrap();
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("sla", graph);
+    loader.add("slaFunction", function);
+    loader.add(
+        "claim",
+        zen_engine::model::DecisionContent::Policy(zen_engine::model::PolicyContent(Arc::new(
+            policy,
+        ))),
+    );
+
+    let without = DecisionEngine::default().with_loader(loader.clone());
+    for key in ["sla", "slaFunction"] {
+        assert!(without
+            .evaluate(key, json!({ "submitted": "2025-12-24" }).into())
+            .await
+            .is_err());
+    }
+
+    let calendars =
+        CalendarRegistry::from_json(r#"{ "DE": { "holidays": ["2025-12-25", "2025-12-26"] } }"#)
+            .unwrap();
+    let engine = without.with_calendars(calendars);
+    for (submitted, due) in [("2025-12-24", "2025-12-30"), ("2025-12-25", "2025-12-29")] {
+        let response = engine
+            .evaluate("sla", json!({ "submitted": submitted }).into())
+            .await
+            .unwrap();
+        assert_eq!(
+            response.result.to_value(),
+            json!({ "due": due }),
+            "{submitted}"
+        );
+    }
+
+    for (submitted, due, open) in [
+        ("2025-12-24", "2025-12-30", true),
+        ("2025-12-25", "2025-12-30", false),
+    ] {
+        let response = engine
+            .evaluate("slaFunction", json!({ "submitted": submitted }).into())
+            .await
+            .unwrap();
+        assert_eq!(
+            response.result.to_value(),
+            json!({ "due": due, "open": open }),
+            "{submitted}"
+        );
+    }
+
+    let response = engine
+        .evaluate(
+            "claim",
+            json!({ "claim": { "opened": "2025-12-22", "closed": "2025-12-31" } }).into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value().pointer("/claim/businessDays"),
+        Some(&json!(5))
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn engine_numeric_context() {
+    let graph: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "request", "name": "Request", "type": "inputNode" },
+            {
+                "id": "share", "name": "Share", "type": "expressionNode",
+                "content": { "expressions": [
+                    { "id": "e1", "key": "share", "value": "amount / parts" },
+                    { "id": "e2", "key": "third", "value": "1 / 3" }
+                ] }
+            },
+            { "id": "response", "name": "Response", "type": "outputNode" }
+        ],
+        "edges": [
+            { "id": "a", "sourceId": "request", "targetId": "share" },
+            { "id": "b", "sourceId": "share", "targetId": "response" }
+        ]
+    }))
+    .unwrap();
+    let policy: zen_engine::policy::PolicyDocument = serde_json::from_value(json!({
+        "blocks": [
+            { "id": "dm", "type": "dataModel", "props": { "data": {
+                "name": "claim",
+                "properties": [
+                    { "id": "p1", "name": "amount", "type": "number", "array": false, "optional": false },
+                    { "id": "p2", "name": "parts", "type": "number", "array": false, "optional": false }
+                ]
+            } } },
+            { "id": "e", "type": "expression", "props": { "data": {
+                "key": "claim.share",
+                "value": "claim.amount / claim.parts"
+            } } }
+        ]
+    }))
+    .unwrap();
+
+    let source = r#"
+import zen from 'zen';
+
+export const handler = async (input) => ({
+  share: zen.evaluateExpression('amount / parts', input),
+  rounded: zen.evaluateUnaryExpression('>= amount / parts', { ...input, $: 0.12 }),
+});
+"#;
+    let function: GraphContent = serde_json::from_value(json!({
+        "nodes": [
+            { "id": "request", "name": "Request", "type": "inputNode" },
+            { "id": "fn", "name": "Share", "type": "functionNode", "content": { "source": source } },
+            { "id": "response", "name": "Response", "type": "outputNode" }
+        ],
+        "edges": [
+            { "id": "a", "sourceId": "request", "targetId": "fn" },
+            { "id": "b", "sourceId": "fn", "targetId": "response" }
+        ]
+    }))
+    .unwrap();
+
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("share", graph);
+    loader.add("shareFunction", function);
+    loader.add(
+        "claim",
+        zen_engine::model::DecisionContent::Policy(zen_engine::model::PolicyContent(Arc::new(
+            policy,
+        ))),
+    );
+
+    let input = json!({ "amount": 1, "parts": 8 });
+    let exact = DecisionEngine::default().with_loader(loader.clone());
+    let response = exact.evaluate("share", input.clone().into()).await.unwrap();
+    assert_eq!(response.result.to_value()["share"], json!(0.125));
+    let response = exact
+        .evaluate("shareFunction", input.clone().into())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({ "share": 0.125, "rounded": false })
+    );
+
+    let engine = exact.with_numeric_context(NumericContext {
+        division_scale: Some(2),
+        rounding: RoundingMode::HalfEven,
+    });
+    let response = engine
+        .evaluate("share", input.clone().into())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({ "share": 0.12, "third": 0.33 })
+    );
+    let response = engine
+        .evaluate("shareFunction", input.into())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value(),
+        json!({ "share": 0.12, "rounded": true })
+    );
+
+    let response = engine
+        .evaluate(
+            "claim",
+            json!({ "claim": { "amount": 1, "parts": 8 } }).into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        response.result.to_value().pointer("/claim/share"),
+        Some(&json!(0.12))
+    );
+}
@@ -0,0 +1,221 @@
+use serde_json::json;
+use std::ops::Deref;
+use std::sync::Arc;
+use zen_engine::model::GraphContent;
+use zen_engine::nodes::http_handler::{
+    HttpHandler, HttpHandlerConfig, HttpHandlerRequest, MockHttpHandler, RecordingHttpHandler,
+    ReplayHttpHandler, REDACTED,
+};
+use zen_engine::{DecisionEngine, EvaluationError};
+
+fn quote_graph() -> GraphContent {
+    serde_json::from_value(json!({
+        "nodes": [
+            {"id": "in", "type": "inputNode", "name": "request"},
+            {
+                "id": "quote",
+                "type": "functionNode",
+                "name": "quote",
+                "content": {
+                    "source": "import http from 'http';\nexport const handler = async (input) => {\n  const rate = await http.get('https://rates.test/eur');\n  const quote = await http.post('https://quotes.test/v1/quote', { tier: input.tier, amount: input.amount });\n  return { rate: rate.data.rate, price: quote.data.price, status: quote.status };\n};"
+                }
+            },
+            {"id": "out", "type": "outputNode", "name": "response"}
+        ],
+        "edges": [
+            {"id": "e1", "sourceId": "in", "targetId": "quote"},
+            {"id": "e2", "sourceId": "quote", "targetId": "out"}
+        ]
+    }))
+    .unwrap()
+}
+
+fn mock_config() -> HttpHandlerConfig {
+    serde_json::from_value(json!({
+        "mode": "mock",
+        "mocks": [
+            {
+                "match": { "method": "GET", "url": "https://rates.test/*" },
+                "response": { "status": 200, "data": { "rate": 1.5 } }
+            },
+            {
+                "match": { "method": "POST", "url": "https://quotes.test/*/quote", "body": { "tier": "gold" } },
+                "response": { "status": 201, "data": { "price": 90 } }
+            },
+            {
+                "match": { "method": "POST", "url": "https://quotes.test/*/quote" },
+                "response": { "status": 201, "data": { "price": 100 } }
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+async fn evaluate_quote(
+    http_handler: Arc<dyn HttpHandler + Send + Sync>,
+    tier: &str,
+) -> Result<serde_json::Value, Box<EvaluationError>> {
+    let decision = DecisionEngine::default()
+        .with_http_handler(Some(http_handler))
+        .create_decision(Arc::new(quote_graph().into()))
+        .unwrap();
+
+    decision
+        .evaluate(json!({ "tier": tier, "amount": 100 }).into())
+        .await
+        .map(|response| response.result.to_value())
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn mock_http_handler() {
+    let handler = mock_config().into_http_handler().unwrap();
+
+    let gold = evaluate_quote(handler.clone(), "gold").await.unwrap();
+    assert_eq!(gold, json!({ "rate": 1.5, "price": 90, "status": 201 }));
+
+    let silver = evaluate_quote(handler, "silver").await.unwrap();
+    assert_eq!(silver, json!({ "rate": 1.5, "price": 100, "status": 201 }));
+
+    let unmatched = evaluate_quote(Arc::new(MockHttpHandler::default()), "gold").await;
+    match unmatched.unwrap_err().deref() {
+        EvaluationError::NodeError { source, .. } => {
+            assert!(source.to_string().contains("No HTTP mock matches GET"));
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn record_and_replay_http_handler() {
+    let path = std::env::temp_dir().join(format!("zen-http-recording-{}.json", std::process::id()));
+    let upstream = mock_config().into_http_handler().unwrap();
+
+    let recorder = Arc::new(RecordingHttpHandler::new(upstream, &path));
+    let recorded = evaluate_quote(recorder.clone(), "gold").await.unwrap();
+    assert_eq!(recorder.exchanges().len(), 2);
+    recorder.flush().unwrap();
+
+    let replay = HttpHandlerConfig::Replay {
+        path: path.to_string_lossy().to_string(),
+    }
+    .into_http_handler()
+    .unwrap();
+    let replayed = evaluate_quote(replay.clone(), "gold").await.unwrap();
+    assert_eq!(recorded, replayed);
+
+    let unmatched = evaluate_quote(replay, "silver").await;
+    match unmatched.unwrap_err().deref() {
+        EvaluationError::NodeError { source, .. } => {
+            assert!(source
+                .to_string()
+                .contains("No recorded exchange matches POST https://quotes.test/v1/quote"));
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+
+    let from_file = ReplayHttpHandler::from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(from_file.is_ok());
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn recording_http_handler_redacts_credentials() {
+    let path = std::env::temp_dir().join(format!("zen-http-redacted-{}.json", std::process::id()));
+    let upstream = mock_config().into_http_handler().unwrap();
+    let request: HttpHandlerRequest = serde_json::from_value(json!({
+        "method": "GET",
+        "url": "https://rates.test/eur",
+        "headers": { "Authorization": "Bearer secret", "X-Trace": "abc" },
+        "auth": { "username": "user", "password": "secret" }
+    }))
+    .unwrap();
+
+    {
+        let recorder = RecordingHttpHandler::new(upstream.clone(), &path);
+        recorder.handle(request.clone()).await.unwrap();
+        assert!(!path.exists());
+    }
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("secret"));
+
+    let recorded: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    let exchange = &recorded[0];
+    assert_eq!(exchange["request"]["headers"]["Authorization"], REDACTED);
+    assert_eq!(exchange["request"]["headers"]["X-Trace"], "abc");
+    assert!(exchange["request"]["auth"].is_null());
+
+    let recorder = RecordingHttpHandler::new(upstream, &path).with_redacted_headers(["X-Trace"]);
+    recorder.handle(request).await.unwrap();
+    let exchange = &recorder.exchanges()[0];
+    assert_eq!(exchange.request.headers["Authorization"], "Bearer secret");
+    assert_eq!(exchange.request.headers["X-Trace"], REDACTED);
+    assert!(exchange.request.auth.is_none());
+    drop(recorder);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn recording_http_handler_redacts_query_credentials() {
+    let path = std::env::temp_dir().join(format!("zen-http-query-{}.json", std::process::id()));
+    let upstream = mock_config().into_http_handler().unwrap();
+    let request = |url: &str, token: &str| -> HttpHandlerRequest {
+        serde_json::from_value(json!({
+            "method": "GET",
+            "url": url,
+            "params": { "token": token, "page": "1" }
+        }))
+        .unwrap()
+    };
+
+    let recorder = RecordingHttpHandler::new(upstream.clone(), &path);
+    recorder
+        .handle(request(
+            "https://rates.test/eur?api_key=secret&currency=eur#latest",
+            "secret",
+        ))
+        .await
+        .unwrap();
+    let exchanges = recorder.exchanges();
+    drop(recorder);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(!contents.contains("secret"));
+    assert_eq!(
+        exchanges[0].request.url,
+        format!("https://rates.test/eur?api_key={REDACTED}&currency=eur#latest")
+    );
+    assert_eq!(exchanges[0].request.params["token"], REDACTED);
+    assert_eq!(exchanges[0].request.params["page"], "1");
+
+    // Replays match on the redacted form, whatever the credential is
+    let replay = ReplayHttpHandler::new(exchanges);
+    let other_key = "https://rates.test/eur?api_key=other&currency=eur#latest";
+    assert!(replay.handle(request(other_key, "other")).await.is_ok());
+    let other_currency = "https://rates.test/eur?api_key=secret&currency=usd#latest";
+    assert!(replay
+        .handle(request(other_currency, "secret"))
+        .await
+        .is_err());
+
+    let recorder = RecordingHttpHandler::new(upstream, &path).with_redacted_query_params(["page"]);
+    recorder
+        .handle(request("https://rates.test/eur?api_key=secret", "secret"))
+        .await
+        .unwrap();
+    let exchange = &recorder.exchanges()[0];
+    assert_eq!(
+        exchange.request.url,
+        "https://rates.test/eur?api_key=secret"
+    );
+    assert_eq!(exchange.request.params["page"], REDACTED);
+    assert_eq!(exchange.request.params["token"], "secret");
+    drop(recorder);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -5,7 +5,7 @@ use std::path::Path;
 use std::sync::Arc;
 use zen_engine::policy::{
     Cursor, CursorTarget, EngineEdit, EvaluateRequest, EvaluationError, PolicyWorkspace,
-    ScopeRequest, Severity,
+    ScopeRequest, Severity, WorkspaceEditError,
 };
 
 fn rewritten_block_json(edits: &[EngineEdit], block_id: &str) -> Option<String> {
@@ -32,7 +32,7 @@ fn any_touches_block(edits: &[EngineEdit], block_id: &str) -> bool {
     edits.iter().any(|e| match e {
         EngineEdit::ReplaceBlock { block_id: bid, .. }
         | EngineEdit::DeleteBlock { block_id: bid, .. } => bid.as_ref() == block_id,
-        EngineEdit::InsertBlock { .. } | EngineEdit::ReplaceNode { .. } => false,
+        _ => false,
     })
 }
 use zen_expression::variable::{Variable, VariableType};
@@ -2847,6 +2847,84 @@ fn input_validation_rejects_wrong_type_for_global() {
     );
 }
 
+#[test]
+fn input_coercion_converts_scalars_to_data_model_types() {
+    let doc = json!({
+        "blocks": [
+            { "id": "dm-globals", "type": "dataModel", "props": { "data": json!({
+                "name": "platform",
+                "scope": "global",
+                "properties": [
+                    { "id": "g1", "name": "taxRate", "type": "number", "array": false, "optional": false }
+                ]
+            }) }},
+            { "id": "dm-customer", "type": "dataModel", "props": { "data": json!({
+                "name": "customer",
+                "properties": [
+                    { "id": "p1", "name": "age", "type": "number", "array": false, "optional": false },
+                    { "id": "p2", "name": "vip", "type": "boolean", "array": false, "optional": false },
+                    { "id": "p3", "name": "joinedAt", "type": "date", "array": false, "optional": false }
+                ]
+            }) }},
+            { "id": "assert-adult", "type": "assertion", "props": { "data": {
+                "output": "customer.adult",
+                "conditions": [
+                    { "id": "c1", "expression": "customer.age >= 18 and customer.vip", "operator": "and", "depth": 0 }
+                ]
+            }}, "children": [] }
+        ]
+    });
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("p", serde_json::from_value(doc).unwrap());
+    let req = EvaluateRequest {
+        policy_path: Arc::from("p"),
+        input: Variable::from(json!({
+            "taxRate": "0.2",
+            "customer": { "age": "42", "vip": "true", "joinedAt": "2024-03-01T10:00:00Z" }
+        })),
+        goals: Vec::new(),
+        trace: true,
+    };
+
+    assert!(matches!(
+        ws.evaluate(&req),
+        Err(EvaluationError::InputValidationFailed { .. })
+    ));
+
+    ws.set_input_coercion(true);
+    let result = ws.evaluate(&req).unwrap();
+    let output = serde_json::to_value(&result.output).unwrap();
+    assert_eq!(output["taxRate"], json!(0.2));
+    assert_eq!(output["customer"]["age"], json!(42));
+    assert_eq!(output["customer"]["joinedAt"], json!("2024-03-01"));
+    assert_eq!(output["customer"]["adult"], json!(true));
+
+    let trace = serde_json::to_value(result.trace.unwrap()).unwrap();
+    let mut coerced: Vec<&str> = trace["coercions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["path"].as_str().unwrap())
+        .collect();
+    coerced.sort();
+    assert_eq!(
+        coerced,
+        [
+            "customer.age",
+            "customer.joinedAt",
+            "customer.vip",
+            "taxRate"
+        ]
+    );
+    assert_eq!(trace["coercions"][0]["kind"], json!("coerce"));
+
+    assert_eq!(
+        serde_json::to_value(&req.input).unwrap()["customer"]["age"],
+        json!("42"),
+        "request input is left untouched"
+    );
+}
+
 #[test]
 fn expression_diagnostics_distinguish_key_from_value() {
     let mut ws = PolicyWorkspace::new();
@@ -4916,3 +4994,139 @@ fn completions_offered_for_empty_and_trailing_space_sources() {
         "cursor past trimmed source should offer scope completions: {partial:?}"
     );
 }
+
+#[test]
+fn transaction_reports_diagnostics_delta_and_supports_undo_redo() {
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy(
+        "p",
+        serde_json::from_value(json!({
+            "blocks": [
+                { "id": "dm", "type": "dataModel", "props": { "data": {
+                    "name": "customer",
+                    "properties": [
+                        { "id": "p1", "name": "age", "type": "number", "array": false, "optional": false }
+                    ]
+                }}},
+                { "id": "e1", "type": "expression", "props": { "data": { "key": "customer.next", "value": "customer.agee + 1" } } }
+            ]
+        }))
+        .unwrap(),
+    );
+    let undefined = |ws: &PolicyWorkspace| {
+        ws.all_diagnostics()
+            .iter()
+            .filter(|d| format!("{:?}", d.code) == "UndefinedVariable")
+            .count()
+    };
+    assert_eq!(undefined(&ws), 1);
+
+    let fix = EngineEdit::ReplaceBlock {
+        policy_path: Arc::from("p"),
+        block_id: Arc::from("e1"),
+        new_block: json!({ "id": "e1", "type": "expression", "props": { "data": { "key": "customer.next", "value": "customer.age + 1" } } }),
+    };
+    let broken = EngineEdit::InsertBlock {
+        policy_path: Arc::from("p"),
+        after_block_id: Some(Arc::from("e1")),
+        new_block: json!({ "id": "e2", "type": "expression", "props": { "data": { "key": "customer.other", "value": "missing * 2" } } }),
+    };
+
+    ws.begin_transaction().unwrap();
+    assert!(matches!(
+        ws.begin_transaction(),
+        Err(WorkspaceEditError::TransactionOpen)
+    ));
+    ws.apply_edit(fix.clone()).unwrap();
+    ws.apply_edit(broken.clone()).unwrap();
+    let delta = ws.transaction_delta().expect("transaction is open");
+    assert_eq!(delta.resolved.len(), 1, "{delta:#?}");
+    assert_eq!(delta.introduced.len(), 1, "{delta:#?}");
+    assert_eq!(
+        delta.introduced[0].location.expression_id.as_deref(),
+        Some("e2")
+    );
+
+    ws.rollback_transaction().unwrap();
+    assert!(ws.transaction_delta().is_none());
+    assert_eq!(undefined(&ws), 1);
+    assert_eq!(ws.get_policy("p").unwrap().blocks.len(), 2);
+
+    ws.begin_transaction().unwrap();
+    ws.apply_edit(fix).unwrap();
+    assert!(
+        !ws.undo(),
+        "undo is unavailable while a transaction is open"
+    );
+    let delta = ws.commit_transaction().unwrap();
+    assert_eq!((delta.introduced.len(), delta.resolved.len()), (0, 1));
+    assert_eq!(undefined(&ws), 0);
+
+    assert!(matches!(
+        ws.apply_edit(EngineEdit::DeleteBlock {
+            policy_path: Arc::from("p"),
+            block_id: Arc::from("nope"),
+        }),
+        Err(WorkspaceEditError::BlockNotFound { .. })
+    ));
+
+    assert!(ws.undo());
+    assert_eq!(undefined(&ws), 1);
+    assert!(ws.can_redo());
+    assert!(ws.redo());
+    assert_eq!(undefined(&ws), 0);
+
+    ws.apply_edit(broken).unwrap();
+    assert!(!ws.can_redo());
+    assert!(ws.undo());
+    assert_eq!(ws.get_policy("p").unwrap().blocks.len(), 2);
+}
+
+#[test]
+fn document_loads_are_not_undo_steps() {
+    let policy = |value: &str| -> zen_engine::policy::PolicyDocument {
+        serde_json::from_value(json!({
+            "blocks": [
+                { "id": "e1", "type": "expression", "props": { "data": { "key": "total", "value": value } } }
+            ]
+        }))
+        .unwrap()
+    };
+    let value = |ws: &PolicyWorkspace, path: &str| -> serde_json::Value {
+        serde_json::to_value(&ws.get_policy(path).unwrap().blocks[0]).unwrap()["props"]["data"]
+            ["value"]
+            .clone()
+    };
+
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("p", policy("1"));
+    ws.set_policy("q", policy("1"));
+    ws.set_policy("p", policy("2"));
+    assert!(!ws.can_undo());
+
+    let edit = |value: &str| EngineEdit::ReplaceBlock {
+        policy_path: Arc::from("p"),
+        block_id: Arc::from("e1"),
+        new_block: json!({ "id": "e1", "type": "expression", "props": { "data": { "key": "total", "value": value } } }),
+    };
+    ws.apply_edit(edit("3")).unwrap();
+    ws.set_policy("q", policy("2"));
+    assert!(ws.undo(), "loading another document keeps the edit");
+    assert_eq!(value(&ws, "p"), json!("2"));
+    assert_eq!(value(&ws, "q"), json!("2"));
+
+    assert!(ws.redo());
+    ws.set_policy("p", policy("4"));
+    assert!(
+        !ws.can_undo(),
+        "a reload drops the steps that would bring back what it replaced"
+    );
+
+    ws.begin_transaction().unwrap();
+    ws.set_policy("p", policy("5"));
+    ws.remove_path("q");
+    ws.commit_transaction().unwrap();
+    assert!(ws.undo());
+    assert_eq!(value(&ws, "p"), json!("4"));
+    assert_eq!(value(&ws, "q"), json!("2"));
+}
@@ -0,0 +1,353 @@
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use zen_engine::loader::{DecisionLoader, LoaderError, LoaderResponse, MemoryLoader};
+use zen_engine::model::DecisionContent;
+use zen_engine::policy::{OpenApiInfo, PolicyWorkspace, ScopeRequest};
+use zen_engine::DecisionEngine;
+
+fn flat_policy() -> Value {
+    json!({
+        "blocks": [
+            { "id": "dict", "type": "dictionary", "props": { "data": {
+                "name": "customerTier",
+                "entries": [
+                    { "id": "e1", "value": "VIP", "label": "" },
+                    { "id": "e2", "value": "STD", "label": "" }
+                ]
+            } } },
+            { "id": "dm", "type": "dataModel", "props": { "data": {
+                "name": "customer",
+                "properties": [
+                    { "id": "p1", "name": "income", "type": "number", "array": false, "optional": false },
+                    { "id": "p2", "name": "tier", "type": "relationship", "target": "customerTier", "array": false, "optional": false },
+                    { "id": "p3", "name": "nickname", "type": "string", "array": false, "optional": true }
+                ]
+            } } },
+            { "id": "e", "type": "expression", "props": { "data": {
+                "key": "customer.limit",
+                "value": "customer.tier == 'VIP' ? customer.income * 3 : customer.income"
+            } } },
+            { "id": "n", "type": "expression", "props": { "data": {
+                "key": "customer.greeting",
+                "value": "customer.nickname ?? 'customer'"
+            } } }
+        ]
+    })
+}
+
+fn union_policy() -> Value {
+    json!({
+        "blocks": [
+            { "id": "dm", "type": "dataModel", "props": { "data": {
+                "name": "customer",
+                "properties": [
+                    { "id": "g", "name": "segment", "type": "string", "enum": ["retail", "corporate"], "array": false, "optional": false },
+                    { "id": "r", "name": "retailData", "type": "number", "array": false, "optional": false },
+                    { "id": "c", "name": "corporateData", "type": "number", "array": false, "optional": false }
+                ]
+            } } },
+            { "id": "m", "type": "match", "props": { "data": {
+                "key": "customer.score",
+                "arms": [
+                    { "id": "a1", "condition": "customer.segment == \"retail\"", "value": "customer.retailData * 2" },
+                    { "id": "a2", "condition": "customer.segment == \"corporate\"", "value": "customer.corporateData * 2" }
+                ]
+            } } }
+        ]
+    })
+}
+
+fn graph() -> Value {
+    json!({
+        "nodes": [
+            { "id": "in", "name": "in", "type": "inputNode", "content": { "schema": json!({
+                "type": "object",
+                "properties": { "age": { "type": "number" } },
+                "required": ["age"]
+            }).to_string() } },
+            { "id": "calc", "name": "calc", "type": "expressionNode", "content": { "expressions": [
+                { "id": "x1", "key": "adult", "value": "age >= 18" }
+            ] } },
+            { "id": "out", "name": "out", "type": "outputNode", "content": {} }
+        ],
+        "edges": [
+            { "id": "e1", "sourceId": "in", "targetId": "calc", "sourceHandle": null },
+            { "id": "e2", "sourceId": "calc", "targetId": "out", "sourceHandle": null }
+        ]
+    })
+}
+
+fn workspace() -> PolicyWorkspace {
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("flat", serde_json::from_value(flat_policy()).unwrap());
+    ws.set_policy("union", serde_json::from_value(union_policy()).unwrap());
+    ws.set_document(
+        "rules/adult.json",
+        serde_json::from_value::<DecisionContent>(graph()).unwrap(),
+    );
+    ws
+}
+
+#[test]
+fn policy_schema_has_required_inputs_and_dictionary_definitions() {
+    let ws = workspace();
+    let contract = ws
+        .json_schema(&ScopeRequest::for_policy("flat"))
+        .expect("known policy");
+
+    let input = &contract.input;
+    assert_eq!(
+        input["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(input["required"], json!(["customer"]));
+    let customer = &input["properties"]["customer"];
+    assert_eq!(customer["required"], json!(["income", "tier"]));
+    assert_eq!(
+        customer["properties"]["income"],
+        json!({ "type": "number" })
+    );
+    assert_eq!(
+        customer["properties"]["tier"],
+        json!({ "$ref": "#/$defs/customerTier" })
+    );
+    assert_eq!(
+        customer["properties"]["nickname"],
+        json!({ "type": "string" })
+    );
+    assert_eq!(
+        input["$defs"]["customerTier"],
+        json!({ "type": "string", "enum": ["VIP", "STD"] })
+    );
+
+    let output = &contract.output;
+    assert_eq!(
+        output["properties"]["customer"]["properties"]["limit"]["type"],
+        json!("number")
+    );
+}
+
+#[test]
+fn discriminated_union_becomes_one_of() {
+    let ws = workspace();
+    let contract = ws
+        .json_schema(&ScopeRequest::for_policy("union"))
+        .expect("known policy");
+
+    let variants = contract.input["oneOf"].as_array().expect("oneOf variants");
+    assert_eq!(variants.len(), 2);
+    let retail = variants
+        .iter()
+        .find(|v| v["properties"]["customer"]["properties"]["segment"]["const"] == "retail")
+        .expect("retail variant");
+    let customer = &retail["properties"]["customer"];
+    assert!(customer["properties"].get("retailData").is_some());
+    assert!(customer["properties"].get("corporateData").is_none());
+    assert_eq!(customer["required"], json!(["retailData", "segment"]));
+}
+
+#[test]
+fn graph_schema_uses_signature() {
+    let ws = workspace();
+    let contract = ws
+        .json_schema(&ScopeRequest::for_policy("rules/adult.json"))
+        .expect("known graph");
+
+    assert_eq!(
+        contract.input["properties"]["age"],
+        json!({ "type": "number" })
+    );
+    assert_eq!(contract.input["required"], json!(["age"]));
+    assert_eq!(
+        contract.output["properties"]["adult"],
+        json!({ "type": "boolean" })
+    );
+    assert!(ws
+        .json_schema(&ScopeRequest::for_policy("missing"))
+        .is_none());
+}
+
+/// Lists a key whose document fails to load
+#[derive(Debug)]
+struct BrokenKeyLoader(Arc<MemoryLoader>);
+
+impl DecisionLoader for BrokenKeyLoader {
+    fn load<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> Pin<Box<dyn Future<Output = LoaderResponse> + 'a + Send>> {
+        self.0.load(key)
+    }
+
+    fn keys(&self) -> Option<Vec<Arc<str>>> {
+        let mut keys = self.0.keys()?;
+        keys.push(Arc::from("broken"));
+        Some(keys)
+    }
+
+    fn load_sync(&self, key: &str) -> Option<LoaderResponse> {
+        if key == "broken" {
+            return Some(Err(LoaderError::Internal {
+                key: key.to_string(),
+                source: anyhow::anyhow!("corrupted"),
+            }));
+        }
+        self.0.load_sync(key)
+    }
+}
+
+#[test]
+fn openapi_covers_every_loader_key() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "flat",
+        serde_json::from_value::<DecisionContent>(flat_policy()).unwrap(),
+    );
+    loader.add(
+        "rules/adult.json",
+        serde_json::from_value::<DecisionContent>(graph()).unwrap(),
+    );
+    let engine = DecisionEngine::default().with_loader(Arc::new(BrokenKeyLoader(loader)));
+
+    let (document, skipped) = engine
+        .openapi(&OpenApiInfo {
+            title: Arc::from("Rules"),
+            version: Arc::from("1.0.0"),
+        })
+        .expect("memory loader lists keys");
+
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].key.as_ref(), "broken");
+    assert_eq!(skipped[0].kind, "load");
+    assert!(skipped[0].error.as_deref().unwrap().contains("corrupted"));
+
+    assert_eq!(document["openapi"], "3.1.0");
+    assert_eq!(document["info"]["title"], "Rules");
+    let paths = document["paths"].as_object().unwrap();
+    let mut keys: Vec<&String> = paths.keys().collect();
+    keys.sort();
+    assert_eq!(keys, vec!["/flat", "/rules/adult.json"]);
+
+    let operation = &document["paths"]["/rules/adult.json"]["post"];
+    assert_eq!(operation["operationId"], "evaluateRulesAdultJson");
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/RulesAdultJsonInput"
+    );
+
+    let schemas = &document["components"]["schemas"];
+    assert!(schemas.get("FlatInput").is_some());
+    assert!(schemas.get("FlatOutput").is_some());
+    assert!(schemas.get("RulesAdultJsonOutput").is_some());
+    assert_eq!(
+        schemas["FlatInput"]["properties"]["customer"]["properties"]["tier"]["$ref"],
+        "#/components/schemas/customerTier"
+    );
+    assert!(schemas.get("customerTier").is_some());
+}
+
+#[test]
+fn union_arm_with_several_values_excludes_the_others() {
+    let mut policy = union_policy();
+    policy["blocks"][0]["props"]["data"]["properties"][0]["enum"] =
+        json!(["retail", "corporate", "public", "charity"]);
+    policy["blocks"][0]["props"]["data"]["properties"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "id": "p", "name": "publicData", "type": "number", "array": false, "optional": false }));
+    policy["blocks"][1]["props"]["data"]["arms"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "id": "a3", "condition": "customer.segment in [\"public\", \"charity\"]", "value": "customer.publicData * 2" }));
+
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("union", serde_json::from_value(policy).unwrap());
+    let contract = ws
+        .json_schema(&ScopeRequest::for_policy("union"))
+        .expect("known policy");
+
+    let variants = contract.input["oneOf"].as_array().expect("oneOf variants");
+    assert_eq!(variants.len(), 3);
+    let open = variants
+        .iter()
+        .find(|v| v["properties"]["customer"]["properties"]["segment"]["const"].is_null())
+        .expect("open variant");
+    assert_eq!(
+        open["properties"]["customer"]["properties"]["segment"]["not"],
+        json!({ "enum": ["retail", "corporate"] })
+    );
+
+    let validator = jsonschema::validator_for(&contract.input).unwrap();
+    let retail = json!({ "customer": { "segment": "retail", "retailData": 1, "publicData": 1 } });
+    let public = json!({ "customer": { "segment": "public", "publicData": 1 } });
+    assert!(validator.is_valid(&retail));
+    assert!(validator.is_valid(&public));
+}
+
+#[test]
+fn openapi_names_stay_unique_across_keys_and_dictionaries() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "loans/approval.json",
+        serde_json::from_value::<DecisionContent>(graph()).unwrap(),
+    );
+    loader.add(
+        "loans_approval.json",
+        serde_json::from_value::<DecisionContent>(graph()).unwrap(),
+    );
+    loader.add(
+        "flat",
+        serde_json::from_value::<DecisionContent>(flat_policy()).unwrap(),
+    );
+    let mut other = flat_policy();
+    other["blocks"][0]["props"]["data"]["entries"] =
+        json!([{ "id": "e1", "value": "GOLD", "label": "" }]);
+    loader.add(
+        "other",
+        serde_json::from_value::<DecisionContent>(other).unwrap(),
+    );
+    let engine = DecisionEngine::default().with_loader(loader);
+
+    let (document, skipped) = engine
+        .openapi(&OpenApiInfo {
+            title: Arc::from("Rules"),
+            version: Arc::from("1.0.0"),
+        })
+        .expect("memory loader lists keys");
+    assert!(skipped.is_empty());
+
+    let operation = |path: &str| document["paths"][path]["post"].clone();
+    let input_ref = |path: &str| {
+        operation(path)["requestBody"]["content"]["application/json"]["schema"]["$ref"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(
+        operation("/loans/approval.json")["operationId"],
+        "evaluateLoansApprovalJson"
+    );
+    assert_eq!(
+        operation("/loans_approval.json")["operationId"],
+        "evaluateLoansApprovalJson2"
+    );
+    assert_ne!(
+        input_ref("/loans/approval.json"),
+        input_ref("/loans_approval.json")
+    );
+
+    let schemas = &document["components"]["schemas"];
+    let tier = |name: &str| {
+        schemas[name]["properties"]["customer"]["properties"]["tier"]["$ref"]
+            .as_str()
+            .unwrap()
+            .trim_start_matches("#/components/schemas/")
+            .to_string()
+    };
+    assert_eq!(tier("FlatInput"), "customerTier");
+    assert_eq!(tier("OtherInput"), "customerTier2");
+    assert_eq!(schemas["customerTier"]["enum"], json!(["VIP", "STD"]));
+    assert_eq!(schemas["customerTier2"]["enum"], json!(["GOLD"]));
+}
@@ -0,0 +1,251 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::sync::Arc;
+use zen_engine::model::{DecisionContent, DecisionNodeKind, GraphContent};
+use zen_engine::policy::{BlockDoc, EvaluateRequest, PolicyWorkspace, Severity};
+use zen_engine::DecisionEngine;
+use zen_expression::Variable;
+
+fn pricing_graph(other_key: &str) -> GraphContent {
+    let schema = json!({
+        "type": "object",
+        "required": ["customer", "basket"],
+        "properties": {
+            "customer": {
+                "type": "object",
+                "required": ["age", "tier"],
+                "properties": {
+                    "age": { "type": "number" },
+                    "tier": { "type": "string", "enum": ["gold", "silver"] },
+                    "address": {
+                        "type": "object",
+                        "properties": { "city": { "type": ["string", "null"] } }
+                    }
+                }
+            },
+            "basket": { "type": "number" },
+            "coupons": { "type": "array", "items": { "type": "string" } }
+        }
+    });
+
+    serde_json::from_value(json!({
+        "nodes": [
+            {
+                "id": "request", "name": "Request", "type": "inputNode",
+                "content": { "schema": schema.to_string() }
+            },
+            {
+                "id": "totals", "name": "Totals", "type": "expressionNode",
+                "content": {
+                    "outputPath": "totals",
+                    "expressions": [
+                        { "id": "e1", "key": "gross", "value": "basket * 1.2" },
+                        { "id": "e2", "key": "net", "value": "$.gross - 5" }
+                    ]
+                }
+            },
+            {
+                "id": "segment", "name": "Segment", "type": "switchNode",
+                "content": { "statements": [
+                    { "id": "senior", "condition": "customer.age >= 65" },
+                    { "id": "other", "condition": "" }
+                ] }
+            },
+            {
+                "id": "senior-rate", "name": "Senior", "type": "expressionNode",
+                "content": { "expressions": [{ "id": "s1", "key": "rate", "value": "0.8" }] }
+            },
+            {
+                "id": "other-rate", "name": "Other", "type": "expressionNode",
+                "content": { "expressions": [{ "id": "o1", "key": other_key, "value": "customer.tier == 'gold' ? 0.9 : 1" }] }
+            },
+            {
+                "id": "fee", "name": "Fee", "type": "decisionTableNode",
+                "content": {
+                    "hitPolicy": "first",
+                    "inputs": [{ "id": "i1", "name": "Net", "field": "totals.net" }],
+                    "outputs": [{ "id": "o1", "name": "Fee", "field": "fee" }],
+                    "rules": [
+                        { "_id": "r1", "i1": "> 100", "o1": "0" },
+                        { "_id": "r2", "i1": "", "o1": "4.5" }
+                    ]
+                }
+            },
+            {
+                "id": "audit", "name": "Audit", "type": "functionNode",
+                "content": "export const handler = async (input) => input;"
+            },
+            { "id": "response", "name": "Response", "type": "outputNode" }
+        ],
+        "edges": [
+            { "id": "a", "sourceId": "request", "targetId": "totals" },
+            { "id": "b", "sourceId": "totals", "targetId": "segment" },
+            { "id": "c", "sourceId": "segment", "targetId": "senior-rate", "sourceHandle": "senior" },
+            { "id": "d", "sourceId": "segment", "targetId": "other-rate", "sourceHandle": "other" },
+            { "id": "e", "sourceId": "senior-rate", "targetId": "fee" },
+            { "id": "f", "sourceId": "other-rate", "targetId": "fee" },
+            { "id": "g", "sourceId": "fee", "targetId": "audit" },
+            { "id": "h", "sourceId": "audit", "targetId": "response" }
+        ]
+    }))
+    .unwrap()
+}
+
+fn evaluate(ws: &PolicyWorkspace, path: &str, input: Value) -> Value {
+    let result = ws
+        .evaluate(&EvaluateRequest {
+            policy_path: Arc::from(path),
+            input: Variable::from(input),
+            goals: Vec::new(),
+            trace: false,
+        })
+        .expect("policy evaluates");
+    result.output.to_value()
+}
+
+#[test]
+fn graph_converts_to_policy_blocks() {
+    let ws = PolicyWorkspace::new();
+    let conversion = ws.graph_to_policy(&pricing_graph("rate"));
+    let blocks = &conversion.document.blocks;
+
+    let models: Vec<String> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            BlockDoc::DataModel { data, .. } => Some(data.name.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(models, vec!["address", "customer", "globals"]);
+
+    let expressions: Vec<(String, String)> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            BlockDoc::Expression { data, .. } => {
+                Some((data.key.to_string(), data.value.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        expressions,
+        vec![
+            ("totals.gross".to_string(), "basket * 1.2".to_string()),
+            ("totals.net".to_string(), "totals.gross - 5".to_string()),
+        ]
+    );
+
+    let matched = blocks
+        .iter()
+        .find_map(|block| match block {
+            BlockDoc::Match { data, .. } => Some(data),
+            _ => None,
+        })
+        .expect("switch folded into a match block");
+    assert_eq!(matched.key.as_ref(), "rate");
+    assert_eq!(matched.arms.len(), 2);
+    assert_eq!(matched.arms[1].condition.as_ref(), "");
+
+    assert!(blocks
+        .iter()
+        .any(|block| matches!(block, BlockDoc::DecisionTable { id, .. } if id.as_ref() == "fee")));
+    assert_eq!(conversion.diagnostics.len(), 1);
+    assert_eq!(conversion.diagnostics[0].id.as_deref(), Some("audit"));
+}
+
+#[test]
+fn converted_policy_matches_graph_results() {
+    let mut ws = PolicyWorkspace::new();
+    let conversion = ws.graph_to_policy(&pricing_graph("rate"));
+    ws.set_policy("pricing", conversion.document);
+    let errors: Vec<_> = ws
+        .diagnostics("pricing")
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect();
+    assert!(errors.is_empty(), "{errors:#?}");
+
+    for (age, tier, basket, rate, fee) in [
+        (70, "silver", 100, 0.8, 0.0),
+        (30, "gold", 50, 0.9, 4.5),
+        (30, "silver", 50, 1.0, 4.5),
+    ] {
+        let output = evaluate(
+            &ws,
+            "pricing",
+            json!({ "customer": { "age": age, "tier": tier }, "basket": basket }),
+        );
+        assert_eq!(
+            output["rate"].as_f64(),
+            Some(rate),
+            "age {age}, tier {tier}"
+        );
+        assert_eq!(output["fee"].as_f64(), Some(fee), "basket {basket}");
+        assert_eq!(
+            output["totals"]["net"].as_f64(),
+            Some(basket as f64 * 1.2 - 5.0)
+        );
+    }
+}
+
+#[test]
+fn unfoldable_switch_is_reported() {
+    let conversion = PolicyWorkspace::new().graph_to_policy(&pricing_graph("discount"));
+    assert!(conversion
+        .diagnostics
+        .iter()
+        .any(|d| d.id.as_deref() == Some("segment") && d.message.contains("different keys")));
+    assert!(!conversion
+        .document
+        .blocks
+        .iter()
+        .any(|block| matches!(block, BlockDoc::Match { .. })));
+}
+
+#[tokio::test]
+async fn policy_exports_to_runnable_graph() {
+    let fixture =
+        fs::read_to_string("tests/data/policy/fixtures/analysis.json").expect("fixture exists");
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("analysis", serde_json::from_str(&fixture).unwrap());
+
+    let conversion = ws.policy_to_graph("analysis").expect("policy exists");
+    assert!(
+        conversion.diagnostics.is_empty(),
+        "{:?}",
+        conversion.diagnostics
+    );
+    let graph = conversion.graph;
+
+    let order: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_ref()).collect();
+    let position = |id: &str| order.iter().position(|n| *n == id).unwrap();
+    assert!(position("ds1") < position("dt1"));
+    assert!(position("f1") < position("dt1"));
+    let DecisionNodeKind::InputNode { content } = &graph.nodes[0].kind else {
+        panic!("first node is the input node");
+    };
+    let schema = content.schema.as_ref().expect("input schema");
+    assert!(schema["properties"]["customer"].is_object());
+    assert!(schema.get("$schema").is_none());
+
+    let input = json!({ "customer": {
+        "name": "Alice", "age": 35, "country": "US",
+        "companies": [{ "id": "c1", "name": "ACME", "iban": "DE1", "revenue": 300000 },
+                      { "id": "c2", "name": "Globex", "iban": "DE2", "revenue": 250000 }],
+        "creditReport": { "score": 800, "delinquencies": 0, "totalDebt": 5000 }
+    } });
+    let expected = evaluate(&ws, "analysis", input.clone());
+
+    let engine = DecisionEngine::default();
+    let decision = engine
+        .create_decision(Arc::new(DecisionContent::from(graph)))
+        .expect("graph content");
+    let response = decision.evaluate(input.into()).await.expect("evaluates");
+    let result: Value = response.result.into();
+    for field in ["totalRevenue", "creditTier", "discount"] {
+        assert_eq!(
+            result["customer"][field], expected["customer"][field],
+            "{field}"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use zen_engine::policy::{EvaluateRequest, PolicyWorkspace};
+use zen_expression::variable::Variable;
+
+fn workspace() -> PolicyWorkspace {
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy(
+        "p",
+        serde_json::from_value(json!({
+            "blocks": [
+                { "id": "dm", "type": "dataModel", "props": { "data": {
+                    "name": "customer",
+                    "properties": [
+                        { "id": "p1", "name": "age", "type": "number", "array": false, "optional": false },
+                        { "id": "p2", "name": "income", "type": "number", "array": false, "optional": false },
+                        { "id": "p3", "name": "bureauScore", "type": "number", "array": false, "optional": false },
+                        { "id": "p4", "name": "segment", "type": "string", "array": false, "optional": true }
+                    ]
+                } } },
+                { "id": "adult", "type": "assertion", "props": { "data": {
+                    "output": "customer.adult",
+                    "conditions": [
+                        { "id": "c1", "expression": "customer.age >= 18", "operator": "and", "depth": 0 }
+                    ]
+                } } },
+                { "id": "m", "type": "match", "props": { "data": {
+                    "key": "customer.approved",
+                    "arms": [
+                        { "id": "a1", "condition": "customer.income >= 1000", "value": "true" },
+                        { "id": "a2", "condition": "", "value": "customer.bureauScore >= 700" }
+                    ]
+                } } },
+                { "id": "e", "type": "expression", "props": { "data": {
+                    "key": "customer.tier",
+                    "value": "customer.approved ? (customer.segment ?? 'standard') : 'none'"
+                } } }
+            ]
+        }))
+        .unwrap(),
+    );
+    ws
+}
+
+fn request(input: Value, goals: Vec<&str>) -> EvaluateRequest {
+    EvaluateRequest {
+        policy_path: Arc::from("p"),
+        input: Variable::from(input),
+        goals: goals.into_iter().map(Arc::from).collect(),
+        trace: false,
+    }
+}
+
+fn determined(partial: &zen_engine::policy::PartialEvaluation, goal: &str) -> Option<Value> {
+    partial.determined.get(goal).map(|v| v.clone().into())
+}
+
+fn missing_of(partial: &zen_engine::policy::PartialEvaluation, goal: &str) -> Vec<String> {
+    partial
+        .pending
+        .iter()
+        .find(|p| p.goal.as_ref() == goal)
+        .map(|p| p.missing.iter().map(|m| m.to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn complete_input_determines_every_goal() {
+    let ws = workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(
+                json!({ "customer": { "age": 30, "income": 200, "bureauScore": 720 } }),
+                vec!["customer.adult", "customer.approved", "customer.tier"],
+            ),
+            false,
+        )
+        .expect("partial evaluation ok");
+
+    assert!(partial.pending.is_empty(), "{:?}", partial.pending);
+    assert_eq!(determined(&partial, "customer.adult"), Some(json!(true)));
+    assert_eq!(determined(&partial, "customer.approved"), Some(json!(true)));
+    assert_eq!(
+        determined(&partial, "customer.tier"),
+        Some(json!("standard"))
+    );
+}
+
+#[test]
+fn goals_not_reading_missing_inputs_are_determined() {
+    let ws = workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(
+                json!({ "customer": { "income": 5000 } }),
+                vec!["customer.adult", "customer.approved", "customer.tier"],
+            ),
+            false,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(determined(&partial, "customer.approved"), Some(json!(true)));
+    assert_eq!(
+        determined(&partial, "customer.tier"),
+        Some(json!("standard"))
+    );
+    assert_eq!(missing_of(&partial, "customer.adult"), vec!["customer.age"]);
+}
+
+#[test]
+fn missing_inputs_propagate_through_computed_properties() {
+    let ws = workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(
+                json!({ "customer": { "age": 30, "income": 200 } }),
+                vec!["customer.approved", "customer.tier"],
+            ),
+            false,
+        )
+        .expect("partial evaluation ok");
+
+    assert!(partial.determined.is_empty(), "{:?}", partial.determined);
+    assert_eq!(
+        missing_of(&partial, "customer.approved"),
+        vec!["customer.bureauScore"]
+    );
+    assert_eq!(
+        missing_of(&partial, "customer.tier"),
+        vec!["customer.bureauScore"],
+        "optional segment is never required"
+    );
+}
+
+#[test]
+fn undecided_selection_requires_every_branch() {
+    let ws = workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(json!({ "customer": {} }), vec!["customer.approved"]),
+            true,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(
+        missing_of(&partial, "customer.approved"),
+        vec!["customer.bureauScore", "customer.income"]
+    );
+    let residual: Vec<(String, String)> = partial.pending[0]
+        .residual
+        .iter()
+        .map(|r| (r.condition_id.to_string(), r.expression.to_string()))
+        .collect();
+    assert_eq!(
+        residual,
+        vec![("a1".to_string(), "customer.income >= 1000".to_string())]
+    );
+}
+
+#[test]
+fn default_goals_are_terminal_properties() {
+    let ws = workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(json!({ "customer": { "age": 12 } }), vec![]),
+            false,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(determined(&partial, "customer.adult"), Some(json!(false)));
+    assert_eq!(
+        missing_of(&partial, "customer.tier"),
+        vec!["customer.bureauScore", "customer.income"]
+    );
+    let serialized = serde_json::to_value(&partial).expect("serializable");
+    assert!(serialized.get("determined").is_some());
+    assert!(serialized.get("pending").is_some());
+}
+
+fn residual_of(partial: &zen_engine::policy::PartialEvaluation, goal: &str) -> Vec<String> {
+    partial
+        .pending
+        .iter()
+        .find(|p| p.goal.as_ref() == goal)
+        .map(|p| {
+            p.residual
+                .iter()
+                .map(|r| r.expression.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn offer_workspace() -> PolicyWorkspace {
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy(
+        "p",
+        serde_json::from_value(json!({
+            "blocks": [
+                { "id": "dm", "type": "dataModel", "props": { "data": {
+                    "name": "customer",
+                    "properties": [
+                        { "id": "p1", "name": "age", "type": "number", "array": false, "optional": false },
+                        { "id": "p2", "name": "income", "type": "number", "array": false, "optional": false },
+                        { "id": "p3", "name": "bureauScore", "type": "number", "array": false, "optional": false },
+                        { "id": "p4", "name": "debt", "type": "number", "array": false, "optional": false }
+                    ]
+                } } },
+                { "id": "m", "type": "match", "props": { "data": {
+                    "key": "customer.offer",
+                    "arms": [
+                        { "id": "a1", "condition": "customer.age >= 18 and customer.income >= customer.age * 100", "value": "'premium'" },
+                        { "id": "a2", "condition": "customer.age < 18 or customer.debt > 0", "value": "'none'" },
+                        { "id": "a3", "condition": "", "value": "customer.bureauScore >= 700 ? 'standard' : 'none'" }
+                    ]
+                } } }
+            ]
+        }))
+        .unwrap(),
+    );
+    ws
+}
+
+#[test]
+fn residual_conditions_substitute_known_properties() {
+    let ws = offer_workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(json!({ "customer": { "age": 30 } }), vec!["customer.offer"]),
+            true,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(
+        residual_of(&partial, "customer.offer"),
+        vec!["customer.income >= 3000", "customer.debt > 0"]
+    );
+    assert_eq!(
+        missing_of(&partial, "customer.offer"),
+        vec!["customer.bureauScore", "customer.debt", "customer.income"]
+    );
+}
+
+#[test]
+fn arms_ruled_out_by_known_properties_are_not_required() {
+    let ws = offer_workspace();
+    let partial = ws
+        .partial_evaluate(
+            &request(
+                json!({ "customer": { "age": 16, "income": 200 } }),
+                vec!["customer.offer"],
+            ),
+            true,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(
+        determined(&partial, "customer.offer"),
+        Some(json!("none")),
+        "{:?}",
+        partial.pending
+    );
+
+    let partial = ws
+        .partial_evaluate(
+            &request(
+                json!({ "customer": { "age": 30, "income": 200 } }),
+                vec!["customer.offer"],
+            ),
+            true,
+        )
+        .expect("partial evaluation ok");
+
+    assert_eq!(
+        residual_of(&partial, "customer.offer"),
+        vec!["customer.debt > 0"]
+    );
+    assert_eq!(
+        missing_of(&partial, "customer.offer"),
+        vec!["customer.bureauScore", "customer.debt"]
+    );
+}
@@ -0,0 +1,385 @@
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zen_engine::loader::MemoryLoader;
+use zen_engine::model::{DecisionContent, PolicyContent};
+use zen_engine::policy::{
+    EvaluateRequest, EvaluationError, PolicyWorkspace, PropertyRequest, PropertyResolver,
+};
+use zen_engine::{
+    DecisionEngine, EvaluationError as EngineError, EvaluationOptions, EvaluationSerializedOptions,
+};
+use zen_expression::variable::Variable;
+
+#[derive(Debug, Default)]
+struct BureauResolver {
+    requests: Mutex<Vec<PropertyRequest>>,
+    fail: bool,
+    delay: Option<Duration>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl BureauResolver {
+    fn paths(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.to_string())
+            .collect()
+    }
+}
+
+impl PropertyResolver for BureauResolver {
+    fn resolve(
+        &self,
+        request: PropertyRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Value>, String>> + Send + '_>> {
+        Box::pin(async move {
+            let path = request.path.clone();
+            let parent = request.parent.clone();
+            self.requests.lock().unwrap().push(request);
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if self.fail {
+                return Err("bureau unavailable".to_string());
+            }
+
+            Ok(match path.as_ref() {
+                "customer.bureauScore" => parent.get("id").map(|id| match id.as_str() {
+                    Some("c-1") => json!(720),
+                    _ => json!(510),
+                }),
+                _ => None,
+            })
+        })
+    }
+}
+
+fn policy_doc() -> Value {
+    json!({
+        "blocks": [
+            { "id": "dm", "type": "dataModel", "props": { "data": {
+                "name": "customer",
+                "properties": [
+                    { "id": "p1", "name": "id", "type": "string", "array": false, "optional": false },
+                    { "id": "p2", "name": "income", "type": "number", "array": false, "optional": false },
+                    { "id": "p3", "name": "bureauScore", "type": "number", "array": false, "optional": false },
+                    { "id": "p4", "name": "segment", "type": "string", "array": false, "optional": true }
+                ]
+            } } },
+            { "id": "m", "type": "match", "props": { "data": {
+                "key": "customer.approved",
+                "arms": [
+                    { "id": "a1", "condition": "customer.income >= 1000", "value": "true" },
+                    { "id": "a2", "condition": "", "value": "customer.bureauScore >= 700" }
+                ]
+            } } },
+            { "id": "e", "type": "expression", "props": { "data": {
+                "key": "customer.tier",
+                "value": "customer.approved ? (customer.segment ?? 'standard') : 'none'"
+            } } }
+        ]
+    })
+}
+
+fn workspace_with(resolver: Arc<BureauResolver>) -> PolicyWorkspace {
+    let mut ws = PolicyWorkspace::new();
+    ws.set_policy("p", serde_json::from_value(policy_doc()).unwrap());
+    ws.set_property_resolver(Some(resolver));
+    ws
+}
+
+fn request(input: Value, goals: Vec<&str>) -> EvaluateRequest {
+    EvaluateRequest {
+        policy_path: Arc::from("p"),
+        input: Variable::from(input),
+        goals: goals.into_iter().map(Arc::from).collect(),
+        trace: true,
+    }
+}
+
+#[tokio::test]
+async fn resolves_property_only_when_demanded() {
+    let resolver = Arc::new(BureauResolver::default());
+    let ws = workspace_with(resolver.clone());
+
+    let result = ws
+        .evaluate_async(&request(
+            json!({ "customer": { "id": "c-1", "income": 5000 } }),
+            vec!["customer.approved"],
+        ))
+        .await
+        .expect("evaluate ok");
+
+    let output: Value = result.output.into();
+    assert_eq!(output.pointer("/customer/approved"), Some(&json!(true)));
+    assert!(
+        resolver.paths().is_empty(),
+        "matched arm does not read the bureau score: {:?}",
+        resolver.paths()
+    );
+    assert!(result.trace.unwrap().resolved.is_empty());
+}
+
+#[tokio::test]
+async fn resolved_value_feeds_evaluation_and_trace() {
+    let resolver = Arc::new(BureauResolver::default());
+    let ws = workspace_with(resolver.clone());
+
+    let result = ws
+        .evaluate_async(&request(
+            json!({ "customer": { "id": "c-1", "income": 200 } }),
+            vec![],
+        ))
+        .await
+        .expect("evaluate ok");
+
+    let output: Value = result.output.into();
+    assert_eq!(output.pointer("/customer/approved"), Some(&json!(true)));
+    assert_eq!(output.pointer("/customer/bureauScore"), Some(&json!(720)));
+
+    let requests = resolver.requests.lock().unwrap().clone();
+    let bureau: Vec<_> = requests
+        .iter()
+        .filter(|r| r.path.as_ref() == "customer.bureauScore")
+        .collect();
+    assert_eq!(
+        bureau.len(),
+        1,
+        "resolved once per evaluation: {requests:?}"
+    );
+    assert_eq!(bureau[0].property.as_ref(), "customer.bureauScore");
+    assert_eq!(bureau[0].parent.get("id"), Some(&json!("c-1")));
+
+    let trace = result.trace.expect("trace");
+    let resolved: Vec<(String, Value)> = trace
+        .resolved
+        .iter()
+        .map(|r| (r.path.to_string(), r.value.clone().into()))
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![("customer.bureauScore".to_string(), json!(720))]
+    );
+}
+
+#[tokio::test]
+async fn unresolved_optional_property_is_requested_once() {
+    let resolver = Arc::new(BureauResolver::default());
+    let ws = workspace_with(resolver.clone());
+
+    let result = ws
+        .evaluate_async(&request(
+            json!({ "customer": { "id": "c-2", "income": 5000 } }),
+            vec!["customer.tier"],
+        ))
+        .await
+        .expect("evaluate ok");
+
+    let output: Value = result.output.into();
+    assert_eq!(output.pointer("/customer/tier"), Some(&json!("standard")));
+    assert_eq!(resolver.paths(), vec!["customer.segment".to_string()]);
+}
+
+#[tokio::test]
+async fn missing_inputs_of_a_pass_are_resolved_together() {
+    let resolver = Arc::new(BureauResolver {
+        delay: Some(Duration::from_millis(20)),
+        ..Default::default()
+    });
+    let mut ws = PolicyWorkspace::new();
+    let mut doc = policy_doc();
+    doc["blocks"].as_array_mut().unwrap().push(json!({
+        "id": "s", "type": "expression", "props": { "data": {
+            "key": "customer.summary",
+            "value": "(customer.segment ?? 'standard') + ':' + string(customer.bureauScore)"
+        } }
+    }));
+    ws.set_policy("p", serde_json::from_value(doc).unwrap());
+    ws.set_property_resolver(Some(resolver.clone()));
+
+    let result = ws
+        .evaluate_async(&request(
+            json!({ "customer": { "id": "c-1", "income": 5000 } }),
+            vec!["customer.summary"],
+        ))
+        .await
+        .expect("evaluate ok");
+
+    let output: Value = result.output.into();
+    assert_eq!(
+        output.pointer("/customer/summary"),
+        Some(&json!("standard:720"))
+    );
+    let mut paths = resolver.paths();
+    paths.sort();
+    assert_eq!(paths, vec!["customer.bureauScore", "customer.segment"]);
+    assert_eq!(resolver.max_in_flight.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn required_goal_inputs_may_come_from_resolver() {
+    let resolver = Arc::new(BureauResolver::default());
+    let ws = workspace_with(resolver.clone());
+    let req = request(
+        json!({ "customer": { "id": "c-2", "income": 200 } }),
+        vec!["customer.approved"],
+    );
+
+    let sync_err = ws.evaluate(&req).expect_err("sync evaluation lacks inputs");
+    assert!(matches!(
+        sync_err,
+        EvaluationError::MissingRequiredInputs { .. }
+    ));
+
+    let result = ws.evaluate_async(&req).await.expect("evaluate ok");
+    let output: Value = result.output.into();
+    assert_eq!(output.pointer("/customer/approved"), Some(&json!(false)));
+}
+
+#[tokio::test]
+async fn resolver_failure_surfaces_as_error() {
+    let resolver = Arc::new(BureauResolver {
+        fail: true,
+        ..Default::default()
+    });
+    let ws = workspace_with(resolver);
+
+    let err = ws
+        .evaluate_async(&request(
+            json!({ "customer": { "id": "c-1", "income": 200 } }),
+            vec![],
+        ))
+        .await
+        .expect_err("resolver failed");
+
+    match err {
+        EvaluationError::PropertyResolutionFailed { path, message, .. } => {
+            assert_eq!(path.as_ref(), "customer.bureauScore");
+            assert_eq!(message, "bureau unavailable");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn engine_uses_registered_resolver() {
+    let policy: zen_engine::policy::PolicyDocument =
+        serde_json::from_value(policy_doc()).expect("valid policy fixture");
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "policy",
+        DecisionContent::Policy(PolicyContent(Arc::new(policy))),
+    );
+
+    let resolver = Arc::new(BureauResolver::default());
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_property_resolver(Some(resolver.clone()));
+    let context = json!({ "customer": { "id": "c-1", "income": 200 } });
+
+    let response = engine
+        .evaluate_with_opts(
+            "policy",
+            context.clone().into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("evaluate ok");
+    let output: Value = response.result.into();
+    assert_eq!(output.pointer("/customer/approved"), Some(&json!(true)));
+
+    assert!(engine.compile().is_empty());
+    let response = engine
+        .evaluate("policy", context.into())
+        .await
+        .expect("precompiled evaluate ok");
+    let output: Value = response.result.into();
+    assert_eq!(output.pointer("/customer/approved"), Some(&json!(true)));
+    let bureau_requests = resolver
+        .paths()
+        .iter()
+        .filter(|p| p.as_str() == "customer.bureauScore")
+        .count();
+    assert_eq!(bureau_requests, 2, "cache is scoped to one evaluation");
+}
+
+#[tokio::test]
+async fn engine_policy_evaluation_respects_deadline() {
+    let policy: zen_engine::policy::PolicyDocument =
+        serde_json::from_value(policy_doc()).expect("valid policy fixture");
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add(
+        "policy",
+        DecisionContent::Policy(PolicyContent(Arc::new(policy))),
+    );
+
+    let resolver = Arc::new(BureauResolver {
+        delay: Some(Duration::from_millis(100)),
+        ..Default::default()
+    });
+    let engine = DecisionEngine::default()
+        .with_loader(loader)
+        .with_property_resolver(Some(resolver));
+    let context = json!({ "customer": { "id": "c-1", "income": 200 } });
+    let deadline = || Some(Instant::now() + Duration::from_millis(20));
+
+    // Exceeded while the resolver fetches the bureau score, stopping before the next block
+    let error = engine
+        .evaluate_with_opts(
+            "policy",
+            context.clone().into(),
+            EvaluationOptions {
+                deadline: deadline(),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("deadline exceeded");
+    assert!(
+        matches!(*error, EngineError::DeadlineExceeded { .. }),
+        "{error:?}"
+    );
+
+    let error = engine
+        .evaluate_serialized(
+            "policy",
+            context.clone().into(),
+            EvaluationSerializedOptions {
+                deadline: deadline(),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("deadline exceeded");
+    assert_eq!(error["type"], json!("DeadlineExceeded"));
+
+    assert!(engine.compile().is_empty());
+    let error = engine
+        .evaluate_with_opts(
+            "policy",
+            context.into(),
+            EvaluationOptions {
+                deadline: deadline(),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("precompiled deadline exceeded");
+    assert!(
+        matches!(*error, EngineError::DeadlineExceeded { .. }),
+        "{error:?}"
+    );
+}
@@ -242,7 +242,7 @@ async fn serialized_string_trace_for_policies() {
         .evaluate_serialized(
             "policy",
             json!({ "customer": { "age": 30 } }).into(),
-            options,
+            options.clone(),
         )
         .await
         .expect("evaluate ok");
@@ -352,3 +352,200 @@ async fn trace_serializes_for_policies() {
         "policy trace should serialize with `executions` field"
     );
 }
+
+fn optional_input_policy_json() -> serde_json::Value {
+    json!({
+        "blocks": [
+            {
+                "id": "dm",
+                "type": "dataModel",
+                "props": { "data": {
+                    "name": "customer",
+                    "properties": [
+                        { "id": "p1", "name": "age", "type": "number", "array": false, "optional": false },
+                        { "id": "p2", "name": "nickname", "type": "string", "array": false, "optional": true }
+                    ]
+                }},
+                "children": []
+            },
+            {
+                "id": "assert-adult",
+                "type": "assertion",
+                "props": { "data": {
+                    "output": "customer.adult",
+                    "conditions": [
+                        { "id": "c1", "expression": "customer.age >= 18", "operator": "and", "depth": 0 }
+                    ]
+                }},
+                "children": []
+            },
+            {
+                "id": "assert-greeted",
+                "type": "assertion",
+                "props": { "data": {
+                    "output": "customer.greeted",
+                    "conditions": [
+                        { "id": "c2", "expression": "customer.nickname != null", "operator": "and", "depth": 0 }
+                    ]
+                }},
+                "children": []
+            }
+        ]
+    })
+}
+
+fn graph_calling(node: serde_json::Value) -> DecisionContent {
+    serde_json::from_value(json!({
+        "nodes": [
+            { "id": "in", "type": "inputNode", "name": "request" },
+            node,
+            { "id": "out", "type": "outputNode", "name": "response" }
+        ],
+        "edges": [
+            { "id": "e1", "sourceId": "in", "targetId": "sub" },
+            { "id": "e2", "sourceId": "sub", "targetId": "out" }
+        ]
+    }))
+    .expect("valid graph fixture")
+}
+
+fn decision_node_graph(key: &str, goals: &[&str]) -> DecisionContent {
+    graph_calling(json!({
+        "id": "sub",
+        "type": "decisionNode",
+        "name": "policy",
+        "content": { "key": key, "goals": goals }
+    }))
+}
+
+#[tokio::test]
+async fn decision_node_evaluates_policy() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("base", make_policy_content(simple_policy_json()));
+    loader.add(
+        "policy",
+        make_policy_content(importing_policy_json("base", "canVote")),
+    );
+    loader.add("graph", decision_node_graph("policy", &[]));
+    let engine = engine_with(loader);
+
+    let result = engine
+        .evaluate_with_opts(
+            "graph",
+            json!({ "customer": { "age": 30 } }).into(),
+            EvaluationOptions {
+                trace: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("evaluate ok");
+
+    let result_json: serde_json::Value = result.result.into();
+    assert_eq!(result_json.pointer("/customer/isAdult"), Some(&json!(true)));
+    assert_eq!(result_json.pointer("/customer/canVote"), Some(&json!(true)));
+
+    let trace = serde_json::to_value(result.trace.expect("trace requested")).unwrap();
+    let policy_trace = trace
+        .pointer("/sub/traceData")
+        .expect("decision node trace");
+    assert!(policy_trace.pointer("/properties").is_some());
+    assert!(policy_trace.pointer("/executions").is_some());
+}
+
+#[tokio::test]
+async fn decision_node_passes_policy_goals() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("policy", make_policy_content(optional_input_policy_json()));
+    loader.add(
+        "greeted",
+        decision_node_graph("policy", &["customer.greeted"]),
+    );
+    loader.add("all", decision_node_graph("policy", &[]));
+    let engine = engine_with(loader);
+
+    let input = json!({ "customer": { "nickname": "Ann" } });
+    let greeted = engine
+        .evaluate("greeted", input.clone().into())
+        .await
+        .expect("goal only needs optional input");
+    let greeted_json: serde_json::Value = greeted.result.into();
+    assert_eq!(
+        greeted_json.pointer("/customer/greeted"),
+        Some(&json!(true))
+    );
+
+    let all = engine.evaluate("all", input.into()).await;
+    assert!(
+        matches!(
+            all.as_ref().map_err(|e| e.as_ref()),
+            Err(EvaluationError::NodeError { .. })
+        ),
+        "evaluating every output requires customer.age; got {all:?}",
+    );
+}
+
+#[tokio::test]
+async fn zen_evaluate_calls_policy() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("policy", make_policy_content(optional_input_policy_json()));
+    loader.add(
+        "graph",
+        graph_calling(json!({
+            "id": "sub",
+            "type": "functionNode",
+            "name": "function",
+            "content": {
+                "source": "import zen from 'zen';\nexport const handler = async (input) => {\n  const response = await zen.evaluate('policy', input, { goals: ['customer.greeted'], trace: true });\n  return { greeted: response.result.customer.greeted, traced: Array.isArray(response.trace.executions) };\n};"
+            }
+        })),
+    );
+    let engine = engine_with(loader);
+
+    let result = engine
+        .evaluate("graph", json!({ "customer": { "nickname": "Ann" } }).into())
+        .await
+        .expect("evaluate ok");
+
+    let result_json: serde_json::Value = result.result.into();
+    assert_eq!(result_json, json!({ "greeted": true, "traced": true }));
+}
+
+#[tokio::test]
+async fn policy_sub_decisions_count_towards_depth() {
+    let loader = Arc::new(MemoryLoader::default());
+    loader.add("policy", make_policy_content(simple_policy_json()));
+    loader.add("graph", decision_node_graph("policy", &[]));
+    let engine = engine_with(loader);
+
+    let input: serde_json::Value = json!({ "customer": { "age": 30 } });
+    let within = engine
+        .evaluate_with_opts(
+            "graph",
+            input.clone().into(),
+            EvaluationOptions {
+                max_depth: 2,
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(within.is_ok(), "{within:?}");
+
+    let exceeded = engine
+        .evaluate_with_opts(
+            "graph",
+            input.into(),
+            EvaluationOptions {
+                max_depth: 1,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("policy at depth 1 exceeds max_depth 1");
+    match exceeded.as_ref() {
+        EvaluationError::NodeError { source, .. } => {
+            assert_eq!(source.to_string(), "Depth limit exceeded")
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
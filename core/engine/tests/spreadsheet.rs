@@ -0,0 +1,254 @@
+use zen_engine::model::{DecisionNodeKind, DecisionTableContent, DecisionTableHitPolicy};
+use zen_engine::policy::DecisionTableDoc;
+use zen_engine::spreadsheet::{self, SpreadsheetError};
+
+use crate::support::load_test_data;
+
+mod support;
+
+const PRICING: &str = "\
+id,input,input,output,description
+,age,tier,rate,
+,customer.age,customer.tier,discount,
+,Age,Tier,Discount,
+,number,string,number,
+r1,>= 65,,0.2,Seniors
+r2,\"(17..65)\",\"'gold', 'platinum'\",0.1,\"Members, mid age\"
+r3,,,0,
+";
+
+fn table_content() -> DecisionTableContent {
+    let graph = load_test_data("8k.json");
+    graph
+        .nodes
+        .iter()
+        .find_map(|node| match &node.kind {
+            DecisionNodeKind::DecisionTableNode { content } => Some(content.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn csv_import_reads_header_and_rules() {
+    let import = spreadsheet::import_csv(PRICING).unwrap();
+    assert!(import.is_valid(), "{:?}", import.errors);
+
+    let table = import.table;
+    assert_eq!(table.inputs.len(), 2);
+    assert_eq!(table.inputs[0].id.as_ref(), "age");
+    assert_eq!(table.inputs[0].field.as_deref(), Some("customer.age"));
+    assert_eq!(table.inputs[0].column_type.as_deref(), Some("number"));
+    assert_eq!(table.inputs[1].column_type.as_deref(), Some("string"));
+    assert_eq!(table.outputs[0].column_type.as_deref(), Some("number"));
+
+    assert_eq!(table.rules.len(), 3);
+    let second = &table.rules[1];
+    assert_eq!(second.get("_id").map(|v| v.as_ref()), Some("r2"));
+    assert_eq!(
+        second.get("tier").map(|v| v.as_ref()),
+        Some("'gold', 'platinum'")
+    );
+    assert_eq!(
+        second.get("_description").map(|v| v.as_ref()),
+        Some("Members, mid age")
+    );
+    assert!(!table.rules[2].contains_key("_description"));
+}
+
+#[test]
+fn csv_import_reports_cell_coordinates() {
+    let csv = "\
+input,input,output
+,,
+customer.age,customer.tier,
+,,
+number,string,colour[]x
+'young',> 10,1 +
+< 10,'gold',
+";
+    let import = spreadsheet::import_csv(csv).unwrap();
+    let errors: Vec<(String, usize, usize)> = import
+        .errors
+        .iter()
+        .map(|e| (e.reference(), e.row, e.column))
+        .collect();
+
+    assert_eq!(
+        errors,
+        vec![
+            ("C3".to_string(), 3, 3),
+            ("C5".to_string(), 5, 3),
+            ("A6".to_string(), 6, 1),
+            ("B6".to_string(), 6, 2),
+            ("C6".to_string(), 6, 3),
+        ]
+    );
+    assert!(import.errors[2].message.contains("expected a number test"));
+    assert_eq!(import.table.rules.len(), 2);
+}
+
+#[test]
+fn csv_import_rejects_malformed_input() {
+    assert!(matches!(
+        spreadsheet::import_csv("input\nid\n"),
+        Err(SpreadsheetError::MissingHeader(2))
+    ));
+    assert!(matches!(
+        spreadsheet::import_csv("input\n\"open\n"),
+        Err(SpreadsheetError::Csv { row: 2, .. })
+    ));
+}
+
+#[test]
+fn csv_round_trip_preserves_large_table() {
+    let content = table_content();
+    let doc = DecisionTableDoc::from(&content);
+
+    let csv = spreadsheet::export_csv(&doc);
+    let import = spreadsheet::import_csv(&csv).unwrap();
+    assert!(import.is_valid(), "{:?}", &import.errors[..1]);
+
+    let mut restored = import.into_content();
+    restored.transform_attributes = content.transform_attributes.clone();
+    assert_eq!(restored, content);
+}
+
+#[test]
+fn csv_round_trip_keeps_column_types() {
+    let doc = spreadsheet::import_csv(PRICING).unwrap().table;
+    let csv = spreadsheet::export_csv(&doc);
+    assert_eq!(csv.lines().nth(4), Some(",number,string,number,"));
+
+    let import = spreadsheet::import_csv(&csv).unwrap();
+    assert!(import.is_valid(), "{:?}", import.errors);
+    assert_eq!(import.table.inputs, doc.inputs);
+    assert_eq!(import.table.outputs, doc.outputs);
+}
+
+#[test]
+fn xlsx_round_trip_preserves_table() {
+    let mut doc = spreadsheet::import_csv(PRICING).unwrap().table;
+    doc.hit_policy = DecisionTableHitPolicy::Collect;
+
+    let bytes = spreadsheet::export_xlsx(&doc).unwrap();
+    let import = spreadsheet::import_xlsx(&bytes).unwrap();
+    assert!(import.is_valid(), "{:?}", import.errors);
+
+    assert_eq!(
+        spreadsheet::export_csv(&import.table),
+        spreadsheet::export_csv(&doc)
+    );
+    assert_eq!(import.table.hit_policy, DecisionTableHitPolicy::Collect);
+
+    assert!(matches!(
+        spreadsheet::import_xlsx(b"not a zip"),
+        Err(SpreadsheetError::Xlsx(_))
+    ));
+}
+
+#[test]
+fn csv_import_reads_hit_policy_column() {
+    let with_hit_policy = |hit_policy: &str| -> String {
+        PRICING
+            .lines()
+            .enumerate()
+            .map(|(i, line)| match i {
+                0 => format!("hitPolicy,{line}\n"),
+                1 => format!("{hit_policy},{line}\n"),
+                _ => format!(",{line}\n"),
+            })
+            .collect()
+    };
+
+    let import = spreadsheet::import_csv(&with_hit_policy("collect")).unwrap();
+    assert!(import.is_valid(), "{:?}", import.errors);
+    assert_eq!(import.table.hit_policy, DecisionTableHitPolicy::Collect);
+    assert_eq!(import.table.inputs.len(), 2);
+
+    let import = spreadsheet::import_csv(&with_hit_policy("unique")).unwrap();
+    assert_eq!(import.errors.len(), 1, "{:?}", import.errors);
+}
+
+fn xlsx_package(sheet: &str, shared: &str) -> Vec<u8> {
+    let workbook = r#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Fees" sheetId="1" r:id="rId7"/></sheets></workbook>"#;
+    let relationships = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId7" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/fees.xml"/>
+</Relationships>"#;
+
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    {
+        let mut writer = zip::ZipWriter::new(&mut cursor);
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, xml) in [
+            ("xl/workbook.xml", workbook),
+            ("xl/_rels/workbook.xml.rels", relationships),
+            ("xl/sharedStrings.xml", shared),
+            ("xl/worksheets/fees.xml", sheet),
+        ] {
+            writer.start_file(name, options).unwrap();
+            std::io::Write::write_all(&mut writer, xml.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+    cursor.into_inner()
+}
+
+#[test]
+fn xlsx_import_reads_shared_strings_and_numbers() {
+    let sheet = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+<row r="3"><c r="A3" t="s"><v>2</v></c><c r="B3" t="s"><v>3</v></c></row>
+<row r="5"><c r="B5" t="s"><v>4</v></c></row>
+<row r="6"><c r="A6" t="s"><v>5</v></c><c r="B6"><v>12.5</v></c></row>
+<row r="8"><c r="B8" t="b"><v>1</v></c></row>
+</sheetData></worksheet>"#;
+    let shared = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<si><t>input</t></si><si><t>output</t></si><si><t>amount</t></si>
+<si><r><t>fee</t></r><r><t>.value</t></r></si><si><t>number</t></si><si><t>&gt; 100</t></si>
+</sst>"#;
+
+    let import = spreadsheet::import_xlsx(&xlsx_package(sheet, shared)).unwrap();
+    assert!(import.is_valid(), "{:?}", import.errors);
+    let table = import.table;
+    assert_eq!(table.inputs[0].field.as_deref(), Some("amount"));
+    assert_eq!(table.outputs[0].field.as_ref(), "fee.value");
+    assert_eq!(table.outputs[0].column_type.as_deref(), Some("number"));
+    assert_eq!(table.rules.len(), 2);
+    assert_eq!(
+        table.rules[0].get("input-1").map(|v| v.as_ref()),
+        Some("> 100")
+    );
+    assert_eq!(
+        table.rules[0].get("output-2").map(|v| v.as_ref()),
+        Some("12.5")
+    );
+    assert_eq!(
+        table.rules[1].get("output-2").map(|v| v.as_ref()),
+        Some("true")
+    );
+}
+
+#[test]
+fn xlsx_import_rejects_references_outside_the_sheet() {
+    let shared = r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"/>"#;
+    for sheet in [
+        r#"<worksheet><sheetData><row r="1048577"><c r="A1048577"><v>1</v></c></row></sheetData></worksheet>"#,
+        r#"<worksheet><sheetData><row r="99999999999999999999"/></sheetData></worksheet>"#,
+        r#"<worksheet><sheetData><row r="1"><c r="XFE1"><v>1</v></c></row></sheetData></worksheet>"#,
+        r#"<worksheet><sheetData><row r="1"><c r="ZZZZZZZZZZZZZZZ1"><v>1</v></c></row></sheetData></worksheet>"#,
+    ] {
+        assert!(
+            matches!(
+                spreadsheet::import_xlsx(&xlsx_package(sheet, shared)),
+                Err(SpreadsheetError::Xlsx(_))
+            ),
+            "{sheet}"
+        );
+    }
+}
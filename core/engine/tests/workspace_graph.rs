@@ -2,11 +2,13 @@ use std::sync::Arc;
 
 use serde_json::{json, Value};
 use zen_engine::model::DecisionContent;
+use zen_engine::nodes::custom::{CustomNodeDefinition, CustomNodeRegistry, NoopCustomNode};
 use zen_engine::policy::{
-    Cursor, CursorTarget, DiagnosticCode, EvaluateRequest, EvaluationError, ScopeRequest, Severity,
-    Workspace,
+    Cursor, CursorTarget, DiagnosticCode, EvaluateRequest, EvaluationError, GraphEdit,
+    GraphEditError, GraphEditor, ScopeRequest, Severity, Workspace,
 };
 use zen_expression::variable::VariableType;
+use zen_types::decision::DecisionNode;
 
 fn document(value: Value) -> DecisionContent {
     serde_json::from_value(value).expect("valid decision content")
@@ -254,6 +256,115 @@ fn function_node_taints_downstream() {
     assert!(!unchecked.contains(&"in"));
 }
 
+#[test]
+fn wasm_node_output_is_typed_by_declared_schema() {
+    let mut ws = Workspace::new();
+    let output_schema = json!({
+        "type": "object",
+        "properties": { "score": { "type": "number" } },
+        "required": ["score"]
+    });
+    let wasm = node(
+        "wasm",
+        "wasmNode",
+        json!({ "key": "wasm/score.wasm", "outputSchema": output_schema.to_string() }),
+    );
+    ws.set_document(
+        "g",
+        document(linear_graph(
+            Some(person_schema()),
+            vec![
+                wasm,
+                expression_node("after", &[("double", "score * 2"), ("x", "age + 1")]),
+            ],
+        )),
+    );
+    assert_eq!(
+        error_codes(&ws, "g"),
+        vec![DiagnosticCode::UndefinedVariable]
+    );
+    assert!(ws.unchecked_nodes("g").is_empty());
+
+    ws.set_document(
+        "g",
+        document(linear_graph(
+            Some(person_schema()),
+            vec![
+                node("wasm", "wasmNode", json!({ "key": "wasm/score.wasm" })),
+                expression_node("after", &[("x", "whatever + 1")]),
+            ],
+        )),
+    );
+    let diagnostics = ws.diagnostics("g");
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.code == DiagnosticCode::UncheckedNode),
+        "{diagnostics:?}"
+    );
+    let unchecked = ws.unchecked_nodes("g");
+    let unchecked: Vec<&str> = unchecked.iter().map(|s| s.as_ref()).collect();
+    assert!(unchecked.contains(&"after"));
+}
+
+#[test]
+fn custom_node_registry_types_outputs_and_checks_config() {
+    let registry = CustomNodeRegistry::default().with_node(
+        "score",
+        CustomNodeDefinition::new(Arc::new(NoopCustomNode))
+            .with_config_schema(json!({
+                "type": "object",
+                "properties": { "model": { "type": "string" } },
+                "required": ["model"]
+            }))
+            .with_output(&VariableType::from(json!({ "score": 1, "band": "A" }))),
+    );
+    let mut ws = Workspace::new();
+    ws.set_custom_nodes(Some(Arc::new(registry)));
+
+    let custom = |config: Value| {
+        node(
+            "custom",
+            "customNode",
+            json!({ "kind": "score", "config": config }),
+        )
+    };
+    ws.set_document(
+        "g",
+        document(linear_graph(
+            Some(person_schema()),
+            vec![
+                custom(json!({ "model": "v2" })),
+                expression_node("after", &[("double", "score * 2"), ("x", "missing + 1")]),
+            ],
+        )),
+    );
+    assert_eq!(
+        error_codes(&ws, "g"),
+        vec![DiagnosticCode::UndefinedVariable]
+    );
+    assert!(ws.unchecked_nodes("g").is_empty());
+
+    ws.set_document(
+        "g",
+        document(linear_graph(
+            Some(person_schema()),
+            vec![custom(json!({ "model": 2 }))],
+        )),
+    );
+    assert_eq!(
+        error_codes(&ws, "g"),
+        vec![DiagnosticCode::InvalidNodeConfig]
+    );
+
+    ws.set_custom_nodes(None);
+    assert!(error_codes(&ws, "g").is_empty());
+    assert!(ws
+        .diagnostics("g")
+        .iter()
+        .any(|d| d.code == DiagnosticCode::UncheckedNode));
+}
+
 #[test]
 fn parse_errors_still_reported_downstream_of_function() {
     let mut ws = Workspace::new();
@@ -484,6 +595,46 @@ fn transform_loop_iterates_elements() {
     );
 }
 
+#[test]
+fn transform_loop_collecting_errors_outputs_parallel_arrays() {
+    let mut ws = Workspace::new();
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "customers": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": { "age": { "type": "number" } },
+                    "required": ["age"]
+                }
+            }
+        },
+        "required": ["customers"]
+    });
+    let mut looped = expression_node("loop", &[("doubled", "age * 2")]);
+    looped["content"]["inputField"] = json!("customers");
+    looped["content"]["executionMode"] = json!("loop");
+    looped["content"]["outputPath"] = json!("checked");
+    looped["content"]["loopOptions"] = json!({ "concurrency": 4, "collectErrors": true });
+    ws.set_document("g", document(linear_graph(Some(schema), vec![looped])));
+    let diagnostics = ws.diagnostics("g");
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let outputs = ws.outputs(&ScopeRequest::for_policy("g"));
+    let checked = outputs
+        .iter()
+        .find(|o| o.path.as_ref() == "checked")
+        .expect("checked output");
+    for field in ["results", "errors"] {
+        assert!(
+            matches!(checked.resolved_type.get(field), VariableType::Array(_)),
+            "{field}: {:?}",
+            checked.resolved_type
+        );
+    }
+}
+
 #[test]
 fn transform_loop_over_non_array_is_reported() {
     let mut ws = Workspace::new();
@@ -1584,6 +1735,56 @@ fn request_and_push_flow_without_native_resolver() {
     assert!(ws.function_resolution_requests().is_empty());
 }
 
+#[test]
+fn requests_carry_imported_library_modules() {
+    let function = node(
+        "fn",
+        "functionNode",
+        json!({ "source": "import { total } from 'lib:totals.ts';\nexport const handler = async (input: { age: number }) => ({ total: total(input.age) });" }),
+    );
+    let graph = linear_graph(
+        Some(person_schema()),
+        vec![
+            function,
+            expression_node("after", &[("grand", "total + 1")]),
+        ],
+    );
+    let mut ws = Workspace::new();
+    ws.set_library(
+        "lib/totals.ts",
+        "import { factor } from './factor.ts';\nexport const total = (age: number): number => age * factor;",
+    );
+    ws.set_library("lib/factor.ts", "export const factor = 2;");
+    ws.set_library("lib/unused.ts", "export const unused = 1;");
+    ws.set_document("g", document(graph));
+
+    let requests = ws.function_resolution_requests();
+    assert_eq!(requests.len(), 1, "{requests:?}");
+    let keys: Vec<&str> = requests[0]
+        .libraries
+        .iter()
+        .map(|library| library.key.as_ref())
+        .collect();
+    assert_eq!(keys, vec!["lib/factor.ts", "lib/totals.ts"]);
+
+    ws.set_function_type(
+        &requests[0].source,
+        &requests[0].input,
+        Some("Promise<{ total: number }>"),
+    );
+    let outputs = ws.outputs(&ScopeRequest::for_policy("g"));
+    assert!(outputs.iter().any(|o| o.path.as_ref() == "grand"));
+    assert!(ws.function_resolution_requests().is_empty());
+
+    ws.set_library("lib/factor.ts", "export const factor = 3;");
+    let requests = ws.function_resolution_requests();
+    assert_eq!(requests.len(), 1, "{requests:?}");
+    assert!(requests[0]
+        .libraries
+        .iter()
+        .any(|library| library.source.contains("factor = 3")));
+}
+
 #[test]
 fn resolver_sees_dynamic_input_types() {
     use std::cell::RefCell as StdRefCell;
@@ -3347,3 +3548,163 @@ fn missing_member_through_local_names_the_member_not_the_alias() {
         "the specific member must be blamed, not the alias: {errors:?}"
     );
 }
+
+fn decision_node(value: Value) -> DecisionNode {
+    serde_json::from_value(value).expect("valid node")
+}
+
+#[test]
+fn graph_editor_builds_validated_graph() {
+    let mut graph = GraphEditor::default();
+    let edits = [
+        GraphEdit::AddNode(decision_node(node("in", "inputNode", json!({})))),
+        GraphEdit::AddNode(decision_node(expression_node(
+            "calc",
+            &[("total", "age * 2")],
+        ))),
+        GraphEdit::AddNode(decision_node(node("out", "outputNode", json!({})))),
+        GraphEdit::Connect {
+            source_id: "in".into(),
+            target_id: "calc".into(),
+            source_handle: None,
+        },
+        GraphEdit::Connect {
+            source_id: "calc".into(),
+            target_id: "out".into(),
+            source_handle: None,
+        },
+    ];
+    for edit in edits {
+        graph.apply(edit).expect("edit applies");
+    }
+
+    let cyclic = graph
+        .clone()
+        .with_edit(GraphEdit::Connect {
+            source_id: "calc".into(),
+            target_id: "in".into(),
+            source_handle: None,
+        })
+        .expect("edge applies")
+        .finish();
+    assert!(matches!(cyclic, Err(GraphEditError::InvalidGraph(_))));
+
+    let duplicate = graph
+        .clone()
+        .with_edit(GraphEdit::AddNode(decision_node(node(
+            "calc",
+            "outputNode",
+            json!({}),
+        ))));
+    assert!(matches!(duplicate, Err(GraphEditError::DuplicateNode(_))));
+
+    let content = graph.finish().expect("valid graph");
+    let edges: Vec<(&str, &str, &str)> = content
+        .edges
+        .iter()
+        .map(|e| (e.id.as_ref(), e.source_id.as_ref(), e.target_id.as_ref()))
+        .collect();
+    assert_eq!(
+        edges,
+        [("in-calc", "in", "calc"), ("calc-out", "calc", "out")]
+    );
+
+    let mut ws = Workspace::new();
+    ws.set_document("g", DecisionContent::Graph(Arc::new(content)));
+    assert!(error_codes(&ws, "g").is_empty());
+}
+
+#[test]
+fn edit_graph_moves_switch_branches_and_rewires_removed_nodes() {
+    let switch = node(
+        "sw",
+        "switchNode",
+        json!({
+            "hitPolicy": "first",
+            "statements": [
+                { "id": "s1", "condition": "age >= 18" },
+                { "id": "s2", "condition": "" }
+            ]
+        }),
+    );
+    let mut ws = Workspace::new();
+    ws.set_document(
+        "g",
+        document(json!({
+            "nodes": [
+                node("in", "inputNode", json!({ "schema": person_schema().to_string() })),
+                switch,
+                expression_node("adult", &[("adult", "true")]),
+                expression_node("minor", &[("adult", "false")]),
+                node("out", "outputNode", json!({}))
+            ],
+            "edges": [
+                edge("e1", "in", "sw"),
+                { "id": "e2", "sourceId": "sw", "targetId": "adult", "sourceHandle": "s1" },
+                { "id": "e3", "sourceId": "sw", "targetId": "minor", "sourceHandle": "s2" },
+                edge("e4", "adult", "out"),
+                edge("e5", "minor", "out")
+            ]
+        })),
+    );
+
+    let missing_handle = ws.edit_graph(
+        "g",
+        vec![GraphEdit::Connect {
+            source_id: "sw".into(),
+            target_id: "out".into(),
+            source_handle: None,
+        }],
+    );
+    assert!(matches!(
+        missing_handle,
+        Err(GraphEditError::MissingSourceHandle(_))
+    ));
+
+    let edits = ws
+        .edit_graph(
+            "g",
+            vec![
+                GraphEdit::MoveSwitchBranch {
+                    node_id: "sw".into(),
+                    statement_id: "s2".into(),
+                    index: 0,
+                },
+                GraphEdit::RemoveNode {
+                    node_id: "minor".into(),
+                    rewire: true,
+                },
+            ],
+        )
+        .expect("valid edit");
+    let rendered: Vec<Value> = edits
+        .iter()
+        .map(|e| serde_json::to_value(e).unwrap())
+        .collect();
+    let summary: Vec<(&str, &str)> = rendered
+        .iter()
+        .map(|e| {
+            let id = ["edgeId", "nodeId"]
+                .iter()
+                .find_map(|k| e[k].as_str())
+                .or_else(|| e["newEdge"]["id"].as_str())
+                .unwrap();
+            (e["kind"].as_str().unwrap(), id)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("deleteEdge", "e3"),
+            ("deleteEdge", "e5"),
+            ("deleteNode", "minor"),
+            ("replaceNode", "sw"),
+            ("insertEdge", "sw-s2-out"),
+        ]
+    );
+    assert_eq!(
+        rendered[3]["newNode"]["content"]["statements"][0]["id"],
+        json!("s2")
+    );
+    assert_eq!(rendered[4]["newEdge"]["sourceHandle"], json!("s2"));
+}
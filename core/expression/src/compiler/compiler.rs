@@ -1,31 +1,50 @@
 use crate::compiler::error::{CompilerError, CompilerResult};
 use crate::compiler::opcode::{FetchFastTarget, Jump};
-use crate::compiler::{Compare, Opcode};
+use crate::compiler::optimizer;
+use crate::compiler::{Compare, ConstantSet, ConstantValue, Opcode};
 use crate::functions::registry::FunctionRegistry;
 use crate::functions::{ClosureFunction, FunctionKind, InternalFunction, MethodRegistry};
 use crate::lexer::{ArithmeticOperator, ComparisonOperator, LogicalOperator, Operator};
 use crate::parser::Node;
+use crate::scope::Scope;
+use crate::variable::Variable;
+use crate::vm::VM;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct Compiler {
     bytecode: Vec<Opcode>,
+    optimize: bool,
 }
 
 impl Compiler {
     pub fn new() -> Self {
         Self {
             bytecode: Default::default(),
+            optimize: true,
         }
     }
 
+    /// Enables constant folding, branch pruning and hashed `in` lookups (default: enabled)
+    pub fn with_optimizations(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    pub fn set_optimizations(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
     pub fn compile(&mut self, root: &Node) -> CompilerResult<&[Opcode]> {
         self.bytecode.clear();
 
-        CompilerInner::new(&mut self.bytecode, root).compile()?;
+        CompilerInner::new(&mut self.bytecode, root, self.optimize).compile()?;
         Ok(self.bytecode.as_slice())
     }
 
@@ -39,14 +58,28 @@ struct CompilerInner<'arena, 'bytecode_ref> {
     root: &'arena Node<'arena>,
     bytecode: &'bytecode_ref mut Vec<Opcode>,
     closure_aliases: Vec<Option<&'arena str>>,
+    optimize: bool,
+    /// Values of the subtrees evaluated at compile time, shared with the compilers evaluating
+    /// their parents so that every subtree is evaluated once
+    constants: Rc<RefCell<HashMap<*const Node<'arena>, Option<Variable>>>>,
+    /// Set while compiling a subtree for evaluation, folded children are inlined regardless of
+    /// their size as the bytecode is discarded afterwards
+    evaluating: bool,
 }
 
 impl<'arena, 'bytecode_ref> CompilerInner<'arena, 'bytecode_ref> {
-    pub fn new(bytecode: &'bytecode_ref mut Vec<Opcode>, root: &'arena Node<'arena>) -> Self {
+    pub fn new(
+        bytecode: &'bytecode_ref mut Vec<Opcode>,
+        root: &'arena Node<'arena>,
+        optimize: bool,
+    ) -> Self {
         Self {
             root,
             bytecode,
             closure_aliases: Vec::new(),
+            optimize,
+            constants: Default::default(),
+            evaluating: false,
         }
     }
 
@@ -113,6 +146,108 @@ impl<'arena, 'bytecode_ref> CompilerInner<'arena, 'bytecode_ref> {
         self.bytecode.len() + 1 - to
     }
 
+    /// Evaluates a pure subtree at compile time. Failures are discarded so that the subtree
+    /// is compiled as-is and reports the same error during evaluation.
+    ///
+    /// Children are folded first and pushed as constants, so evaluating a subtree only runs its
+    /// own operators and nested constants are not evaluated again at every parent.
+    fn evaluate_constant(&self, node: &'arena Node<'arena>) -> Option<Variable> {
+        if !self.optimize {
+            return None;
+        }
+
+        let key = std::ptr::from_ref(node);
+        if let Some(value) = self.constants.borrow().get(&key) {
+            return value.clone();
+        }
+
+        let value = optimizer::is_constant(node)
+            .then(|| {
+                let mut bytecode = Vec::new();
+                let mut inner = CompilerInner::new(&mut bytecode, node, true);
+                inner.constants = self.constants.clone();
+                inner.evaluating = true;
+                inner.compile_unfolded(node).ok()?;
+
+                VM::for_folding()
+                    .run(bytecode.as_slice(), &Scope::default())
+                    .ok()
+            })
+            .flatten();
+
+        self.constants.borrow_mut().insert(key, value.clone());
+        value
+    }
+
+    fn fold_constant(&self, node: &'arena Node<'arena>) -> Option<Opcode> {
+        match node {
+            Node::Null | Node::Bool(_) | Node::Number(_) | Node::String(_) => return None,
+            // Closure bodies are evaluated once per element of the enclosing loop
+            Node::Closure { .. } => return None,
+            _ => {}
+        }
+
+        let value = self.evaluate_constant(node)?;
+        let constant = ConstantValue::from_variable(&value)?;
+        if !self.evaluating && constant.weight() > optimizer::node_count(node) {
+            return None;
+        }
+
+        Some(match constant {
+            ConstantValue::Null => Opcode::PushNull,
+            ConstantValue::Bool(b) => Opcode::PushBool(b),
+            ConstantValue::Number(n) => Opcode::PushNumber(n),
+            ConstantValue::String(s) => Opcode::PushString(s),
+            c => Opcode::PushConstant(Arc::new(c)),
+        })
+    }
+
+    fn constant_set(&self, node: &'arena Node<'arena>) -> Option<Arc<ConstantSet>> {
+        let Variable::Array(arr) = self.evaluate_constant(node)? else {
+            return None;
+        };
+
+        let set = ConstantSet::from_array(arr.borrow().as_slice());
+        if set.len() > optimizer::node_count(node) {
+            return None;
+        }
+
+        Some(Arc::new(set))
+    }
+
+    /// Compiles a branch that was pruned away, only to surface its compilation errors
+    fn validate(&self, node: &'arena Node<'arena>) -> CompilerResult<()> {
+        let mut bytecode = Vec::new();
+        CompilerInner::new(&mut bytecode, node, false).compile()
+    }
+
+    /// Short-circuits `or`, `and` and `??` whose left side is known at compile time
+    fn compile_pruned_logical(
+        &mut self,
+        operator: LogicalOperator,
+        left: &'arena Node<'arena>,
+        right: &'arena Node<'arena>,
+    ) -> CompilerResult<Option<usize>> {
+        let Some(value) = self.evaluate_constant(left) else {
+            return Ok(None);
+        };
+
+        let takes_left = match (operator, &value) {
+            (LogicalOperator::Or, Variable::Bool(b)) => *b,
+            (LogicalOperator::And, Variable::Bool(b)) => !*b,
+            (LogicalOperator::NullishCoalescing, v) => !matches!(v, Variable::Null),
+            _ => return Ok(None),
+        };
+
+        if takes_left {
+            let l = self.compile_node(left)?;
+            self.validate(right)?;
+            Ok(Some(l))
+        } else {
+            self.compile_node(right).map(Some)
+        }
+    }
+
     fn compile_argument<T: ToString>(
         &mut self,
         function_kind: T,
@@ -161,8 +296,16 @@ impl<'arena, 'bytecode_ref> CompilerInner<'arena, 'bytecode_ref> {
         }
     }
 
-    #[cfg_attr(not(target_family = "wasm"), recursive::recursive)]
     fn compile_node(&mut self, node: &'arena Node<'arena>) -> CompilerResult<usize> {
+        if let Some(op) = self.fold_constant(node) {
+            return Ok(self.emit(op));
+        }
+
+        self.compile_unfolded(node)
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), recursive::recursive)]
+    fn compile_unfolded(&mut self, node: &'arena Node<'arena>) -> CompilerResult<usize> {
         match node {
             Node::Null => Ok(self.emit(Opcode::PushNull)),
             Node::Bool(v) => Ok(self.emit(Opcode::PushBool(*v))),
@@ -282,6 +425,20 @@ impl<'arena, 'bytecode_ref> CompilerInner<'arena, 'bytecode_ref> {
                 on_true,
                 on_false,
             } => {
+                if let Some(Variable::Bool(b)) = self.evaluate_constant(condition) {
+                    return match b {
+                        true => {
+                            let t = self.compile_node(on_true)?;
+                            self.validate(on_false)?;
+                            Ok(t)
+                        }
+                        false => {
+                            self.validate(on_true)?;
+                            self.compile_node(on_false)
+                        }
+                    };
+                }
+
                 self.compile_node(condition)?;
                 let otherwise = self.emit(Opcode::Jump(Jump::IfFalse, 0));
 
@@ -316,112 +473,130 @@ impl<'arena, 'bytecode_ref> CompilerInner<'arena, 'bytecode_ref> {
                 left,
                 right,
                 operator,
-            } => match *operator {
-                Operator::Comparison(ComparisonOperator::Equal) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-
-                    Ok(self.emit(Opcode::Equal))
+            } => {
+                if let Operator::Logical(logical) = *operator {
+                    if let Some(r) = self.compile_pruned_logical(logical, left, right)? {
+                        return Ok(r);
+                    }
                 }
-                Operator::Comparison(ComparisonOperator::NotEqual) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
 
-                    self.emit(Opcode::Equal);
-                    Ok(self.emit(Opcode::Not))
-                }
-                Operator::Logical(LogicalOperator::Or) => {
-                    self.compile_node(left)?;
-                    let end = self.emit(Opcode::Jump(Jump::IfTrue, 0));
-                    self.emit(Opcode::Pop);
-                    let r = self.compile_node(right)?;
-                    self.replace(end, Opcode::Jump(Jump::IfTrue, (r - end) as u32));
-
-                    Ok(r)
-                }
-                Operator::Logical(LogicalOperator::And) => {
-                    self.compile_node(left)?;
-                    let end = self.emit(Opcode::Jump(Jump::IfFalse, 0));
-                    self.emit(Opcode::Pop);
-                    let r = self.compile_node(right)?;
-                    self.replace(end, Opcode::Jump(Jump::IfFalse, (r - end) as u32));
-
-                    Ok(r)
-                }
-                Operator::Logical(LogicalOperator::NullishCoalescing) => {
-                    self.compile_node(left)?;
-                    let end = self.emit(Opcode::Jump(Jump::IfNotNull, 0));
-                    self.emit(Opcode::Pop);
-                    let r = self.compile_node(right)?;
-                    self.replace(end, Opcode::Jump(Jump::IfNotNull, (r - end) as u32));
-
-                    Ok(r)
-                }
-                Operator::Comparison(ComparisonOperator::In) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::In))
-                }
-                Operator::Comparison(ComparisonOperator::NotIn) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    self.emit(Opcode::In);
-                    Ok(self.emit(Opcode::Not))
-                }
-                Operator::Comparison(ComparisonOperator::LessThan) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Compare(Compare::Less)))
-                }
-                Operator::Comparison(ComparisonOperator::LessThanOrEqual) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Compare(Compare::LessOrEqual)))
-                }
-                Operator::Comparison(ComparisonOperator::GreaterThan) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Compare(Compare::More)))
-                }
-                Operator::Comparison(ComparisonOperator::GreaterThanOrEqual) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Compare(Compare::MoreOrEqual)))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Add) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Add))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Subtract) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Subtract))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Multiply) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Multiply))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Divide) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Divide))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Modulus) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Modulo))
-                }
-                Operator::Arithmetic(ArithmeticOperator::Power) => {
-                    self.compile_node(left)?;
-                    self.compile_node(right)?;
-                    Ok(self.emit(Opcode::Exponent))
+                match *operator {
+                    Operator::Comparison(ComparisonOperator::Equal) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+
+                        Ok(self.emit(Opcode::Equal))
+                    }
+                    Operator::Comparison(ComparisonOperator::NotEqual) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+
+                        self.emit(Opcode::Equal);
+                        Ok(self.emit(Opcode::Not))
+                    }
+                    Operator::Logical(LogicalOperator::Or) => {
+                        self.compile_node(left)?;
+                        let end = self.emit(Opcode::Jump(Jump::IfTrue, 0));
+                        self.emit(Opcode::Pop);
+                        let r = self.compile_node(right)?;
+                        self.replace(end, Opcode::Jump(Jump::IfTrue, (r - end) as u32));
+
+                        Ok(r)
+                    }
+                    Operator::Logical(LogicalOperator::And) => {
+                        self.compile_node(left)?;
+                        let end = self.emit(Opcode::Jump(Jump::IfFalse, 0));
+                        self.emit(Opcode::Pop);
+                        let r = self.compile_node(right)?;
+                        self.replace(end, Opcode::Jump(Jump::IfFalse, (r - end) as u32));
+
+                        Ok(r)
+                    }
+                    Operator::Logical(LogicalOperator::NullishCoalescing) => {
+                        self.compile_node(left)?;
+                        let end = self.emit(Opcode::Jump(Jump::IfNotNull, 0));
+                        self.emit(Opcode::Pop);
+                        let r = self.compile_node(right)?;
+                        self.replace(end, Opcode::Jump(Jump::IfNotNull, (r - end) as u32));
+
+                        Ok(r)
+                    }
+                    Operator::Comparison(ComparisonOperator::In) => {
+                        self.compile_node(left)?;
+                        if let Some(set) = self.constant_set(right) {
+                            return Ok(self.emit(Opcode::InSet(set)));
+                        }
+
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::In))
+                    }
+                    Operator::Comparison(ComparisonOperator::NotIn) => {
+                        self.compile_node(left)?;
+                        match self.constant_set(right) {
+                            Some(set) => self.emit(Opcode::InSet(set)),
+                            None => {
+                                self.compile_node(right)?;
+                                self.emit(Opcode::In)
+                            }
+                        };
+
+                        Ok(self.emit(Opcode::Not))
+                    }
+                    Operator::Comparison(ComparisonOperator::LessThan) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Compare(Compare::Less)))
+                    }
+                    Operator::Comparison(ComparisonOperator::LessThanOrEqual) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Compare(Compare::LessOrEqual)))
+                    }
+                    Operator::Comparison(ComparisonOperator::GreaterThan) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Compare(Compare::More)))
+                    }
+                    Operator::Comparison(ComparisonOperator::GreaterThanOrEqual) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Compare(Compare::MoreOrEqual)))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Add) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Add))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Subtract) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Subtract))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Multiply) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Multiply))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Divide) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Divide))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Modulus) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Modulo))
+                    }
+                    Operator::Arithmetic(ArithmeticOperator::Power) => {
+                        self.compile_node(left)?;
+                        self.compile_node(right)?;
+                        Ok(self.emit(Opcode::Exponent))
+                    }
+                    _ => Err(CompilerError::UnknownBinaryOperator {
+                        operator: operator.to_string(),
+                    }),
                 }
-                _ => Err(CompilerError::UnknownBinaryOperator {
-                    operator: operator.to_string(),
-                }),
-            },
+            }
             Node::FunctionCall { kind, arguments } => match kind {
                 FunctionKind::Internal(_) | FunctionKind::Deprecated(_) => {
                     let function = FunctionRegistry::get_definition(kind).ok_or_else(|| {
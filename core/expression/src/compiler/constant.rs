@@ -0,0 +1,188 @@
+use crate::lexer::Bracket;
+use crate::variable::{Variable, VariableMap};
+use crate::vm::date::DynamicVariableExt;
+use crate::vm::interval::{VmInterval, VmIntervalData};
+use crate::vm::VmDate;
+use ahash::HashSet;
+use chrono::DateTime;
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use std::rc::Rc;
+use std::sync::Arc;
+use zen_types::symbol::Symbol;
+
+/// Value precomputed during compilation.
+///
+/// Constants are materialised into a fresh [`Variable`] every time they are pushed, so
+/// evaluations never share mutable state through the bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstantValue {
+    Null,
+    Bool(bool),
+    Number(Decimal),
+    String(Arc<str>),
+    Array(Arc<[ConstantValue]>),
+    Object(Arc<[(Arc<str>, ConstantValue)]>),
+    Date(Option<DateTime<Tz>>),
+    Interval {
+        left_bracket: Bracket,
+        right_bracket: Bracket,
+        left: Arc<ConstantValue>,
+        right: Arc<ConstantValue>,
+    },
+}
+
+impl ConstantValue {
+    pub(crate) fn from_variable(variable: &Variable) -> Option<Self> {
+        Some(match variable {
+            Variable::Null => Self::Null,
+            Variable::Bool(b) => Self::Bool(*b),
+            Variable::Number(n) => Self::Number(*n),
+            Variable::String(s) => Self::String(Arc::from(s.as_str())),
+            Variable::Array(a) => {
+                let arr = a.borrow();
+                Self::Array(arr.iter().map(Self::from_variable).collect::<Option<_>>()?)
+            }
+            Variable::Object(o) => {
+                let obj = o.borrow();
+                Self::Object(
+                    obj.iter()
+                        .map(|(k, v)| Some((Arc::from(k.as_str()), Self::from_variable(v)?)))
+                        .collect::<Option<_>>()?,
+                )
+            }
+            Variable::Dynamic(d) => {
+                if let Some(date) = d.as_date() {
+                    return Some(Self::Date(date.0));
+                }
+
+                let interval = d.as_any().downcast_ref::<VmInterval>()?;
+                let from_data = |data: &VmIntervalData| match data {
+                    VmIntervalData::Number(n) => Self::Number(*n),
+                    VmIntervalData::Date(d) => Self::Date(d.0),
+                };
+
+                Self::Interval {
+                    left_bracket: interval.left_bracket,
+                    right_bracket: interval.right_bracket,
+                    left: Arc::new(from_data(&interval.left)),
+                    right: Arc::new(from_data(&interval.right)),
+                }
+            }
+        })
+    }
+
+    pub(crate) fn to_variable(&self) -> Variable {
+        match self {
+            Self::Null => Variable::Null,
+            Self::Bool(b) => Variable::Bool(*b),
+            Self::Number(n) => Variable::Number(*n),
+            Self::String(s) => Variable::String(Symbol::from(s.as_ref())),
+            Self::Array(arr) => Variable::from_array(arr.iter().map(Self::to_variable).collect()),
+            Self::Object(obj) => {
+                let mut map = VariableMap::with_capacity(obj.len());
+                for (key, value) in obj.iter() {
+                    map.insert(Symbol::from(key.as_ref()), value.to_variable());
+                }
+
+                Variable::from_object(map)
+            }
+            Self::Date(d) => Variable::Dynamic(Rc::new(VmDate(*d))),
+            Self::Interval {
+                left_bracket,
+                right_bracket,
+                left,
+                right,
+            } => {
+                let to_data = |c: &ConstantValue| match c {
+                    ConstantValue::Date(d) => VmIntervalData::Date(VmDate(*d)),
+                    ConstantValue::Number(n) => VmIntervalData::Number(*n),
+                    _ => VmIntervalData::Number(Decimal::ZERO),
+                };
+
+                Variable::Dynamic(Rc::new(VmInterval {
+                    left_bracket: *left_bracket,
+                    right_bracket: *right_bracket,
+                    left: to_data(left),
+                    right: to_data(right),
+                }))
+            }
+        }
+    }
+
+    /// Number of values held by the constant, used to make sure folding never grows bytecode
+    pub(crate) fn weight(&self) -> usize {
+        match self {
+            Self::Array(arr) => 1 + arr.iter().map(Self::weight).sum::<usize>(),
+            Self::Object(obj) => 1 + obj.iter().map(|(_, v)| 1 + v.weight()).sum::<usize>(),
+            _ => 1,
+        }
+    }
+}
+
+/// Hashed lookup table for `in` checks against a constant array.
+///
+/// Membership follows the same rules as the `In` opcode: numbers, strings, booleans, null and
+/// dates are compared by value, every other array element can never match.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConstantSet {
+    numbers: HashSet<Decimal>,
+    strings: HashSet<Arc<str>>,
+    dates: HashSet<Option<DateTime<Tz>>>,
+    has_true: bool,
+    has_false: bool,
+    has_null: bool,
+}
+
+impl ConstantSet {
+    pub(crate) fn from_array(items: &[Variable]) -> Self {
+        let mut set = Self::default();
+        for item in items {
+            match item {
+                Variable::Null => set.has_null = true,
+                Variable::Bool(true) => set.has_true = true,
+                Variable::Bool(false) => set.has_false = true,
+                Variable::Number(n) => {
+                    set.numbers.insert(*n);
+                }
+                Variable::String(s) => {
+                    set.strings.insert(Arc::from(s.as_str()));
+                }
+                Variable::Dynamic(d) => {
+                    if let Some(date) = d.as_date() {
+                        set.dates.insert(date.0);
+                    }
+                }
+                Variable::Array(_) | Variable::Object(_) => {}
+            }
+        }
+
+        set
+    }
+
+    pub fn len(&self) -> usize {
+        self.numbers.len()
+            + self.strings.len()
+            + self.dates.len()
+            + [self.has_true, self.has_false, self.has_null]
+                .iter()
+                .filter(|b| **b)
+                .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `None` when `needle` is of a type that cannot be looked up in an array
+    pub(crate) fn contains(&self, needle: &Variable) -> Option<bool> {
+        match needle {
+            Variable::Null => Some(self.has_null),
+            Variable::Bool(b) => Some(if *b { self.has_true } else { self.has_false }),
+            Variable::Number(n) => Some(self.numbers.contains(n)),
+            Variable::String(s) => Some(self.strings.contains(s.as_str())),
+            Variable::Dynamic(d) => d.as_date().map(|date| self.dates.contains(&date.0)),
+            Variable::Array(_) | Variable::Object(_) => None,
+        }
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! The Compiler module transforms an Abstract Syntax Tree (AST) representation of source code into machine-readable opcodes.
 mod compiler;
+mod constant;
 mod error;
 mod opcode;
+mod optimizer;
 
 pub use compiler::Compiler;
+pub use constant::{ConstantSet, ConstantValue};
 pub use error::CompilerError;
 pub use opcode::{Compare, FetchFastTarget, Jump, Opcode};
@@ -1,3 +1,4 @@
+use crate::compiler::{ConstantSet, ConstantValue};
 use crate::functions::{FunctionKind, MethodKind};
 use crate::lexer::Bracket;
 use rust_decimal::Decimal;
@@ -19,6 +20,7 @@ pub enum Opcode {
     PushBool(bool),
     PushString(Arc<str>),
     PushNumber(Decimal),
+    PushConstant(Arc<ConstantValue>),
     Pop,
     Flatten,
     Join,
@@ -31,6 +33,8 @@ pub enum Opcode {
     Equal,
     Jump(Jump, u32),
     In,
+    /// `In` against a constant array, replacing the linear scan with a hashed lookup
+    InSet(Arc<ConstantSet>),
     Compare(Compare),
     Add,
     Subtract,
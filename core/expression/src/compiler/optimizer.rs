@@ -0,0 +1,104 @@
+use crate::functions::{DateMethod, FunctionKind, InternalFunction, MethodKind};
+use crate::parser::Node;
+use chrono_tz::Tz;
+use std::cell::Cell;
+use std::str::FromStr;
+
+/// Checks whether `node` evaluates to the same value regardless of the environment and the
+/// current time, which makes it safe to evaluate once during compilation.
+pub(crate) fn is_constant<'a>(node: &'a Node<'a>) -> bool {
+    ConstantAnalyzer::default().check(node)
+}
+
+pub(crate) fn node_count(node: &Node) -> usize {
+    let count = Cell::new(0);
+    node.walk(|_| count.set(count.get() + 1));
+    count.get()
+}
+
+#[derive(Default)]
+struct ConstantAnalyzer<'a> {
+    closure_aliases: Vec<Option<&'a str>>,
+}
+
+impl<'a> ConstantAnalyzer<'a> {
+    #[cfg_attr(not(target_family = "wasm"), recursive::recursive)]
+    fn check(&mut self, node: &'a Node<'a>) -> bool {
+        match node {
+            Node::Null | Node::Bool(_) | Node::Number(_) | Node::String(_) => true,
+            Node::Pointer => !self.closure_aliases.is_empty(),
+            Node::Identifier(name) => self.closure_aliases.contains(&Some(*name)),
+            Node::Root | Node::Assignments { .. } | Node::Error { .. } => false,
+            Node::TemplateString(parts) | Node::Array(parts) => {
+                parts.iter().all(|&n| self.check(n))
+            }
+            Node::Object(entries) => entries.iter().all(|&(k, v)| self.check(k) && self.check(v)),
+            Node::Closure { body, alias } => {
+                self.closure_aliases.push(*alias);
+                let result = self.check(body);
+                self.closure_aliases.pop();
+                result
+            }
+            Node::Parenthesized(n) => self.check(n),
+            Node::Member { node, property } => self.check(node) && self.check(property),
+            Node::Slice { node, from, to } => {
+                self.check(node)
+                    && from.is_none_or(|n| self.check(n))
+                    && to.is_none_or(|n| self.check(n))
+            }
+            Node::Interval { left, right, .. } => self.check(left) && self.check(right),
+            Node::Conditional {
+                condition,
+                on_true,
+                on_false,
+            } => self.check(condition) && self.check(on_true) && self.check(on_false),
+            Node::Unary { node, .. } => self.check(node),
+            Node::Binary { left, right, .. } => self.check(left) && self.check(right),
+            Node::FunctionCall { kind, arguments } => {
+                is_pure_function(kind, arguments) && arguments.iter().all(|&n| self.check(n))
+            }
+            Node::MethodCall {
+                kind,
+                this,
+                arguments,
+            } => {
+                is_pure_method(kind) && self.check(this) && arguments.iter().all(|&n| self.check(n))
+            }
+        }
+    }
+}
+
+fn is_pure_function(kind: &FunctionKind, arguments: &[&Node]) -> bool {
+    match kind {
+        FunctionKind::Internal(InternalFunction::Rand) => false,
+        // `d()` is the current time, and so is `d('Europe/Berlin')`
+        FunctionKind::Internal(InternalFunction::Date) => match arguments.first() {
+            Some(Node::Number(_)) => true,
+            Some(Node::String(s)) => Tz::from_str(s).is_err(),
+            _ => false,
+        },
+        FunctionKind::Internal(_) | FunctionKind::Closure(_) => true,
+        // Deprecated date helpers accept `'now'` anywhere a date is expected
+        FunctionKind::Deprecated(_) => arguments.iter().all(|arg| match arg {
+            Node::Number(_) => true,
+            Node::String(s) => *s != "now",
+            _ => false,
+        }),
+    }
+}
+
+fn is_pure_method(kind: &MethodKind) -> bool {
+    match kind {
+        MethodKind::DateMethod(dm) => !matches!(
+            dm,
+            DateMethod::IsToday
+                | DateMethod::IsYesterday
+                | DateMethod::IsTomorrow
+                | DateMethod::AddBusinessDays
+                | DateMethod::IsBusinessDay
+                | DateMethod::BusinessDaysBetween
+                | DateMethod::NextBusinessDay
+        ),
+        MethodKind::DurationMethod(_) => true,
+    }
+}
@@ -43,6 +43,12 @@ pub enum DateMethod {
     IsToday,
     IsTomorrow,
     IsLeapYear,
+
+    // Business days
+    AddBusinessDays,
+    IsBusinessDay,
+    BusinessDaysBetween,
+    NextBusinessDay,
 }
 
 enum CompareOperation {
@@ -86,6 +92,10 @@ impl From<&DateMethod> for Rc<dyn FunctionDefinition> {
                 parameters: vec![VT::Date, VT::String],
                 return_type: VT::Date,
             },
+            FunctionSignature {
+                parameters: vec![VT::Date, VT::Duration],
+                return_type: VT::Date,
+            },
             FunctionSignature {
                 parameters: vec![VT::Date, VT::Number, unit_vt.clone()],
                 return_type: VT::Date,
@@ -179,6 +189,35 @@ impl From<&DateMethod> for Rc<dyn FunctionDefinition> {
             DateMethod::IsToday => imp::getter(GetterOperation::IsToday),
             DateMethod::IsTomorrow => imp::getter(GetterOperation::IsTomorrow),
             DateMethod::IsLeapYear => imp::getter(GetterOperation::IsLeapYear),
+
+            DM::AddBusinessDays => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::add_business_days),
+                signature: FunctionSignature {
+                    parameters: vec![VT::Date, VT::Number, VT::String],
+                    return_type: VT::Date,
+                },
+            }),
+            DM::IsBusinessDay => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::is_business_day),
+                signature: FunctionSignature {
+                    parameters: vec![VT::Date, VT::String],
+                    return_type: VT::Bool,
+                },
+            }),
+            DM::BusinessDaysBetween => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::business_days_between),
+                signature: FunctionSignature {
+                    parameters: vec![VT::Date, VT::Date, VT::String],
+                    return_type: VT::Number,
+                },
+            }),
+            DM::NextBusinessDay => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::next_business_day),
+                signature: FunctionSignature {
+                    parameters: vec![VT::Date, VT::String],
+                    return_type: VT::Date,
+                },
+            }),
         }
     }
 }
@@ -190,11 +229,12 @@ mod imp {
         CompositeFunction, FunctionDefinition, FunctionSignature, StaticFunction,
     };
     use crate::variable::VariableType as VT;
-    use crate::vm::date::{Duration, DurationUnit};
+    use crate::vm::calendar::active_calendar;
+    use crate::vm::date::{Duration, DurationUnit, DynamicVariableExt};
     use crate::vm::VmDate;
     use crate::Variable as V;
     use anyhow::{anyhow, Context};
-    use chrono::{Datelike, Timelike};
+    use chrono::{DateTime, Datelike, Days, NaiveDate, Timelike};
     use chrono_tz::Tz;
     use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
     use rust_decimal::Decimal;
@@ -208,6 +248,10 @@ mod imp {
                 let unit = __internal_extract_duration_unit(args, from + 1)?;
                 Ok(Duration::from_unit(*n, unit).context("Invalid duration unit")?)
             }
+            V::Dynamic(d) => match d.as_duration() {
+                Some(duration) => Ok(duration.duration().clone()),
+                None => Err(anyhow!("Invalid duration arguments")),
+            },
             _ => Err(anyhow!("Invalid duration arguments")),
         }
     }
@@ -309,6 +353,62 @@ mod imp {
         Ok(V::Dynamic(Rc::new(this.tz(timezone))))
     }
 
+    /// Moves to another day keeping the time of day, `None` when it does not exist there
+    fn __internal_with_date(date_time: &DateTime<Tz>, date: NaiveDate) -> Option<DateTime<Tz>> {
+        let days = (date - date_time.date_naive()).num_days();
+        match u64::try_from(days) {
+            Ok(days) => date_time.checked_add_days(Days::new(days)),
+            Err(_) => date_time.checked_sub_days(Days::new(days.unsigned_abs())),
+        }
+    }
+
+    pub fn add_business_days(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDate>(0)?;
+        let days = args.number(1)?.to_i64().context("Invalid number of days")?;
+        let calendar = active_calendar(args.str(2)?)?;
+
+        let date_time = this.0.and_then(|dt| {
+            let date = calendar.add_business_days(dt.date_naive(), days)?;
+            __internal_with_date(&dt, date)
+        });
+        Ok(V::Dynamic(Rc::new(VmDate(date_time))))
+    }
+
+    pub fn next_business_day(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDate>(0)?;
+        let calendar = active_calendar(args.str(1)?)?;
+
+        let date_time = this.0.and_then(|dt| {
+            let date = calendar.next_business_day(dt.date_naive())?;
+            __internal_with_date(&dt, date)
+        });
+        Ok(V::Dynamic(Rc::new(VmDate(date_time))))
+    }
+
+    pub fn is_business_day(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDate>(0)?;
+        let calendar = active_calendar(args.str(1)?)?;
+
+        Ok(match this.0 {
+            Some(dt) => V::Bool(calendar.is_business_day(dt.date_naive())),
+            None => V::Null,
+        })
+    }
+
+    pub fn business_days_between(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDate>(0)?;
+        let other = VmDate::new(args.var(1)?.clone(), None);
+        let calendar = active_calendar(args.str(2)?)?;
+
+        Ok(match (this.0, other.0) {
+            (Some(a), Some(b)) => {
+                let to = b.with_timezone(&a.timezone()).date_naive();
+                V::Number(calendar.business_days_between(a.date_naive(), to).into())
+            }
+            _ => V::Null,
+        })
+    }
+
     pub fn compare_using(op: CompareOperation) -> Rc<dyn FunctionDefinition> {
         Rc::new(CompositeFunction {
             signatures: vec![
@@ -174,6 +174,15 @@ mod imp {
         let a = args.var(0)?;
 
         let dur = match a {
+            V::String(a)
+                if a.as_str()
+                    .trim_start_matches(['-', '+'])
+                    .starts_with(['P', 'p']) =>
+            {
+                return Err(anyhow!(
+                    "duration() returns seconds and does not accept ISO-8601, use dur('{a}') for a duration value"
+                ));
+            }
             V::String(a) => humantime::parse_duration(a.as_ref())?.as_secs(),
             V::Number(n) => n.to_u64().context("Number overflow")?,
             _ => return Err(anyhow!("Unsupported type for duration function")),
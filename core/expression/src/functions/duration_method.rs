@@ -0,0 +1,123 @@
+use crate::functions::defs::{FunctionDefinition, FunctionSignature, StaticFunction};
+use crate::vm::date::DurationUnit;
+use std::rc::Rc;
+use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
+
+#[derive(Debug, PartialEq, Eq, Hash, Display, EnumString, EnumIter, IntoStaticStr, Clone, Copy)]
+#[strum(serialize_all = "camelCase")]
+pub enum DurationMethod {
+    Total,
+    ToIso,
+    Negated,
+    Abs,
+
+    // Getters
+    Years,
+    Months,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+enum GetterOperation {
+    Years,
+    Months,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl From<&DurationMethod> for Rc<dyn FunctionDefinition> {
+    fn from(value: &DurationMethod) -> Self {
+        use crate::variable::VariableType as VT;
+        use DurationMethod as DM;
+
+        match value {
+            DM::Total => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::total),
+                signature: FunctionSignature {
+                    parameters: vec![VT::Duration, DurationUnit::variable_type()],
+                    return_type: VT::Number,
+                },
+            }),
+            DM::ToIso => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::to_iso),
+                signature: FunctionSignature::single(VT::Duration, VT::String),
+            }),
+            DM::Negated => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::negated),
+                signature: FunctionSignature::single(VT::Duration, VT::Duration),
+            }),
+            DM::Abs => Rc::new(StaticFunction {
+                implementation: Rc::new(imp::abs),
+                signature: FunctionSignature::single(VT::Duration, VT::Duration),
+            }),
+
+            DM::Years => imp::getter(GetterOperation::Years),
+            DM::Months => imp::getter(GetterOperation::Months),
+            DM::Days => imp::getter(GetterOperation::Days),
+            DM::Hours => imp::getter(GetterOperation::Hours),
+            DM::Minutes => imp::getter(GetterOperation::Minutes),
+            DM::Seconds => imp::getter(GetterOperation::Seconds),
+        }
+    }
+}
+
+mod imp {
+    use crate::functions::arguments::Arguments;
+    use crate::functions::defs::{FunctionDefinition, FunctionSignature, StaticFunction};
+    use crate::functions::duration_method::GetterOperation;
+    use crate::variable::VariableType as VT;
+    use crate::vm::date::DurationUnit;
+    use crate::vm::VmDuration;
+    use crate::Variable as V;
+    use anyhow::Context;
+    use rust_decimal::Decimal;
+    use std::rc::Rc;
+
+    pub fn total(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDuration>(0)?;
+        let unit = DurationUnit::parse(args.str(1)?).context("Invalid duration unit")?;
+
+        let total = this.duration().total(unit).context("Duration overflow")?;
+        Ok(V::Number(total.normalize()))
+    }
+
+    pub fn to_iso(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDuration>(0)?;
+        Ok(V::String(this.to_string().into()))
+    }
+
+    pub fn negated(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDuration>(0)?;
+        Ok(V::Dynamic(Rc::new(this.negated())))
+    }
+
+    pub fn abs(args: Arguments) -> anyhow::Result<V> {
+        let this = args.dynamic::<VmDuration>(0)?;
+        Ok(V::Dynamic(Rc::new(this.abs())))
+    }
+
+    pub fn getter(op: GetterOperation) -> Rc<dyn FunctionDefinition> {
+        Rc::new(StaticFunction {
+            signature: FunctionSignature::single(VT::Duration, VT::Number),
+            implementation: Rc::new(move |args: Arguments| -> anyhow::Result<V> {
+                let duration = args.dynamic::<VmDuration>(0)?.duration();
+                let day = DurationUnit::Day.as_secs().unwrap_or_default() as i64;
+
+                let value = match op {
+                    GetterOperation::Years => duration.years as i64,
+                    GetterOperation::Months => duration.months as i64,
+                    GetterOperation::Days => duration.seconds / day,
+                    GetterOperation::Hours => duration.seconds % day / 3600,
+                    GetterOperation::Minutes => duration.seconds % 3600 / 60,
+                    GetterOperation::Seconds => duration.seconds % 60,
+                };
+
+                Ok(V::Number(Decimal::from(value)))
+            }),
+        })
+    }
+}
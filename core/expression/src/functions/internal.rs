@@ -1,6 +1,8 @@
 use crate::functions::defs::{
     CompositeFunction, FunctionDefinition, FunctionSignature, StaticFunction,
 };
+use crate::vm::date::DurationUnit;
+use crate::vm::RoundingMode;
 use std::rc::Rc;
 use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
 
@@ -52,6 +54,10 @@ pub enum InternalFunction {
 
     #[strum(serialize = "d")]
     Date,
+    /// Named `dur` because `duration` is the deprecated helper returning seconds, which existing
+    /// rules still call
+    #[strum(serialize = "dur")]
+    Duration,
 }
 
 impl From<&InternalFunction> for Rc<dyn FunctionDefinition> {
@@ -206,6 +212,10 @@ impl From<&InternalFunction> for Rc<dyn FunctionDefinition> {
                         parameters: vec![VT::Number, VT::Number],
                         return_type: VT::Number,
                     },
+                    FunctionSignature {
+                        parameters: vec![VT::Number, VT::Number, RoundingMode::variable_type()],
+                        return_type: VT::Number,
+                    },
                 ],
             }),
 
@@ -317,6 +327,18 @@ impl From<&InternalFunction> for Rc<dyn FunctionDefinition> {
                     },
                 ],
             }),
+
+            IF::Duration => Rc::new(CompositeFunction {
+                implementation: Rc::new(imp::duration),
+                signatures: vec![
+                    FunctionSignature::single(VT::String, VT::Duration),
+                    FunctionSignature::single(VT::Duration, VT::Duration),
+                    FunctionSignature {
+                        parameters: vec![VT::Number, DurationUnit::variable_type()],
+                        return_type: VT::Duration,
+                    },
+                ],
+            }),
         };
 
         s
@@ -326,7 +348,8 @@ impl From<&InternalFunction> for Rc<dyn FunctionDefinition> {
 pub(crate) mod imp {
     use crate::functions::arguments::Arguments;
     use crate::vm::date::DynamicVariableExt;
-    use crate::vm::VmDate;
+    use crate::vm::date::{Duration, DurationUnit};
+    use crate::vm::{RoundingMode, VmDate, VmDuration};
     use crate::{Variable as V, Variable};
     use anyhow::{anyhow, Context};
     use chrono_tz::Tz;
@@ -335,7 +358,7 @@ pub(crate) mod imp {
     #[cfg(feature = "regex-lite")]
     use regex_lite::Regex;
     use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
-    use rust_decimal::{Decimal, RoundingStrategy};
+    use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
     use std::collections::BTreeMap;
     use std::rc::Rc;
@@ -603,11 +626,13 @@ pub(crate) mod imp {
             .map(|v| v.to_u32().context("Invalid number of decimal places"))
             .transpose()?
             .unwrap_or(0);
+        let mode = args
+            .ostr(2)?
+            .map(|v| RoundingMode::from_str(v).context("Invalid rounding mode"))
+            .transpose()?
+            .unwrap_or_default();
 
-        Ok(V::Number(a.round_dp_with_strategy(
-            dp,
-            RoundingStrategy::MidpointAwayFromZero,
-        )))
+        Ok(V::Number(mode.round(a, dp)))
     }
 
     pub fn trunc(args: Arguments) -> anyhow::Result<V> {
@@ -918,4 +943,21 @@ pub(crate) mod imp {
 
         Ok(V::Dynamic(Rc::new(date_time)))
     }
+
+    pub fn duration(args: Arguments) -> anyhow::Result<V> {
+        let value = args.var(0)?;
+        let unit = args
+            .ostr(1)?
+            .map(|unit| DurationUnit::parse(unit).context("Invalid duration unit"))
+            .transpose()?;
+
+        let duration = match value {
+            V::String(s) => VmDuration::from(Duration::parse(s)?),
+            V::Number(_) => VmDuration::new(value, Some(unit.context("Missing duration unit")?))
+                .context("Invalid duration")?,
+            _ => VmDuration::new(value, unit).context("Invalid duration")?,
+        };
+
+        Ok(V::Dynamic(Rc::new(duration)))
+    }
 }
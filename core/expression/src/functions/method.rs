@@ -1,5 +1,6 @@
 use crate::functions::date_method::DateMethod;
 use crate::functions::defs::FunctionDefinition;
+use crate::functions::duration_method::DurationMethod;
 use nohash_hasher::{BuildNoHashHasher, IsEnabled};
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -8,17 +9,21 @@ use std::rc::Rc;
 use strum::IntoEnumIterator;
 
 impl IsEnabled for DateMethod {}
+impl IsEnabled for DurationMethod {}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum MethodKind {
     DateMethod(DateMethod),
+    DurationMethod(DurationMethod),
 }
 
 impl TryFrom<&str> for MethodKind {
     type Error = strum::ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        DateMethod::try_from(value).map(MethodKind::DateMethod)
+        DateMethod::try_from(value)
+            .map(MethodKind::DateMethod)
+            .or_else(|_| DurationMethod::try_from(value).map(MethodKind::DurationMethod))
     }
 }
 
@@ -26,12 +31,15 @@ impl Display for MethodKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             MethodKind::DateMethod(d) => write!(f, "{d}"),
+            MethodKind::DurationMethod(d) => write!(f, "{d}"),
         }
     }
 }
 
 pub struct MethodRegistry {
     date_methods: HashMap<DateMethod, Rc<dyn FunctionDefinition>, BuildNoHashHasher<DateMethod>>,
+    duration_methods:
+        HashMap<DurationMethod, Rc<dyn FunctionDefinition>, BuildNoHashHasher<DurationMethod>>,
 }
 
 impl MethodRegistry {
@@ -44,6 +52,9 @@ impl MethodRegistry {
             MethodKind::DateMethod(dm) => {
                 Self::INSTANCE.with_borrow(|i| i.date_methods.get(&dm).cloned())
             }
+            MethodKind::DurationMethod(dm) => {
+                Self::INSTANCE.with_borrow(|i| i.duration_methods.get(dm).cloned())
+            }
         }
     }
 
@@ -52,6 +63,11 @@ impl MethodRegistry {
             .map(|i| (i.clone(), (&i).into()))
             .collect();
 
-        Self { date_methods }
+        let duration_methods = DurationMethod::iter().map(|i| (i, (&i).into())).collect();
+
+        Self {
+            date_methods,
+            duration_methods,
+        }
     }
 }
@@ -1,6 +1,7 @@
 pub use crate::functions::date_method::DateMethod;
 pub use crate::functions::defs::FunctionTypecheck;
 pub use crate::functions::deprecated::DeprecatedFunction;
+pub use crate::functions::duration_method::DurationMethod;
 pub use crate::functions::internal::InternalFunction;
 pub use crate::functions::method::{MethodKind, MethodRegistry};
 pub use crate::functions::registry::FunctionRegistry;
@@ -12,6 +13,7 @@ pub(crate) mod arguments;
 mod date_method;
 pub(crate) mod defs;
 mod deprecated;
+mod duration_method;
 pub(crate) mod internal;
 mod method;
 pub(crate) mod registry;
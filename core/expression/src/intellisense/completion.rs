@@ -1,8 +1,8 @@
 use crate::functions::registry::FunctionRegistry;
-use crate::functions::DateMethod;
 use crate::functions::{
     ClosureFunction, DeprecatedFunction, FunctionKind, InternalFunction, MethodKind, MethodRegistry,
 };
+use crate::functions::{DateMethod, DurationMethod};
 use crate::intellisense::IntelliSenseToken;
 use crate::variable::VariableType;
 use serde::Serialize;
@@ -85,7 +85,10 @@ impl Completions {
             }
         }
 
-        for mk in DateMethod::iter().map(MethodKind::DateMethod) {
+        let methods = DateMethod::iter()
+            .map(MethodKind::DateMethod)
+            .chain(DurationMethod::iter().map(MethodKind::DurationMethod));
+        for mk in methods {
             let def = MethodRegistry::get_definition(&mk);
             let applies = def
                 .as_ref()
@@ -249,7 +252,9 @@ fn function_info(fk: &FunctionKind) -> String {
             InternalFunction::Mode => "Finds the mode(s) of the input array",
             InternalFunction::Floor => "Rounds a number down to the nearest integer",
             InternalFunction::Ceil => "Rounds a number up to the nearest integer",
-            InternalFunction::Round => "Rounds a number to a specified number of decimal places",
+            InternalFunction::Round => {
+                "Rounds a number to a specified number of decimal places and rounding mode"
+            }
             InternalFunction::Trunc => "Truncates a number to a specified number of decimal places",
             InternalFunction::IsNumeric => "Checks if the given value is of a numeric type",
             InternalFunction::String => "Converts the given value to a string",
@@ -263,6 +268,7 @@ fn function_info(fk: &FunctionKind) -> String {
                 "Returns an array of a given object's own enumerable property values"
             }
             InternalFunction::Date => "Returns a new date time instance",
+            InternalFunction::Duration => "Returns a new duration instance",
             InternalFunction::Merge => "Merges multiple objects into one",
             InternalFunction::MergeDeep => "Deeply merges multiple objects into one",
         },
@@ -336,7 +342,8 @@ fn function_param_names(fk: &FunctionKind) -> Vec<&'static str> {
             | InternalFunction::Median
             | InternalFunction::Mode => vec!["arr"],
             InternalFunction::Rand => vec!["max"],
-            InternalFunction::Round | InternalFunction::Trunc => vec!["num", "digits"],
+            InternalFunction::Round => vec!["num", "digits", "mode"],
+            InternalFunction::Trunc => vec!["num", "digits"],
             InternalFunction::IsNumeric
             | InternalFunction::String
             | InternalFunction::Number
@@ -344,6 +351,7 @@ fn function_param_names(fk: &FunctionKind) -> Vec<&'static str> {
             | InternalFunction::Type => vec!["value"],
             InternalFunction::Keys | InternalFunction::Values => vec!["obj"],
             InternalFunction::Date => vec!["dateOrTimezone", "timezone"],
+            InternalFunction::Duration => vec!["durationOrAmount", "unit"],
             InternalFunction::Merge | InternalFunction::MergeDeep => vec!["objects"],
         },
         FunctionKind::Deprecated(d) => match d {
@@ -446,6 +454,22 @@ fn method_info(mk: &MethodKind) -> String {
             DateMethod::IsToday => "Checks if a date is today",
             DateMethod::IsTomorrow => "Checks if a date is tomorrow",
             DateMethod::IsLeapYear => "Checks if the year is a leap year",
+            DateMethod::AddBusinessDays => "Adds business days using a registered calendar",
+            DateMethod::IsBusinessDay => "Checks if a date is a business day in a calendar",
+            DateMethod::BusinessDaysBetween => "Counts business days until another date",
+            DateMethod::NextBusinessDay => "Returns the next business day in a calendar",
+        },
+        MethodKind::DurationMethod(dm) => match dm {
+            DurationMethod::Total => "Returns the length of a duration in the given unit",
+            DurationMethod::ToIso => "Formats a duration as an ISO-8601 string",
+            DurationMethod::Negated => "Returns the duration with its sign flipped",
+            DurationMethod::Abs => "Returns the absolute duration",
+            DurationMethod::Years => "Gets the years of a duration",
+            DurationMethod::Months => "Gets the months of a duration",
+            DurationMethod::Days => "Gets the days of a duration",
+            DurationMethod::Hours => "Gets the hours of a duration",
+            DurationMethod::Minutes => "Gets the minutes of a duration",
+            DurationMethod::Seconds => "Gets the seconds of a duration",
         },
     };
     s.to_string()
@@ -465,6 +489,13 @@ fn method_param_names(mk: &MethodKind) -> Vec<&'static str> {
             | DateMethod::IsAfter
             | DateMethod::IsSameOrBefore
             | DateMethod::IsSameOrAfter => vec!["otherDate", "unit"],
+            DateMethod::AddBusinessDays => vec!["days", "calendar"],
+            DateMethod::IsBusinessDay | DateMethod::NextBusinessDay => vec!["calendar"],
+            DateMethod::BusinessDaysBetween => vec!["otherDate", "calendar"],
+            _ => vec![],
+        },
+        MethodKind::DurationMethod(dm) => match dm {
+            DurationMethod::Total => vec!["unit"],
             _ => vec![],
         },
     }
@@ -178,7 +178,7 @@ impl IntelliSense {
 
         let dep_result = DependencyResolutionWalker::walk(ast, &metadata);
 
-        let mut compiler = Compiler::new();
+        let mut compiler = Compiler::new().with_optimizations(false);
         if let Err(err) = compiler.compile(ast) {
             diagnostics.push(compiler_error_to_diagnostic(&err));
         }
@@ -536,7 +536,7 @@ impl IntelliSense {
 
         let dep_result = DependencyResolutionWalker::walk_with_locals(ast, &metadata, &["$"]);
 
-        let mut compiler = Compiler::new();
+        let mut compiler = Compiler::new().with_optimizations(false);
         if let Err(err) = compiler.compile(ast) {
             diagnostics.push(compiler_error_to_diagnostic(&err));
         }
@@ -307,15 +307,31 @@ impl TypesProvider {
                         ArithmeticOperator::Add => match (left_type.widen(), right_type.widen()) {
                             (VariableType::Number, VariableType::Number) => V(VariableType::Number),
                             (VariableType::String, VariableType::String) => V(VariableType::String),
-                            (VariableType::Any, VariableType::Number | VariableType::String | VariableType::Any) => V(VariableType::Any),
-                            (VariableType::Number | VariableType::String, VariableType::Any) => V(VariableType::Any),
+                            (VariableType::Date, VariableType::Duration) | (VariableType::Duration, VariableType::Date) => V(VariableType::Date),
+                            (VariableType::Duration, VariableType::Duration) => V(VariableType::Duration),
+                            (VariableType::Any, VariableType::Number | VariableType::String | VariableType::Date | VariableType::Duration | VariableType::Any) => V(VariableType::Any),
+                            (VariableType::Number | VariableType::String | VariableType::Date | VariableType::Duration, VariableType::Any) => V(VariableType::Any),
                             _ => Error(format!(
                                 "Operator `{operator}` cannot be applied to types `{left_type}` and `{right_type}`."
                             )),
                         },
-                        ArithmeticOperator::Subtract
-                        | ArithmeticOperator::Multiply
-                        | ArithmeticOperator::Divide
+                        ArithmeticOperator::Subtract => match (left_type.deref(), right_type.deref()) {
+                            (VariableType::Date, VariableType::Duration) => V(VariableType::Date),
+                            (VariableType::Date, VariableType::Date) | (VariableType::Duration, VariableType::Duration) => V(VariableType::Duration),
+                            (VariableType::Date | VariableType::Duration, VariableType::Any) | (VariableType::Any, VariableType::Date | VariableType::Duration) => V(VariableType::Any),
+                            (VariableType::Number | VariableType::Any, VariableType::Number | VariableType::Any) => V(VariableType::Number),
+                            _ => Error(format!(
+                                "Operator `{operator}` cannot be applied to types `{left_type}` and `{right_type}`."
+                            )),
+                        },
+                        ArithmeticOperator::Multiply => match (left_type.deref(), right_type.deref()) {
+                            (VariableType::Duration, VariableType::Number | VariableType::Any) | (VariableType::Number | VariableType::Any, VariableType::Duration) => V(VariableType::Duration),
+                            (VariableType::Number | VariableType::Any, VariableType::Number | VariableType::Any) => V(VariableType::Number),
+                            _ => Error(format!(
+                                "Operator `{operator}` cannot be applied to types `{left_type}` and `{right_type}`."
+                            )),
+                        },
+                        ArithmeticOperator::Divide
                         | ArithmeticOperator::Modulus
                         | ArithmeticOperator::Power => match (left_type.deref(), right_type.deref()) {
                             (VariableType::Number | VariableType::Any, VariableType::Number | VariableType::Any) => V(VariableType::Number),
@@ -398,6 +414,7 @@ impl TypesProvider {
                         | ComparisonOperator::LessThanOrEqual
                         | ComparisonOperator::GreaterThanOrEqual => match (left_type.deref(), right_type.deref()) {
                             (VariableType::Date | VariableType::Any, VariableType::Date | VariableType::Any) => V(VariableType::Bool),
+                            (VariableType::Duration | VariableType::Any, VariableType::Duration | VariableType::Any) => V(VariableType::Bool),
                             (VariableType::Number | VariableType::Any, VariableType::Number | VariableType::Any) => V(VariableType::Bool),
                             _ => Error(format!(
                                 "Operator `{operator}` cannot be applied to types `{left_type}` and `{right_type}`."
@@ -459,6 +476,11 @@ impl TypesProvider {
 
                 match operator {
                     Operator::Arithmetic(arith) => match arith {
+                        ArithmeticOperator::Subtract
+                            if matches!(node_type.deref(), VariableType::Duration) =>
+                        {
+                            V(VariableType::Duration)
+                        }
                         ArithmeticOperator::Add | ArithmeticOperator::Subtract => {
                             if !node_type.satisfies(&VariableType::Number) {
                                 self.set_error(node, format!("Operator `{operator}` cannot be applied to type `{node_type}`."))
@@ -10,7 +10,7 @@ use crate::lexer::{Lexer, LexerError};
 use crate::parser::{Parser, ParserError};
 use crate::scope::Scope;
 use crate::variable::Variable;
-use crate::vm::{VMError, VM};
+use crate::vm::{CalendarRegistry, InterruptHandler, NumericContext, VMError, VM};
 use crate::{Expression, ExpressionKind};
 use bumpalo::Bump;
 use zen_types::symbol::Symbol;
@@ -60,6 +60,27 @@ impl Isolate {
         self
     }
 
+    /// Toggles compile-time optimizations, see [`Compiler::with_optimizations`]
+    pub fn with_optimizations(mut self, optimize: bool) -> Self {
+        self.compiler.set_optimizations(optimize);
+        self
+    }
+
+    /// Installs a handler polled on every closure iteration, see [`InterruptHandler`]
+    pub fn set_interrupt_handler(&mut self, handler: Option<InterruptHandler>) {
+        self.vm.set_interrupt_handler(handler);
+    }
+
+    /// Calendars looked up by name in business-day date methods, see [`CalendarRegistry`]
+    pub fn set_calendars(&mut self, calendars: Option<Arc<CalendarRegistry>>) {
+        self.vm.set_calendars(calendars);
+    }
+
+    /// Scale and rounding applied to division results, see [`NumericContext`]
+    pub fn set_numeric_context(&mut self, numeric: NumericContext) {
+        self.vm.set_numeric_context(numeric);
+    }
+
     pub fn set_environment(&mut self, variable: Variable) {
         self.scope.set_base(variable);
         self.references.clear();
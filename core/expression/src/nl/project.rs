@@ -876,6 +876,7 @@ impl<'a> Projector<'a> {
             VariableType::String | VariableType::Const(_) => TypeTag::String,
             VariableType::Bool => TypeTag::Bool,
             VariableType::Date => TypeTag::Date,
+            VariableType::Duration => TypeTag::Duration,
             VariableType::Interval => TypeTag::Interval,
             VariableType::Object(_) => TypeTag::Object,
             VariableType::Null => TypeTag::Null,
@@ -89,6 +89,7 @@ pub enum TypeTag {
     String,
     Bool,
     Date,
+    Duration,
     Interval,
     Object,
     Null,
@@ -1,5 +1,6 @@
 use crate::functions::{
-    ClosureFunction, DateMethod, DeprecatedFunction, FunctionKind, InternalFunction, MethodKind,
+    ClosureFunction, DateMethod, DeprecatedFunction, DurationMethod, FunctionKind,
+    InternalFunction, MethodKind,
 };
 use crate::lexer::{Bracket, ComparisonOperator, Identifier, LogicalOperator, Operator, TokenKind};
 use crate::parser::ast::{AstNodeError, Node};
@@ -378,6 +379,7 @@ impl From<&Node<'_>> for UnaryNodeBehaviour {
                     InternalFunction::Values => CompareWithReference(In),
                     InternalFunction::Type => CompareWithReference(Equal),
                     InternalFunction::Date => CompareWithReference(Equal),
+                    InternalFunction::Duration => CompareWithReference(Equal),
                 },
                 FunctionKind::Deprecated(d) => match d {
                     DeprecatedFunction::Date => CompareWithReference(Equal),
@@ -438,6 +440,22 @@ impl From<&Node<'_>> for UnaryNodeBehaviour {
                     DateMethod::IsToday => AsBoolean,
                     DateMethod::IsTomorrow => AsBoolean,
                     DateMethod::IsLeapYear => AsBoolean,
+                    DateMethod::AddBusinessDays => CompareWithReference(Equal),
+                    DateMethod::NextBusinessDay => CompareWithReference(Equal),
+                    DateMethod::BusinessDaysBetween => CompareWithReference(Equal),
+                    DateMethod::IsBusinessDay => AsBoolean,
+                },
+                MethodKind::DurationMethod(dm) => match dm {
+                    DurationMethod::Total => CompareWithReference(Equal),
+                    DurationMethod::ToIso => CompareWithReference(Equal),
+                    DurationMethod::Negated => CompareWithReference(Equal),
+                    DurationMethod::Abs => CompareWithReference(Equal),
+                    DurationMethod::Years => CompareWithReference(Equal),
+                    DurationMethod::Months => CompareWithReference(Equal),
+                    DurationMethod::Days => CompareWithReference(Equal),
+                    DurationMethod::Hours => CompareWithReference(Equal),
+                    DurationMethod::Minutes => CompareWithReference(Equal),
+                    DurationMethod::Seconds => CompareWithReference(Equal),
                 },
             },
             Node::Error { .. } => AsBoolean,
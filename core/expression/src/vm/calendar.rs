@@ -0,0 +1,232 @@
+use ahash::HashMap;
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ops::Bound;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CalendarError {
+    #[error("Invalid weekday {0}, expected 1 (Monday) to 7 (Sunday)")]
+    InvalidWeekday(u8),
+
+    #[error("Invalid holiday '{0}', expected YYYY-MM-DD")]
+    InvalidHoliday(String),
+
+    #[error("Calendar has no business days")]
+    NoBusinessDays,
+
+    #[error("Invalid calendars: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Business-day calendar made of weekend days and a list of holidays.
+///
+/// Deserializes from `{ "weekend": [6, 7], "holidays": ["2025-12-25"] }`, weekdays numbered
+/// from Monday like `date.weekday()`. The weekend defaults to Saturday and Sunday.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "CalendarDefinition")]
+pub struct Calendar {
+    weekend: [bool; 7],
+    holidays: BTreeSet<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalendarDefinition {
+    #[serde(default = "CalendarDefinition::default_weekend")]
+    weekend: Vec<u8>,
+    #[serde(default)]
+    holidays: Vec<String>,
+}
+
+impl CalendarDefinition {
+    fn default_weekend() -> Vec<u8> {
+        vec![6, 7]
+    }
+}
+
+impl TryFrom<CalendarDefinition> for Calendar {
+    type Error = CalendarError;
+
+    fn try_from(value: CalendarDefinition) -> Result<Self, Self::Error> {
+        let weekend = value
+            .weekend
+            .into_iter()
+            .map(|day| {
+                day.checked_sub(1)
+                    .and_then(|index| Weekday::try_from(index).ok())
+                    .ok_or(CalendarError::InvalidWeekday(day))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let holidays = value
+            .holidays
+            .into_iter()
+            .map(|holiday| {
+                NaiveDate::parse_from_str(&holiday, "%Y-%m-%d")
+                    .map_err(|_| CalendarError::InvalidHoliday(holiday))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Calendar::new(weekend, holidays)
+    }
+}
+
+impl Calendar {
+    pub fn new(
+        weekend: impl IntoIterator<Item = Weekday>,
+        holidays: impl IntoIterator<Item = NaiveDate>,
+    ) -> Result<Self, CalendarError> {
+        let mut weekend_days = [false; 7];
+        for day in weekend {
+            weekend_days[day.num_days_from_monday() as usize] = true;
+        }
+
+        if weekend_days.iter().all(|w| *w) {
+            return Err(CalendarError::NoBusinessDays);
+        }
+
+        Ok(Self {
+            weekend: weekend_days,
+            holidays: holidays.into_iter().collect(),
+        })
+    }
+
+    pub fn is_weekend(&self, date: NaiveDate) -> bool {
+        self.weekend[date.weekday().num_days_from_monday() as usize]
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.is_weekend(date) && !self.is_holiday(date)
+    }
+
+    /// First business day strictly after `date`
+    pub fn next_business_day(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.add_business_days(date, 1)
+    }
+
+    /// Date `days` business days away from `date`, counting backwards when negative
+    pub fn add_business_days(&self, date: NaiveDate, days: i64) -> Option<NaiveDate> {
+        let step = days.signum();
+        let per_week = self.weekend.iter().filter(|w| !**w).count() as i64;
+        let mut remaining = days.abs();
+        let mut current = date;
+
+        while remaining > 0 {
+            // Whole weeks never cover every remaining day, so the last steps land on a business day
+            let weeks = (remaining - 1) / per_week;
+            if weeks > 0 {
+                let target = current.checked_add_signed(TimeDelta::try_days(weeks * 7 * step)?)?;
+                remaining -= match step {
+                    1 => self.business_days_between(current, target),
+                    _ => self.business_days_between(target.pred_opt()?, current.pred_opt()?),
+                };
+                current = target;
+                continue;
+            }
+
+            current = current.checked_add_signed(TimeDelta::days(step))?;
+            if self.is_business_day(current) {
+                remaining -= 1;
+            }
+        }
+
+        Some(current)
+    }
+
+    /// Business days after `from` up to and including `to`, negative when `to` comes first
+    pub fn business_days_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        if to < from {
+            return -self.business_days_between(to, from);
+        }
+
+        let days = (to - from).num_days();
+        let per_week = self.weekend.iter().filter(|w| !**w).count() as i64;
+        let tail_start = from.weekday().num_days_from_monday() as i64 + days / 7 * 7;
+        let tail = (1..=days % 7)
+            .filter(|offset| !self.weekend[((tail_start + offset) % 7) as usize])
+            .count() as i64;
+        let holidays = self
+            .holidays
+            .range((Bound::Excluded(from), Bound::Included(to)))
+            .filter(|holiday| !self.is_weekend(**holiday))
+            .count() as i64;
+
+        days / 7 * per_week + tail - holidays
+    }
+}
+
+/// Named calendars available to the business-day date methods.
+///
+/// Deserializes from an object keyed by calendar name, e.g. `{ "DE": { "holidays": [...] } }`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct CalendarRegistry {
+    calendars: HashMap<Arc<str>, Arc<Calendar>>,
+}
+
+impl CalendarRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CalendarError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn insert(&mut self, name: impl Into<Arc<str>>, calendar: Calendar) {
+        self.calendars.insert(name.into(), Arc::new(calendar));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<Calendar>> {
+        self.calendars.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.calendars.keys().map(|name| name.as_ref())
+    }
+}
+
+thread_local!(
+    static ACTIVE_CALENDARS: RefCell<Option<Arc<CalendarRegistry>>> = const { RefCell::new(None) }
+);
+
+/// Makes a registry visible to methods for the duration of a VM run, restoring the previous
+/// one on drop
+pub(crate) struct CalendarScope {
+    previous: Option<Arc<CalendarRegistry>>,
+}
+
+impl CalendarScope {
+    pub(crate) fn enter(registry: &Arc<CalendarRegistry>) -> Self {
+        let previous = ACTIVE_CALENDARS.with(|active| active.replace(Some(registry.clone())));
+        Self { previous }
+    }
+}
+
+impl Drop for CalendarScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        ACTIVE_CALENDARS.with(|active| active.replace(previous));
+    }
+}
+
+pub(crate) fn active_calendar(name: &str) -> anyhow::Result<Arc<Calendar>> {
+    ACTIVE_CALENDARS.with(|active| {
+        let active = active.borrow();
+        let registry = active
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No calendars registered"))?;
+
+        registry
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown calendar '{name}'"))
+    })
+}
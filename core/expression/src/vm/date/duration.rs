@@ -1,10 +1,28 @@
-use crate::vm::date::duration_parser::{DurationParseError, DurationParser};
+use crate::vm::date::duration_parser::{DurationParseError, DurationParser, IsoDurationParser};
 use crate::vm::date::duration_unit::DurationUnit;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
+use std::fmt::Write;
 use std::ops::Neg;
 
-#[derive(Debug, Clone, Default)]
+/// Days in a calendar month when months are converted to fixed units
+const MONTH_DAYS: i64 = 30;
+/// Days in a calendar year when years are converted to fixed units
+const YEAR_DAYS: i64 = 365;
+
+/// Fewest and most days spanned by `months` consecutive calendar months, `months` below 12
+fn month_span_days(months: usize) -> (i64, i64) {
+    const MONTH_LENGTHS: [i64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+    (0..12).fold((i64::MAX, 0), |(min, max), start| {
+        let span = start..start + months;
+        let days: i64 = span.clone().map(|month| MONTH_LENGTHS[month % 12]).sum();
+        let leap_day = span.clone().any(|month| month % 12 == 1) as i64;
+        (min.min(days), max.max(days + leap_day))
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub(crate) struct Duration {
     pub seconds: i64,
     pub months: i32,
@@ -12,7 +30,16 @@ pub(crate) struct Duration {
 }
 
 impl Duration {
+    /// Parses both ISO-8601 durations (`P1DT2H`, `-PT30M`) and the short form (`1d 2h`)
     pub fn parse(s: &str) -> Result<Self, DurationParseError> {
+        let trimmed = s.trim();
+        if trimmed
+            .trim_start_matches(['-', '+'])
+            .starts_with(['P', 'p'])
+        {
+            return IsoDurationParser::new(trimmed).parse();
+        }
+
         DurationParser {
             iter: s.chars(),
             src: s,
@@ -60,4 +87,128 @@ impl Duration {
             ..Default::default()
         }
     }
+
+    /// Months carried over into years, so equal durations have equal fields
+    pub fn normalized(self) -> Self {
+        let months = self.total_months();
+        Self {
+            years: (months / 12) as i32,
+            months: (months % 12) as i32,
+            seconds: self.seconds,
+        }
+    }
+
+    pub fn total_months(&self) -> i64 {
+        self.years as i64 * 12 + self.months as i64
+    }
+
+    /// Length in seconds, counting a month as 30 days and a year as 365 days
+    pub fn approx_seconds(&self) -> i128 {
+        let day = DurationUnit::Day.as_secs().unwrap_or_default() as i128;
+        self.seconds as i128
+            + self.months as i128 * MONTH_DAYS as i128 * day
+            + self.years as i128 * YEAR_DAYS as i128 * day
+    }
+
+    /// Shortest and longest length in seconds, as months span 28 to 31 days and years 365 or 366
+    /// depending on the date they are applied to
+    pub fn seconds_range(&self) -> (i128, i128) {
+        let day = DurationUnit::Day.as_secs().unwrap_or_default() as i128;
+        let months = self.total_months();
+        let (years, rest) = (months.abs() / 12, (months.abs() % 12) as usize);
+        let (rest_min, rest_max) = month_span_days(rest);
+
+        let min = (years as i128 * 365 + rest_min as i128) * day;
+        let max = (years as i128 * 366 + rest_max as i128) * day;
+        let (min, max) = match months < 0 {
+            true => (-max, -min),
+            false => (min, max),
+        };
+        (self.seconds as i128 + min, self.seconds as i128 + max)
+    }
+
+    /// Length expressed in `unit`, with fractions
+    pub fn total(&self, unit: DurationUnit) -> Option<Decimal> {
+        let day = DurationUnit::Day.as_secs()?;
+        let unit_secs = match unit {
+            DurationUnit::Month => day * MONTH_DAYS as u64,
+            DurationUnit::Quarter => day * MONTH_DAYS as u64 * 3,
+            DurationUnit::Year => day * YEAR_DAYS as u64,
+            unit => unit.as_secs()?,
+        };
+
+        Decimal::from_i128(self.approx_seconds())?.checked_div(Decimal::from_u64(unit_secs)?)
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            seconds: self.seconds.checked_add(other.seconds)?,
+            months: self.months.checked_add(other.months)?,
+            years: self.years.checked_add(other.years)?,
+        })
+    }
+
+    /// Scales every component, calendar components only by factors that keep them whole
+    pub fn checked_mul(&self, factor: Decimal) -> Option<Self> {
+        let scale = |n: Decimal| -> Option<Decimal> {
+            let scaled = n.checked_mul(factor)?;
+            scaled.fract().is_zero().then_some(scaled)
+        };
+
+        Some(Self {
+            seconds: Decimal::from(self.seconds)
+                .checked_mul(factor)?
+                .round()
+                .to_i64()?,
+            months: scale(Decimal::from(self.months))?.to_i32()?,
+            years: scale(Decimal::from(self.years))?.to_i32()?,
+        })
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.approx_seconds() < 0
+    }
+
+    /// ISO-8601 representation, `-` prefixed when every component is negative
+    pub fn to_iso(&self) -> String {
+        let duration = self.clone().normalized();
+        if duration.years <= 0
+            && duration.months <= 0
+            && duration.seconds <= 0
+            && duration != Self::default()
+        {
+            return format!("-{}", duration.negate().to_iso());
+        }
+
+        let day = DurationUnit::Day.as_secs().unwrap_or_default() as i64;
+        let days = duration.seconds / day;
+        let rest = duration.seconds % day;
+        let (hours, minutes, seconds) = (rest / 3600, rest % 3600 / 60, rest % 60);
+
+        let mut iso = String::from("P");
+        for (value, designator) in [
+            (duration.years as i64, 'Y'),
+            (duration.months as i64, 'M'),
+            (days, 'D'),
+        ] {
+            if value != 0 {
+                let _ = write!(iso, "{value}{designator}");
+            }
+        }
+
+        if hours != 0 || minutes != 0 || seconds != 0 {
+            iso.push('T');
+            for (value, designator) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+                if value != 0 {
+                    let _ = write!(iso, "{value}{designator}");
+                }
+            }
+        }
+
+        if iso.len() == 1 {
+            iso.push_str("T0S");
+        }
+
+        iso
+    }
 }
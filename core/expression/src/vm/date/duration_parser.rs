@@ -142,3 +142,125 @@ impl DurationParser<'_> {
         }
     }
 }
+
+/// Parser for ISO-8601 durations: `[-]P[nY][nM][nW][nD][T[nH][nM][nS]]`, where every component
+/// may carry its own sign
+pub(crate) struct IsoDurationParser<'a> {
+    src: &'a str,
+    pos: usize,
+    duration: Duration,
+}
+
+impl<'a> IsoDurationParser<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            pos: 0,
+            duration: Duration::default(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn sign(&mut self) -> i64 {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                -1
+            }
+            Some('+') => {
+                self.pos += 1;
+                1
+            }
+            _ => 1,
+        }
+    }
+
+    fn number(&mut self) -> Result<i64, DurationParseError> {
+        let sign = self.sign();
+        let start = self.pos;
+        let mut n: i64 = 0;
+        while let Some(c @ '0'..='9') = self.peek() {
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(c as i64 - '0' as i64))
+                .ok_or(DurationParseError::NumberOverflow)?;
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(DurationParseError::NumberExpected(start));
+        }
+
+        Ok(sign * n)
+    }
+
+    fn add_seconds(&mut self, n: i64, unit: DurationUnit) -> Result<(), DurationParseError> {
+        let secs = unit
+            .as_secs()
+            .and_then(|s| s.to_i64())
+            .ok_or(DurationParseError::NumberOverflow)?;
+        self.duration.seconds = n
+            .checked_mul(secs)
+            .and_then(|s| self.duration.seconds.checked_add(s))
+            .ok_or(DurationParseError::NumberOverflow)?;
+        Ok(())
+    }
+
+    pub fn parse(mut self) -> Result<Duration, DurationParseError> {
+        let sign = self.sign();
+        if !matches!(self.peek(), Some('P' | 'p')) {
+            return Err(DurationParseError::InvalidCharacter(self.pos));
+        }
+        self.pos += 1;
+
+        let mut time = false;
+        let mut components = 0;
+        while let Some(c) = self.peek() {
+            if matches!(c, 'T' | 't') && !time {
+                time = true;
+                self.pos += 1;
+                continue;
+            }
+
+            let start = self.pos;
+            let n = self.number()?;
+            let unit_start = self.pos;
+            let unit = self
+                .peek()
+                .ok_or(DurationParseError::InvalidCharacter(self.pos))?;
+            self.pos += unit.len_utf8();
+            let overflow = DurationParseError::NumberOverflow;
+
+            match (time, unit.to_ascii_uppercase()) {
+                (false, 'Y') => self.duration.years = n.to_i32().ok_or(overflow)?,
+                (false, 'M') => self.duration.months = n.to_i32().ok_or(overflow)?,
+                (false, 'W') => self.add_seconds(n, DurationUnit::Week)?,
+                (false, 'D') => self.add_seconds(n, DurationUnit::Day)?,
+                (true, 'H') => self.add_seconds(n, DurationUnit::Hour)?,
+                (true, 'M') => self.add_seconds(n, DurationUnit::Minute)?,
+                (true, 'S') => self.add_seconds(n, DurationUnit::Second)?,
+                _ => {
+                    return Err(DurationParseError::UnknownUnit {
+                        start,
+                        end: self.pos,
+                        value: n.unsigned_abs(),
+                        unit: self.src[unit_start..self.pos].to_string(),
+                    })
+                }
+            }
+            components += 1;
+        }
+
+        if components == 0 {
+            return Err(DurationParseError::Empty);
+        }
+
+        Ok(match sign < 0 {
+            true => self.duration.negate(),
+            false => self.duration,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::compiler::Compare;
 use crate::variable::DynamicVariable;
 pub(crate) use crate::vm::date::duration::Duration;
 pub(crate) use crate::vm::date::duration_unit::DurationUnit;
@@ -175,6 +176,103 @@ impl VmDate {
     }
 }
 
+/// Duration value, kept normalized so that structurally equal durations compare equal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VmDuration(Duration);
+
+impl DynamicVariable for VmDuration {
+    fn type_name(&self) -> &'static str {
+        "duration"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_iso())
+    }
+}
+
+impl Display for VmDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.to_iso())
+    }
+}
+
+impl From<Duration> for VmDuration {
+    fn from(value: Duration) -> Self {
+        Self(value.normalized())
+    }
+}
+
+impl VmDuration {
+    pub fn duration(&self) -> &Duration {
+        &self.0
+    }
+
+    /// Duration from a string, a number of `unit`s or another duration
+    pub fn new(var: &Variable, unit: Option<DurationUnit>) -> Option<Self> {
+        match var {
+            Variable::String(s) => Duration::parse(s).ok().map(Self::from),
+            Variable::Number(n) => Duration::from_unit(*n, unit?).map(Self::from),
+            Variable::Dynamic(d) => d.as_duration().cloned(),
+            _ => None,
+        }
+    }
+
+    /// Exact time between two dates, `None` when either is invalid
+    pub fn between(a: &VmDate, b: &VmDate) -> Option<Self> {
+        let millis = a.diff(b, None)?;
+        Some(Self::from(Duration {
+            seconds: millis / 1000,
+            ..Default::default()
+        }))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(&other.0).map(Self::from)
+    }
+
+    /// Outcome of `self <comparison> other` when it holds whichever dates the calendar components
+    /// are applied to, `None` when it depends on them, as for `P1M < P31D`
+    pub fn compare(&self, other: &Self, comparison: &Compare) -> Option<bool> {
+        let (a_min, a_max) = self.0.seconds_range();
+        let (b_min, b_max) = other.0.seconds_range();
+        let (always, never) = match comparison {
+            Compare::Less => (a_max < b_min, a_min >= b_max),
+            Compare::LessOrEqual => (a_max <= b_min, a_min > b_max),
+            Compare::More => (a_min > b_max, a_max <= b_min),
+            Compare::MoreOrEqual => (a_min >= b_max, a_max < b_min),
+        };
+
+        match (always, never) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(&other.negated().0).map(Self::from)
+    }
+
+    pub fn checked_mul(&self, factor: rust_decimal::Decimal) -> Option<Self> {
+        self.0.checked_mul(factor).map(Self::from)
+    }
+
+    pub fn negated(&self) -> Self {
+        Self::from(self.0.clone().negate())
+    }
+
+    pub fn abs(&self) -> Self {
+        match self.0.is_negative() {
+            true => self.negated(),
+            false => self.clone(),
+        }
+    }
+}
+
 mod helper {
     use crate::vm::date::{utc_now, Duration, DurationUnit, DynamicVariableExt};
     use crate::Variable;
@@ -478,12 +576,18 @@ mod helper {
 
 pub(crate) trait DynamicVariableExt {
     fn as_date(&self) -> Option<&VmDate>;
+
+    fn as_duration(&self) -> Option<&VmDuration>;
 }
 
 impl DynamicVariableExt for dyn DynamicVariable {
     fn as_date(&self) -> Option<&VmDate> {
         self.as_any().downcast_ref::<VmDate>()
     }
+
+    fn as_duration(&self) -> Option<&VmDuration> {
+        self.as_any().downcast_ref::<VmDuration>()
+    }
 }
 
 pub(crate) fn utc_now() -> DateTime<Utc> {
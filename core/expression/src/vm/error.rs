@@ -16,6 +16,9 @@ pub enum VMError {
 
     #[error("Number conversion error")]
     NumberConversionError,
+
+    #[error("Evaluation interrupted")]
+    Interrupted,
 }
 
 pub(crate) type VMResult<T> = Result<T, VMError>;
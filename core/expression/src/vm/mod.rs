@@ -1,13 +1,17 @@
 //! Virtual Machine - Evaluation of Opcodes
 //!
 //! The VM (Virtual Machine) module executes the generated machine-readable opcodes.
+pub use calendar::{Calendar, CalendarError, CalendarRegistry};
 pub use error::VMError;
-pub use vm::VM;
+pub use numeric::{NumericContext, RoundingMode};
+pub use vm::{InterruptHandler, VM};
 
+pub(crate) mod calendar;
 pub(crate) mod date;
 mod error;
 pub(crate) mod helpers;
-mod interval;
+pub(crate) mod interval;
+mod numeric;
 mod vm;
 
-pub(crate) use date::VmDate;
+pub(crate) use date::{VmDate, VmDuration};
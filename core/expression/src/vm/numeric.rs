@@ -0,0 +1,89 @@
+use crate::variable::VariableType;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+
+/// Rounding mode accepted by `round` and used for division results under a [`NumericContext`]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumIter,
+    IntoStaticStr,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RoundingMode {
+    /// Midpoint towards the even neighbour, also known as banker's rounding
+    HalfEven,
+    /// Midpoint away from zero
+    #[default]
+    HalfUp,
+    /// Towards zero
+    Down,
+    /// Away from zero
+    Up,
+    /// Towards positive infinity
+    Ceiling,
+    /// Towards negative infinity
+    Floor,
+}
+
+impl RoundingMode {
+    pub(crate) fn variable_type() -> VariableType {
+        VariableType::Enum(
+            Some(Rc::from("RoundingMode")),
+            Self::iter()
+                .map(|mode| Rc::from(<&'static str>::from(mode)))
+                .collect(),
+        )
+    }
+
+    pub fn strategy(&self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+            RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+            RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+        }
+    }
+
+    pub fn round(&self, value: Decimal, scale: u32) -> Decimal {
+        value.round_dp_with_strategy(scale, self.strategy())
+    }
+}
+
+/// Numeric settings of an evaluation.
+///
+/// With a `division_scale`, every division result is rounded to that many decimal places
+/// using `rounding`, otherwise divisions keep the full 28 digit precision of `Decimal`.
+/// Numbers are `Decimal` with or without the `arbitrary_precision` feature, so results do not
+/// depend on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumericContext {
+    #[serde(default)]
+    pub division_scale: Option<u32>,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+}
+
+impl NumericContext {
+    pub fn divide(&self, a: Decimal, b: Decimal) -> Option<Decimal> {
+        let result = a.checked_div(b)?;
+        Some(match self.division_scale {
+            Some(scale) => self.rounding.round(result, scale),
+            None => result,
+        })
+    }
+}
@@ -3,16 +3,21 @@ use crate::functions::arguments::Arguments;
 use crate::functions::registry::FunctionRegistry;
 use crate::functions::{internal, MethodRegistry};
 use crate::scope::Scope;
-use crate::variable::Variable;
 use crate::variable::Variable::*;
+use crate::variable::{DynamicVariable, Variable};
+use crate::vm::calendar::{CalendarRegistry, CalendarScope};
 use crate::vm::date::DynamicVariableExt;
 use crate::vm::error::VMError::*;
 use crate::vm::error::VMResult;
 use crate::vm::interval::{VmInterval, VmIntervalData};
+use crate::vm::numeric::NumericContext;
+use crate::vm::VmDuration;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::{Decimal, MathematicalOps};
+use std::fmt::{Debug, Formatter};
 use std::rc::Rc;
 use std::string::String as StdString;
+use std::sync::Arc;
 use zen_types::symbol::Symbol;
 
 #[derive(Debug)]
@@ -23,10 +28,28 @@ pub struct LoopScope {
     count: usize,
 }
 
-#[derive(Debug)]
+/// Polled on every closure iteration, returning `true` aborts the evaluation
+pub type InterruptHandler = Rc<dyn Fn() -> bool>;
+
 pub struct VM {
     scopes: Vec<LoopScope>,
     stack: Vec<Variable>,
+    interrupt_handler: Option<InterruptHandler>,
+    calendars: Option<Arc<CalendarRegistry>>,
+    numeric: NumericContext,
+    folding: bool,
+}
+
+impl Debug for VM {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VM")
+            .field("scopes", &self.scopes)
+            .field("stack", &self.stack)
+            .field("interrupt_handler", &self.interrupt_handler.is_some())
+            .field("calendars", &self.calendars)
+            .field("numeric", &self.numeric)
+            .finish()
+    }
 }
 
 impl VM {
@@ -34,14 +57,48 @@ impl VM {
         Self {
             scopes: Default::default(),
             stack: Default::default(),
+            interrupt_handler: None,
+            calendars: None,
+            numeric: NumericContext::default(),
+            folding: false,
         }
     }
 
+    /// VM evaluating constant subtrees during compilation. Divisions whose quotient is not an
+    /// integer fail, leaving them to the numeric context of the VM running the expression.
+    pub(crate) fn for_folding() -> Self {
+        Self {
+            folding: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn set_interrupt_handler(&mut self, handler: Option<InterruptHandler>) {
+        self.interrupt_handler = handler;
+    }
+
+    pub fn set_calendars(&mut self, calendars: Option<Arc<CalendarRegistry>>) {
+        self.calendars = calendars;
+    }
+
+    pub fn set_numeric_context(&mut self, numeric: NumericContext) {
+        self.numeric = numeric;
+    }
+
     pub fn run(&mut self, bytecode: &[Opcode], scope: &Scope) -> VMResult<Variable> {
         self.stack.clear();
         self.scopes.clear();
+        let _calendars = self.calendars.as_ref().map(CalendarScope::enter);
 
-        let s = VMInner::new(bytecode, &mut self.stack, &mut self.scopes).run(scope);
+        let s = VMInner::new(
+            bytecode,
+            &mut self.stack,
+            &mut self.scopes,
+            self.interrupt_handler.as_ref(),
+            self.numeric,
+            self.folding,
+        )
+        .run(scope);
         Ok(s?)
     }
 }
@@ -50,6 +107,9 @@ struct VMInner<'parent_ref, 'bytecode_ref> {
     scopes: &'parent_ref mut Vec<LoopScope>,
     stack: &'parent_ref mut Vec<Variable>,
     bytecode: &'bytecode_ref [Opcode],
+    interrupt_handler: Option<&'parent_ref InterruptHandler>,
+    numeric: NumericContext,
+    folding: bool,
     ip: u32,
 }
 
@@ -58,12 +118,18 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
         bytecode: &'bytecode_ref [Opcode],
         stack: &'parent_ref mut Vec<Variable>,
         scopes: &'parent_ref mut Vec<LoopScope>,
+        interrupt_handler: Option<&'parent_ref InterruptHandler>,
+        numeric: NumericContext,
+        folding: bool,
     ) -> Self {
         Self {
             ip: 0,
             scopes,
             stack,
             bytecode,
+            interrupt_handler,
+            numeric,
+            folding,
         }
     }
 
@@ -77,6 +143,33 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
         })
     }
 
+    /// `date + duration`, `duration + date` and `duration + duration`
+    fn add_dynamic(a: &Rc<dyn DynamicVariable>, b: &Rc<dyn DynamicVariable>) -> Option<Variable> {
+        let result: Rc<dyn DynamicVariable> = match (a.as_date(), b.as_date()) {
+            (Some(date), None) => Rc::new(date.add(b.as_duration()?.duration().clone())),
+            (None, Some(date)) => Rc::new(date.add(a.as_duration()?.duration().clone())),
+            (None, None) => Rc::new(a.as_duration()?.checked_add(b.as_duration()?)?),
+            (Some(_), Some(_)) => return None,
+        };
+
+        Some(Dynamic(result))
+    }
+
+    /// `date - duration`, `date - date` and `duration - duration`
+    fn subtract_dynamic(
+        a: &Rc<dyn DynamicVariable>,
+        b: &Rc<dyn DynamicVariable>,
+    ) -> Option<Variable> {
+        let result: Rc<dyn DynamicVariable> = match (a.as_date(), b.as_date()) {
+            (Some(date), None) => Rc::new(date.sub(b.as_duration()?.duration().clone())),
+            (Some(a), Some(b)) => Rc::new(VmDuration::between(a, b)?),
+            (None, None) => Rc::new(a.as_duration()?.checked_sub(b.as_duration()?)?),
+            (None, Some(_)) => return None,
+        };
+
+        Some(Dynamic(result))
+    }
+
     pub fn run(&mut self, root_scope: &Scope) -> VMResult<Variable> {
         let mut env = root_scope.clone();
         let mut assigned_objects: Vec<Variable> = Vec::new();
@@ -100,6 +193,7 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                 Opcode::PushBool(b) => self.push(Bool(*b)),
                 Opcode::PushNumber(n) => self.push(Number(*n)),
                 Opcode::PushString(s) => self.push(String((s.as_ref()).into())),
+                Opcode::PushConstant(c) => self.push(c.to_variable()),
                 Opcode::Pop => {
                     self.pop()?;
                 }
@@ -208,6 +302,13 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                         Number(n) => {
                             self.push(Number(-n));
                         }
+                        Dynamic(d) => {
+                            let duration = d.as_duration().ok_or_else(|| OpcodeErr {
+                                opcode: "Negate".into(),
+                                message: "Unsupported type".into(),
+                            })?;
+                            self.push(Dynamic(Rc::new(duration.negated())));
+                        }
                         _ => {
                             return Err(OpcodeErr {
                                 opcode: "Negate".into(),
@@ -245,10 +346,16 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                             self.push(Bool(true));
                         }
                         (Dynamic(a), Dynamic(b)) => {
-                            let a = a.as_date();
-                            let b = b.as_date();
+                            let equal = match (a.as_duration(), b.as_duration()) {
+                                (Some(a), Some(b)) => a == b,
+                                _ => {
+                                    let a = a.as_date();
+                                    let b = b.as_date();
+                                    a.is_some() && b.is_some() && a == b
+                                }
+                            };
 
-                            self.push(Bool(a.is_some() && b.is_some() && a == b));
+                            self.push(Bool(equal));
                         }
                         _ => {
                             self.push(Bool(false));
@@ -427,6 +534,15 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                         }
                     }
                 }
+                Opcode::InSet(set) => {
+                    let a = self.pop()?;
+                    let is_in = set.contains(&a).ok_or_else(|| OpcodeErr {
+                        opcode: "In".into(),
+                        message: "Unsupported type".into(),
+                    })?;
+
+                    self.push(Bool(is_in));
+                }
                 Opcode::Compare(comparison) => {
                     let b = self.pop()?;
                     let a = self.pop()?;
@@ -443,17 +559,27 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                     match (a, b) {
                         (Number(a), Number(b)) => self.push(Bool(compare(&a, &b, comparison))),
                         (Dynamic(a), Dynamic(b)) => {
-                            let (a, b) = match (a.as_date(), b.as_date()) {
-                                (Some(a), Some(b)) => (a, b),
-                                _ => {
-                                    return Err(OpcodeErr {
-                                        opcode: "Compare".into(),
-                                        message: "Unsupported type".into(),
-                                    })
-                                }
+                            let result = match (a.as_date(), b.as_date()) {
+                                (Some(a), Some(b)) => compare(a, b, comparison),
+                                _ => match (a.as_duration(), b.as_duration()) {
+                                    (Some(a), Some(b)) => {
+                                        a.compare(b, comparison).ok_or_else(|| OpcodeErr {
+                                            opcode: "Compare".into(),
+                                            message: format!(
+                                                "Comparing {a} with {b} depends on the length of calendar months and years"
+                                            ),
+                                        })?
+                                    }
+                                    _ => {
+                                        return Err(OpcodeErr {
+                                            opcode: "Compare".into(),
+                                            message: "Unsupported type".into(),
+                                        })
+                                    }
+                                },
                             };
 
-                            self.push(Bool(compare(a, b, comparison)));
+                            self.push(Bool(result));
                         }
                         _ => {
                             return Err(OpcodeErr {
@@ -483,6 +609,13 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
 
                             self.push(String((c.as_str()).into()));
                         }
+                        (Dynamic(a), Dynamic(b)) => {
+                            let result = Self::add_dynamic(&a, &b).ok_or_else(|| OpcodeErr {
+                                opcode: "Add".into(),
+                                message: "Unsupported type".into(),
+                            })?;
+                            self.push(result);
+                        }
                         _ => {
                             return Err(OpcodeErr {
                                 opcode: "Add".into(),
@@ -503,6 +636,14 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                             })?;
                             self.push(Number(result));
                         }
+                        (Dynamic(a), Dynamic(b)) => {
+                            let result =
+                                Self::subtract_dynamic(&a, &b).ok_or_else(|| OpcodeErr {
+                                    opcode: "Subtract".into(),
+                                    message: "Unsupported type".into(),
+                                })?;
+                            self.push(result);
+                        }
                         _ => {
                             return Err(OpcodeErr {
                                 opcode: "Subtract".into(),
@@ -523,6 +664,21 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                             })?;
                             self.push(Number(result));
                         }
+                        (Dynamic(d), Number(n)) | (Number(n), Dynamic(d)) => {
+                            let result = d
+                                .as_duration()
+                                .ok_or_else(|| OpcodeErr {
+                                    opcode: "Multiply".into(),
+                                    message: "Unsupported type".into(),
+                                })?
+                                .checked_mul(n)
+                                .ok_or_else(|| OpcodeErr {
+                                    opcode: "Multiply".into(),
+                                    message: "Duration overflow or fractional calendar units"
+                                        .into(),
+                                })?;
+                            self.push(Dynamic(Rc::new(result)));
+                        }
                         _ => {
                             return Err(OpcodeErr {
                                 opcode: "Multiply".into(),
@@ -542,7 +698,13 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                         });
                     };
 
-                    let result = match a.checked_div(b) {
+                    let result = match self.numeric.divide(a, b) {
+                        Some(r) if self.folding && !r.fract().is_zero() => {
+                            return Err(OpcodeErr {
+                                opcode: "Divide".into(),
+                                message: "Inexact division depends on the numeric context".into(),
+                            });
+                        }
                         Some(r) => Number(r),
                         None => Null,
                     };
@@ -884,6 +1046,9 @@ impl<'arena, 'parent_ref, 'bytecode_ref> VMInner<'parent_ref, 'bytecode_ref> {
                     })?;
 
                     scope.iter += 1;
+                    if self.interrupt_handler.is_some_and(|interrupt| interrupt()) {
+                        return Err(Interrupted);
+                    }
                 }
                 Opcode::IncrementCount => {
                     let scope = self.scopes.last_mut().ok_or_else(|| OpcodeErr {
@@ -1,7 +1,14 @@
+use chrono::{NaiveDate, TimeDelta, Weekday};
+use rust_decimal_macros::dec;
 use serde_json::{json, Value};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Arc;
 
+use zen_expression::compiler::Opcode;
 use zen_expression::variable::Variable;
-use zen_expression::Isolate;
+use zen_expression::vm::{Calendar, CalendarRegistry, NumericContext, RoundingMode, VMError};
+use zen_expression::{Isolate, IsolateError};
 
 struct TestEnv {
     env: Value,
@@ -907,6 +914,51 @@ mod test {
         }
     }
 
+    fn test_csv_optimizer_parity(csv_data: &str, unary: bool) {
+        let mut r = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(csv_data.as_bytes());
+
+        while let Some(maybe_row) = r.records().next() {
+            let Ok(row) = maybe_row else {
+                continue;
+            };
+
+            let (expression, input_str) = (row.index(0), row.index(1));
+            if expression.starts_with("#") {
+                continue;
+            }
+
+            let results = [true, false].map(|optimize| {
+                let mut isolate = Isolate::new().with_optimizations(optimize);
+                if !input_str.is_empty() {
+                    let input: Value = serde_json5::from_str(input_str).unwrap();
+                    isolate.set_environment(input.into());
+                }
+
+                match unary {
+                    true => isolate.run_unary(expression).map(Value::Bool),
+                    false => isolate.run_standard(expression).map(|v| v.to_value()),
+                }
+                .map_err(|err| err.to_string())
+            });
+
+            assert_eq!(
+                results[0], results[1],
+                "Expression {expression} differs with optimizations"
+            );
+        }
+    }
+
+    #[test]
+    fn optimizer_parity_csv() {
+        env::set_var("TZ", "UTC");
+
+        test_csv_optimizer_parity(include_str!("data/standard.csv"), false);
+        test_csv_optimizer_parity(include_str!("data/date.csv"), false);
+        test_csv_optimizer_parity(include_str!("data/unary.csv"), true);
+    }
+
     #[test]
     fn isolate_test_decimals() {
         let mut isolate = Isolate::new();
@@ -986,6 +1038,115 @@ fn arithmetic_overflow_errors_instead_of_panicking() {
         .is_err());
 }
 
+#[test]
+fn constant_subtrees_are_folded() {
+    let mut isolate = Isolate::new();
+
+    let folded = isolate.compile_standard("100 * 12 / 4").unwrap();
+    assert_eq!(folded.bytecode().as_ref(), &[Opcode::PushNumber(dec!(300))]);
+
+    let array = isolate.compile_standard("[1, 2, 3]").unwrap();
+    assert!(matches!(
+        array.bytecode().as_ref(),
+        [Opcode::PushConstant(_)]
+    ));
+
+    let date = isolate.compile_standard("d('2024-01-01')").unwrap();
+    assert!(matches!(
+        date.bytecode().as_ref(),
+        [Opcode::PushConstant(_)]
+    ));
+
+    let pruned = isolate.compile_standard("true ? a : b").unwrap();
+    assert_eq!(
+        pruned.bytecode().as_ref(),
+        &[Opcode::FetchEnv(Arc::from("a"))]
+    );
+
+    for impure in [
+        "d()",
+        "d('Europe/Berlin')",
+        "rand(10)",
+        "d('2024-01-01').isToday()",
+    ] {
+        let compiled = isolate.compile_standard(impure).unwrap();
+        assert!(
+            compiled
+                .bytecode()
+                .iter()
+                .any(|op| matches!(op, Opcode::CallFunction { .. } | Opcode::CallMethod { .. })),
+            "{impure} must be evaluated at runtime"
+        );
+    }
+
+    let unoptimized = Isolate::new()
+        .with_optimizations(false)
+        .compile_standard("100 * 12 / 4")
+        .unwrap();
+    assert_eq!(unoptimized.bytecode().len(), 5);
+}
+
+#[test]
+fn nested_constant_subtrees_are_folded_once() {
+    let nested = (0..500).fold(String::from("1"), |acc, _| format!("({acc} + 1)"));
+    let mut isolate = Isolate::with_environment(json!({ "x": 1 }).into());
+
+    let compiled = isolate.compile_standard(&format!("x + {nested}")).unwrap();
+    assert!(compiled
+        .bytecode()
+        .iter()
+        .any(|op| op == &Opcode::PushNumber(dec!(501))));
+
+    // Children too large to inline still fold into the parent evaluating them
+    let result = isolate
+        .run_standard("x + len(map([0..1000), [#, # * 2]))")
+        .unwrap();
+    assert_eq!(result.to_value(), json!(1001));
+}
+
+#[test]
+fn in_over_constant_array_uses_hashed_lookup() {
+    let mut isolate = Isolate::with_environment(json!({ "x": "b", "n": 2.0, "arr": [1] }).into());
+
+    let compiled = isolate.compile_standard("x in ['a', 'b', 'c']").unwrap();
+    assert!(compiled
+        .bytecode()
+        .iter()
+        .any(|op| matches!(op, Opcode::InSet(_))));
+
+    let cases = [
+        ("x in ['a', 'b', 'c']", json!(true)),
+        ("x not in ['a', 'b', 'c']", json!(false)),
+        ("n in [1, 2, 3]", json!(true)),
+        ("null in [1, null]", json!(true)),
+        ("true in [false]", json!(false)),
+        (
+            "d('2024-01-01') in [d('2023-01-01'), d('2024-01-01')]",
+            json!(true),
+        ),
+        ("map([1, 5], # in [1, 2])", json!([true, false])),
+    ];
+
+    for (expression, expected) in cases {
+        let result = isolate.run_standard(expression).unwrap();
+        assert_eq!(result.to_value(), expected, "{expression}");
+    }
+
+    assert!(isolate.run_standard("arr in [[1]]").is_err());
+}
+
+#[test]
+fn failed_folding_keeps_errors() {
+    let mut isolate = Isolate::new();
+
+    let max = "79228162514264337593543950335";
+    assert!(isolate.compile_standard(&format!("{max} + 1")).is_ok());
+    assert!(isolate.run_standard(&format!("{max} + 1")).is_err());
+
+    assert!(isolate.compile_standard("true ? 1 : len()").is_err());
+    assert!(isolate.compile_standard("false or len()").is_err());
+}
+
 #[test]
 fn division_and_modulo_by_zero_return_null() {
     let mut isolate = Isolate::new();
@@ -1046,3 +1207,200 @@ fn string_index_at_usize_max_returns_null() {
     let result = isolate.run_standard("s[i]").unwrap();
     assert_eq!(result, Variable::Null);
 }
+
+#[test]
+fn interrupt_handler_aborts_closure_loops() {
+    let polls = Rc::new(Cell::new(0));
+    let handler_polls = polls.clone();
+
+    let mut isolate = Isolate::with_environment(json!({ "items": [1, 2, 3], "n": 10000 }).into());
+    isolate.set_interrupt_handler(Some(Rc::new(move || {
+        handler_polls.set(handler_polls.get() + 1);
+        handler_polls.get() > 100
+    })));
+
+    let short = isolate.run_standard("map(items, # * 2)").unwrap();
+    assert_eq!(short, Variable::from(json!([2, 4, 6])));
+    assert_eq!(polls.get(), 3);
+
+    let long = isolate.run_standard("sum(map([0..n], # * 2))");
+    assert!(matches!(
+        long,
+        Err(IsolateError::VMError {
+            source: VMError::Interrupted
+        })
+    ));
+    assert_eq!(polls.get(), 101);
+
+    isolate.set_interrupt_handler(None);
+    assert!(isolate.run_standard("sum(map([0..n], # * 2))").is_ok());
+}
+
+#[test]
+fn add_business_days_from_non_business_days() {
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let calendar = Calendar::new(
+        [Weekday::Sat, Weekday::Sun],
+        [date("2025-01-13"), date("2025-12-25")],
+    )
+    .unwrap();
+
+    let cases = [
+        ("2025-01-04", 5, "2025-01-10"),
+        ("2025-01-05", -5, "2024-12-30"),
+        ("2025-01-13", -5, "2025-01-06"),
+        ("2025-01-13", 5, "2025-01-20"),
+        ("2025-12-25", 1, "2025-12-26"),
+        ("2025-12-25", -1, "2025-12-24"),
+    ];
+    for (from, days, expected) in cases {
+        let result = calendar.add_business_days(date(from), days);
+        assert_eq!(result, Some(date(expected)), "{from} {days:+}");
+    }
+
+    let stepped = |from: NaiveDate, days: i64| {
+        let mut current = from;
+        for _ in 0..days.abs() {
+            current += TimeDelta::days(days.signum());
+            while !calendar.is_business_day(current) {
+                current += TimeDelta::days(days.signum());
+            }
+        }
+        current
+    };
+    for start in 0..14 {
+        let from = date("2025-01-01") + TimeDelta::days(start);
+        for days in -12..=12 {
+            let result = calendar.add_business_days(from, days);
+            assert_eq!(result, Some(stepped(from, days)), "{from} {days:+}");
+        }
+    }
+}
+
+#[test]
+fn business_day_methods_use_registered_calendars() {
+    let calendars = CalendarRegistry::from_json(
+        r#"{
+            "DE": { "holidays": ["2025-12-25", "2025-12-26", "2026-01-01"] },
+            "AE": { "weekend": [6, 7], "holidays": [] },
+            "IL": { "weekend": [5, 6] }
+        }"#,
+    )
+    .unwrap();
+
+    let mut isolate = Isolate::new();
+    assert!(isolate
+        .run_standard("d('2025-12-24').isBusinessDay('DE')")
+        .is_err());
+
+    isolate.set_calendars(Some(Arc::new(calendars)));
+    let cases = [
+        ("d('2025-12-24').isBusinessDay('DE')", json!(true)),
+        ("d('2025-12-25').isBusinessDay('DE')", json!(false)),
+        ("d('2025-12-27').isBusinessDay('DE')", json!(false)),
+        ("d('2025-12-26').isBusinessDay('IL')", json!(false)),
+        ("d('2025-12-28').isBusinessDay('IL')", json!(true)),
+        (
+            "d('2025-12-24 10:30').addBusinessDays(2, 'DE').format('%Y-%m-%d %H:%M')",
+            json!("2025-12-30 10:30"),
+        ),
+        (
+            "d('2026-01-02').addBusinessDays(-3, 'DE').format('%Y-%m-%d')",
+            json!("2025-12-29"),
+        ),
+        (
+            "d('2025-01-01').addBusinessDays(260, 'AE').format('%Y-%m-%d')",
+            json!("2025-12-31"),
+        ),
+        (
+            "d('2025-12-24').addBusinessDays(0, 'DE').format('%Y-%m-%d')",
+            json!("2025-12-24"),
+        ),
+        (
+            "d('2025-12-24').nextBusinessDay('DE').format('%Y-%m-%d')",
+            json!("2025-12-29"),
+        ),
+        (
+            "d('2025-12-22').businessDaysBetween(d('2026-01-05'), 'DE')",
+            json!(7),
+        ),
+        (
+            "d('2026-01-05').businessDaysBetween('2025-12-22', 'DE')",
+            json!(-7),
+        ),
+        (
+            "d('2025-12-22').businessDaysBetween('2025-12-22', 'AE')",
+            json!(0),
+        ),
+    ];
+
+    for (expression, expected) in cases {
+        let result = isolate.run_standard(expression).unwrap();
+        assert_eq!(result, Variable::from(expected), "{expression}");
+    }
+
+    let unknown = isolate.run_standard("d('2025-12-24').isBusinessDay('FR')");
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn numeric_context_rounds_division_results() {
+    let mut isolate = Isolate::with_environment(json!({ "total": 100, "parts": 3 }).into());
+    assert_eq!(
+        isolate.run_standard("10 / 4").unwrap(),
+        Variable::Number(dec!(2.5))
+    );
+
+    isolate.set_numeric_context(NumericContext {
+        division_scale: Some(2),
+        rounding: RoundingMode::HalfEven,
+    });
+    for (expression, expected) in [
+        ("total / parts", dec!(33.33)),
+        ("2 / 3", dec!(0.67)),
+        ("1 / 8", dec!(0.12)),
+        ("3 / 8", dec!(0.38)),
+        ("100 * 12 / 4", dec!(300)),
+    ] {
+        assert_eq!(
+            isolate.run_standard(expression).unwrap(),
+            Variable::Number(expected),
+            "{expression}"
+        );
+    }
+    assert_eq!(isolate.run_standard("1 / 0").unwrap(), Variable::Null);
+
+    isolate.set_numeric_context(NumericContext {
+        division_scale: Some(0),
+        rounding: RoundingMode::Floor,
+    });
+    assert_eq!(
+        isolate.run_standard("-7 / 2").unwrap(),
+        Variable::Number(dec!(-4))
+    );
+
+    let context: NumericContext =
+        serde_json::from_value(json!({ "divisionScale": 4, "rounding": "half-even" })).unwrap();
+    assert_eq!(context.division_scale, Some(4));
+    assert_eq!(context.rounding, RoundingMode::HalfEven);
+    assert!(isolate.run_standard("round(1.5, 0, 'sideways')").is_err());
+}
+
+#[test]
+fn calendar_duration_comparisons_that_depend_on_the_date_fail() {
+    let mut isolate = Isolate::new();
+    for expression in [
+        "dur('P1M') < dur('P31D')",
+        "dur('P1M') >= dur('P30D')",
+        "dur('P1Y') == dur('P365D') or dur('P1Y') < dur('P366D')",
+    ] {
+        let err = isolate.run_standard(expression).unwrap_err();
+        assert!(
+            err.to_string().contains("length of calendar months"),
+            "{expression}: {err}"
+        );
+    }
+
+    let err = isolate.run_standard("duration('P1D')").unwrap_err();
+    assert!(err.to_string().contains("dur('P1D')"), "{err}");
+}
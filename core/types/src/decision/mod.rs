@@ -66,6 +66,26 @@ pub enum DecisionNodeKind {
     CustomNode {
         content: CustomNodeContent,
     },
+    WasmNode {
+        content: WasmNodeContent,
+    },
+}
+
+impl DecisionNodeKind {
+    /// Value of the `type` tag in JDM
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DecisionNodeKind::InputNode { .. } => "inputNode",
+            DecisionNodeKind::OutputNode { .. } => "outputNode",
+            DecisionNodeKind::FunctionNode { .. } => "functionNode",
+            DecisionNodeKind::DecisionNode { .. } => "decisionNode",
+            DecisionNodeKind::DecisionTableNode { .. } => "decisionTableNode",
+            DecisionNodeKind::ExpressionNode { .. } => "expressionNode",
+            DecisionNodeKind::SwitchNode { .. } => "switchNode",
+            DecisionNodeKind::CustomNode { .. } => "customNode",
+            DecisionNodeKind::WasmNode { .. } => "wasmNode",
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
@@ -73,6 +93,10 @@ pub enum DecisionNodeKind {
 pub struct InputNodeContent {
     #[serde(default, deserialize_with = "empty_value_string_is_none_safe")]
     pub schema: Option<Arc<Value>>,
+    /// Applies schema defaults, coerces scalars and strips undeclared properties before
+    /// validating
+    #[serde(default)]
+    pub coerce: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
@@ -100,6 +124,9 @@ pub struct FunctionContent {
 #[serde(rename_all = "camelCase")]
 pub struct DecisionNodeContent {
     pub key: Arc<str>,
+    /// Output properties requested when `key` points to a policy, all outputs when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Arc<str>>,
     #[serde(flatten)]
     pub transform_attributes: TransformAttributes,
 }
@@ -132,6 +159,13 @@ pub struct DecisionTableInputField {
     pub name: Arc<str>,
     #[serde(default, deserialize_with = "empty_string_is_none")]
     pub field: Option<Arc<str>>,
+    #[serde(
+        rename = "type",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "empty_string_is_none"
+    )]
+    pub column_type: Option<Arc<str>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -214,6 +248,10 @@ pub struct TransformAttributes {
     pub execution_mode: TransformExecutionMode,
     #[serde(default)]
     pub pass_through: bool,
+    /// Applies to loop execution, items run one at a time and the first failing item fails the
+    /// node when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_options: Option<LoopOptions>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
@@ -224,6 +262,32 @@ pub enum TransformExecutionMode {
     Loop,
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopOptions {
+    /// Items evaluated at the same time
+    #[serde(default = "default_concurrency")]
+    pub concurrency: u16,
+    /// Failing items are reported next to the results instead of failing the node. The output
+    /// becomes `{ results, errors }`, two arrays parallel to the input where each item has
+    /// either a result or an error and `null` in the other.
+    #[serde(default)]
+    pub collect_errors: bool,
+}
+
+fn default_concurrency() -> u16 {
+    1
+}
+
+impl Default for LoopOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: default_concurrency(),
+            collect_errors: false,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomNodeContent {
@@ -231,6 +295,27 @@ pub struct CustomNodeContent {
     pub config: Arc<Value>,
 }
 
+/// WebAssembly module exporting `handle`, either embedded or fetched through the loader
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmNodeContent {
+    /// Base64 encoded module bytes
+    #[serde(default, deserialize_with = "empty_string_is_none")]
+    pub module: Option<Arc<str>>,
+    /// Loader key of the module, used when `module` is empty
+    #[serde(default, deserialize_with = "empty_string_is_none")]
+    pub key: Option<Arc<str>>,
+    /// Fuel available to a single invocation, engine default when unset
+    #[serde(default)]
+    pub fuel: Option<u64>,
+    /// Upper bound of linear memory in bytes, engine default when unset
+    #[serde(default)]
+    pub max_memory_bytes: Option<u64>,
+    /// JSON schema of the produced output, used to type downstream nodes
+    #[serde(default, deserialize_with = "empty_value_string_is_none_safe")]
+    pub output_schema: Option<Arc<Value>>,
+}
+
 fn empty_string_is_none<'de, D>(deserializer: D) -> Result<Option<Arc<str>>, D::Error>
 where
     D: Deserializer<'de>,
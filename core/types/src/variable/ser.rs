@@ -26,12 +26,15 @@ impl Serialize for Variable {
             }
             #[cfg(not(feature = "arbitrary_precision"))]
             Variable::Number(v) => {
-                if let Some(u) = v.to_u64() {
-                    return serializer.serialize_u64(u);
-                }
+                // Integer conversions truncate, fractions go through f64
+                if v.fract().is_zero() {
+                    if let Some(u) = v.to_u64() {
+                        return serializer.serialize_u64(u);
+                    }
 
-                if let Some(i) = v.to_i64() {
-                    return serializer.serialize_i64(i);
+                    if let Some(i) = v.to_i64() {
+                        return serializer.serialize_i64(i);
+                    }
                 }
 
                 if let Some(f) = v.to_f64() {
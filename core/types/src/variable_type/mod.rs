@@ -19,6 +19,7 @@ pub enum VariableType {
     String,
     Number,
     Date,
+    Duration,
     Interval,
     Array(Rc<VariableType>),
     Object(RcCell<HashMap<Rc<str>, VariableType>>),
@@ -49,6 +50,7 @@ impl Display for VariableType {
             VariableType::String => write!(f, "string"),
             VariableType::Number => write!(f, "number"),
             VariableType::Date => write!(f, "date"),
+            VariableType::Duration => write!(f, "duration"),
             VariableType::Interval => write!(f, "interval"),
             VariableType::Const(c) => write!(f, "\"{c}\""),
             VariableType::Enum(name, e) => {
@@ -87,6 +89,7 @@ impl Hash for VariableType {
             VariableType::Number => 4.hash(state),
             VariableType::Date => 5.hash(state),
             VariableType::Interval => 6.hash(state),
+            VariableType::Duration => 12.hash(state),
             VariableType::Const(c) => {
                 7.hash(state);
                 c.hash(state)
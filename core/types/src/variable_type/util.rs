@@ -55,6 +55,7 @@ impl VariableType {
             (VariableType::Date, VariableType::Date) => true,
             (VariableType::Number, VariableType::Date) => true,
             (_, VariableType::Date) if self.widen().is_string() => true,
+            (VariableType::Duration, VariableType::Duration) => true,
             (VariableType::Interval, VariableType::Interval) => true,
             (VariableType::Array(a1), VariableType::Array(a2)) => a1.satisfies(a2),
             (VariableType::Object(o1), VariableType::Object(o2)) => {
@@ -155,6 +156,7 @@ impl VariableType {
             (VariableType::String, VariableType::String) => VariableType::String,
             (VariableType::Number, VariableType::Number) => VariableType::Number,
             (VariableType::Date, VariableType::Date) => VariableType::Date,
+            (VariableType::Duration, VariableType::Duration) => VariableType::Duration,
             (VariableType::Interval, VariableType::Interval) => VariableType::Interval,
             (VariableType::Array(a1), VariableType::Array(a2)) => {
                 if Rc::ptr_eq(a1, a2) {
@@ -254,6 +256,7 @@ impl VariableType {
             VariableType::String => VariableType::String,
             VariableType::Number => VariableType::Number,
             VariableType::Date => VariableType::Date,
+            VariableType::Duration => VariableType::Duration,
             VariableType::Interval => VariableType::Interval,
             VariableType::Array(arr) => VariableType::Array(arr.clone()),
             VariableType::Object(obj) => VariableType::Object(obj.clone()),
@@ -204,3 +204,16 @@ fn edge_cases() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn serialize_numbers() -> TestResult {
+    let numbers = Variable::from_array(vec![
+        Variable::Number(dec!(42)),
+        Variable::Number(dec!(-7)),
+        Variable::Number(dec!(0.2)),
+        Variable::Number(dec!(-1.5)),
+    ]);
+
+    assert_eq!(serde_json::to_value(&numbers)?, json!([42, -7, 0.2, -1.5]));
+    Ok(())
+}